@@ -0,0 +1,147 @@
+//! Command shortcuts from the global config's `[aliases]` table:
+//!
+//! ```toml
+//! [aliases]
+//! d = ["devnet"]
+//! tb = ["test", "tests/basic.toml", "--profile", "devnet"]
+//! ```
+//!
+//! The first argument after `trix` is expanded before clap sees the command
+//! line, and anything after it is kept, so `trix tb --verbose` runs
+//! `trix test tests/basic.toml --profile devnet --verbose`. An alias may
+//! expand to another alias. Built-in subcommands always win over an alias of
+//! the same name, and `--no-alias` turns expansion off.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+
+use clap::CommandFactory as _;
+use miette::bail;
+
+pub const NO_ALIAS_FLAG: &str = "--no-alias";
+
+/// Names clap already dispatches: every subcommand, its aliases, and `help`.
+pub fn builtin_names() -> Vec<String> {
+    let cli = crate::cli::Cli::command();
+
+    let mut names = vec!["help".to_string()];
+
+    for sub in cli.get_subcommands() {
+        names.push(sub.get_name().to_string());
+        names.extend(sub.get_all_aliases().map(String::from));
+    }
+
+    names
+}
+
+/// Expand a leading alias in `args` (the full argv, program name first).
+pub fn expand(
+    args: Vec<OsString>,
+    aliases: &BTreeMap<String, Vec<String>>,
+    builtins: &[String],
+) -> miette::Result<Vec<OsString>> {
+    if args.iter().any(|arg| arg == NO_ALIAS_FLAG) {
+        return Ok(args);
+    }
+
+    let mut args = args.into_iter();
+    let program = args.next();
+    let rest: Vec<OsString> = args.collect();
+
+    let Some(first) = rest.first().and_then(|arg| arg.to_str()) else {
+        return Ok(program.into_iter().chain(rest).collect());
+    };
+
+    let mut head: Vec<String> = vec![first.to_string()];
+    let mut chain: Vec<String> = vec![];
+
+    loop {
+        let name = &head[0];
+
+        if builtins.contains(name) {
+            break;
+        }
+
+        let Some(target) = aliases.get(name) else {
+            break;
+        };
+
+        if chain.contains(name) {
+            chain.push(name.clone());
+            bail!(
+                help = "edit the aliases with `trix alias set` or `trix alias remove`",
+                "alias cycle: {}",
+                chain.join(" -> ")
+            );
+        }
+
+        if target.is_empty() {
+            bail!(
+                help = format!("remove it with `trix alias remove {name}`"),
+                "alias `{name}` expands to nothing"
+            );
+        }
+
+        chain.push(name.clone());
+        head = target
+            .iter()
+            .cloned()
+            .chain(head.into_iter().skip(1))
+            .collect();
+    }
+
+    Ok(program
+        .into_iter()
+        .chain(head.into_iter().map(OsString::from))
+        .chain(rest.into_iter().skip(1))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    fn aliases(entries: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(name, target)| {
+                let target = target.iter().map(|s| s.to_string()).collect();
+                (name.to_string(), target)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn expands_and_keeps_trailing_args() {
+        let aliases = aliases(&[("tb", &["test", "tests/basic.toml"]), ("t", &["tb"])]);
+        let builtins = builtin_names();
+
+        let expanded = expand(argv(&["trix", "t", "--verbose"]), &aliases, &builtins).unwrap();
+        assert_eq!(
+            expanded,
+            argv(&["trix", "test", "tests/basic.toml", "--verbose"])
+        );
+
+        let untouched = argv(&["trix", "tb", "--no-alias"]);
+        assert_eq!(
+            expand(untouched.clone(), &aliases, &builtins).unwrap(),
+            untouched
+        );
+    }
+
+    #[test]
+    fn builtins_win_and_cycles_fail() {
+        let aliases = aliases(&[("test", &["devnet"]), ("a", &["b"]), ("b", &["a"])]);
+        let builtins = builtin_names();
+
+        let args = argv(&["trix", "test"]);
+        assert_eq!(expand(args.clone(), &aliases, &builtins).unwrap(), args);
+
+        let err = expand(argv(&["trix", "a"]), &aliases, &builtins).unwrap_err();
+        assert!(err.to_string().contains("a -> b -> a"), "{err}");
+    }
+}
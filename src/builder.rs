@@ -1,4 +1,7 @@
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+
+use globset::{GlobBuilder, GlobMatcher};
+use miette::{Context as _, IntoDiagnostic as _};
 
 use crate::{config::RootConfig, spawn};
 
@@ -17,8 +20,102 @@ fn define_tii_output_path(config: &RootConfig) -> miette::Result<PathBuf> {
     Ok(dir.join("main.tii"))
 }
 
+/// Matcher for one `[protocol] sources` pattern, relative to the project
+/// root. `*` stops at `/`; `**` crosses directories.
+pub fn source_glob(pattern: &str) -> Result<GlobMatcher, globset::Error> {
+    GlobBuilder::new(pattern.trim_start_matches("./"))
+        .literal_separator(true)
+        .build()
+        .map(|glob| glob.compile_matcher())
+}
+
+/// The directory a pattern's matches can be under: its components up to
+/// the first one with a glob character.
+fn literal_prefix(pattern: &str) -> PathBuf {
+    let is_literal = |c: &Component| {
+        !c.as_os_str()
+            .to_string_lossy()
+            .contains(['*', '?', '[', '{'])
+    };
+
+    Path::new(pattern)
+        .components()
+        .take_while(is_literal)
+        .collect()
+}
+
+/// Every file of the protocol: `main` first, then the files matched by
+/// `[protocol] sources`, each once and in path order.
+pub fn source_files(config: &RootConfig, root: &Path) -> miette::Result<Vec<PathBuf>> {
+    let main = root.join(&config.protocol.main);
+    let mut files = vec![main.clone()];
+
+    let identity = |path: &Path| std::fs::canonicalize(path).unwrap_or(path.to_path_buf());
+    let mut seen = vec![identity(&main)];
+
+    for pattern in config.protocol.sources.iter().flatten() {
+        let glob = source_glob(pattern)
+            .into_diagnostic()
+            .with_context(|| format!("invalid `[protocol] sources` pattern {pattern:?}"))?;
+
+        let base = root.join(literal_prefix(pattern));
+
+        let matched: Vec<PathBuf> = crate::fsutil::files_under(&base)?
+            .into_iter()
+            .filter(|path| path.strip_prefix(root).is_ok_and(|rel| glob.is_match(rel)))
+            .collect();
+
+        if matched.is_empty() {
+            eprintln!("warning: `[protocol] sources` pattern {pattern:?} matched no files");
+        }
+
+        for path in matched {
+            let id = identity(&path);
+
+            if !seen.contains(&id) {
+                seen.push(id);
+                files.push(path);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// The single file handed to `tx3c`. That is `main` itself unless
+/// `[protocol] sources` is set; then `main` and its dependencies are joined
+/// into `.tx3/sources/<name>.tx3`, each part behind a comment naming the
+/// file it came from.
+pub fn entry_source(config: &RootConfig) -> miette::Result<PathBuf> {
+    if config.protocol.sources.is_none() {
+        return Ok(config.protocol.main.clone());
+    }
+
+    let root = crate::dirs::protocol_root()?;
+    let mut combined = String::new();
+
+    for file in source_files(config, &root)? {
+        let content = std::fs::read_to_string(&file)
+            .into_diagnostic()
+            .with_context(|| format!("reading {}", file.display()))?;
+
+        let relative = file.strip_prefix(&root).unwrap_or(&file);
+        combined.push_str(&format!("// --- {} ---\n", relative.display()));
+        combined.push_str(&content);
+
+        if !content.ends_with('\n') {
+            combined.push('\n');
+        }
+    }
+
+    let path = crate::dirs::target_dir("sources")?.join(format!("{}.tx3", config.protocol.name));
+    crate::fsutil::write_atomic(&path, combined)?;
+
+    Ok(path)
+}
+
 pub fn build_tii(config: &RootConfig) -> miette::Result<PathBuf> {
-    let source = config.protocol.main.clone();
+    let source = entry_source(config)?;
 
     let output_path = define_tii_output_path(config)?;
 
@@ -27,6 +124,17 @@ pub fn build_tii(config: &RootConfig) -> miette::Result<PathBuf> {
     Ok(output_path)
 }
 
+/// Read a built or cached TII as JSON.
+pub fn load_tii(tii_file: &Path) -> miette::Result<serde_json::Value> {
+    let content = std::fs::read_to_string(tii_file)
+        .into_diagnostic()
+        .context(format!("reading {}", tii_file.display()))?;
+
+    serde_json::from_str(&content)
+        .into_diagnostic()
+        .context(format!("parsing {}", tii_file.display()))
+}
+
 #[allow(dead_code)]
 pub fn ensure_tii(config: &RootConfig) -> miette::Result<PathBuf> {
     let output_path = define_tii_output_path(config)?;
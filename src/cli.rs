@@ -17,6 +17,24 @@ pub struct Cli {
 
     #[arg(long, short, global = true)]
     pub verbose: bool,
+
+    /// Seconds a non-interactive cshell call may run before it is killed
+    /// (default: `[cshell] timeout_secs` from the global config, or 120).
+    #[arg(long, global = true, value_name = "SECS")]
+    pub cshell_timeout: Option<u64>,
+
+    /// Print per-host HTTP request counts, errors and latency when the
+    /// command ends.
+    #[arg(long, global = true)]
+    pub net_stats: bool,
+
+    /// Don't expand `[aliases]` from the global config
+    #[arg(long, global = true)]
+    pub no_alias: bool,
+
+    /// When to color output. `auto` honors NO_COLOR and CLICOLOR_FORCE.
+    #[arg(long, global = true, value_enum, default_value_t = crate::term::console::ColorChoice::Auto)]
+    pub color: crate::term::console::ColorChoice,
 }
 
 #[derive(Subcommand)]
@@ -27,6 +45,9 @@ pub enum Commands {
     /// Invoke a transaction template
     Invoke(commands::invoke::Args),
 
+    /// Explore the protocol from a prompt sharing one devnet and wallet
+    Shell(commands::shell::Args),
+
     /// Start development network (powered by Dolos)
     Devnet(commands::devnet::Args),
 
@@ -34,14 +55,27 @@ pub enum Commands {
     Explore(commands::explore::Args),
 
     /// Generate bindings for smart contracts
+    #[command(alias = "bindgen")]
     Codegen(commands::codegen::Args),
 
-    /// Check the project's Tx3 protocol for errors
+    /// Check the project's Tx3 protocol for errors and trix.toml for unused entries
     Check(commands::check::Args),
 
+    /// Check trix.toml for broken references, missing files and malformed URLs
+    ConfigValidate(commands::config::validate::Args),
+
+    /// Compare the effective profiles and networks of two versions of trix.toml
+    ConfigDiff(commands::config::diff::Args),
+
+    /// Upgrade trix.toml written for an older trix to the current config schema
+    Migrate(commands::migrate::Args),
+
     /// Inspect a Tx3 file
     Inspect(commands::inspect::Args),
 
+    /// List the transactions submitted from this project to the profile's network
+    Submissions(commands::submissions::Args),
+
     /// Run a Tx3 testing file
     Test(commands::test::Args),
 
@@ -51,6 +85,9 @@ pub enum Commands {
     /// Manage crypographic identities
     Identities(commands::identities::Args),
 
+    /// Manage the wallets behind the project's identities
+    Wallet(commands::wallet::Args),
+
     /// Inspect and manage profiles
     Profile(commands::profile::Args),
 
@@ -61,6 +98,15 @@ pub enum Commands {
     #[command(name = "use")]
     Use(commands::use_cmd::Args),
 
+    /// Show where trix keeps its state and finds its tools
+    Doctor(commands::doctor::Args),
+
+    /// Manage command shortcuts kept in the global config
+    Alias(commands::alias::Args),
+
+    /// Manage secrets in the OS keychain, referenced as `keyring:service/account`
+    Secret(commands::secret::Args),
+
     /// Telemetry configuration. Trix collects anonymous usage data to improve the tool.
     Telemetry(commands::telemetry::Args),
 }
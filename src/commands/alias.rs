@@ -0,0 +1,75 @@
+use std::ffi::OsString;
+
+use clap::{Args as ClapArgs, Subcommand};
+use miette::bail;
+
+#[derive(ClapArgs)]
+pub struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Show the defined aliases
+    List,
+    /// Define or replace an alias, e.g. `trix alias set tb test tests/basic.toml`
+    Set {
+        /// Name typed in place of the command
+        name: String,
+
+        /// Arguments the alias expands to
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    /// Delete an alias
+    Remove { name: String },
+}
+
+pub fn run(args: Args) -> miette::Result<()> {
+    let mut global_config = crate::global::read_config()?;
+
+    match args.command {
+        Command::List => {
+            if global_config.aliases.is_empty() {
+                println!("no aliases defined (add one with `trix alias set <name> <command>...`)");
+            }
+
+            for (name, target) in &global_config.aliases {
+                println!("{name} = {}", target.join(" "));
+            }
+        }
+        Command::Set { name, command } => {
+            let builtins = crate::alias::builtin_names();
+
+            if builtins.contains(&name) {
+                bail!(
+                    help = "pick a name that isn't a trix subcommand",
+                    "`{name}` is a built-in command and can't be aliased"
+                );
+            }
+
+            global_config.aliases.insert(name.clone(), command);
+
+            // Refuse a definition that would loop before it's saved.
+            let probe = ["trix", name.as_str()].map(OsString::from).to_vec();
+            crate::alias::expand(probe, &global_config.aliases, &builtins)?;
+
+            crate::global::save_config(&global_config)?;
+            println!("{name} = {}", global_config.aliases[&name].join(" "));
+        }
+        Command::Remove { name } => {
+            if global_config.aliases.remove(&name).is_none() {
+                bail!(
+                    help = "see the defined aliases with `trix alias list`",
+                    "no alias named `{name}`"
+                );
+            }
+
+            crate::global::save_config(&global_config)?;
+            println!("removed alias `{name}`");
+        }
+    }
+
+    Ok(())
+}
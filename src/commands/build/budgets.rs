@@ -0,0 +1,388 @@
+//! `trix build --check-budgets`: resolve every template that has a
+//! `[budgets.<template>]` entry without submitting it, and compare its fee
+//! and execution units against the entry. The transaction goes through
+//! cshell and the profile's TRP endpoint like `trix invoke --dry-run`, so
+//! the units are the ones TRP's script evaluation put in the redeemers.
+//!
+//! Args come from the entry's `args`; whatever is left is filled in with
+//! placeholders: one identity per party the template refers to, none of
+//! them already named in `args`, and a fixed lovelace amount per integer
+//! param.
+
+use std::path::Path;
+
+use miette::{Context as _, IntoDiagnostic as _, bail};
+use pallas::ledger::traverse::MultiEraTx;
+
+use crate::commands::invoke::{args as invoke_args, parties};
+use crate::commands::steps::{self, ArgMap, Transaction};
+use crate::config::{BudgetConfig, ProfileConfig, RootConfig};
+use crate::wallet::WalletProxy;
+
+/// Placeholder for integer params: 2 ADA, enough for a min-UTxO output.
+const PLACEHOLDER_INT: u64 = 2_000_000;
+
+/// What resolving a template cost.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Measured {
+    pub fee: u64,
+    pub ex_mem: u64,
+    pub ex_steps: u64,
+}
+
+impl Measured {
+    /// Read the fee and summed redeemer units of the hex transaction CBOR
+    /// cshell reported.
+    fn from_cbor(cbor: &str) -> miette::Result<Self> {
+        let bytes = hex::decode(cbor)
+            .into_diagnostic()
+            .context("transaction cbor is not hex")?;
+
+        let tx = MultiEraTx::decode(&bytes)
+            .into_diagnostic()
+            .context("decoding the resolved transaction")?;
+
+        let (ex_mem, ex_steps) = tx
+            .redeemers()
+            .iter()
+            .map(|redeemer| redeemer.ex_units())
+            .fold((0, 0), |(mem, steps), units| {
+                (mem + units.mem, steps + units.steps)
+            });
+
+        Ok(Self {
+            fee: tx.fee().unwrap_or_default(),
+            ex_mem,
+            ex_steps,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Overrun {
+    pub what: &'static str,
+    pub measured: u64,
+    pub allowed: u64,
+}
+
+/// Every limit of `budget` that `measured` exceeds.
+fn overruns(budget: &BudgetConfig, measured: &Measured) -> Vec<Overrun> {
+    [
+        ("fee", budget.max_fee, measured.fee),
+        ("ex_mem", budget.max_ex_mem, measured.ex_mem),
+        ("ex_steps", budget.max_ex_steps, measured.ex_steps),
+    ]
+    .into_iter()
+    .filter_map(|(what, allowed, measured)| {
+        let allowed = allowed?;
+        (measured > allowed).then_some(Overrun {
+            what,
+            measured,
+            allowed,
+        })
+    })
+    .collect()
+}
+
+/// `budget.args` for `template`, completed with a distinct identity per
+/// remaining party of `parties` (the ones `template` refers to) and
+/// [`PLACEHOLDER_INT`] per remaining integer param. Identities `args`
+/// already references as `@name` aren't handed out again.
+fn placeholder_args(
+    tii: &serde_json::Value,
+    template: &str,
+    parties: &[String],
+    budget: &BudgetConfig,
+    identities: &[String],
+) -> miette::Result<ArgMap> {
+    let mut args: ArgMap = budget
+        .args
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+
+    let parties: Vec<String> = parties
+        .iter()
+        .map(|party| party.to_lowercase())
+        .filter(|party| !args.keys().any(|key| key.eq_ignore_ascii_case(party)))
+        .collect();
+
+    let used: Vec<&str> = args
+        .values()
+        .filter_map(|value| value.as_str()?.strip_prefix('@'))
+        .collect();
+
+    let identities: Vec<&String> = identities
+        .iter()
+        .filter(|identity| !used.contains(&identity.as_str()))
+        .collect();
+
+    if identities.len() < parties.len() {
+        bail!(
+            help = format!("give the parties wallets in `[budgets.{template}] args`"),
+            "`{template}` has {} parties to fill, but the profile has only {} identities left",
+            parties.len(),
+            identities.len()
+        );
+    }
+
+    for (party, identity) in parties.iter().zip(identities) {
+        args.insert(party.clone(), format!("@{identity}").into());
+    }
+
+    let params = tii
+        .pointer(&format!("/transactions/{template}/params/properties"))
+        .and_then(|p| p.as_object())
+        .cloned()
+        .unwrap_or_default();
+
+    for (name, schema) in params {
+        if args.contains_key(&name) {
+            continue;
+        }
+
+        match schema.get("type").and_then(|t| t.as_str()) {
+            Some("integer") => {
+                args.insert(name, PLACEHOLDER_INT.into());
+            }
+            _ => bail!(
+                help = format!("set it in `[budgets.{template}] args`"),
+                "can't make up a value for `{name}` of `{template}`"
+            ),
+        }
+    }
+
+    invoke_args::validate(&args, tii, template)?;
+
+    Ok(args)
+}
+
+fn measure(
+    config: &RootConfig,
+    profile: &ProfileConfig,
+    wallet: &WalletProxy,
+    tii_file: &Path,
+    template: &str,
+    args: ArgMap,
+) -> miette::Result<Measured> {
+    let transaction = Transaction {
+        description: format!("budget check of {template}"),
+        template: template.to_string(),
+        args: args.into_iter().collect(),
+        signers: vec![],
+    };
+
+    let args = steps::define_args(&transaction, wallet)?;
+
+    let output = wallet
+        .invoke_json(tii_file, template, &args, vec![], &profile.name, true)
+        .map_err(|err| {
+            crate::commands::invoke::resolve_error::explain(err, Some(config), template)
+        })?;
+
+    let cbor = output
+        .get("cbor")
+        .and_then(|c| c.as_str())
+        .ok_or_else(|| miette::miette!("cshell did not report the resolved transaction"))?;
+
+    Measured::from_cbor(cbor)
+}
+
+fn describe(budget: &BudgetConfig, measured: &Measured) -> String {
+    [
+        ("fee", budget.max_fee, measured.fee),
+        ("ex_mem", budget.max_ex_mem, measured.ex_mem),
+        ("ex_steps", budget.max_ex_steps, measured.ex_steps),
+    ]
+    .into_iter()
+    .map(|(what, allowed, measured)| match allowed {
+        Some(allowed) => format!("{what} {measured}/{allowed}"),
+        None => format!("{what} {measured}"),
+    })
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+pub fn run(tii_file: &Path, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    let tii = crate::builder::load_tii(tii_file)?;
+
+    let mut templates: Vec<String> = tii
+        .get("transactions")
+        .and_then(|t| t.as_object())
+        .map(|t| t.keys().cloned().collect())
+        .unwrap_or_default();
+    templates.sort();
+
+    if let Some(unknown) = config.budgets.keys().find(|name| !templates.contains(name)) {
+        bail!(
+            help = format!("templates: {}", templates.join(", ")),
+            "`[budgets.{unknown}]` names no transaction template of the protocol"
+        );
+    }
+
+    let wallet = crate::wallet::setup(config, profile)?;
+
+    let mut identities: Vec<_> = wallet.addresses.keys().cloned().collect();
+    identities.sort();
+
+    let mut failures = vec![];
+
+    for template in &templates {
+        let Some(budget) = config.budgets.get(template) else {
+            println!("{template}: no budget");
+            continue;
+        };
+
+        let measured = parties::of_template(tii_file, &tii, template)
+            .and_then(|parties| placeholder_args(&tii, template, &parties, budget, &identities))
+            .and_then(|args| measure(config, profile, &wallet, tii_file, template, args));
+
+        let measured = match measured {
+            Ok(measured) => measured,
+            Err(err) => {
+                println!("{template}: not evaluated");
+                failures.push(format!("`{template}` couldn't be evaluated: {err}"));
+                continue;
+            }
+        };
+
+        let over = overruns(budget, &measured);
+
+        let verdict = match over.is_empty() {
+            true => "within budget",
+            false => "OVER budget",
+        };
+        println!("{template}: {verdict} ({})", describe(budget, &measured));
+
+        failures.extend(over.into_iter().map(|overrun| {
+            format!(
+                "`{template}` {}: measured {}, allowed {}",
+                overrun.what, overrun.measured, overrun.allowed
+            )
+        }));
+    }
+
+    if !failures.is_empty() {
+        bail!(
+            "{} budget check(s) failed:\n  {}",
+            failures.len(),
+            failures.join("\n  ")
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tii() -> serde_json::Value {
+        serde_json::json!({
+            "parties": { "Sender": {}, "Receiver": {} },
+            "transactions": {
+                "transfer": {
+                    "params": {
+                        "properties": {
+                            "quantity": { "type": "integer" }
+                        }
+                    }
+                },
+                "tag": {
+                    "params": {
+                        "properties": {
+                            "label": { "$ref": "https://tx3.land/specs/v1beta0/core#Bytes" }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn identities() -> Vec<String> {
+        vec!["alice".to_string(), "bob".to_string()]
+    }
+
+    fn parties() -> Vec<String> {
+        vec!["Receiver".to_string(), "Sender".to_string()]
+    }
+
+    #[test]
+    fn reports_each_limit_exceeded() {
+        let budget = BudgetConfig {
+            max_fee: Some(200_000),
+            max_ex_mem: Some(1_000),
+            max_ex_steps: None,
+            args: Default::default(),
+        };
+
+        let measured = Measured {
+            fee: 250_000,
+            ex_mem: 1_000,
+            ex_steps: 9_999_999,
+        };
+
+        assert_eq!(
+            overruns(&budget, &measured),
+            [Overrun {
+                what: "fee",
+                measured: 250_000,
+                allowed: 200_000,
+            }]
+        );
+        assert_eq!(
+            describe(&budget, &measured),
+            "fee 250000/200000, ex_mem 1000/1000, ex_steps 9999999"
+        );
+    }
+
+    #[test]
+    fn fills_parties_and_integers_with_placeholders() {
+        let budget: BudgetConfig =
+            toml::from_str("max_fee = 1\nargs = { sender = \"@bob\" }").unwrap();
+
+        let args =
+            placeholder_args(&tii(), "transfer", &parties(), &budget, &identities()).unwrap();
+
+        assert_eq!(args["sender"], "@bob");
+        assert_eq!(args["receiver"], "@alice");
+        assert_eq!(args["quantity"], PLACEHOLDER_INT);
+    }
+
+    #[test]
+    fn skips_identities_args_already_use() {
+        let budget: BudgetConfig = toml::from_str("args = { sender = \"@alice\" }").unwrap();
+
+        let args =
+            placeholder_args(&tii(), "transfer", &parties(), &budget, &identities()).unwrap();
+
+        assert_eq!(args["sender"], "@alice");
+        assert_eq!(args["receiver"], "@bob");
+    }
+
+    #[test]
+    fn fills_only_the_parties_of_the_template() {
+        let parties = vec!["Sender".to_string()];
+
+        let args = placeholder_args(
+            &tii(),
+            "transfer",
+            &parties,
+            &BudgetConfig::default(),
+            &identities()[..1],
+        )
+        .unwrap();
+
+        assert_eq!(args["sender"], "@alice");
+        assert!(!args.contains_key("receiver"));
+    }
+
+    #[test]
+    fn asks_for_args_it_cant_make_up() {
+        let err = placeholder_args(&tii(), "tag", &[], &BudgetConfig::default(), &identities())
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("`label` of `tag`"), "{err}");
+    }
+}
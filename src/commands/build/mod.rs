@@ -0,0 +1,43 @@
+use crate::{
+    builder,
+    config::{ProfileConfig, RootConfig},
+};
+use clap::Args as ClapArgs;
+
+mod budgets;
+mod watch;
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Rebuild whenever a .tx3 file in the project changes
+    #[arg(long)]
+    watch: bool,
+
+    /// With --watch, stop at the first failed build instead of waiting for
+    /// the next change
+    #[arg(long, requires = "watch")]
+    exit_on_error: bool,
+
+    /// Resolve every template with a `[budgets]` entry against the
+    /// profile's network and fail if its fee or execution units exceed it
+    #[arg(long, conflicts_with = "watch")]
+    check_budgets: bool,
+}
+
+/// `build` is strictly project-only: it produces the project's own TII and
+/// nothing else. External protocol interfaces are an orthogonal concern, not
+/// inputs to this build — they are materialized/verified lazily by the
+/// commands that actually consume them (`invoke`, `codegen`, `inspect tir`).
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    if args.watch {
+        return watch::run(config, args.exit_on_error);
+    }
+
+    let tii_file = builder::build_tii(config)?;
+
+    if args.check_budgets {
+        budgets::run(&tii_file, config, profile)?;
+    }
+
+    Ok(())
+}
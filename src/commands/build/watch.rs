@@ -0,0 +1,173 @@
+//! `trix build --watch`: rebuild whenever a `.tx3` file under the project
+//! root changes. Bursts of events (editors often write a file several
+//! times) are folded into one rebuild once the tree has been quiet for
+//! [`DEBOUNCE`]. Ctrl-C goes through the shared interrupt handler, which
+//! kills a build still running before trix exits.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, channel};
+use std::time::Duration;
+
+use miette::{Context as _, IntoDiagnostic as _};
+use notify::{Event, EventKind, RecursiveMode, Watcher as _};
+
+use crate::config::RootConfig;
+
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Tool-owned state lives here; the TII a build writes must not trigger
+/// the next build.
+const TARGET_DIR: &str = ".tx3";
+
+/// Whether `event` touches a tx3 source, ignoring reads and anything under
+/// the target dir.
+fn is_source_change(root: &Path, event: &Event) -> bool {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return false;
+    }
+
+    event.paths.iter().any(|path| {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+
+        path.extension().is_some_and(|ext| ext == "tx3") && !relative.starts_with(TARGET_DIR)
+    })
+}
+
+/// Block until a source change arrives, then keep absorbing events until
+/// none has come in for `quiet`. Returns `None` once the watcher is gone.
+fn next_batch(
+    root: &Path,
+    events: &Receiver<notify::Result<Event>>,
+    quiet: Duration,
+) -> Option<Vec<PathBuf>> {
+    let mut changed = vec![];
+
+    loop {
+        let event = events.recv().ok()?;
+
+        if let Ok(event) = event
+            && is_source_change(root, &event)
+        {
+            changed.extend(event.paths);
+            break;
+        }
+    }
+
+    loop {
+        match events.recv_timeout(quiet) {
+            Ok(Ok(event)) if is_source_change(root, &event) => changed.extend(event.paths),
+            Ok(_) => {}
+            Err(RecvTimeoutError::Timeout) => break,
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    changed.sort();
+    changed.dedup();
+
+    Some(changed)
+}
+
+fn build_once(config: &RootConfig, exit_on_error: bool) -> miette::Result<()> {
+    match crate::builder::build_tii(config) {
+        Ok(path) => {
+            eprintln!("build ok: {}", path.display());
+            Ok(())
+        }
+        Err(err) if exit_on_error => Err(err),
+        Err(err) => {
+            eprintln!("build failed:\n{err:?}");
+            Ok(())
+        }
+    }
+}
+
+pub fn run(config: &RootConfig, exit_on_error: bool) -> miette::Result<()> {
+    let root = crate::dirs::protocol_root()?;
+
+    let (tx, events) = channel();
+
+    let mut watcher = notify::recommended_watcher(tx)
+        .into_diagnostic()
+        .context("starting file watcher")?;
+
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .into_diagnostic()
+        .with_context(|| format!("watching {}", root.display()))?;
+
+    build_once(config, exit_on_error)?;
+
+    eprintln!(
+        "watching {} for .tx3 changes (Ctrl-C to stop)",
+        root.display()
+    );
+
+    while let Some(changed) = next_batch(&root, &events, DEBOUNCE) {
+        for path in &changed {
+            let relative = path.strip_prefix(&root).unwrap_or(path);
+            eprintln!("changed: {}", relative.display());
+        }
+
+        build_once(config, exit_on_error)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, ModifyKind};
+
+    fn event(kind: EventKind, path: &str) -> Event {
+        Event::new(kind).add_path(PathBuf::from(path))
+    }
+
+    #[test]
+    fn only_tx3_sources_outside_target_dir_count() {
+        let root = Path::new("/proj");
+        let modify = EventKind::Modify(ModifyKind::Any);
+
+        assert!(is_source_change(root, &event(modify, "/proj/main.tx3")));
+        assert!(is_source_change(
+            root,
+            &event(modify, "/proj/lib/types.tx3")
+        ));
+        assert!(!is_source_change(root, &event(modify, "/proj/trix.toml")));
+        assert!(!is_source_change(
+            root,
+            &event(modify, "/proj/.tx3/tii/main.tx3")
+        ));
+        assert!(!is_source_change(
+            root,
+            &event(EventKind::Access(AccessKind::Any), "/proj/main.tx3")
+        ));
+    }
+
+    #[test]
+    fn burst_of_events_becomes_one_batch() {
+        let root = Path::new("/proj");
+        let (tx, rx) = channel();
+
+        let create = EventKind::Create(CreateKind::File);
+        let modify = EventKind::Modify(ModifyKind::Any);
+
+        tx.send(Ok(event(modify, "/proj/trix.toml"))).unwrap();
+        tx.send(Ok(event(create, "/proj/main.tx3"))).unwrap();
+        tx.send(Ok(event(modify, "/proj/main.tx3"))).unwrap();
+        tx.send(Ok(event(modify, "/proj/lib.tx3"))).unwrap();
+
+        let batch = next_batch(root, &rx, Duration::from_millis(20)).unwrap();
+        assert_eq!(
+            batch,
+            vec![
+                PathBuf::from("/proj/lib.tx3"),
+                PathBuf::from("/proj/main.tx3")
+            ]
+        );
+
+        drop(tx);
+        assert!(next_batch(root, &rx, Duration::from_millis(20)).is_none());
+    }
+}
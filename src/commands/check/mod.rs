@@ -0,0 +1,101 @@
+use crate::config::{ProfileConfig, RootConfig};
+use crate::spawn::tx3c;
+use clap::Args as ClapArgs;
+use miette::Diagnostic;
+use thiserror::Error;
+
+pub mod secrets;
+mod unused;
+
+/// A warning from one of the config analyses. It is reported, but the
+/// check still passes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// Where the entry lives, e.g. `profiles.preview` in `trix.toml`.
+    pub location: String,
+    pub message: String,
+    pub help: String,
+}
+
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "warning: {}: {}\n  help: {}",
+            self.location, self.message, self.help
+        )
+    }
+}
+
+/// A single analyzer diagnostic, reconstructed from `tx3c`'s JSON contract.
+/// `trix` owns the rendering (message + diagnostic code), so the human output
+/// is unchanged even though the analysis now runs out-of-process.
+#[derive(Debug, Error)]
+#[error("{message}")]
+struct Diag {
+    message: String,
+    code: Option<String>,
+}
+
+impl Diagnostic for Diag {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        self.code
+            .as_ref()
+            .map(|c| Box::new(c.clone()) as Box<dyn std::fmt::Display>)
+    }
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("check failed")]
+struct Error {
+    #[related]
+    results: Vec<Diag>,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Look for API keys and other secrets written literally in trix.toml
+    /// and tracked env files
+    #[arg(long, value_enum, default_value_t = secrets::Scan::On)]
+    secrets_scan: secrets::Scan,
+
+    /// Also run the checks `trix publish` makes before uploading: scope,
+    /// repository, and the main, readme and logo files
+    #[arg(long)]
+    publish: bool,
+}
+
+pub fn run(args: Args, config: &RootConfig, _profile: &ProfileConfig) -> miette::Result<()> {
+    let root = std::path::Path::new(".");
+
+    for finding in unused::analyze(config, root) {
+        eprintln!("{finding}");
+    }
+
+    if args.secrets_scan == secrets::Scan::On {
+        for finding in secrets::scan(config, root) {
+            eprintln!("{finding}");
+        }
+    }
+
+    let diagnostics = tx3c::check(&crate::builder::entry_source(config)?)?;
+
+    if !diagnostics.is_empty() {
+        let results = diagnostics
+            .into_iter()
+            .map(|d| Diag {
+                message: d.message,
+                code: d.code,
+            })
+            .collect();
+        return Err(Error { results }.into());
+    }
+
+    if args.publish {
+        crate::commands::publish::validate_package(config)?;
+    }
+
+    println!("check passed, no errors found");
+
+    Ok(())
+}
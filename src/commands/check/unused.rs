@@ -0,0 +1,322 @@
+//! Dead-config analysis for `trix check`: entries in `trix.toml` that
+//! nothing else in the project refers to. Findings are warnings only; a
+//! project with unused config still passes the check.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::Finding;
+use crate::config::RootConfig;
+
+const DEVNET_FILE: &str = "devnet.toml";
+const TESTS_DIR: &str = "tests";
+
+/// Every string value in a TOML document, at any depth.
+fn collect_strings(value: &toml::Value, out: &mut HashSet<String>) {
+    match value {
+        toml::Value::String(s) => {
+            out.insert(s.clone());
+        }
+        toml::Value::Array(items) => items.iter().for_each(|v| collect_strings(v, out)),
+        toml::Value::Table(table) => table.values().for_each(|v| collect_strings(v, out)),
+        _ => {}
+    }
+}
+
+fn read_strings(path: &Path, out: &mut HashSet<String>) {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return;
+    };
+
+    if let Ok(value) = toml::from_str::<toml::Value>(&content) {
+        collect_strings(&value, out);
+    }
+}
+
+fn test_files(root: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(root.join(TESTS_DIR))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect()
+}
+
+/// Strings that may name an identity. Test files name wallets bare
+/// (`signers = ["alice"]`) as well as with `@`; devnet.toml and presets
+/// only through `@alice`.
+struct References {
+    bare: HashSet<String>,
+    prefixed: HashSet<String>,
+}
+
+impl References {
+    fn gather(config: &RootConfig, root: &Path) -> Self {
+        let mut bare = HashSet::new();
+        let mut prefixed = HashSet::new();
+
+        read_strings(&root.join(DEVNET_FILE), &mut prefixed);
+
+        for test in test_files(root) {
+            read_strings(&test, &mut bare);
+        }
+
+        for preset in config.invoke.iter().flat_map(|i| i.presets.values()) {
+            for value in preset.args.values() {
+                if let Some(s) = value.as_str() {
+                    prefixed.insert(s.to_string());
+                }
+            }
+        }
+
+        prefixed.extend(bare.iter().cloned());
+
+        Self { bare, prefixed }
+    }
+
+    fn mentions(&self, identity: &str) -> bool {
+        self.bare.contains(identity) || self.prefixed.contains(&format!("@{identity}"))
+    }
+}
+
+fn unused_identities(config: &RootConfig, refs: &References) -> Vec<Finding> {
+    let mut out = vec![];
+
+    for (profile, profile_config) in config.profiles.iter() {
+        for identity in profile_config.identities.keys() {
+            if refs.mentions(identity) {
+                continue;
+            }
+
+            out.push(Finding {
+                location: format!("profiles.{profile}.identities.{identity}"),
+                message: format!(
+                    "identity `{identity}` is not used by devnet.toml, any test or any invoke preset"
+                ),
+                help: "remove the identity, or reference it as `@name`".into(),
+            });
+        }
+    }
+
+    out
+}
+
+fn unused_networks(config: &RootConfig) -> Vec<Finding> {
+    let used: HashSet<String> = config
+        .available_profiles()
+        .iter()
+        .filter_map(|name| config.resolve_profile(name).ok())
+        .map(|profile| profile.network)
+        .collect();
+
+    config
+        .networks
+        .keys()
+        .filter(|network| !used.contains(*network))
+        .map(|network| Finding {
+            location: format!("networks.{network}"),
+            message: format!("no profile uses network `{network}`"),
+            help: format!("point a profile at it with `network = \"{network}\"`, or remove it"),
+        })
+        .collect()
+}
+
+/// Whether git ignores `path`. Outside a repository (or without git)
+/// nothing counts as ignored.
+fn git_ignores(root: &Path, path: &Path) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["check-ignore", "-q"])
+        .arg(path)
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+fn unbuilt_codegen(config: &RootConfig, root: &Path) -> Vec<Finding> {
+    let mut out = vec![];
+
+    for (index, codegen) in config.codegen.iter().enumerate() {
+        let Ok(dir) = codegen.output_dir() else {
+            continue;
+        };
+
+        let full = root.join(&dir);
+
+        if full.exists() || !git_ignores(root, &dir) {
+            continue;
+        }
+
+        out.push(Finding {
+            location: format!("codegen[{index}]"),
+            message: format!(
+                "codegen job `{}` has never been built and `{}` is ignored by git",
+                codegen.job_id(),
+                dir.display()
+            ),
+            help: "run `trix codegen`, or remove the entry if the bindings are no longer needed".into(),
+        });
+    }
+
+    out
+}
+
+fn profiles_without_env(config: &RootConfig, root: &Path) -> Vec<Finding> {
+    config
+        .profiles
+        .iter()
+        .filter(|(_, profile)| !root.join(profile.env_file_path()).is_file())
+        .map(|(name, profile)| Finding {
+            location: format!("profiles.{name}"),
+            message: format!(
+                "profile `{name}` has no env file at `{}`",
+                profile.env_file_path().display()
+            ),
+            help: "create the env file, set `env_file`, or remove the profile".into(),
+        })
+        .collect()
+}
+
+/// Run every analysis against the project rooted at `root`.
+pub fn analyze(config: &RootConfig, root: &Path) -> Vec<Finding> {
+    let refs = References::gather(config, root);
+
+    let mut findings = vec![];
+    findings.extend(unused_identities(config, &refs));
+    findings.extend(unused_networks(config));
+    findings.extend(unbuilt_codegen(config, root));
+    findings.extend(profiles_without_env(config, root));
+
+    findings.sort_by(|a, b| a.location.cmp(&b.location));
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        [protocol]
+        name = "demo"
+        version = "0.0.0"
+        main = "main.tx3"
+
+        [ledger]
+        family = "cardano"
+
+        [[codegen]]
+        plugin = "ts-client"
+        output_dir = "gen/ts"
+
+        [networks.staging]
+        is_testnet = true
+        trp = { url = "http://localhost:8164", headers = {} }
+        u5c = { url = "http://localhost:50051" }
+
+        [profiles.local]
+        network = "cardano-local"
+
+        [profiles.local.identities.alice]
+        type = "RandomKey"
+        random_key = true
+
+        [profiles.local.identities.bob]
+        type = "RandomKey"
+        random_key = true
+
+        [profiles.local.identities.carol]
+        type = "RandomKey"
+        random_key = true
+
+        [profiles.preview]
+        network = "cardano-preview"
+    "#;
+
+    fn fixture() -> (tempfile::TempDir, RootConfig) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        std::fs::write(root.join(".env.local"), "").unwrap();
+        std::fs::write(
+            root.join(DEVNET_FILE),
+            "[[utxos]]\naddress = \"@alice\"\nvalue = 1\n",
+        )
+        .unwrap();
+        std::fs::create_dir(root.join(TESTS_DIR)).unwrap();
+        std::fs::write(
+            root.join(TESTS_DIR).join("basic.toml"),
+            "[[transactions]]\ndescription = \"x\"\ntemplate = \"t\"\nsigners = [\"bob\"]\nargs = {}\n",
+        )
+        .unwrap();
+
+        let _ = Command::new("git").arg("init").arg("-q").arg(root).status();
+        std::fs::write(root.join(".gitignore"), "gen/\n").unwrap();
+
+        (dir, toml::from_str(CONFIG).unwrap())
+    }
+
+    fn locations(findings: &[Finding]) -> Vec<&str> {
+        findings.iter().map(|f| f.location.as_str()).collect()
+    }
+
+    #[test]
+    fn flags_identity_referenced_nowhere() {
+        let (dir, config) = fixture();
+        let findings = analyze(&config, dir.path());
+        let found = locations(&findings);
+
+        assert!(found.contains(&"profiles.local.identities.carol"));
+        assert!(!found.contains(&"profiles.local.identities.alice"));
+        assert!(!found.contains(&"profiles.local.identities.bob"));
+    }
+
+    #[test]
+    fn preset_reference_counts_as_use() {
+        let (dir, mut config) = fixture();
+        config.invoke = Some(
+            toml::from_str(
+                "[presets.tip]\ntemplate = \"transfer\"\nargs = { receiver = \"@carol\" }\n",
+            )
+            .unwrap(),
+        );
+
+        let findings = analyze(&config, dir.path());
+
+        assert!(!locations(&findings).contains(&"profiles.local.identities.carol"));
+    }
+
+    #[test]
+    fn flags_network_without_profile() {
+        let (dir, config) = fixture();
+        let findings = analyze(&config, dir.path());
+
+        assert!(locations(&findings).contains(&"networks.staging"));
+    }
+
+    #[test]
+    fn flags_profile_without_env_file() {
+        let (dir, config) = fixture();
+        let findings = analyze(&config, dir.path());
+        let found = locations(&findings);
+
+        assert!(found.contains(&"profiles.preview"));
+        assert!(!found.contains(&"profiles.local"));
+    }
+
+    #[test]
+    fn flags_ignored_unbuilt_codegen_until_built() {
+        let (dir, config) = fixture();
+
+        if !git_ignores(dir.path(), Path::new("gen/ts")) {
+            // No git on this machine; the category can't be exercised.
+            return;
+        }
+
+        assert!(locations(&analyze(&config, dir.path())).contains(&"codegen[0]"));
+
+        std::fs::create_dir_all(dir.path().join("gen/ts")).unwrap();
+        assert!(!locations(&analyze(&config, dir.path())).contains(&"codegen[0]"));
+    }
+}
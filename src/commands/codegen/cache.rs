@@ -0,0 +1,189 @@
+//! On-disk cache of downloaded template archives, under
+//! `<cache dir>/bindgen/<sha256(repo@ref)>.zip`. The ETag GitHub sent with
+//! the archive is stored next to it; later runs revalidate with
+//! `If-None-Match` and reuse the archive on a `304 Not Modified`.
+
+use std::path::{Path, PathBuf};
+
+use cryptoxide::{digest::Digest as _, sha2::Sha256};
+use miette::IntoDiagnostic as _;
+use reqwest::{StatusCode, header};
+
+use crate::net::HttpClient;
+
+/// Cached archive for one `repo` at one `ref`. A different ref hashes to a
+/// different entry, so changing it never picks up a stale archive.
+pub struct Entry {
+    zip: PathBuf,
+    etag: PathBuf,
+}
+
+impl Entry {
+    pub fn new(dir: &Path, repo: &str, r#ref: &str) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.input_str(&format!("{repo}@{ref}"));
+        let key = hasher.result_str();
+
+        Self {
+            zip: dir.join(format!("{key}.zip")),
+            etag: dir.join(format!("{key}.etag")),
+        }
+    }
+
+    pub fn for_repo(repo: &str, r#ref: &str) -> miette::Result<Self> {
+        let dir = crate::dirs::cache_dir()?.join("bindgen");
+        std::fs::create_dir_all(&dir).into_diagnostic()?;
+        Ok(Self::new(&dir, repo, r#ref))
+    }
+
+    fn cached_etag(&self) -> Option<String> {
+        if !self.zip.is_file() {
+            return None;
+        }
+
+        std::fs::read_to_string(&self.etag)
+            .ok()
+            .map(|etag| etag.trim().to_string())
+            .filter(|etag| !etag.is_empty())
+    }
+
+    fn store(&self, bytes: &[u8], etag: Option<&str>) -> miette::Result<()> {
+        crate::fsutil::write_atomic(&self.zip, bytes)?;
+
+        match etag {
+            Some(etag) => crate::fsutil::write_atomic(&self.etag, etag)?,
+            None => {
+                let _ = std::fs::remove_file(&self.etag);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Archive bytes for `url`. With an `entry`, a cached archive whose ETag the
+/// server confirms is reused instead of downloaded, and a fresh download
+/// replaces it. Without one, the archive is always downloaded and nothing
+/// is cached.
+pub async fn fetch(
+    client: &HttpClient,
+    url: &str,
+    entry: Option<&Entry>,
+) -> miette::Result<Vec<u8>> {
+    let mut request = client.get(url);
+
+    let cached = entry.and_then(Entry::cached_etag);
+    if let Some(etag) = &cached {
+        request = request.header(header::IF_NONE_MATCH, etag);
+    }
+
+    let response = request.send().await.into_diagnostic()?;
+
+    if response.status() == StatusCode::NOT_MODIFIED
+        && let Some(entry) = entry
+    {
+        eprintln!("Using cached template archive (unchanged upstream)");
+        return std::fs::read(&entry.zip).into_diagnostic();
+    }
+
+    if !response.status().is_success() {
+        return Err(miette::miette!(
+            "Failed to download GitHub repository: HTTP {}",
+            response.status()
+        ));
+    }
+
+    let etag = response
+        .headers()
+        .get(header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let bytes = response.bytes().await.into_diagnostic()?.to_vec();
+
+    if let Some(entry) = entry {
+        entry.store(&bytes, etag.as_deref())?;
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn key_changes_with_ref() {
+        let dir = Path::new("/cache");
+
+        let main = Entry::new(dir, "acme/sdk", "main");
+        let tag = Entry::new(dir, "acme/sdk", "v1.0.0");
+
+        assert_ne!(main.zip, tag.zip);
+        assert_eq!(main.zip, Entry::new(dir, "acme/sdk", "main").zip);
+    }
+
+    #[test]
+    fn revalidates_with_etag_and_reuses_archive() {
+        let mut server = mockito::Server::new();
+        let cache = tempfile::tempdir().unwrap();
+        let entry = Entry::new(cache.path(), "acme/sdk", "main");
+        let url = format!("{}/acme/sdk/archive/main.zip", server.url());
+        let client = HttpClient::default();
+
+        let first = server
+            .mock("GET", "/acme/sdk/archive/main.zip")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_header("etag", "\"v1\"")
+            .with_body("zip-v1")
+            .expect(1)
+            .create();
+
+        let bytes = runtime()
+            .block_on(fetch(&client, &url, Some(&entry)))
+            .unwrap();
+        assert_eq!(bytes, b"zip-v1");
+        first.assert();
+
+        let revalidated = server
+            .mock("GET", "/acme/sdk/archive/main.zip")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .expect(1)
+            .create();
+
+        let bytes = runtime()
+            .block_on(fetch(&client, &url, Some(&entry)))
+            .unwrap();
+        assert_eq!(bytes, b"zip-v1");
+        revalidated.assert();
+    }
+
+    #[test]
+    fn no_cache_always_downloads() {
+        let mut server = mockito::Server::new();
+        let url = format!("{}/archive.zip", server.url());
+
+        let mock = server
+            .mock("GET", "/archive.zip")
+            .match_header("if-none-match", mockito::Matcher::Missing)
+            .with_body("zip")
+            .expect(2)
+            .create();
+
+        let client = HttpClient::default();
+        for _ in 0..2 {
+            let bytes = runtime().block_on(fetch(&client, &url, None)).unwrap();
+            assert_eq!(bytes, b"zip");
+        }
+
+        mock.assert();
+    }
+}
@@ -0,0 +1,796 @@
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::config::{
+    CodegenConfig, CodegenPlugin, CodegenPluginConfig, KNOWN_CODEGEN_PLUGINS, KnownCodegenPlugin,
+    ProfileConfig, RootConfig,
+};
+use clap::{Args as ClapArgs, Subcommand};
+use miette::IntoDiagnostic;
+use tempfile::TempDir;
+use zip::ZipArchive;
+
+mod cache;
+mod progress;
+mod summary;
+mod vendor;
+mod verify;
+
+use crate::generated::{Guard, MANIFEST_FILE, OverwritePolicy};
+use crate::term::OutputFormat;
+use progress::{ConsoleReporter, JsonReporter, Reporter};
+use summary::{JobView, SummaryView};
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Check that the bindings on disk match a fresh generation, without
+    /// writing anything. Exits non-zero listing stale, missing and
+    /// orphaned files.
+    Verify,
+    /// Download each job's templates into `vendor/trix-templates/<job_id>/`
+    /// and mark the job `vendored`, so later runs work offline.
+    Vendor(vendor::Args),
+    /// Compare the commit of each vendored job with its upstream ref.
+    Outdated,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct ListPluginsArgs {
+    /// Print the built-in plugins with their template repo and pinned
+    /// ref, then exit. Works outside of a project.
+    #[arg(long)]
+    pub list_plugins: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub list: ListPluginsArgs,
+
+    /// Same as `trix codegen verify`
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Codegen plugin to use, e.g. `ts-client`, `rust-client`,
+    /// `python-client`, `go-client`. If no `[[codegen]]` entry exists for
+    /// this plugin yet, one is appended to `trix.toml` before generation
+    /// runs. With this flag, `trix codegen` is the only path that needs
+    /// to know plugin names; hand-editing `trix.toml` stays supported for
+    /// custom plugins and bespoke `output_dir`s.
+    #[arg(long, value_name = "NAME")]
+    pub plugin: Option<String>,
+
+    /// Generate without persisting a newly-seeded `[[codegen]]` entry
+    /// back to `trix.toml`. Intended for CI / one-shot scripts that emit
+    /// bindings without mutating the project file.
+    #[arg(long)]
+    pub no_save: bool,
+
+    /// Stop at the first failing `[[codegen]]` job instead of running the
+    /// rest. Either way the exit code is non-zero if any job failed.
+    #[arg(long)]
+    pub fail_fast: bool,
+
+    /// Always download template archives, bypassing the archive cache
+    /// under the user cache dir.
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// What to do with generated files that were edited by hand since the
+    /// last run.
+    #[arg(long, value_enum, default_value_t = OverwritePolicy::Ask)]
+    pub overwrite_policy: OverwritePolicy,
+
+    /// Replace existing files in output dirs that trix never generated.
+    #[arg(long)]
+    pub force: bool,
+
+    /// Format of the run summary. `json` prints it to stdout for CI
+    /// annotation; progress still goes to stderr.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    pub output: OutputFormat,
+
+    /// Format of the progress report. `json` streams one JSON event per
+    /// line to stdout (`start`, `file_written`, `done`) in place of the
+    /// progress lines and the human summary.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    pub progress: OutputFormat,
+}
+
+impl Args {
+    /// The listing to print, if `--list-plugins` was given. Dispatched
+    /// before the project config is loaded.
+    pub fn list_plugins(&self) -> Option<&ListPluginsArgs> {
+        self.list.list_plugins.then_some(&self.list)
+    }
+}
+
+pub fn list_plugins(_args: &ListPluginsArgs) -> miette::Result<()> {
+    let rows: Vec<_> = KNOWN_CODEGEN_PLUGINS
+        .iter()
+        .map(|plugin| {
+            let config = CodegenPluginConfig::from(*plugin);
+            let pinned = config.r#ref.unwrap_or_else(|| "-".to_string());
+            (plugin.to_string(), config.repo, pinned)
+        })
+        .collect();
+
+    let width = |column: fn(&(String, String, String)) -> &String| {
+        rows.iter().map(|row| column(row).len()).fold(4, usize::max)
+    };
+    let name_width = width(|(name, ..)| name);
+    let repo_width = width(|(_, repo, _)| repo);
+
+    println!("{:<name_width$}  {:<repo_width$}  REF", "NAME", "REPO");
+
+    for (name, repo, pinned) in rows {
+        println!("{name:<name_width$}  {repo:<repo_width$}  {pinned}");
+    }
+
+    Ok(())
+}
+
+async fn extract_github_templates(
+    github_url: &str,
+    temp_dir: &TempDir,
+    path: &str,
+    use_cache: bool,
+) -> miette::Result<PathBuf> {
+    let local_root = PathBuf::from(github_url);
+    if local_root.is_dir() {
+        let template_root = local_root.join(path);
+        if !template_root.is_dir() {
+            return Err(miette::miette!(
+                "Template path '{}' does not exist",
+                template_root.display()
+            ));
+        }
+        return Ok(template_root);
+    }
+
+    let (owner, repo, branch) = github_parts(github_url)?;
+
+    let zip_url = format!(
+        "https://github.com/{}/{}/archive/{}.zip",
+        owner, repo, branch
+    );
+
+    eprintln!(
+        "Reading template from https://github.com/{}/{} (ref: {})",
+        owner, repo, branch
+    );
+
+    let entry = if use_cache {
+        Some(cache::Entry::for_repo(&format!("{owner}/{repo}"), branch)?)
+    } else {
+        None
+    };
+
+    let client = crate::net::build_http_client()?;
+    let content = cache::fetch(&client, &zip_url, entry.as_ref()).await?;
+
+    let template_root = temp_dir.path().join("templates");
+    extract_archive(&content, path, &template_root)?;
+
+    Ok(template_root)
+}
+
+/// Split `owner/repo` or `owner/repo/ref`; the ref defaults to `main`.
+fn github_parts(github_url: &str) -> miette::Result<(&str, &str, &str)> {
+    let parts: Vec<&str> = github_url.split('/').collect();
+    if parts.len() < 2 {
+        return Err(miette::miette!(
+            "Invalid GitHub URL format. Use 'owner/repo' or 'owner/repo/branch'"
+        ));
+    }
+
+    let branch = if parts.len() > 2 { parts[2] } else { "main" };
+
+    Ok((parts[0], parts[1], branch))
+}
+
+/// Unpack the `path` directory of a GitHub archive into `template_root`,
+/// leaving out `trix-bindgen.toml`.
+fn extract_archive(content: &[u8], path: &str, template_root: &Path) -> miette::Result<()> {
+    let mut archive = ZipArchive::new(std::io::Cursor::new(content)).into_diagnostic()?;
+
+    let mut bindgen_path = PathBuf::new();
+    let root_dir_name = archive.name_for_index(0).unwrap_or("");
+    bindgen_path.push(root_dir_name);
+    bindgen_path.push(path);
+    bindgen_path.push("");
+
+    let bindgen_path_string = bindgen_path.to_string_lossy().to_string();
+    let archive_bindgen_index = archive.index_for_name(&bindgen_path_string).unwrap_or(0);
+
+    std::fs::create_dir_all(template_root).into_diagnostic()?;
+
+    for i in archive_bindgen_index..archive.len() {
+        let mut file = archive.by_index(i).into_diagnostic()?;
+        let name = file.name().to_owned();
+
+        if !name.starts_with(&bindgen_path_string) {
+            break;
+        }
+
+        if file.is_dir() || name.ends_with("trix-bindgen.toml") {
+            continue;
+        }
+
+        let relative = name.strip_prefix(&bindgen_path_string).unwrap_or(&name);
+        let dest_path = template_root.join(relative);
+        if let Some(parent) = dest_path.parent() {
+            std::fs::create_dir_all(parent).into_diagnostic()?;
+        }
+
+        let mut out_file = std::fs::File::create(&dest_path).into_diagnostic()?;
+        std::io::copy(&mut file, &mut out_file).into_diagnostic()?;
+    }
+
+    Ok(())
+}
+
+/// Where a `[[codegen]]` job's templates come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TemplateSource {
+    /// `local_path`, resolved against the project root and joined with
+    /// `path`.
+    Local(PathBuf),
+    /// `repo` with its ref, as `owner/repo/ref`, or a `repo` that already
+    /// names a directory on disk.
+    Github(String),
+    /// The copy `trix codegen vendor` stored under the project, for an
+    /// entry marked `vendored = true`.
+    Vendored(PathBuf),
+}
+
+impl TemplateSource {
+    /// Source for a `[[codegen]]` entry: its vendored copy when it's marked
+    /// `vendored`, which never touches the network, otherwise its plugin's.
+    pub(crate) fn for_job(
+        codegen: &CodegenConfig,
+        plugin: &CodegenPluginConfig,
+        project_root: &Path,
+    ) -> miette::Result<Self> {
+        if !codegen.vendored {
+            return Self::for_plugin(plugin, project_root);
+        }
+
+        let job_id = codegen.job_id();
+        let dir = vendor::templates_dir(project_root, &job_id);
+
+        if !dir.is_dir() {
+            return Err(miette::miette!(
+                help = format!("run `trix codegen vendor --job {job_id}` on a connected machine"),
+                "codegen job `{job_id}` is vendored, but {} does not exist",
+                dir.display()
+            ));
+        }
+
+        Ok(TemplateSource::Vendored(dir))
+    }
+
+    pub(crate) fn for_plugin(
+        plugin: &CodegenPluginConfig,
+        project_root: &Path,
+    ) -> miette::Result<Self> {
+        match (&plugin.local_path, plugin.repo.is_empty()) {
+            (Some(_), false) => Err(miette::miette!(
+                help = "keep `repo` to download templates, or `local_path` to read them from disk",
+                "codegen plugin sets both `repo` and `local_path`"
+            )),
+            (None, true) => Err(miette::miette!(
+                help = "set `repo = \"owner/repo\"` or `local_path = \"./templates/my-lang\"`",
+                "codegen plugin sets neither `repo` nor `local_path`"
+            )),
+            (Some(local), true) => {
+                let dir = project_root.join(local).join(&plugin.path);
+                if !dir.is_dir() {
+                    return Err(miette::miette!(
+                        "Template path '{}' does not exist",
+                        dir.display()
+                    ));
+                }
+                Ok(TemplateSource::Local(dir))
+            }
+            (None, false) if PathBuf::from(&plugin.repo).is_dir() => {
+                Ok(TemplateSource::Github(plugin.repo.clone()))
+            }
+            (None, false) => Ok(TemplateSource::Github(format!(
+                "{}/{}",
+                &plugin.repo,
+                plugin.r#ref.as_deref().unwrap_or("main")
+            ))),
+        }
+    }
+
+    fn describe(&self, plugin: &CodegenPluginConfig) -> String {
+        match self {
+            TemplateSource::Local(dir) => dir.display().to_string(),
+            TemplateSource::Github(url) => format!("{} ({})", url, plugin.path),
+            TemplateSource::Vendored(dir) => format!("{} (vendored)", dir.display()),
+        }
+    }
+}
+
+/// Output-subdir names, in generation order: the project (if it has a `tx3`
+/// source on disk) first, then each interface alias. The name doubles as
+/// the per-protocol output subdir — the layout is unconditional, so the
+/// path a binding lands at never depends on interface count.
+fn codegen_targets(project_name: Option<&str>, dep_aliases: &[String]) -> Vec<String> {
+    let mut out = Vec::with_capacity(usize::from(project_name.is_some()) + dep_aliases.len());
+    if let Some(name) = project_name {
+        out.push(name.to_string());
+    }
+    out.extend(dep_aliases.iter().cloned());
+    out
+}
+
+/// Resolves each codegen target to `(subdir_name, tii_path)`. The project's
+/// TII is built from source; each interface's TII is the cached, pre-built
+/// published one (not recompiled), consistent with `trix build`.
+///
+/// Consumer projects (those bootstrapped by `trix use` with no `main.tx3`)
+/// don't have a project of their own to generate for — only interfaces. We
+/// detect that by looking for `protocol.main` on disk relative to the
+/// project root; if it's missing, the project is silently skipped.
+fn collect_codegen_targets(
+    config: &RootConfig,
+    project_root: &Path,
+) -> miette::Result<Vec<(String, PathBuf)>> {
+    let dep_aliases: Vec<String> = config
+        .interfaces
+        .values()
+        .map(|e| e.alias.clone())
+        .collect();
+
+    let project_source = project_root.join(&config.protocol.main);
+    let project_name = project_source
+        .is_file()
+        .then_some(config.protocol.name.as_str());
+
+    let order = codegen_targets(project_name, &dep_aliases);
+    if order.is_empty() {
+        return Err(miette::miette!(
+            "nothing to generate: no `{}` found and no interfaces declared in trix.toml",
+            config.protocol.main.display()
+        ));
+    }
+
+    let mut targets = Vec::with_capacity(order.len());
+    for name in order {
+        // `validate` guarantees no interface alias equals the project
+        // name, so name == protocol.name ⇒ the project.
+        let tii = if name == config.protocol.name {
+            crate::builder::build_tii(config)?
+        } else {
+            let entry = config
+                .interfaces
+                .values()
+                .find(|e| e.alias == name)
+                .expect("alias originates from config.interfaces");
+            crate::interfaces::cache_paths(entry)?.tii
+        };
+        targets.push((name, tii));
+    }
+
+    Ok(targets)
+}
+
+/// Resolve which plugin (if any) the user requested for this invocation,
+/// either through `--plugin <name>` or — when no `[[codegen]]` is configured
+/// and stdin is a TTY — an interactive prompt. Returns `None` when the
+/// project already has at least one target and the user passed no flag
+/// (i.e. the existing non-interactive behavior).
+fn resolve_requested_plugin(
+    explicit: Option<&str>,
+    config: &RootConfig,
+) -> miette::Result<Option<KnownCodegenPlugin>> {
+    if let Some(name) = explicit {
+        let plugin: KnownCodegenPlugin = name.parse().map_err(|e: String| miette::miette!("{e}"))?;
+        return Ok(Some(plugin));
+    }
+
+    if !config.codegen.is_empty() {
+        return Ok(None);
+    }
+
+    if !std::io::stdin().is_terminal() {
+        return Err(miette::miette!(
+            "no [[codegen]] targets configured; pass --plugin <{}> to seed one",
+            KNOWN_CODEGEN_PLUGINS
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join("|")
+        ));
+    }
+
+    let choice =
+        crate::term::prompt::select("Generate bindings for:", KNOWN_CODEGEN_PLUGINS.to_vec())?;
+
+    Ok(Some(choice))
+}
+
+/// Seed-if-absent: if `config` has no `[[codegen]]` entry matching `plugin`,
+/// append a minimal one and persist (unless `no_save`). Returns the
+/// possibly-mutated config to use for the rest of the run. Comparison goes
+/// through the enum so the verbose `KnownOrCustom::Known` form in TOML still
+/// dedups against the short form.
+fn seed_plugin_if_absent(
+    mut config: RootConfig,
+    plugin: KnownCodegenPlugin,
+    config_path: &Path,
+    no_save: bool,
+) -> miette::Result<RootConfig> {
+    let already = config.codegen.iter().any(|c| match c.plugin {
+        CodegenPlugin::Known(known) => std::mem::discriminant(&known) == std::mem::discriminant(&plugin),
+        CodegenPlugin::Custom(_) => false,
+    });
+
+    if already {
+        return Ok(config);
+    }
+
+    config.codegen.push(CodegenConfig {
+        plugin: CodegenPlugin::Known(plugin),
+        job_id: None,
+        output_dir: None,
+        options: None,
+        verify_ignore: vec![],
+        vendored: false,
+    });
+
+    if !no_save {
+        config.save(&config_path.to_path_buf())?;
+        eprintln!("Added [[codegen]] plugin = \"{plugin}\" to trix.toml.");
+    }
+
+    Ok(config)
+}
+
+pub async fn run(
+    args: Args,
+    config: &RootConfig,
+    config_path: &Path,
+    _profile: &ProfileConfig,
+) -> miette::Result<()> {
+    if args.verify || matches!(args.command, Some(Command::Verify)) {
+        return verify::run(config, config_path, args.output, !args.no_cache).await;
+    }
+
+    match args.command {
+        Some(Command::Vendor(vendor)) => return vendor::run(vendor, config, config_path).await,
+        Some(Command::Outdated) => return vendor::outdated(config).await,
+        _ => {}
+    }
+
+    if args.progress == OutputFormat::Json && args.output == OutputFormat::Json {
+        miette::bail!(
+            help = "the `done` events carry each job's summary",
+            "--progress json and --output json both write to stdout; pick one"
+        );
+    }
+
+    let requested = resolve_requested_plugin(args.plugin.as_deref(), config)?;
+    let config = match requested {
+        Some(plugin) => seed_plugin_if_absent(config.clone(), plugin, config_path, args.no_save)?,
+        None => config.clone(),
+    };
+    let config = &config;
+
+    crate::interfaces::validate(config)?;
+    crate::interfaces::restore_all(config)?;
+
+    let project_root = config_path.parent().unwrap_or_else(|| Path::new("."));
+    let targets = collect_codegen_targets(config, project_root)?;
+
+    let write = WriteOptions {
+        use_cache: !args.no_cache,
+        policy: args.overwrite_policy,
+        force: args.force,
+    };
+
+    let mut reporter: Box<dyn Reporter> = match args.progress {
+        OutputFormat::Human => Box::new(ConsoleReporter),
+        OutputFormat::Json => Box::new(JsonReporter::new(std::io::stdout())),
+    };
+
+    let total = config.codegen.len();
+    let mut jobs = Vec::with_capacity(total);
+    let mut not_run = vec![];
+
+    for (i, codegen) in config.codegen.iter().enumerate() {
+        if args.fail_fast && jobs.iter().any(|j: &JobView| j.error.is_some()) {
+            not_run.push(codegen.job_id());
+            continue;
+        }
+
+        let plugin = CodegenPluginConfig::from(codegen.plugin.clone());
+        let source = TemplateSource::for_job(codegen, &plugin, project_root)?;
+
+        let base_output_dir = codegen.output_dir()?;
+
+        let mut job = JobView::new(
+            codegen.job_id(),
+            source.describe(&plugin),
+            base_output_dir.clone(),
+        );
+
+        reporter.start(i + 1, total, &job);
+
+        let started = Instant::now();
+        let result = run_job(
+            &source,
+            &plugin,
+            &base_output_dir,
+            &targets,
+            &mut job,
+            &write,
+            reporter.as_mut(),
+        )
+        .await;
+        job.finish(started.elapsed(), result);
+
+        reporter.done(&job);
+
+        jobs.push(job);
+    }
+
+    let view = SummaryView { jobs, not_run };
+
+    if args.progress == OutputFormat::Human {
+        summary::render(&view, args.output)?;
+    }
+
+    let failed = view.failed();
+    if failed > 0 {
+        return Err(miette::miette!(
+            "{} of {} codegen jobs failed",
+            failed,
+            total
+        ));
+    }
+
+    Ok(())
+}
+
+/// How a run treats the template cache and files already on disk.
+struct WriteOptions {
+    use_cache: bool,
+    policy: OverwritePolicy,
+    force: bool,
+}
+
+/// Directory holding a job's templates. A local directory is read in place;
+/// nothing is downloaded or copied. Downloads land in `temp`, and go
+/// through the archive cache unless `use_cache` is off.
+async fn templates_dir(
+    source: &TemplateSource,
+    plugin: &CodegenPluginConfig,
+    temp: &TempDir,
+    use_cache: bool,
+) -> miette::Result<PathBuf> {
+    match source {
+        TemplateSource::Local(dir) | TemplateSource::Vendored(dir) => Ok(dir.clone()),
+        TemplateSource::Github(url) => {
+            extract_github_templates(url, temp, &plugin.path, use_cache).await
+        }
+    }
+}
+
+/// One `[[codegen]]` entry: extract its templates once, then generate every
+/// target into `<output_dir>/<name>`. File counts are recorded on `job` as
+/// each file is written, so a failure still reports what was written.
+async fn run_job(
+    source: &TemplateSource,
+    plugin: &CodegenPluginConfig,
+    base_output_dir: &Path,
+    targets: &[(String, PathBuf)],
+    job: &mut JobView,
+    write: &WriteOptions,
+    reporter: &mut dyn Reporter,
+) -> miette::Result<()> {
+    std::fs::create_dir_all(base_output_dir).into_diagnostic()?;
+
+    // Extract templates once per [[codegen]] entry, reuse across protocols.
+    let template_temp = TempDir::new().into_diagnostic()?;
+    let templates_dir = templates_dir(source, plugin, &template_temp, write.use_cache).await?;
+
+    let mut guard = Guard::open(
+        base_output_dir,
+        base_output_dir.join(MANIFEST_FILE),
+        write.policy,
+        write.force,
+    )?;
+
+    let result = write_targets(&templates_dir, targets, &mut guard, job, reporter);
+
+    // Record what was written even when a later target failed, so those
+    // files aren't mistaken for user-owned on the next run.
+    guard.finish()?;
+
+    result
+}
+
+/// Render each target into scratch space, then hand every file to `guard`,
+/// so nothing on disk is replaced without going through it.
+fn write_targets(
+    templates_dir: &Path,
+    targets: &[(String, PathBuf)],
+    guard: &mut Guard,
+    job: &mut JobView,
+    reporter: &mut dyn Reporter,
+) -> miette::Result<()> {
+    let scratch = TempDir::new().into_diagnostic()?;
+
+    for (name, tii_path) in targets {
+        let rendered = scratch.path().join(name);
+        std::fs::create_dir_all(&rendered).into_diagnostic()?;
+        crate::spawn::tx3c::codegen(tii_path, templates_dir, &rendered)?;
+
+        store_rendered(&rendered, name, guard, job, reporter)?;
+    }
+
+    Ok(())
+}
+
+/// Move one target's rendered files into `<output_dir>/<name>` through
+/// `guard`, reporting each.
+fn store_rendered(
+    rendered: &Path,
+    name: &str,
+    guard: &mut Guard,
+    job: &mut JobView,
+    reporter: &mut dyn Reporter,
+) -> miette::Result<()> {
+    for relative in summary::snapshot(rendered)?.keys() {
+        let contents = std::fs::read(rendered.join(relative)).into_diagnostic()?;
+        let path = Path::new(name).join(relative);
+
+        let outcome = guard.write(&path, &contents)?;
+        reporter.file(job, &path, outcome);
+        job.record(outcome);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::progress::{JsonReporter, Reporter as _};
+    use super::{CodegenPluginConfig, Guard, JobView, OverwritePolicy, TemplateSource};
+    use super::{MANIFEST_FILE, codegen_targets, store_rendered};
+
+    fn plugin(repo: &str, local_path: Option<&str>) -> CodegenPluginConfig {
+        CodegenPluginConfig {
+            repo: repo.to_string(),
+            path: String::new(),
+            r#ref: None,
+            local_path: local_path.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn local_path_resolves_against_project_root() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("templates/my-lang")).unwrap();
+
+        let source =
+            TemplateSource::for_plugin(&plugin("", Some("./templates/my-lang")), root.path()).unwrap();
+
+        assert_eq!(
+            source,
+            TemplateSource::Local(root.path().join("templates/my-lang"))
+        );
+    }
+
+    #[test]
+    fn repo_and_local_path_together_are_rejected() {
+        let root = tempfile::tempdir().unwrap();
+
+        let err = TemplateSource::for_plugin(&plugin("acme/sdk", Some("tpl")), root.path())
+            .unwrap_err();
+
+        assert!(err.to_string().contains("both `repo` and `local_path`"));
+    }
+
+    #[test]
+    fn repo_defaults_to_main_ref() {
+        let root = tempfile::tempdir().unwrap();
+
+        let source = TemplateSource::for_plugin(&plugin("acme/sdk", None), root.path()).unwrap();
+
+        assert_eq!(source, TemplateSource::Github("acme/sdk/main".to_string()));
+    }
+
+    #[test]
+    fn targets_without_deps_still_nest_project() {
+        assert_eq!(codegen_targets(Some("proj"), &[]), vec!["proj".to_string()]);
+    }
+
+    #[test]
+    fn targets_project_first_then_deps_in_order() {
+        assert_eq!(
+            codegen_targets(Some("proj"), &["a".to_string(), "b".to_string()]),
+            vec!["proj".to_string(), "a".to_string(), "b".to_string()]
+        );
+    }
+
+    #[test]
+    fn consumer_project_targets_skip_own_protocol() {
+        assert_eq!(
+            codegen_targets(None, &["a".to_string(), "b".to_string()]),
+            vec!["a".to_string(), "b".to_string()]
+        );
+    }
+
+    #[test]
+    fn consumer_project_with_no_deps_is_empty() {
+        assert!(codegen_targets(None, &[]).is_empty());
+    }
+
+    #[test]
+    fn json_progress_reports_every_written_path() {
+        let rendered = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(rendered.path().join("src")).unwrap();
+        std::fs::write(rendered.path().join("index.ts"), "export {}").unwrap();
+        std::fs::write(rendered.path().join("src/tx.ts"), "export {}").unwrap();
+
+        let out = tempfile::tempdir().unwrap();
+        let output_dir = out.path().join("gen");
+
+        let mut job = JobView::new(
+            "ts-client".to_string(),
+            "./templates/ts".to_string(),
+            output_dir.clone(),
+        );
+        let mut guard = Guard::open(
+            &output_dir,
+            output_dir.join(MANIFEST_FILE),
+            OverwritePolicy::Never,
+            false,
+        )
+        .unwrap();
+
+        let mut stream = vec![];
+        let mut reporter = JsonReporter::new(&mut stream);
+
+        reporter.start(1, 1, &job);
+        store_rendered(rendered.path(), "demo", &mut guard, &mut job, &mut reporter).unwrap();
+        reporter.done(&job);
+        drop(reporter);
+
+        let events: Vec<serde_json::Value> = String::from_utf8(stream)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        let kinds: Vec<_> = events
+            .iter()
+            .map(|e| e["event"].as_str().unwrap())
+            .collect();
+        assert_eq!(kinds, ["start", "file_written", "file_written", "done"]);
+
+        let paths: Vec<_> = events.iter().map(|e| e["path"].as_str().unwrap()).collect();
+        let expected = [
+            output_dir.clone(),
+            output_dir.join("demo/index.ts"),
+            output_dir.join("demo/src/tx.ts"),
+            output_dir.clone(),
+        ];
+        assert_eq!(paths, expected.map(|p| p.display().to_string()));
+
+        for path in &expected[1..3] {
+            assert!(path.is_file(), "{} should be on disk", path.display());
+        }
+
+        assert!(events.iter().all(|e| e["template"] == "./templates/ts"));
+        assert_eq!(events[3]["written"], 2);
+    }
+}
@@ -0,0 +1,138 @@
+//! Progress of a `trix codegen` run, reported as jobs start, files land and
+//! jobs finish. The run itself only talks to a [`Reporter`]; what the user
+//! sees is up to the implementation:
+//!
+//! - [`ConsoleReporter`]: one `[i/n] job → dir` line per job on stderr.
+//! - [`JsonReporter`]: newline-delimited JSON events on stdout, for IDE
+//!   plugins and CI, one object per line:
+//!
+//! ```json
+//! {"event":"start","job":"ts-client","path":"gen/ts","template":"tx3-lang/web-sdk/main"}
+//! {"event":"file_written","job":"ts-client","path":"gen/ts/demo/index.ts","template":"tx3-lang/web-sdk/main","outcome":"written"}
+//! {"event":"done","job":"ts-client","path":"gen/ts","template":"tx3-lang/web-sdk/main","status":"ok","written":1,"updated":0,"skipped":0,"kept":0}
+//! ```
+
+use std::io::Write;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::generated::Outcome;
+
+use super::summary::{FileCounts, JobStatus, JobView};
+
+pub trait Reporter {
+    /// Job `index` (1-based) of `total` is about to run.
+    fn start(&mut self, index: usize, total: usize, job: &JobView);
+
+    /// `path` (under the job's output dir) was handed to the overwrite
+    /// guard with this outcome.
+    fn file(&mut self, job: &JobView, path: &Path, outcome: Outcome);
+
+    /// The job finished; `job` carries its counts and error.
+    fn done(&mut self, job: &JobView);
+}
+
+// ============================================================================
+// Console
+// ============================================================================
+
+pub struct ConsoleReporter;
+
+impl Reporter for ConsoleReporter {
+    fn start(&mut self, index: usize, total: usize, job: &JobView) {
+        eprintln!(
+            "[{index}/{total}] {} → {}",
+            job.job_id,
+            job.output_dir.display()
+        );
+    }
+
+    fn file(&mut self, _job: &JobView, _path: &Path, _outcome: Outcome) {}
+
+    fn done(&mut self, _job: &JobView) {}
+}
+
+// ============================================================================
+// JSON
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event<'a> {
+    Start {
+        job: &'a str,
+        path: &'a Path,
+        template: &'a str,
+    },
+    FileWritten {
+        job: &'a str,
+        path: &'a Path,
+        template: &'a str,
+        outcome: &'static str,
+    },
+    Done {
+        job: &'a str,
+        path: &'a Path,
+        template: &'a str,
+        status: JobStatus,
+        #[serde(flatten)]
+        files: FileCounts,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<&'a str>,
+    },
+}
+
+pub struct JsonReporter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> JsonReporter<W> {
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+
+    fn emit(&mut self, event: &Event) {
+        // A closed pipe on the reading side shouldn't fail the generation.
+        let line = serde_json::to_string(event).expect("events serialize");
+        let _ = writeln!(self.out, "{line}");
+        let _ = self.out.flush();
+    }
+}
+
+impl<W: Write> Reporter for JsonReporter<W> {
+    fn start(&mut self, _index: usize, _total: usize, job: &JobView) {
+        self.emit(&Event::Start {
+            job: &job.job_id,
+            path: &job.output_dir,
+            template: &job.template,
+        });
+    }
+
+    fn file(&mut self, job: &JobView, path: &Path, outcome: Outcome) {
+        let outcome = match outcome {
+            Outcome::Written => "written",
+            Outcome::Updated => "updated",
+            // Nothing landed on disk.
+            Outcome::Unchanged | Outcome::Kept => return,
+        };
+
+        self.emit(&Event::FileWritten {
+            job: &job.job_id,
+            path: &job.output_dir.join(path),
+            template: &job.template,
+            outcome,
+        });
+    }
+
+    fn done(&mut self, job: &JobView) {
+        self.emit(&Event::Done {
+            job: &job.job_id,
+            path: &job.output_dir,
+            template: &job.template,
+            status: job.status,
+            files: job.files,
+            error: job.error.as_deref(),
+        });
+    }
+}
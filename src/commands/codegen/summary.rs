@@ -0,0 +1,217 @@
+//! Per-job run summary for `trix codegen`.
+//!
+//! Each `[[codegen]]` entry is one job. Jobs run independently, so when one
+//! fails halfway the summary is the record of what the others left on disk.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use askama::Template;
+use miette::IntoDiagnostic as _;
+use serde::Serialize;
+
+use crate::generated::Outcome;
+use crate::term::OutputFormat;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Ok,
+    Failed,
+}
+
+impl std::fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ok => write!(f, "ok"),
+            Self::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// File counts for one job, relative to what was on disk before it ran.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct FileCounts {
+    /// Files that did not exist before.
+    pub written: usize,
+    /// Files whose content changed.
+    pub updated: usize,
+    /// Files the job left byte-identical.
+    pub skipped: usize,
+    /// Hand-edited or user-owned files left in place instead of regenerated.
+    pub kept: usize,
+}
+
+impl FileCounts {
+    pub fn tally(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Written => self.written += 1,
+            Outcome::Updated => self.updated += 1,
+            Outcome::Unchanged => self.skipped += 1,
+            Outcome::Kept => self.kept += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobView {
+    pub job_id: String,
+    /// Where the templates came from: a local path or `owner/repo/ref`, plus
+    /// the path inside it.
+    pub template: String,
+    pub output_dir: PathBuf,
+    #[serde(flatten)]
+    pub files: FileCounts,
+    pub elapsed_ms: u64,
+    pub status: JobStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl JobView {
+    pub fn new(job_id: String, template: String, output_dir: PathBuf) -> Self {
+        Self {
+            job_id,
+            template,
+            output_dir,
+            files: FileCounts::default(),
+            elapsed_ms: 0,
+            status: JobStatus::Ok,
+            error: None,
+        }
+    }
+
+    pub fn record(&mut self, outcome: Outcome) {
+        self.files.tally(outcome);
+    }
+
+    pub fn finish(&mut self, elapsed: Duration, result: miette::Result<()>) {
+        self.elapsed_ms = elapsed.as_millis() as u64;
+
+        if let Err(err) = result {
+            self.status = JobStatus::Failed;
+            let chain: Vec<String> = err.chain().map(|e| e.to_string()).collect();
+            self.error = Some(chain.join(": "));
+        }
+    }
+
+    pub fn output_dir_display(&self) -> String {
+        self.output_dir.display().to_string()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SummaryView {
+    pub jobs: Vec<JobView>,
+    /// Jobs never started because an earlier one failed under `--fail-fast`.
+    pub not_run: Vec<String>,
+}
+
+impl SummaryView {
+    pub fn failed(&self) -> usize {
+        self.jobs
+            .iter()
+            .filter(|j| j.status == JobStatus::Failed)
+            .count()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "codegen/summary.md")]
+struct SummaryTemplate<'a> {
+    view: &'a SummaryView,
+}
+
+pub fn render(view: &SummaryView, format: OutputFormat) -> miette::Result<()> {
+    match format {
+        OutputFormat::Human => {
+            let markdown = SummaryTemplate { view }
+                .render()
+                .expect("Template rendering failed");
+            crate::term::console::print_markdown(&markdown);
+        }
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(view).into_diagnostic()?;
+            println!("{json}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Content hash of every file under `dir`, keyed by relative path. A missing
+/// dir is an empty snapshot. The generated-files manifest is bookkeeping,
+/// not output, and is left out.
+pub fn snapshot(dir: &Path) -> miette::Result<BTreeMap<PathBuf, String>> {
+    let mut out = BTreeMap::new();
+
+    if dir.is_dir() {
+        walk(dir, dir, &mut out)?;
+    }
+
+    out.remove(Path::new(crate::generated::MANIFEST_FILE));
+
+    Ok(out)
+}
+
+fn walk(root: &Path, dir: &Path, out: &mut BTreeMap<PathBuf, String>) -> miette::Result<()> {
+    for entry in std::fs::read_dir(dir).into_diagnostic()? {
+        let path = entry.into_diagnostic()?.path();
+
+        if path.is_dir() {
+            walk(root, &path, out)?;
+            continue;
+        }
+
+        let bytes = std::fs::read(&path).into_diagnostic()?;
+
+        let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+        out.insert(relative, crate::generated::hash(&bytes));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tally_counts_each_outcome() {
+        let mut counts = FileCounts::default();
+        for outcome in [
+            Outcome::Written,
+            Outcome::Updated,
+            Outcome::Unchanged,
+            Outcome::Unchanged,
+            Outcome::Kept,
+        ] {
+            counts.tally(outcome);
+        }
+
+        assert_eq!(
+            counts,
+            FileCounts {
+                written: 1,
+                updated: 1,
+                skipped: 2,
+                kept: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn json_summary_flattens_counts() {
+        let mut job = JobView::new("ts".into(), "local".into(), PathBuf::from("gen"));
+        job.record(Outcome::Written);
+        job.record(Outcome::Written);
+        job.record(Outcome::Unchanged);
+        job.finish(Duration::from_millis(5), Err(miette::miette!("boom")));
+
+        let json = serde_json::to_value(&job).unwrap();
+        assert_eq!(json["written"], 2);
+        assert_eq!(json["status"], "failed");
+        assert_eq!(json["error"], "boom");
+    }
+}
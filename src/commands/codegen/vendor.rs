@@ -0,0 +1,341 @@
+//! Vendored template bundles, for projects that build without network
+//! access. `trix codegen vendor` downloads a job's templates once, at the
+//! commit its ref points to, into
+//!
+//! ```text
+//! vendor/trix-templates/<job_id>/
+//!   templates/        templates and static files, as extracted for a run
+//!   provenance.toml   repo, ref, path and commit they came from
+//! ```
+//!
+//! and marks the `[[codegen]]` entry `vendored = true`; from then on the job
+//! reads only that copy. `trix codegen outdated` compares the recorded
+//! commit with the ref's current one upstream.
+
+use std::path::{Path, PathBuf};
+
+use clap::Args as ClapArgs;
+use miette::{Context as _, IntoDiagnostic as _, bail};
+use reqwest::header;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{CodegenConfig, CodegenPluginConfig, RootConfig};
+use crate::net::HttpClient;
+
+use super::TemplateSource;
+
+pub const VENDOR_DIR: &str = "vendor/trix-templates";
+
+const PROVENANCE_FILE: &str = "provenance.toml";
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Only vendor this `[[codegen]]` job
+    #[arg(long, value_name = "JOB_ID")]
+    job: Option<String>,
+}
+
+/// Where a vendored bundle came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub repo: String,
+    #[serde(rename = "ref")]
+    pub r#ref: String,
+    pub path: String,
+    /// Commit the ref pointed to when vendored; unset for a `repo` that is
+    /// a directory on disk.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+pub fn job_dir(project_root: &Path, job_id: &str) -> PathBuf {
+    project_root.join(VENDOR_DIR).join(job_id)
+}
+
+pub fn templates_dir(project_root: &Path, job_id: &str) -> PathBuf {
+    job_dir(project_root, job_id).join("templates")
+}
+
+fn load_provenance(project_root: &Path, job_id: &str) -> miette::Result<Provenance> {
+    let path = job_dir(project_root, job_id).join(PROVENANCE_FILE);
+
+    let content = std::fs::read_to_string(&path)
+        .into_diagnostic()
+        .with_context(|| format!("reading {}", path.display()))?;
+
+    toml::from_str(&content)
+        .into_diagnostic()
+        .with_context(|| format!("parsing {}", path.display()))
+}
+
+/// Commit `ref` currently points to in `repo` (`owner/repo`).
+async fn upstream_commit(client: &HttpClient, repo: &str, r#ref: &str) -> miette::Result<String> {
+    let url = format!("https://api.github.com/repos/{repo}/commits/{ref}");
+
+    let request = client
+        .get(&url)
+        .header(header::ACCEPT, "application/vnd.github.sha")
+        .header(header::USER_AGENT, "trix");
+
+    let response = request.send().await.into_diagnostic()?;
+
+    if !response.status().is_success() {
+        bail!(
+            "can't resolve `{ref}` of {repo} on GitHub: HTTP {}",
+            response.status()
+        );
+    }
+
+    let sha = response.text().await.into_diagnostic()?;
+
+    Ok(sha.trim().to_string())
+}
+
+fn copy_tree(from: &Path, to: &Path) -> miette::Result<()> {
+    std::fs::create_dir_all(to).into_diagnostic()?;
+
+    for entry in std::fs::read_dir(from).into_diagnostic()? {
+        let path = entry.into_diagnostic()?.path();
+        let dest = to.join(path.file_name().unwrap_or_default());
+
+        if path.is_dir() {
+            copy_tree(&path, &dest)?;
+        } else if !path.ends_with("trix-bindgen.toml") {
+            std::fs::copy(&path, &dest).into_diagnostic()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Replace the bundle in `job_dir` with the templates in `extracted`.
+fn store(job_dir: &Path, extracted: &Path, provenance: &Provenance) -> miette::Result<()> {
+    if job_dir.exists() {
+        std::fs::remove_dir_all(job_dir)
+            .into_diagnostic()
+            .with_context(|| format!("removing old bundle {}", job_dir.display()))?;
+    }
+
+    copy_tree(extracted, &job_dir.join("templates"))?;
+
+    let provenance = toml::to_string_pretty(provenance).into_diagnostic()?;
+    crate::fsutil::write_atomic(&job_dir.join(PROVENANCE_FILE), provenance)
+}
+
+async fn vendor_job(
+    client: &HttpClient,
+    codegen: &CodegenConfig,
+    project_root: &Path,
+) -> miette::Result<Provenance> {
+    let job_id = codegen.job_id();
+    let plugin = CodegenPluginConfig::from(codegen.plugin.clone());
+
+    let r#ref = plugin.r#ref.clone().unwrap_or_else(|| "main".to_string());
+    let temp = tempfile::TempDir::new().into_diagnostic()?;
+
+    let (extracted, commit) = match TemplateSource::for_plugin(&plugin, project_root)? {
+        TemplateSource::Local(_) | TemplateSource::Vendored(_) => bail!(
+            help = "`local_path` templates are already read from the project",
+            "codegen job `{job_id}` has no repo to vendor"
+        ),
+        TemplateSource::Github(url) if Path::new(&url).is_dir() => {
+            (Path::new(&url).join(&plugin.path), None)
+        }
+        TemplateSource::Github(_) => {
+            let commit = upstream_commit(client, &plugin.repo, &r#ref).await?;
+
+            // Pin the download to the commit, so the bundle matches the
+            // provenance even if the ref moves meanwhile.
+            let url = format!("https://github.com/{}/archive/{commit}.zip", plugin.repo);
+            let content = super::cache::fetch(client, &url, None).await?;
+
+            let extracted = temp.path().join("templates");
+            super::extract_archive(&content, &plugin.path, &extracted)?;
+
+            (extracted, Some(commit))
+        }
+    };
+
+    let provenance = Provenance {
+        repo: plugin.repo.clone(),
+        r#ref,
+        path: plugin.path.clone(),
+        commit,
+    };
+
+    store(&job_dir(project_root, &job_id), &extracted, &provenance)?;
+
+    Ok(provenance)
+}
+
+pub async fn run(args: Args, config: &RootConfig, config_path: &Path) -> miette::Result<()> {
+    let project_root = config_path.parent().unwrap_or_else(|| Path::new("."));
+
+    if let Some(job) = &args.job
+        && !config.codegen.iter().any(|c| &c.job_id() == job)
+    {
+        let jobs: Vec<_> = config.codegen.iter().map(|c| c.job_id()).collect();
+        bail!(
+            help = format!("jobs: {}", jobs.join(", ")),
+            "no `[[codegen]]` job `{job}` in trix.toml"
+        );
+    }
+
+    let client = crate::net::build_http_client()?;
+    let mut next = config.clone();
+
+    for codegen in next.codegen.iter_mut() {
+        let job_id = codegen.job_id();
+
+        if args.job.as_ref().is_some_and(|job| job != &job_id) {
+            continue;
+        }
+
+        let provenance = vendor_job(&client, codegen, project_root).await?;
+        codegen.vendored = true;
+
+        let commit = provenance.commit.as_deref().unwrap_or("local");
+        println!(
+            "vendored `{job_id}` ({}@{commit}) into {}",
+            provenance.repo,
+            templates_dir(Path::new("."), &job_id).display()
+        );
+    }
+
+    next.save(&config_path.to_path_buf())?;
+
+    Ok(())
+}
+
+pub async fn outdated(config: &RootConfig) -> miette::Result<()> {
+    let project_root = crate::dirs::protocol_root()?;
+    let client = crate::net::build_http_client()?;
+
+    for codegen in &config.codegen {
+        let job_id = codegen.job_id();
+
+        if !codegen.vendored {
+            println!("{job_id}: not vendored, downloaded on every run");
+            continue;
+        }
+
+        let provenance = load_provenance(&project_root, &job_id)?;
+
+        let Some(commit) = &provenance.commit else {
+            println!(
+                "{job_id}: vendored from {}, no commit to compare",
+                provenance.repo
+            );
+            continue;
+        };
+
+        let upstream = upstream_commit(&client, &provenance.repo, &provenance.r#ref).await?;
+
+        match &upstream == commit {
+            true => println!(
+                "{job_id}: up to date with {}@{}",
+                provenance.repo, provenance.r#ref
+            ),
+            false => println!(
+                "{job_id}: outdated, vendored {} but {}@{} is at {} (run `trix codegen vendor --job {job_id}`)",
+                short(commit),
+                provenance.repo,
+                provenance.r#ref,
+                short(&upstream)
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+fn short(commit: &str) -> &str {
+    &commit[..commit.len().min(12)]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+
+    use super::*;
+    use crate::commands::codegen::summary::snapshot;
+
+    /// A GitHub-style archive: everything under a `<repo>-<commit>/` root.
+    fn archive() -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+        let options = zip::write::SimpleFileOptions::default();
+
+        for dir in ["sdk-abc/", "sdk-abc/bindgen/", "sdk-abc/bindgen/ts/"] {
+            zip.add_directory(dir, options).unwrap();
+        }
+
+        for (name, body) in [
+            ("sdk-abc/bindgen/ts/trix-bindgen.toml", "ignored"),
+            (
+                "sdk-abc/bindgen/ts/index.ts.hbs",
+                "export const {{name}} = 1;\n",
+            ),
+            ("sdk-abc/bindgen/ts/static/logo.png", "\u{89}PNG"),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(body.as_bytes()).unwrap();
+        }
+
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn vendored_templates_match_direct_extraction() {
+        let direct = tempfile::tempdir().unwrap();
+        super::super::extract_archive(&archive(), "bindgen/ts", direct.path()).unwrap();
+
+        let project = tempfile::tempdir().unwrap();
+        let provenance = Provenance {
+            repo: "acme/sdk".to_string(),
+            r#ref: "main".to_string(),
+            path: "bindgen/ts".to_string(),
+            commit: Some("abc".to_string()),
+        };
+
+        store(&job_dir(project.path(), "ts"), direct.path(), &provenance).unwrap();
+        // A second vendor replaces the bundle instead of merging into it.
+        store(&job_dir(project.path(), "ts"), direct.path(), &provenance).unwrap();
+
+        let vendored = templates_dir(project.path(), "ts");
+        assert_eq!(
+            snapshot(&vendored).unwrap(),
+            snapshot(direct.path()).unwrap()
+        );
+        assert!(vendored.join("static/logo.png").is_file());
+        assert!(!vendored.join("trix-bindgen.toml").exists());
+        assert_eq!(load_provenance(project.path(), "ts").unwrap(), provenance);
+    }
+
+    #[test]
+    fn vendored_job_reads_only_the_bundle() {
+        let project = tempfile::tempdir().unwrap();
+        let mut codegen: CodegenConfig = toml::from_str(
+            r#"
+            job_id = "ts"
+            vendored = true
+            plugin = { repo = "acme/sdk", path = "bindgen/ts" }
+            "#,
+        )
+        .unwrap();
+        let plugin = CodegenPluginConfig::from(codegen.plugin.clone());
+
+        let err = TemplateSource::for_job(&codegen, &plugin, project.path()).unwrap_err();
+        assert!(err.to_string().contains("is vendored"), "{err}");
+
+        std::fs::create_dir_all(templates_dir(project.path(), "ts")).unwrap();
+        let source = TemplateSource::for_job(&codegen, &plugin, project.path()).unwrap();
+        assert_eq!(
+            source,
+            TemplateSource::Vendored(templates_dir(project.path(), "ts"))
+        );
+
+        codegen.vendored = false;
+        let source = TemplateSource::for_job(&codegen, &plugin, project.path()).unwrap();
+        assert_eq!(source, TemplateSource::Github("acme/sdk/main".to_string()));
+    }
+}
@@ -0,0 +1,276 @@
+//! `trix codegen verify`: render every job into a scratch directory and
+//! compare it with what is on disk, so CI can tell when committed bindings
+//! are out of date. Nothing under the configured output dirs is touched.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use askama::Template;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use miette::{IntoDiagnostic as _, bail};
+use serde::Serialize;
+use tempfile::TempDir;
+
+use crate::config::{CodegenConfig, CodegenPluginConfig, RootConfig};
+use crate::term::OutputFormat;
+
+use super::{TemplateSource, collect_codegen_targets, summary, templates_dir};
+
+// ============================================================================
+// View Model
+// ============================================================================
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JobDrift {
+    pub job_id: String,
+    pub output_dir: PathBuf,
+    /// On disk, but different from a fresh generation.
+    pub stale: Vec<PathBuf>,
+    /// Generated, but missing on disk.
+    pub missing: Vec<PathBuf>,
+    /// On disk, but no longer generated.
+    pub orphaned: Vec<PathBuf>,
+}
+
+impl JobDrift {
+    pub fn is_clean(&self) -> bool {
+        self.stale.is_empty() && self.missing.is_empty() && self.orphaned.is_empty()
+    }
+
+    pub fn output_dir_display(&self) -> String {
+        self.output_dir.display().to_string()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyView {
+    pub jobs: Vec<JobDrift>,
+}
+
+impl VerifyView {
+    fn drifted(&self) -> Vec<&JobDrift> {
+        self.jobs.iter().filter(|j| !j.is_clean()).collect()
+    }
+}
+
+// ============================================================================
+// Askama Template
+// ============================================================================
+
+#[derive(Template)]
+#[template(path = "codegen/verify.md")]
+struct VerifyTemplate<'a> {
+    view: &'a VerifyView,
+}
+
+// ============================================================================
+// Command Entry Point
+// ============================================================================
+
+pub async fn run(
+    config: &RootConfig,
+    config_path: &Path,
+    output: OutputFormat,
+    use_cache: bool,
+) -> miette::Result<()> {
+    if config.codegen.is_empty() {
+        bail!("no [[codegen]] targets configured; nothing to verify");
+    }
+
+    crate::interfaces::validate(config)?;
+    crate::interfaces::restore_all(config)?;
+
+    let project_root = config_path.parent().unwrap_or_else(|| Path::new("."));
+    let targets = collect_codegen_targets(config, project_root)?;
+
+    let mut jobs = vec![];
+
+    for (i, codegen) in config.codegen.iter().enumerate() {
+        eprintln!(
+            "[{}/{}] verifying {}",
+            i + 1,
+            config.codegen.len(),
+            codegen.job_id()
+        );
+
+        jobs.push(verify_job(codegen, project_root, &targets, use_cache).await?);
+    }
+
+    let view = VerifyView { jobs };
+
+    match output {
+        OutputFormat::Human => {
+            let markdown = VerifyTemplate { view: &view }
+                .render()
+                .expect("Template rendering failed");
+            crate::term::console::print_markdown(&markdown);
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&view).into_diagnostic()?);
+        }
+    }
+
+    let drifted = view.drifted();
+
+    if !drifted.is_empty() {
+        let dirs = drifted
+            .iter()
+            .map(|j| j.output_dir_display())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        bail!(
+            help = format!("run `trix codegen` and commit the result (`git add {dirs}`)"),
+            "{} of {} codegen jobs are out of date",
+            drifted.len(),
+            view.jobs.len()
+        );
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Comparison
+// ============================================================================
+
+fn ignore_set(patterns: &[String]) -> miette::Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        let glob = Glob::new(pattern)
+            .map_err(|e| miette::miette!("invalid verify_ignore glob `{pattern}`: {e}"))?;
+        builder.add(glob);
+    }
+
+    builder.build().into_diagnostic()
+}
+
+/// Whether `ignore` matches `path`, taken from the job's output dir or
+/// from inside its `<protocol-name>/` dir.
+fn ignored(ignore: &GlobSet, path: &Path) -> bool {
+    let mut within_protocol = path.components();
+    within_protocol.next();
+
+    ignore.is_match(path) || ignore.is_match(within_protocol.as_path())
+}
+
+/// Classify every path of `expected` (fresh generation) against `actual`
+/// (on disk). Both are keyed relative to the job's output dir and already
+/// sorted, so the lists come out in a stable order.
+fn diff(
+    expected: &BTreeMap<PathBuf, String>,
+    actual: &BTreeMap<PathBuf, String>,
+    ignore: &GlobSet,
+    drift: &mut JobDrift,
+) {
+    for (path, hash) in expected {
+        if ignored(ignore, path) {
+            continue;
+        }
+
+        match actual.get(path) {
+            None => drift.missing.push(path.clone()),
+            Some(found) if found != hash => drift.stale.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+
+    for path in actual.keys() {
+        if !ignored(ignore, path) && !expected.contains_key(path) {
+            drift.orphaned.push(path.clone());
+        }
+    }
+}
+
+async fn verify_job(
+    codegen: &CodegenConfig,
+    project_root: &Path,
+    targets: &[(String, PathBuf)],
+    use_cache: bool,
+) -> miette::Result<JobDrift> {
+    let plugin = CodegenPluginConfig::from(codegen.plugin.clone());
+    let source = TemplateSource::for_job(codegen, &plugin, project_root)?;
+    let ignore = ignore_set(&codegen.verify_ignore)?;
+
+    let output_dir = codegen.output_dir()?;
+
+    let template_temp = TempDir::new().into_diagnostic()?;
+    let templates_dir = templates_dir(&source, &plugin, &template_temp, use_cache).await?;
+
+    let scratch = TempDir::new().into_diagnostic()?;
+
+    for (name, tii_path) in targets {
+        let dest = scratch.path().join(name);
+        std::fs::create_dir_all(&dest).into_diagnostic()?;
+        crate::spawn::tx3c::codegen(tii_path, &templates_dir, &dest)?;
+    }
+
+    let expected = summary::snapshot(scratch.path())?;
+    let actual = summary::snapshot(&output_dir)?;
+
+    let mut drift = JobDrift {
+        job_id: codegen.job_id(),
+        output_dir,
+        ..Default::default()
+    };
+
+    diff(&expected, &actual, &ignore, &mut drift);
+
+    Ok(drift)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(entries: &[(&str, &str)]) -> BTreeMap<PathBuf, String> {
+        entries
+            .iter()
+            .map(|(p, h)| (PathBuf::from(p), h.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn diff_reports_stale_missing_and_orphaned() {
+        let expected = files(&[("p/a.ts", "1"), ("p/b.ts", "2"), ("p/new.ts", "3")]);
+        let actual = files(&[("p/a.ts", "1"), ("p/b.ts", "9"), ("p/old.ts", "4")]);
+
+        let mut drift = JobDrift::default();
+        diff(&expected, &actual, &ignore_set(&[]).unwrap(), &mut drift);
+
+        assert_eq!(drift.stale, vec![PathBuf::from("p/b.ts")]);
+        assert_eq!(drift.missing, vec![PathBuf::from("p/new.ts")]);
+        assert_eq!(drift.orphaned, vec![PathBuf::from("p/old.ts")]);
+    }
+
+    #[test]
+    fn verify_ignore_skips_matching_files() {
+        let expected = files(&[("p/a.ts", "1")]);
+        let actual = files(&[("p/a.ts", "1"), ("p/node_modules/x/index.js", "5")]);
+
+        let mut drift = JobDrift::default();
+        let ignore = ignore_set(&["**/node_modules/**".to_string()]).unwrap();
+        diff(&expected, &actual, &ignore, &mut drift);
+
+        assert!(drift.is_clean());
+    }
+
+    #[test]
+    fn verify_ignore_matches_inside_the_protocol_dir() {
+        let expected = files(&[("p/a.ts", "1")]);
+        let actual = files(&[("p/a.ts", "1"), ("p/node_modules/x/index.js", "5")]);
+
+        let mut drift = JobDrift::default();
+        let ignore = ignore_set(&["node_modules/**".to_string()]).unwrap();
+        diff(&expected, &actual, &ignore, &mut drift);
+
+        assert!(drift.is_clean());
+
+        let mut drift = JobDrift::default();
+        let ignore = ignore_set(&["p/node_modules/**".to_string()]).unwrap();
+        diff(&expected, &actual, &ignore, &mut drift);
+
+        assert!(drift.is_clean());
+    }
+}
@@ -0,0 +1,443 @@
+//! `trix config-diff`: how the *effective* configuration changed between
+//! two versions of trix.toml. Both versions are resolved the way commands
+//! see them (built-in profiles and networks, `extends` chains, default env
+//! files) and compared value by value, so a change that only moves a
+//! default shows up too. Literal header values and mnemonics are masked.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use clap::Args as ClapArgs;
+use miette::{Context as _, IntoDiagnostic as _, bail};
+use serde::Serialize;
+
+use crate::commands::profile::mask_value;
+use crate::config::{IdentityConfig, NetworkConfig, RootConfig};
+use crate::secrets::SecretRef;
+use crate::term::OutputFormat;
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Compare trix.toml as of this git revision with the working copy
+    #[arg(
+        long,
+        value_name = "REF",
+        conflicts_with = "file",
+        required_unless_present = "file"
+    )]
+    git: Option<String>,
+
+    /// Compare two trix.toml files; pass it twice, the old one first
+    #[arg(long, value_name = "PATH")]
+    file: Vec<PathBuf>,
+
+    /// Only compare this profile and the network it targets
+    #[arg(value_name = "PROFILE")]
+    name: Option<String>,
+
+    /// Report format; `json` is meant for PR comments and other tooling
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    output: OutputFormat,
+}
+
+/// One effective value: `raw` is compared, `shown` is printed.
+#[derive(Debug, Clone, PartialEq)]
+struct Value {
+    raw: String,
+    shown: String,
+}
+
+impl Value {
+    fn plain(value: impl ToString) -> Self {
+        let value = value.to_string();
+
+        Self {
+            raw: value.clone(),
+            shown: value,
+        }
+    }
+
+    /// Secrets references (`env:`, `keyring:`) are shown as written; a
+    /// literal is masked.
+    fn secret(value: &str) -> Self {
+        let shown = match SecretRef::parse(value) {
+            Ok(SecretRef::Literal(literal)) => mask_value(literal),
+            Ok(_) => value.to_string(),
+            Err(_) => mask_value(value),
+        };
+
+        Self {
+            raw: value.to_string(),
+            shown,
+        }
+    }
+}
+
+type Effective = BTreeMap<String, Value>;
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+enum Change {
+    Added {
+        key: String,
+        value: String,
+    },
+    Removed {
+        key: String,
+        value: String,
+    },
+    Changed {
+        key: String,
+        from: String,
+        to: String,
+    },
+}
+
+#[derive(Serialize)]
+struct Report {
+    from: String,
+    to: String,
+    changes: Vec<Change>,
+}
+
+fn add_network(network: &NetworkConfig, out: &mut Effective) {
+    let key = |field: &str| format!("networks.{}.{field}", network.name);
+
+    out.insert(key("is_testnet"), Value::plain(network.is_testnet));
+    out.insert(key("trp.url"), Value::plain(&network.trp.url));
+    out.insert(key("u5c.url"), Value::plain(&network.u5c.url));
+
+    for (name, value) in &network.trp.headers {
+        out.insert(key(&format!("trp.headers.{name}")), Value::secret(value));
+    }
+
+    for (name, value) in &network.u5c.headers {
+        out.insert(key(&format!("u5c.headers.{name}")), Value::secret(value));
+    }
+
+    if let Some(template) = &network.explorer_url_template {
+        out.insert(key("explorer_url_template"), Value::plain(template));
+    }
+
+    if let Some(template) = &network.explorer_address_url_template {
+        out.insert(key("explorer_address_url_template"), Value::plain(template));
+    }
+}
+
+fn identity_value(identity: &IdentityConfig) -> Value {
+    match identity {
+        IdentityConfig::RandomKey(_) => Value::plain("random-key"),
+        IdentityConfig::ExplicitKey(explicit) => {
+            Value::plain(format!("explicit-key ({})", explicit.key_path.display()))
+        }
+        IdentityConfig::Mnemonic(mnemonic) => {
+            let source = match (&mnemonic.mnemonic, &mnemonic.mnemonic_env) {
+                (Some(phrase), _) => Value::secret(phrase),
+                (None, Some(var)) => Value::plain(format!("mnemonic_env {var}")),
+                (None, None) => Value::plain("unset"),
+            };
+
+            let account = mnemonic
+                .account_index
+                .map(|index| format!(", account {index}"))
+                .unwrap_or_default();
+
+            Value {
+                raw: format!("mnemonic ({}{account})", source.raw),
+                shown: format!("mnemonic ({}{account})", source.shown),
+            }
+        }
+    }
+}
+
+/// Every effective value of `config`, keyed by a dotted path. With `only`,
+/// just that profile and its network.
+fn effective(config: &RootConfig, only: Option<&str>) -> Effective {
+    let mut out = Effective::new();
+
+    let profiles: BTreeSet<String> = match only {
+        Some(name) => [name.to_string()].into(),
+        None => config.available_profiles().into_iter().collect(),
+    };
+
+    let mut networks: BTreeSet<String> = match only {
+        Some(_) => BTreeSet::new(),
+        None => config.available_networks().into_iter().collect(),
+    };
+
+    for name in &profiles {
+        let key = |field: &str| format!("profiles.{name}.{field}");
+
+        let profile = match config.resolve_profile(name) {
+            Ok(profile) => profile,
+            Err(err) => {
+                out.insert(
+                    format!("profiles.{name}"),
+                    Value::plain(format!("error: {err}")),
+                );
+                continue;
+            }
+        };
+
+        if let Some(extends) = &profile.extends {
+            out.insert(key("extends"), Value::plain(extends));
+        }
+
+        out.insert(key("network"), Value::plain(&profile.network));
+        out.insert(
+            key("env_file"),
+            Value::plain(profile.env_file_path().display()),
+        );
+
+        for (identity, identity_config) in profile.identities.iter() {
+            out.insert(
+                key(&format!("identities.{identity}")),
+                identity_value(identity_config),
+            );
+        }
+
+        networks.insert(profile.network.clone());
+    }
+
+    for name in &networks {
+        match config.resolve_network(name) {
+            Ok(network) => add_network(&network, &mut out),
+            Err(err) => {
+                out.insert(
+                    format!("networks.{name}"),
+                    Value::plain(format!("error: {err}")),
+                );
+            }
+        }
+    }
+
+    out
+}
+
+fn diff(from: &Effective, to: &Effective) -> Vec<Change> {
+    let keys: BTreeSet<&String> = from.keys().chain(to.keys()).collect();
+
+    keys.into_iter()
+        .filter_map(|key| match (from.get(key), to.get(key)) {
+            (None, Some(value)) => Some(Change::Added {
+                key: key.clone(),
+                value: value.shown.clone(),
+            }),
+            (Some(value), None) => Some(Change::Removed {
+                key: key.clone(),
+                value: value.shown.clone(),
+            }),
+            (Some(old), Some(new)) if old.raw != new.raw => {
+                // Two secrets can mask the same; still say that it changed.
+                let to = match old.shown == new.shown {
+                    true => format!("{} (changed)", new.shown),
+                    false => new.shown.clone(),
+                };
+
+                Some(Change::Changed {
+                    key: key.clone(),
+                    from: old.shown.clone(),
+                    to,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// trix.toml as committed at `rev`, read with `git show` without touching
+/// the working tree.
+fn load_at_revision(config_path: &Path, rev: &str) -> miette::Result<RootConfig> {
+    let dir = config_path.parent().unwrap_or_else(|| Path::new("."));
+    let file = config_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "trix.toml".to_string());
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["show", &format!("{rev}:./{file}")])
+        .output()
+        .into_diagnostic()
+        .context("running git")?;
+
+    if !output.status.success() {
+        bail!(
+            help = "pass a commit, branch or tag that has this trix.toml",
+            "can't read {file} at `{rev}`: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    toml::from_str(&String::from_utf8_lossy(&output.stdout))
+        .into_diagnostic()
+        .with_context(|| format!("parsing {file} at `{rev}`"))
+}
+
+fn print_human(report: &Report) {
+    println!("Effective config: {} -> {}\n", report.from, report.to);
+
+    if report.changes.is_empty() {
+        println!("no differences");
+        return;
+    }
+
+    for change in &report.changes {
+        match change {
+            Change::Added { key, value } => println!("+ {key}: {value}"),
+            Change::Removed { key, value } => println!("- {key}: {value}"),
+            Change::Changed { key, from, to } => println!("~ {key}: {from} -> {to}"),
+        }
+    }
+}
+
+pub fn run(args: Args, config: &RootConfig, config_path: &Path) -> miette::Result<()> {
+    let (from, to, old, new) = match (&args.git, args.file.as_slice()) {
+        (Some(rev), _) => (
+            rev.clone(),
+            config_path.display().to_string(),
+            load_at_revision(config_path, rev)?,
+            config.clone(),
+        ),
+        (None, [old, new]) => (
+            old.display().to_string(),
+            new.display().to_string(),
+            RootConfig::load(old).with_context(|| format!("loading {}", old.display()))?,
+            RootConfig::load(new).with_context(|| format!("loading {}", new.display()))?,
+        ),
+        (None, files) => bail!(
+            help = "e.g. `trix config-diff --file old.toml --file new.toml`",
+            "--file takes exactly two files, got {}",
+            files.len()
+        ),
+    };
+
+    let only = args.name.as_deref();
+
+    let report = Report {
+        changes: diff(&effective(&old, only), &effective(&new, only)),
+        from,
+        to,
+    };
+
+    match args.output {
+        OutputFormat::Human => print_human(&report),
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&report).into_diagnostic()?
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"
+        [protocol]
+        name = "demo"
+        version = "0.0.0"
+        main = "main.tx3"
+
+        [ledger]
+        family = "cardano"
+    "#;
+
+    fn config(extra: &str) -> RootConfig {
+        toml::from_str(&format!("{BASE}\n{extra}")).unwrap()
+    }
+
+    #[test]
+    fn reports_effective_changes_only() {
+        let old = config(
+            r#"
+            [profiles.staging]
+            extends = "preview"
+
+            [profiles.staging.identities.alice]
+            type = "RandomKey"
+            random_key = true
+            "#,
+        );
+
+        let new = config(
+            r#"
+            [profiles.staging]
+            extends = "preview"
+            env_file = ".env.shared"
+
+            [profiles.staging.identities.alice]
+            type = "RandomKey"
+            random_key = true
+
+            [profiles.staging.identities.treasury]
+            type = "Mnemonic"
+            mnemonic = "env:TREASURY_MNEMONIC"
+            "#,
+        );
+
+        let changes = diff(
+            &effective(&old, Some("staging")),
+            &effective(&new, Some("staging")),
+        );
+
+        assert_eq!(
+            changes,
+            [
+                Change::Changed {
+                    key: "profiles.staging.env_file".to_string(),
+                    from: ".env.staging".to_string(),
+                    to: ".env.shared".to_string(),
+                },
+                Change::Added {
+                    key: "profiles.staging.identities.treasury".to_string(),
+                    value: "mnemonic (env:TREASURY_MNEMONIC)".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn masks_literal_header_values() {
+        let network = |key: &str| {
+            config(&format!(
+                r#"
+                [networks.custom]
+                is_testnet = true
+                trp = {{ url = "https://trp.example", headers = {{ dmtr-api-key = "{key}" }} }}
+                u5c = {{ url = "https://u5c.example" }}
+                "#
+            ))
+        };
+
+        let changes = diff(
+            &effective(&network("preview1234567890abcd"), None),
+            &effective(&network("preview1234567890wxyz"), None),
+        );
+
+        assert_eq!(
+            changes,
+            [Change::Changed {
+                key: "networks.custom.trp.headers.dmtr-api-key".to_string(),
+                from: "prev...abcd".to_string(),
+                to: "prev...wxyz".to_string(),
+            }]
+        );
+
+        let same_mask = diff(
+            &effective(&network("short"), None),
+            &effective(&network("other"), None),
+        );
+
+        assert!(
+            matches!(&same_mask[..], [Change::Changed { to, .. }] if to == "*** (changed)"),
+            "{same_mask:?}"
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! `trix config-validate`: semantic checks on a `trix.toml` that parsed
+//! fine. Every problem found is collected and reported together, so one run
+//! lists everything that needs fixing.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use clap::Args as ClapArgs;
+use miette::{Diagnostic, miette};
+use thiserror::Error;
+
+use crate::commands::check::secrets::Scan;
+use crate::commands::codegen::TemplateSource;
+use crate::config::serde::Named as _;
+use crate::config::{
+    CodegenPluginConfig, IdentityConfig, NetworkConfig, NetworkOption, RootConfig,
+};
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("trix.toml has {} problem(s)", .problems.len())]
+struct Error {
+    #[related]
+    problems: Vec<miette::Error>,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Look for API keys and other secrets written literally in trix.toml
+    /// and tracked env files
+    #[arg(long, value_enum, default_value_t = Scan::On)]
+    secrets_scan: Scan,
+}
+
+/// Collects problems instead of stopping at the first one.
+#[derive(Default)]
+struct Report {
+    problems: Vec<miette::Error>,
+}
+
+impl Report {
+    fn push(&mut self, problem: miette::Error) {
+        self.problems.push(problem);
+    }
+
+    fn check(&mut self, result: miette::Result<()>) {
+        if let Err(problem) = result {
+            self.push(problem);
+        }
+    }
+
+    fn missing_file(&mut self, location: &str, path: &Path) {
+        if !path.exists() {
+            self.push(miette!("{location}: {} does not exist", path.display()));
+        }
+    }
+}
+
+fn check_protocol(config: &RootConfig, root: &Path, report: &mut Report) {
+    let protocol = &config.protocol;
+
+    report.missing_file("protocol.main", &root.join(&protocol.main));
+
+    if let Some(readme) = &protocol.readme {
+        report.missing_file("protocol.readme", &root.join(readme));
+    }
+
+    if let Some(logo) = &protocol.logo {
+        report.missing_file("protocol.logo", &root.join(logo));
+    }
+
+    for pattern in protocol.sources.iter().flatten() {
+        if let Err(e) = crate::builder::source_glob(pattern) {
+            report.push(miette!(
+                "protocol.sources: `{pattern}` is not a valid glob ({e})"
+            ));
+        }
+    }
+}
+
+fn check_url(location: &str, value: &str) -> miette::Result<()> {
+    let url = url::Url::parse(value)
+        .map_err(|e| miette!("{location}: `{value}` is not a valid URL ({e})"))?;
+
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(miette!(
+            help = "endpoints are reached over http:// or https://",
+            "{location}: `{value}` uses unsupported scheme `{}`",
+            url.scheme()
+        ));
+    }
+
+    if url.host_str().is_none() {
+        return Err(miette!("{location}: `{value}` has no host"));
+    }
+
+    Ok(())
+}
+
+fn check_network(network: &NetworkConfig, report: &mut Report) {
+    let location = format!("networks.{}", network.name);
+
+    report.check(check_url(&format!("{location}.trp.url"), &network.trp.url));
+    report.check(check_url(&format!("{location}.u5c.url"), &network.u5c.url));
+}
+
+fn check_registry(config: &RootConfig, report: &mut Report) {
+    if let Some(registry) = &config.registry {
+        report.check(check_url("registry.url", &registry.url));
+    }
+}
+
+fn check_profiles(config: &RootConfig, root: &Path, report: &mut Report) {
+    let networks = config.available_networks();
+
+    let mut profiles: Vec<_> = config.profiles.iter().collect();
+    profiles.sort_by_key(|(name, _)| name.as_str());
+
+    for (name, profile) in profiles {
+        let location = format!("profiles.{name}");
+
+        match config.resolve_profile(name) {
+            Err(problem) => report.push(miette!("{location}: {problem}")),
+            Ok(resolved) if !networks.contains(&resolved.network) => {
+                let mut known: Vec<_> = networks.iter().map(String::as_str).collect();
+                known.sort();
+
+                report.push(miette!(
+                    help = format!("known networks: {}", known.join(", ")),
+                    "{location}: network `{}` is not defined",
+                    resolved.network
+                ));
+            }
+            Ok(_) => {}
+        }
+
+        // Only an explicit env_file is an error when missing; the default
+        // `.env.<profile>` is optional.
+        if let Some(env_file) = &profile.env_file {
+            report.missing_file(&format!("{location}.env_file"), &root.join(env_file));
+        }
+
+        let mut identities: Vec<_> = profile.identities.iter().collect();
+        identities.sort_by_key(|(name, _)| name.as_str());
+
+        for (identity_name, identity) in identities {
+            if let IdentityConfig::ExplicitKey(explicit) = identity {
+                report.missing_file(
+                    &format!("{location}.identities.{identity_name}.key_path"),
+                    &root.join(&explicit.key_path),
+                );
+            }
+        }
+    }
+}
+
+fn check_codegen(config: &RootConfig, root: &Path, report: &mut Report) {
+    let mut job_ids = HashSet::new();
+
+    for (i, codegen) in config.codegen.iter().enumerate() {
+        let location = format!("codegen[{i}]");
+        let job_id = codegen.job_id();
+
+        if !job_ids.insert(job_id.clone()) {
+            report.push(miette!(
+                help = "set a distinct `job_id` on each [[codegen]] entry",
+                "{location}: job id `{job_id}` is used by an earlier entry"
+            ));
+        }
+
+        let plugin = CodegenPluginConfig::from(codegen.plugin.clone());
+        report.check(
+            TemplateSource::for_plugin(&plugin, root)
+                .map(|_| ())
+                .map_err(|problem| miette!("{location}.plugin: {problem}")),
+        );
+
+        for pattern in &codegen.verify_ignore {
+            if let Err(e) = globset::Glob::new(pattern) {
+                report.push(miette!(
+                    "{location}.verify_ignore: invalid glob `{pattern}`: {e}"
+                ));
+            }
+        }
+    }
+}
+
+fn validate(config: &RootConfig, root: &Path) -> Vec<miette::Error> {
+    let mut report = Report::default();
+
+    check_protocol(config, root, &mut report);
+    check_registry(config, &mut report);
+
+    let mut networks: Vec<_> = config.networks.values().collect();
+    networks.sort_by_key(|network| network.name());
+
+    for network in networks {
+        if let NetworkOption::Custom(network) = network {
+            check_network(network, &mut report);
+        }
+    }
+
+    check_profiles(config, root, &mut report);
+    check_codegen(config, root, &mut report);
+
+    report.problems
+}
+
+pub fn run(args: Args, config: &RootConfig, config_path: &Path) -> miette::Result<()> {
+    let root = config_path.parent().unwrap_or_else(|| Path::new("."));
+
+    // Secrets are reported as warnings: they don't make the file invalid.
+    if args.secrets_scan == Scan::On {
+        for finding in crate::commands::check::secrets::scan(config, root) {
+            eprintln!("{finding}");
+        }
+    }
+
+    let problems = validate(config, root);
+
+    if !problems.is_empty() {
+        return Err(Error { problems }.into());
+    }
+
+    println!("{} is valid", config_path.display());
+
+    Ok(())
+}
@@ -1,12 +1,24 @@
+use std::collections::HashMap;
+use std::path::Path;
+
 use crate::config::{ProfileConfig, RootConfig, U5cConfig};
 
 use utxorpc::{
-    Cardano, ChainUtxo, ClientBuilder, QueryClient,
-    spec::{cardano::TxOutput, query::TxoRef},
+    ChainUtxo,
+    spec::{
+        cardano::{AddressPattern, TxOutput, TxOutputPattern},
+        query::{AnyUtxoPattern, TxoRef, UtxoPredicate, any_utxo_pattern::UtxoPattern},
+    },
 };
 
 use clap::Args as ClapArgs;
-use miette::IntoDiagnostic;
+use miette::{Context as _, IntoDiagnostic, bail};
+use pallas::ledger::addresses::Address;
+use pallas::ledger::traverse::{Era, MultiEraOutput};
+
+use crate::devnet::{AddressSpec, ExplicitUtxoSpec, NativeBytesUtxoSpec, UtxoSpec};
+
+const PAGE_SIZE: u32 = 100;
 
 #[derive(ClapArgs, Debug)]
 pub struct Args {
@@ -15,8 +27,13 @@ pub struct Args {
     profile: String,
 
     /// Transaction hash to search the UTxO dependencies
-    #[arg(long)]
-    utxo_deps: String,
+    #[arg(long, required_unless_present = "all")]
+    utxo_deps: Option<String>,
+
+    /// Append every UTxO at this address to the devnet config; `@name`
+    /// resolves a wallet identity
+    #[arg(long, value_name = "ADDRESS", conflicts_with = "utxo_deps")]
+    all: Option<String>,
 
     /// Path to save the devnet config file
     #[arg(long)]
@@ -28,13 +45,17 @@ pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::
 
     let u5c = &network.u5c;
 
-    let tx_hash = args.utxo_deps;
-
     let mut output = crate::dirs::protocol_root()?.join("devnet.toml");
     if let Some(output_arg) = args.output {
         output = std::path::PathBuf::from(output_arg);
     }
 
+    if let Some(address) = &args.all {
+        return copy_all(address, config, profile, u5c, &output);
+    }
+
+    let tx_hash = args.utxo_deps.unwrap_or_default();
+
     let utxos = futures::executor::block_on(fetch_utxo_deps(u5c, &tx_hash))?;
 
     let mut devnet = crate::devnet::Config::default();
@@ -52,22 +73,213 @@ pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::
 
     let devnet_toml = toml::to_string_pretty(&devnet).into_diagnostic()?;
 
-    std::fs::write(output, devnet_toml).into_diagnostic()?;
+    crate::fsutil::write_atomic(&output, devnet_toml)?;
 
     Ok(())
 }
 
-async fn fetch_utxo_deps(
+/// An ADA-only output as an explicit spec, which reads and edits like the
+/// rest of devnet.toml; anything carrying assets, a datum or a script is
+/// kept as its raw bytes so nothing is lost.
+fn spec_for(r#ref: &str, native: &[u8]) -> miette::Result<UtxoSpec> {
+    let output = MultiEraOutput::decode(Era::Conway, native)
+        .into_diagnostic()
+        .with_context(|| format!("decoding utxo {ref}"))?;
+
+    let value = output.value();
+
+    if !value.assets().is_empty() || output.datum().is_some() || output.script_ref().is_some() {
+        return Ok(UtxoSpec::NativeBytes(NativeBytesUtxoSpec {
+            r#ref: r#ref.to_string(),
+            raw_bytes: hex::encode(native),
+        }));
+    }
+
+    let address = output
+        .address()
+        .into_diagnostic()?
+        .to_bech32()
+        .into_diagnostic()?;
+
+    Ok(UtxoSpec::Explicit(ExplicitUtxoSpec {
+        address: AddressSpec::Address(address),
+        value: value.coin(),
+        assets: Default::default(),
+        datum_inline: None,
+        datum_hash: None,
+        script_ref: None,
+        script_language: None,
+    }))
+}
+
+/// Specs for `utxos`, as `(ref, output cbor)` in ref order, checked the way
+/// devnet startup will load them.
+fn specs(mut utxos: Vec<(String, Vec<u8>)>) -> miette::Result<Vec<UtxoSpec>> {
+    utxos.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let specs = utxos
+        .iter()
+        .map(|(r#ref, native)| spec_for(r#ref, native))
+        .collect::<miette::Result<Vec<_>>>()?;
+
+    let copied = crate::devnet::Config {
+        utxos: specs,
+        ..Default::default()
+    };
+    crate::devnet::build_dolos_utxos(&copied, &HashMap::new())?;
+
+    Ok(copied.utxos)
+}
+
+/// The `[[utxos]]` entries for `specs`, to append to devnet.toml.
+fn render_entries(address: &str, specs: Vec<UtxoSpec>) -> miette::Result<String> {
+    let copied = crate::devnet::Config {
+        utxos: specs,
+        ..Default::default()
+    };
+
+    let entries = toml::to_string_pretty(&copied).into_diagnostic()?;
+
+    Ok(format!(
+        "\n# copied from {address} by `trix devnet copy --all`\n{entries}"
+    ))
+}
+
+fn copy_all(
+    address: &str,
+    config: &RootConfig,
+    profile: &ProfileConfig,
     u5c: &U5cConfig,
-    tx_hash: &str,
-) -> miette::Result<Vec<ChainUtxo<TxOutput>>> {
-    let mut client_builder = ClientBuilder::new().uri(&u5c.url).into_diagnostic()?;
+    output: &Path,
+) -> miette::Result<()> {
+    let address = match address.strip_prefix('@') {
+        Some(name) => {
+            let wallet = crate::wallet::setup(config, profile)?;
+            let Some(address) = wallet.addresses.get(name) else {
+                bail!("unknown wallet `@{name}` in profile `{}`", profile.name);
+            };
+            address.clone()
+        }
+        None => address.to_string(),
+    };
+
+    let utxos = futures::executor::block_on(fetch_at(u5c, &address))?;
+
+    if utxos.is_empty() {
+        bail!(
+            "no utxos at {address} on profile `{}`'s network",
+            profile.name
+        );
+    }
+
+    let specs = specs(utxos)?;
+    let count = specs.len();
+
+    let mut devnet_toml = match output.is_file() {
+        true => std::fs::read_to_string(output)
+            .into_diagnostic()
+            .with_context(|| format!("reading {}", output.display()))?,
+        false => String::new(),
+    };
+
+    devnet_toml.push_str(&render_entries(&address, specs)?);
+
+    toml::from_str::<crate::devnet::Config>(&devnet_toml)
+        .into_diagnostic()
+        .context("appending the copied utxos produced an invalid devnet config")?;
+
+    crate::fsutil::write_atomic(output, devnet_toml)?;
+
+    println!(
+        "copied {count} utxos from {address} to {}",
+        output.display()
+    );
+
+    Ok(())
+}
+
+/// A search for the UTxOs at exactly `address`.
+fn address_predicate(address: &str) -> miette::Result<UtxoPredicate> {
+    let exact = Address::from_bech32(address)
+        .into_diagnostic()
+        .with_context(|| format!("invalid address {address}"))?
+        .to_vec();
+
+    Ok(UtxoPredicate {
+        r#match: Some(AnyUtxoPattern {
+            utxo_pattern: Some(UtxoPattern::Cardano(TxOutputPattern {
+                address: Some(AddressPattern {
+                    exact_address: exact.into(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })),
+        }),
+        ..Default::default()
+    })
+}
+
+/// One page of a UTxO search: its `(ref, output cbor)` items and the token
+/// of the page after it, empty or missing on the last one.
+type Page = (Vec<(String, Vec<u8>)>, Option<String>);
 
-    for (key, value) in u5c.headers.iter() {
-        client_builder = client_builder.metadata(key, value).into_diagnostic()?;
+/// Every item of a paged search. `search` fetches the page starting at a
+/// token, `None` for the first.
+async fn collect_pages(
+    mut search: impl AsyncFnMut(Option<String>) -> miette::Result<Page>,
+) -> miette::Result<Vec<(String, Vec<u8>)>> {
+    let mut out = vec![];
+    let mut start = None;
+
+    loop {
+        let (items, next) = search(start).await?;
+        out.extend(items);
+
+        match next {
+            Some(next) if !next.is_empty() => start = Some(next),
+            _ => break,
+        }
     }
 
-    let mut client = client_builder.build::<QueryClient<Cardano>>().await;
+    Ok(out)
+}
+
+/// Every UTxO at `address`, as `(ref, output cbor)`.
+pub(crate) async fn fetch_at(
+    u5c: &U5cConfig,
+    address: &str,
+) -> miette::Result<Vec<(String, Vec<u8>)>> {
+    let predicate = address_predicate(address)?;
+
+    let mut client = crate::net::u5c_client(u5c).await?;
+
+    collect_pages(async |start| {
+        let page = client
+            .search_utxos(predicate.clone(), start, PAGE_SIZE)
+            .await
+            .into_diagnostic()
+            .with_context(|| format!("querying utxos at {address}"))?;
+
+        let items = page
+            .items
+            .into_iter()
+            .filter_map(|utxo| {
+                let txo_ref = utxo.txo_ref?;
+                let r#ref = format!("{}#{}", hex::encode(&txo_ref.hash), txo_ref.index);
+                Some((r#ref, utxo.native.to_vec()))
+            })
+            .collect();
+
+        Ok((items, page.next))
+    })
+    .await
+}
+
+async fn fetch_utxo_deps(
+    u5c: &U5cConfig,
+    tx_hash: &str,
+) -> miette::Result<Vec<ChainUtxo<TxOutput>>> {
+    let mut client = crate::net::u5c_client(u5c).await?;
 
     let tx_hash_bytes = hex::decode(tx_hash).into_diagnostic()?;
 
@@ -96,3 +308,107 @@ async fn fetch_utxo_deps(
 
     Ok(vec![])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "addr_test1vqqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgasfzjt";
+
+    fn ada_only() -> Vec<u8> {
+        hex::decode(format!("82581d60{}1a004c4b40", "01".repeat(28))).unwrap()
+    }
+
+    fn with_token() -> Vec<u8> {
+        hex::decode(format!(
+            "82581d60{}821a001e8480a1581c{}a1447465737401",
+            "01".repeat(28),
+            "02".repeat(28)
+        ))
+        .unwrap()
+    }
+
+    /// What `search_utxos` returns for ALICE, out of ref order.
+    fn mock_response() -> Vec<(String, Vec<u8>)> {
+        vec![
+            (format!("{}#1", "bb".repeat(32)), with_token()),
+            (format!("{}#0", "aa".repeat(32)), ada_only()),
+        ]
+    }
+
+    #[test]
+    fn ada_only_utxos_become_explicit_specs() {
+        let specs = specs(mock_response()).unwrap();
+
+        match &specs[..] {
+            [UtxoSpec::Explicit(ada), UtxoSpec::NativeBytes(token)] => {
+                assert_eq!(ada.address, AddressSpec::Address(ALICE.to_string()));
+                assert_eq!(ada.value, 5_000_000);
+                assert_eq!(token.r#ref, format!("{}#1", "bb".repeat(32)));
+                assert_eq!(token.raw_bytes, hex::encode(with_token()));
+            }
+            other => panic!("unexpected specs: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn copied_utxos_append_to_devnet_toml() {
+        let mut devnet_toml = "[[utxos]]\naddress = \"@alice\"\nvalue = 1000000\n".to_string();
+        devnet_toml.push_str(&render_entries(ALICE, specs(mock_response()).unwrap()).unwrap());
+
+        assert!(
+            devnet_toml.contains(&format!("# copied from {ALICE}")),
+            "{devnet_toml}"
+        );
+
+        let devnet: crate::devnet::Config = toml::from_str(&devnet_toml).unwrap();
+        assert_eq!(devnet.utxos.len(), 3);
+    }
+
+    #[test]
+    fn fetch_follows_every_page() {
+        let pages: Vec<Page> = vec![
+            (vec![mock_response()[0].clone()], Some("second".to_string())),
+            (vec![mock_response()[1].clone()], Some("third".to_string())),
+            (vec![], Some(String::new())),
+        ];
+
+        let mut starts = vec![];
+
+        let found = futures::executor::block_on(collect_pages(async |start| {
+            starts.push(start);
+            Ok(pages[starts.len() - 1].clone())
+        }))
+        .unwrap();
+
+        assert_eq!(
+            starts,
+            [None, Some("second".to_string()), Some("third".to_string())]
+        );
+        assert_eq!(found, mock_response());
+    }
+
+    #[test]
+    fn fetch_searches_the_exact_address() {
+        let predicate = address_predicate(ALICE).unwrap();
+
+        let Some(AnyUtxoPattern {
+            utxo_pattern: Some(UtxoPattern::Cardano(pattern)),
+        }) = predicate.r#match
+        else {
+            panic!("no cardano pattern");
+        };
+
+        let exact = Address::from_bech32(ALICE).unwrap().to_vec();
+        assert_eq!(pattern.address.unwrap().exact_address.to_vec(), exact);
+
+        assert!(address_predicate("addr_nope").is_err());
+    }
+
+    #[test]
+    fn undecodable_utxos_are_rejected() {
+        let response = vec![(format!("{}#0", "aa".repeat(32)), vec![0xff])];
+
+        assert!(specs(response).is_err());
+    }
+}
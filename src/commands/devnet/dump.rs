@@ -0,0 +1,347 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+use clap::{Args as ClapArgs, ValueEnum};
+use miette::{Context as _, IntoDiagnostic, bail};
+use pallas::ledger::traverse::{Era, MultiEraOutput};
+use serde::Serialize;
+use utxorpc::spec::query::UtxoPredicate;
+
+use crate::config::{KnownNetwork, NetworkConfig, ProfileConfig, RootConfig, U5cConfig};
+use crate::devnet::Ports;
+use crate::spawn::shutdown;
+use crate::wallet::Derivation;
+
+const PAGE_SIZE: u32 = 100;
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Format {
+    /// One JSON object per UTxO
+    Ndjson,
+    /// `[[utxos]]` entries that can be pasted into devnet.toml
+    TomlSpec,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Only dump UTxOs at this address; `@name` resolves a wallet identity
+    #[arg(long)]
+    address: Option<String>,
+
+    /// File to write the dump to, instead of stdout
+    #[arg(long)]
+    out: Option<PathBuf>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Ndjson)]
+    format: Format,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DumpAsset {
+    pub policy: String,
+    pub name: String,
+    pub amount: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DumpUtxo {
+    #[serde(rename = "ref")]
+    pub r#ref: String,
+    pub address: String,
+    pub value: u64,
+    pub assets: Vec<DumpAsset>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datum_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inline_datum: Option<String>,
+    /// Raw output CBOR, kept for the `toml-spec` format.
+    #[serde(skip)]
+    pub raw_bytes: String,
+}
+
+pub(crate) fn decode_utxo(r#ref: String, native: &[u8]) -> miette::Result<DumpUtxo> {
+    let output = MultiEraOutput::decode(Era::Conway, native)
+        .into_diagnostic()
+        .with_context(|| format!("decoding utxo {ref}"))?;
+
+    let address = output
+        .address()
+        .into_diagnostic()?
+        .to_bech32()
+        .into_diagnostic()?;
+
+    let value = output.value();
+
+    let assets = value
+        .assets()
+        .iter()
+        .flat_map(|policy| {
+            policy.assets().into_iter().map(move |asset| DumpAsset {
+                policy: hex::encode(policy.policy()),
+                name: hex::encode(asset.name()),
+                amount: asset.any_coin() as u64,
+            })
+        })
+        .collect();
+
+    let (datum_hash, inline_datum) = match output.datum() {
+        Some(pallas::ledger::primitives::conway::DatumOption::Hash(hash)) => {
+            (Some(hex::encode(hash)), None)
+        }
+        Some(pallas::ledger::primitives::conway::DatumOption::Data(data)) => {
+            let cbor = pallas::codec::minicbor::to_vec(&data.0).into_diagnostic()?;
+            (None, Some(hex::encode(cbor)))
+        }
+        None => (None, None),
+    };
+
+    Ok(DumpUtxo {
+        r#ref,
+        address,
+        value: value.coin(),
+        assets,
+        datum_hash,
+        inline_datum,
+        raw_bytes: hex::encode(native),
+    })
+}
+
+/// u5c endpoint of the project's running devnet, at the port its dolos.toml
+/// serves. The profile may point at another network, or at the devnet's
+/// default port when devnet.toml moved it.
+pub(crate) fn devnet_u5c(config: &RootConfig) -> miette::Result<U5cConfig> {
+    let home = crate::devnet::home_dir(&Derivation::for_project(config).tag())?;
+
+    let running = crate::devnet::lock::holder(&home).is_some_and(|h| shutdown::alive(h.pid));
+    let port = super::status::service_port(&home, "grpc").filter(|_| running);
+
+    let Some(port) = port else {
+        bail!(
+            help = "start one with `trix devnet` or `trix devnet --background`",
+            "no devnet is running for this project"
+        );
+    };
+
+    let mut network = NetworkConfig::from(KnownNetwork::CardanoLocal);
+    Ports {
+        grpc: Some(port),
+        ..Default::default()
+    }
+    .apply(&mut network);
+
+    Ok(network.u5c)
+}
+
+/// Page through every UTxO the node reports. The predicate is left empty
+/// so the whole set comes back; a devnet's set is small enough to filter
+/// afterwards.
+pub(crate) async fn fetch_all(u5c: &U5cConfig) -> miette::Result<Vec<DumpUtxo>> {
+    let mut client = crate::net::u5c_client(u5c).await?;
+
+    let mut out = vec![];
+    let mut start = None;
+
+    loop {
+        let page = client
+            .search_utxos(UtxoPredicate::default(), start, PAGE_SIZE)
+            .await
+            .into_diagnostic()
+            .context("querying devnet utxos")?;
+
+        for utxo in page.items {
+            let Some(txo_ref) = utxo.txo_ref else {
+                continue;
+            };
+
+            let r#ref = format!("{}#{}", hex::encode(&txo_ref.hash), txo_ref.index);
+            out.push(decode_utxo(r#ref, &utxo.native)?);
+        }
+
+        match page.next {
+            Some(next) if !next.is_empty() => start = Some(next),
+            _ => break,
+        }
+    }
+
+    Ok(out)
+}
+
+pub(crate) fn render(utxos: &[DumpUtxo], format: Format) -> miette::Result<String> {
+    match format {
+        Format::Ndjson => {
+            let mut out = String::new();
+            for utxo in utxos {
+                out.push_str(&serde_json::to_string(utxo).into_diagnostic()?);
+                out.push('\n');
+            }
+            Ok(out)
+        }
+        Format::TomlSpec => {
+            let devnet = crate::devnet::Config {
+                utxos: utxos
+                    .iter()
+                    .map(|utxo| {
+                        crate::devnet::UtxoSpec::NativeBytes(crate::devnet::NativeBytesUtxoSpec {
+                            r#ref: utxo.r#ref.clone(),
+                            raw_bytes: utxo.raw_bytes.clone(),
+                        })
+                    })
+                    .collect(),
+                ports: Default::default(),
+                chain: Default::default(),
+            };
+
+            toml::to_string_pretty(&devnet).into_diagnostic()
+        }
+    }
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    let address = match args.address.as_deref() {
+        Some(alias) if alias.starts_with('@') => {
+            let wallet = crate::wallet::setup(config, profile)?;
+            let name = alias.trim_start_matches('@');
+            let Some(address) = wallet.addresses.get(name) else {
+                bail!("unknown wallet `{alias}` in profile `{}`", profile.name);
+            };
+            Some(address.clone())
+        }
+        other => other.map(str::to_string),
+    };
+
+    let u5c = devnet_u5c(config)?;
+
+    let mut utxos = futures::executor::block_on(fetch_all(&u5c))?;
+
+    if let Some(address) = &address {
+        utxos.retain(|utxo| &utxo.address == address);
+    }
+
+    // Sorted by ref so two dumps of the same chain state diff cleanly.
+    utxos.sort_by(|a, b| a.r#ref.cmp(&b.r#ref));
+
+    let rendered = render(&utxos, args.format)?;
+
+    match &args.out {
+        Some(path) => std::fs::write(path, rendered)
+            .into_diagnostic()
+            .with_context(|| format!("writing {}", path.display()))?,
+        None => std::io::stdout()
+            .lock()
+            .write_all(rendered.as_bytes())
+            .into_diagnostic()?,
+    }
+
+    let lovelace: u64 = utxos.iter().map(|u| u.value).sum();
+    let with_assets = utxos.iter().filter(|u| !u.assets.is_empty()).count();
+    let with_datum = utxos
+        .iter()
+        .filter(|u| u.datum_hash.is_some() || u.inline_datum.is_some())
+        .count();
+
+    eprintln!(
+        "{} utxos, {} lovelace, {} with assets, {} with datum",
+        utxos.len(),
+        lovelace,
+        with_assets,
+        with_datum
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use super::*;
+    use crate::devnet::{AddressSpec, ExplicitUtxoSpec};
+
+    const POLICY: &str = "0000000000000000000000000000000000000000000000000000000a";
+
+    fn address() -> String {
+        let mut bytes = vec![0x60];
+        bytes.extend([7u8; 28]);
+        pallas::ledger::addresses::Address::from_bytes(&bytes)
+            .unwrap()
+            .to_bech32()
+            .unwrap()
+    }
+
+    /// Output CBOR as devnet.toml would seed it.
+    fn native(datum_inline: Option<&str>, datum_hash: Option<&str>) -> Vec<u8> {
+        let spec = ExplicitUtxoSpec {
+            address: AddressSpec::Address(address()),
+            value: 5_000_000,
+            assets: BTreeMap::from([(format!("{POLICY}.6e6674"), 1)]),
+            datum_inline: datum_inline.map(|d| serde_json::Value::String(d.to_string())),
+            datum_hash: datum_hash.map(str::to_string),
+            script_ref: None,
+            script_language: None,
+        };
+
+        crate::devnet::dolos_utxo_from_explicit_spec(&spec, 0, &HashMap::new())
+            .unwrap()
+            .cbor
+    }
+
+    #[test]
+    fn decodes_value_assets_and_inline_datum() {
+        let native = native(Some("d87981182a"), None);
+
+        let utxo = decode_utxo("aa#0".to_string(), &native).unwrap();
+
+        assert_eq!(utxo.r#ref, "aa#0");
+        assert_eq!(utxo.address, address());
+        assert_eq!(utxo.value, 5_000_000);
+        assert_eq!(utxo.assets.len(), 1);
+        assert_eq!(utxo.assets[0].policy, POLICY);
+        assert_eq!(utxo.assets[0].name, "6e6674");
+        assert_eq!(utxo.assets[0].amount, 1);
+        assert_eq!(utxo.inline_datum.as_deref(), Some("d87981182a"));
+        assert_eq!(utxo.datum_hash, None);
+        assert_eq!(utxo.raw_bytes, hex::encode(&native));
+    }
+
+    #[test]
+    fn decodes_datum_hash() {
+        let hash = "ab".repeat(32);
+
+        let utxo = decode_utxo("aa#1".to_string(), &native(None, Some(&hash))).unwrap();
+
+        assert_eq!(utxo.datum_hash, Some(hash));
+        assert_eq!(utxo.inline_datum, None);
+    }
+
+    #[test]
+    fn renders_ndjson_and_a_devnet_spec() {
+        let utxos = vec![
+            decode_utxo("aa#0".to_string(), &native(None, None)).unwrap(),
+            decode_utxo("bb#1".to_string(), &native(Some("d87981182a"), None)).unwrap(),
+        ];
+
+        let ndjson = render(&utxos, Format::Ndjson).unwrap();
+        let lines: Vec<serde_json::Value> = ndjson
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["ref"], "aa#0");
+        assert_eq!(lines[0]["value"], 5_000_000);
+        assert!(lines[0].get("inline_datum").is_none());
+        assert!(lines[0].get("raw_bytes").is_none());
+        assert_eq!(lines[1]["inline_datum"], "d87981182a");
+
+        let spec: crate::devnet::Config =
+            toml::from_str(&render(&utxos, Format::TomlSpec).unwrap()).unwrap();
+
+        assert_eq!(spec.utxos.len(), 2);
+        let crate::devnet::UtxoSpec::NativeBytes(first) = &spec.utxos[0] else {
+            panic!("expected a native bytes spec");
+        };
+        assert_eq!(first.r#ref, "aa#0");
+        assert_eq!(first.raw_bytes, utxos[0].raw_bytes);
+    }
+}
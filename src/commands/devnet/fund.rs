@@ -0,0 +1,163 @@
+//! `trix devnet fund`: give a project wallet more lovelace while a
+//! background devnet is running. Dolos keeps its ledger in memory and seeds
+//! UTxOs only from genesis, so the new UTxO is appended to devnet.toml and
+//! the devnet is restarted to pick it up, which discards every transaction
+//! submitted to it since it started; hence the confirmation.
+
+use std::path::{Path, PathBuf};
+
+use clap::Args as ClapArgs;
+use miette::{Context as _, IntoDiagnostic as _, bail};
+
+use crate::config::{ProfileConfig, RootConfig};
+use crate::devnet::{AddressSpec, ExplicitUtxoSpec};
+use crate::spawn::shutdown;
+use crate::wallet::Derivation;
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Wallet to fund, e.g. `alice` or `@alice`
+    wallet: String,
+
+    /// Lovelace to add, as a new UTxO
+    #[arg(value_parser = clap::value_parser!(u64).range(1..))]
+    amount: u64,
+
+    /// Path to the devnet config file
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Seconds to wait for the running devnet to exit before restarting it
+    #[arg(long, default_value_t = 10)]
+    timeout: u64,
+
+    /// Restart the devnet without asking
+    #[arg(short, long)]
+    yes: bool,
+}
+
+fn render_entry(wallet: &str, amount: u64) -> String {
+    format!(
+        "\n# added by `trix devnet fund`\n[[utxos]]\naddress = \"@{wallet}\"\nvalue = {amount}\n"
+    )
+}
+
+/// Append the funding entry to the devnet.toml text at `path`, checking the
+/// result still loads.
+fn append(path: &Path, wallet: &str, amount: u64) -> miette::Result<()> {
+    let mut devnet_toml = std::fs::read_to_string(path)
+        .into_diagnostic()
+        .with_context(|| format!("reading {}", path.display()))?;
+
+    devnet_toml.push_str(&render_entry(wallet, amount));
+
+    toml::from_str::<crate::devnet::Config>(&devnet_toml)
+        .into_diagnostic()
+        .context("appending the funding utxo produced an invalid devnet config")?;
+
+    crate::fsutil::write_atomic(path, devnet_toml)
+}
+
+fn confirm(pid: u32) -> miette::Result<bool> {
+    if !crate::term::prompt::is_interactive() {
+        bail!(
+            help = "pass --yes to restart it without asking",
+            "funding restarts the running devnet (pid {pid}) and discards its chain state"
+        );
+    }
+
+    crate::term::prompt::confirm(
+        "Restart the devnet? Transactions submitted since it started are lost",
+        false,
+    )
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    let home = crate::devnet::home_dir(&Derivation::for_project(config).tag())?;
+
+    let Some(pid) = crate::devnet::read_pid(&home).filter(|pid| shutdown::group_alive(*pid)) else {
+        bail!(
+            help = "start one with `trix devnet --background`, or add the utxo to devnet.toml yourself",
+            "no background devnet is running for this project"
+        );
+    };
+
+    let path = match args.config {
+        Some(path) => path,
+        None => crate::dirs::protocol_root()?.join("devnet.toml"),
+    };
+
+    let name = args.wallet.trim_start_matches('@').to_string();
+
+    let wallet = crate::wallet::setup(config, profile)?;
+
+    if !wallet.addresses.contains_key(&name) {
+        let mut known: Vec<_> = wallet.addresses.keys().cloned().collect();
+        known.sort();
+
+        bail!(
+            help = format!("wallets: {}", known.join(", ")),
+            "unknown wallet `{name}`"
+        );
+    }
+
+    let devnet = crate::devnet::Config::load(&path)?;
+
+    // Build the UTxO the way devnet startup will, so a bad spec fails here
+    // rather than after the running devnet was stopped.
+    let spec = ExplicitUtxoSpec {
+        address: AddressSpec::NamedWallet(name.clone()),
+        value: args.amount,
+        assets: Default::default(),
+        datum_inline: None,
+        datum_hash: None,
+        script_ref: None,
+        script_language: None,
+    };
+    crate::devnet::dolos_utxo_from_explicit_spec(&spec, devnet.utxos.len(), &wallet.addresses)?;
+
+    if !args.yes && !confirm(pid)? {
+        println!("devnet left running; nothing funded");
+        return Ok(());
+    }
+
+    append(&path, &name, args.amount)?;
+
+    super::stop::terminate(pid, args.timeout)?;
+    crate::devnet::clear_pid(&home);
+
+    let devnet = crate::devnet::Config::load(&path)?;
+    let ctx = crate::devnet::Context::from_wallet(&wallet);
+    crate::devnet::start_supervised(&devnet, &ctx)?;
+    super::manifest::write(&home, config, &devnet, &ctx.aliases)?;
+
+    println!("funded @{name} with {} lovelace", args.amount);
+    println!("devnet restarted to apply {}", path.display());
+    eprintln!("warning: transactions submitted since the devnet started were discarded");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appended_entry_loads_as_named_wallet_utxo() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("devnet.toml");
+        std::fs::write(&path, "[[utxos]]\naddress = \"@bob\"\nvalue = 5000000\n").unwrap();
+
+        append(&path, "alice", 123_456_789).unwrap();
+
+        let devnet = crate::devnet::Config::load(&path).unwrap();
+
+        assert_eq!(devnet.utxos.len(), 2);
+        assert!(matches!(
+            &devnet.utxos[1],
+            crate::devnet::UtxoSpec::Explicit(spec)
+                if spec.address == AddressSpec::NamedWallet("alice".to_string())
+                    && spec.value == 123_456_789
+        ));
+    }
+}
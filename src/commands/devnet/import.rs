@@ -0,0 +1,449 @@
+//! `trix devnet import`: turn a Blockfrost or Koios address-UTxO export
+//! into `[[utxos]]` native-bytes entries. Each output is rebuilt from the
+//! JSON and re-encoded with pallas, so the seeded devnet holds the same
+//! address, value, assets and datum as the exported chain.
+
+use std::path::PathBuf;
+
+use clap::{Args as ClapArgs, ValueEnum};
+use miette::{Context as _, IntoDiagnostic, bail};
+use pallas::codec::utils::{CborWrap, KeepRaw};
+use pallas::crypto::hash::Hash;
+use pallas::ledger::primitives::conway::{
+    DatumOption, PlutusData, PostAlonzoTransactionOutput, TransactionOutput, Value,
+};
+use serde::Deserialize;
+
+use crate::config::{ProfileConfig, RootConfig};
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Format {
+    /// `GET /addresses/{address}/utxos`
+    Blockfrost,
+    /// `POST /address_utxos` or `/utxo_info`
+    Koios,
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Format::Blockfrost => write!(f, "blockfrost"),
+            Format::Koios => write!(f, "koios"),
+        }
+    }
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Shape of the export
+    #[arg(long, value_enum)]
+    format: Format,
+
+    /// JSON export to read
+    file: PathBuf,
+
+    /// Devnet config to append to
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+// ============================================================================
+// Export shapes
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct BlockfrostAmount {
+    unit: String,
+    quantity: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockfrostUtxo {
+    address: String,
+    tx_hash: String,
+    output_index: u64,
+    amount: Vec<BlockfrostAmount>,
+    data_hash: Option<String>,
+    inline_datum: Option<String>,
+    reference_script_hash: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KoiosAsset {
+    policy_id: String,
+    asset_name: Option<String>,
+    quantity: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct KoiosInlineDatum {
+    bytes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KoiosUtxo {
+    address: String,
+    tx_hash: String,
+    tx_index: u64,
+    value: String,
+    #[serde(default)]
+    asset_list: Option<Vec<KoiosAsset>>,
+    datum_hash: Option<String>,
+    inline_datum: Option<KoiosInlineDatum>,
+    reference_script: Option<serde_json::Value>,
+}
+
+// ============================================================================
+// Common model
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Datum {
+    None,
+    Hash(String),
+    /// Hex CBOR of the datum body.
+    Inline(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Imported {
+    r#ref: String,
+    address: String,
+    lovelace: u64,
+    assets: Vec<crate::devnet::assets::Asset>,
+    datum: Datum,
+}
+
+fn quantity(value: &str) -> Result<u64, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid quantity `{value}`"))
+}
+
+impl TryFrom<BlockfrostUtxo> for Imported {
+    type Error = String;
+
+    fn try_from(utxo: BlockfrostUtxo) -> Result<Self, String> {
+        if utxo.reference_script_hash.is_some() {
+            return Err("carries a reference script, which the export only gives as a hash".into());
+        }
+
+        let mut lovelace = 0;
+        let mut assets = vec![];
+
+        for amount in &utxo.amount {
+            if amount.unit == "lovelace" {
+                lovelace = quantity(&amount.quantity)?;
+            } else if amount.unit.len() >= 56 {
+                let (policy, name) = amount.unit.split_at(56);
+                assets.push((policy.to_string(), name.to_string(), quantity(&amount.quantity)?));
+            } else {
+                return Err(format!("unknown unit `{}`", amount.unit));
+            }
+        }
+
+        // Blockfrost reports `data_hash` for inline datums too; the body
+        // is what decides.
+        let datum = match (utxo.inline_datum, utxo.data_hash) {
+            (Some(body), _) => Datum::Inline(body),
+            (None, Some(hash)) => Datum::Hash(hash),
+            (None, None) => Datum::None,
+        };
+
+        Ok(Imported {
+            r#ref: format!("{}#{}", utxo.tx_hash, utxo.output_index),
+            address: utxo.address,
+            lovelace,
+            assets,
+            datum,
+        })
+    }
+}
+
+impl TryFrom<KoiosUtxo> for Imported {
+    type Error = String;
+
+    fn try_from(utxo: KoiosUtxo) -> Result<Self, String> {
+        if utxo.reference_script.is_some() {
+            return Err("carries a reference script, which is not imported".into());
+        }
+
+        let assets = utxo
+            .asset_list
+            .unwrap_or_default()
+            .into_iter()
+            .map(|asset| {
+                Ok((
+                    asset.policy_id,
+                    asset.asset_name.unwrap_or_default(),
+                    quantity(&asset.quantity)?,
+                ))
+            })
+            .collect::<Result<_, String>>()?;
+
+        let datum = match (utxo.inline_datum, utxo.datum_hash) {
+            (Some(KoiosInlineDatum { bytes: Some(body) }), _) => Datum::Inline(body),
+            (Some(KoiosInlineDatum { bytes: None }), _) => {
+                return Err("inline datum has no body in the export".into());
+            }
+            (None, Some(hash)) => Datum::Hash(hash),
+            (None, None) => Datum::None,
+        };
+
+        Ok(Imported {
+            r#ref: format!("{}#{}", utxo.tx_hash, utxo.tx_index),
+            address: utxo.address,
+            lovelace: quantity(&utxo.value)?,
+            assets,
+            datum,
+        })
+    }
+}
+
+/// Parse the export into one result per entry, so a single bad entry
+/// only costs that entry.
+fn parse(content: &str, format: Format) -> miette::Result<Vec<(usize, Result<Imported, String>)>> {
+    let entries: Vec<serde_json::Value> = serde_json::from_str(content)
+        .into_diagnostic()
+        .context("the export should be a JSON array of utxos")?;
+
+    Ok(entries
+        .into_iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let imported = match format {
+                Format::Blockfrost => serde_json::from_value::<BlockfrostUtxo>(entry)
+                    .map_err(|e| e.to_string())
+                    .and_then(Imported::try_from),
+                Format::Koios => serde_json::from_value::<KoiosUtxo>(entry)
+                    .map_err(|e| e.to_string())
+                    .and_then(Imported::try_from),
+            };
+            (i, imported)
+        })
+        .collect())
+}
+
+// ============================================================================
+// Output reconstruction
+// ============================================================================
+
+fn hex_hash<const N: usize>(value: &str, what: &str) -> Result<Hash<N>, String> {
+    let bytes = hex::decode(value).map_err(|_| format!("{what} `{value}` is not hex"))?;
+    let bytes: [u8; N] = bytes
+        .try_into()
+        .map_err(|_| format!("{what} `{value}` is not {N} bytes"))?;
+    Ok(Hash::new(bytes))
+}
+
+fn build_value(utxo: &Imported) -> Result<Value, String> {
+    crate::devnet::assets::value(utxo.lovelace, &utxo.assets).map_err(|e| e.to_string())
+}
+
+/// Re-encode `utxo` as Conway output CBOR.
+fn encode(utxo: &Imported) -> Result<Vec<u8>, String> {
+    let address = pallas::ledger::addresses::Address::from_bech32(&utxo.address)
+        .map_err(|e| format!("invalid address `{}`: {e}", utxo.address))?;
+
+    let datum_bytes = match &utxo.datum {
+        Datum::Inline(body) => {
+            Some(hex::decode(body).map_err(|_| "inline datum is not hex".to_string())?)
+        }
+        _ => None,
+    };
+
+    let datum_option = match (&utxo.datum, &datum_bytes) {
+        (Datum::Hash(hash), _) => Some(DatumOption::Hash(hex_hash::<32>(hash, "datum hash")?)),
+        (Datum::Inline(_), Some(bytes)) => {
+            let data: KeepRaw<PlutusData> = pallas::codec::minicbor::decode(bytes)
+                .map_err(|e| format!("inline datum is not valid CBOR: {e}"))?;
+            Some(DatumOption::Data(CborWrap(data)))
+        }
+        _ => None,
+    };
+
+    let output = TransactionOutput::PostAlonzo(KeepRaw::from(PostAlonzoTransactionOutput {
+        address: address.to_vec().into(),
+        value: build_value(utxo)?,
+        datum_option: datum_option.map(Into::into),
+        script_ref: None,
+    }));
+
+    pallas::codec::minicbor::to_vec(&output).map_err(|e| e.to_string())
+}
+
+fn render_entry(utxo: &Imported, raw_bytes: &[u8], format: Format, source: &str) -> String {
+    format!(
+        "\n# imported from {format} export {source}: {} ({} lovelace, {} assets)\n[[utxos]]\nref = \"{}\"\nraw_bytes = \"{}\"\n",
+        utxo.address,
+        utxo.lovelace,
+        utxo.assets.len(),
+        utxo.r#ref,
+        hex::encode(raw_bytes)
+    )
+}
+
+// ============================================================================
+// Command Entry Point
+// ============================================================================
+
+pub fn run(args: Args, _config: &RootConfig, _profile: &ProfileConfig) -> miette::Result<()> {
+    let out = match args.out {
+        Some(path) => path,
+        None => crate::dirs::protocol_root()?.join("devnet.toml"),
+    };
+
+    let content = std::fs::read_to_string(&args.file)
+        .into_diagnostic()
+        .with_context(|| format!("reading {}", args.file.display()))?;
+
+    let mut devnet_toml = if out.is_file() {
+        std::fs::read_to_string(&out).into_diagnostic()?
+    } else {
+        String::new()
+    };
+
+    let existing = if devnet_toml.is_empty() {
+        crate::devnet::Config::default()
+    } else {
+        crate::devnet::Config::load(&out)?
+    };
+
+    let mut known: Vec<String> = existing
+        .utxos
+        .iter()
+        .filter_map(|spec| match spec {
+            crate::devnet::UtxoSpec::NativeBytes(x) => Some(x.r#ref.clone()),
+            crate::devnet::UtxoSpec::Explicit(_) => None,
+        })
+        .collect();
+
+    let source = args.file.display().to_string();
+    let mut imported = 0;
+    let mut skipped = 0;
+
+    for (i, entry) in parse(&content, args.format)? {
+        let result = entry.and_then(|utxo| {
+            if known.contains(&utxo.r#ref) {
+                return Err(format!("{} is already in {}", utxo.r#ref, out.display()));
+            }
+            encode(&utxo).map(|cbor| (utxo, cbor))
+        });
+
+        match result {
+            Ok((utxo, cbor)) => {
+                devnet_toml.push_str(&render_entry(&utxo, &cbor, args.format, &source));
+                known.push(utxo.r#ref);
+                imported += 1;
+            }
+            Err(reason) => {
+                eprintln!("warning: skipping entry {i}: {reason}");
+                skipped += 1;
+            }
+        }
+    }
+
+    if imported == 0 {
+        bail!("no utxos imported from {source} ({skipped} skipped)");
+    }
+
+    // The appended text must still load as a devnet config.
+    toml::from_str::<crate::devnet::Config>(&devnet_toml)
+        .into_diagnostic()
+        .context("appending imported utxos produced an invalid devnet config")?;
+
+    std::fs::write(&out, devnet_toml)
+        .into_diagnostic()
+        .with_context(|| format!("writing {}", out.display()))?;
+
+    eprintln!(
+        "imported {imported} utxos into {}, skipped {skipped}",
+        out.display()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: &str = "a0028f350aaabe0545fdcb56b039bfb08e4bb4d8c4d7c3c7d481c235";
+
+    /// A testnet enterprise address; any valid bech32 will do.
+    fn address() -> String {
+        let mut bytes = vec![0x60];
+        bytes.extend([7u8; 28]);
+        pallas::ledger::addresses::Address::from_bytes(&bytes)
+            .unwrap()
+            .to_bech32()
+            .unwrap()
+    }
+
+    #[test]
+    fn blockfrost_entry_with_assets_encodes() {
+        let export = serde_json::json!([{
+            "address": address(),
+            "tx_hash": "39a7a284c2a0948189dc45dec670211cd4d72f7b66c5726c08d9b3df11e44d58",
+            "tx_index": 0,
+            "output_index": 0,
+            "amount": [
+                { "unit": "lovelace", "quantity": "42000000" },
+                { "unit": format!("{POLICY}484f534b59"), "quantity": "12" }
+            ],
+            "block": "7eb8e27d18686c7db9a18f8bbcfe34e3fed6e047afaa2d969904d15e934847e6",
+            "data_hash": null,
+            "inline_datum": null,
+            "reference_script_hash": null
+        }])
+        .to_string();
+
+        let parsed = parse(&export, Format::Blockfrost).unwrap();
+        let utxo = parsed[0].1.clone().unwrap();
+
+        assert_eq!(utxo.lovelace, 42_000_000);
+        assert_eq!(utxo.assets, vec![(POLICY.to_string(), "484f534b59".to_string(), 12)]);
+
+        let cbor = encode(&utxo).unwrap();
+        let decoded = pallas::ledger::traverse::MultiEraOutput::decode(
+            pallas::ledger::traverse::Era::Conway,
+            &cbor,
+        )
+        .unwrap();
+        assert_eq!(decoded.value().coin(), 42_000_000);
+    }
+
+    #[test]
+    fn koios_entry_without_inline_body_is_skipped() {
+        let export = serde_json::json!([
+            {
+                "address": address(),
+                "tx_hash": "39a7a284c2a0948189dc45dec670211cd4d72f7b66c5726c08d9b3df11e44d58",
+                "tx_index": 1,
+                "value": "2000000",
+                "asset_list": [],
+                "datum_hash": "923918e403bf43c34b4ef6b48eb2ee04babed17320d8d1b9ff9ad086e86f44ec",
+                "inline_datum": { "value": { "int": 1 } },
+                "reference_script": null
+            },
+            {
+                "address": address(),
+                "tx_hash": "39a7a284c2a0948189dc45dec670211cd4d72f7b66c5726c08d9b3df11e44d58",
+                "tx_index": 2,
+                "value": "3000000",
+                "datum_hash": null,
+                "inline_datum": { "bytes": "01", "value": { "int": 1 } },
+                "reference_script": null
+            }
+        ])
+        .to_string();
+
+        let parsed = parse(&export, Format::Koios).unwrap();
+
+        assert!(parsed[0].1.as_ref().unwrap_err().contains("no body"));
+
+        let utxo = parsed[1].1.clone().unwrap();
+        assert_eq!(utxo.datum, Datum::Inline("01".into()));
+        assert!(encode(&utxo).is_ok());
+    }
+}
@@ -0,0 +1,29 @@
+use std::io::Write as _;
+
+use clap::Args as ClapArgs;
+use miette::{bail, IntoDiagnostic as _};
+
+use crate::config::{ProfileConfig, RootConfig};
+use crate::wallet::Derivation;
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {}
+
+pub fn run(_args: Args, config: &RootConfig, _profile: &ProfileConfig) -> miette::Result<()> {
+    let tag = Derivation::for_project(config).tag();
+    let home = crate::devnet::home_dir(&tag)?;
+
+    if crate::spawn::dolos::logs::log_files(&home).is_empty() {
+        bail!(
+            help = "logs are captured for `trix devnet --background` and `trix test` runs",
+            "no devnet logs found in {}",
+            crate::spawn::dolos::logs::log_dir(&home).display()
+        );
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    crate::spawn::dolos::logs::copy_all(&home, &mut stdout)?;
+    stdout.flush().into_diagnostic()?;
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+//! `trix devnet manifest`: the devnet's endpoints and identities as one
+//! JSON document, see [`crate::devnet::manifest`].
+
+use std::path::{Path, PathBuf};
+
+use clap::Args as ClapArgs;
+use miette::bail;
+
+use crate::config::{ProfileConfig, RootConfig};
+use crate::devnet::manifest::Manifest;
+use crate::devnet::{Config as DevnetConfig, Ports};
+use crate::wallet::Derivation;
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Write the manifest to this file instead of stdout
+    #[arg(long, value_name = "PATH")]
+    out: Option<PathBuf>,
+
+    /// Include the mnemonics of random-key identities; testnet profiles only
+    #[arg(long)]
+    include_secrets: bool,
+
+    /// Path to the devnet config file
+    #[arg(long)]
+    config: Option<PathBuf>,
+}
+
+/// Ports the devnet prepared in `home` listens on, falling back to
+/// devnet.toml for a home that wasn't set up yet.
+fn discovered_ports(home: &Path, configured: &Ports) -> Ports {
+    Ports {
+        trp: super::status::service_port(home, "trp").or(configured.trp),
+        grpc: super::status::service_port(home, "grpc").or(configured.grpc),
+        minibf: super::status::service_port(home, "minibf").or(configured.minibf),
+    }
+}
+
+/// Write the manifest of the devnet just started in `home`, so harnesses
+/// driving a `--background` devnet find it there.
+pub(super) fn write(
+    home: &Path,
+    config: &RootConfig,
+    devnet: &DevnetConfig,
+    aliases: &std::collections::HashMap<String, String>,
+) -> miette::Result<PathBuf> {
+    let manifest = Manifest::build(&config.protocol.name, devnet, &devnet.ports, aliases);
+
+    let path = crate::devnet::manifest::path(home);
+    manifest.save(&path)?;
+
+    Ok(path)
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    let network = config.resolve_profile_network(&profile.name)?;
+
+    if args.include_secrets && !network.is_testnet {
+        bail!(
+            help = "drop --include-secrets, or use a testnet profile",
+            "profile `{}` is not on a testnet; refusing to print its mnemonics",
+            profile.name
+        );
+    }
+
+    let path = match args.config {
+        Some(path) => path,
+        None => crate::dirs::protocol_root()?.join("devnet.toml"),
+    };
+
+    let devnet = DevnetConfig::load(&path)?;
+
+    let wallet = crate::wallet::setup(config, profile)?;
+
+    let derivation = Derivation::for_project(config);
+    let home = crate::devnet::home_dir(&derivation.tag())?;
+
+    let ports = discovered_ports(&home, &devnet.ports);
+
+    let mut manifest = Manifest::build(&config.protocol.name, &devnet, &ports, &wallet.addresses);
+
+    if args.include_secrets {
+        manifest.include_mnemonics(profile, &derivation)?;
+    }
+
+    match args.out {
+        Some(out) => {
+            manifest.save(&out)?;
+            println!("wrote devnet manifest to {}", out.display());
+        }
+        None => println!("{}", manifest.to_json()?),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn running_home_ports_win_over_devnet_toml() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::write(
+            home.path().join("dolos.toml"),
+            "[serve.trp]\nlisten_address = \"[::]:28164\"\n",
+        )
+        .unwrap();
+
+        let configured = Ports {
+            trp: Some(18164),
+            grpc: Some(15164),
+            minibf: None,
+        };
+
+        let ports = discovered_ports(home.path(), &configured);
+
+        assert_eq!(ports.trp(), 28164);
+        assert_eq!(ports.grpc(), 15164);
+        assert_eq!(ports.minibf(), crate::devnet::ports::DEFAULT_MINIBF);
+    }
+}
@@ -6,14 +6,60 @@ use crate::config::{ProfileConfig, RootConfig};
 use crate::devnet::Config as DevnetConfig;
 
 pub mod copy;
+pub mod dump;
+pub mod fund;
+pub mod import;
+pub mod logs;
+pub mod manifest;
 pub mod new;
+pub mod probe;
+pub mod reset;
+pub mod restore;
+pub mod simulate;
+pub mod snapshot;
+pub mod status;
+pub mod stop;
 
 #[derive(Subcommand, Debug)]
 pub enum Command {
     /// Retrieve the UTxO dependencies for one transaction
     Copy(copy::Args),
+    /// Write the running devnet's UTxO set to a diffable file
+    DumpUtxos(dump::Args),
+    /// Add a lovelace UTxO for a wallet and restart the background devnet
+    Fund(fund::Args),
+    /// Append UTxOs from a Blockfrost or Koios JSON export to devnet.toml
+    Import(import::Args),
     /// Create a new devnet configuration file
     New(new::Args),
+    /// Print the captured devnet log, across rotated files
+    Logs(logs::Args),
+    /// Describe the devnet's endpoints and identities as JSON
+    Manifest(manifest::Args),
+    /// Healthcheck for orchestrators: exit 0 if the devnet is alive or ready
+    Probe(probe::Args),
+    /// Stop the project's devnet and delete its ledger state
+    Reset(reset::Args),
+    /// Start a devnet from a saved snapshot
+    Restore(restore::Args),
+    /// Submit randomized transactions at a steady rate, for load testing
+    Simulate(simulate::Args),
+    /// Save the running devnet's UTxO set and genesis to a named snapshot
+    Snapshot(snapshot::Args),
+    /// Show whether the project's background devnet is running
+    Status(status::Args),
+    /// Stop the devnet started with `--background`
+    Stop(stop::Args),
+    /// Run dolos for a prepared home and capture its output (internal)
+    #[command(hide = true)]
+    Supervise(SuperviseArgs),
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct SuperviseArgs {
+    /// Prepared dolos home
+    #[arg(long)]
+    home: PathBuf,
 }
 
 #[derive(ClapArgs, Debug)]
@@ -30,10 +76,34 @@ pub struct Args {
     background: bool,
 }
 
+impl Args {
+    /// The probe to run, if this is `trix devnet probe`. Probes are
+    /// dispatched before any startup work.
+    pub fn probe(&self) -> Option<&probe::Args> {
+        match &self.command {
+            Some(Command::Probe(args)) => Some(args),
+            _ => None,
+        }
+    }
+}
+
 pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
     match args.command {
         Some(Command::Copy(args)) => copy::run(args, config, profile),
+        Some(Command::DumpUtxos(args)) => dump::run(args, config, profile),
+        Some(Command::Fund(args)) => fund::run(args, config, profile),
+        Some(Command::Import(args)) => import::run(args, config, profile),
         Some(Command::New(args)) => new::run(args, config, profile),
+        Some(Command::Logs(args)) => logs::run(args, config, profile),
+        Some(Command::Manifest(args)) => manifest::run(args, config, profile),
+        Some(Command::Probe(args)) => probe::run(&args),
+        Some(Command::Reset(args)) => reset::run(args, config, profile),
+        Some(Command::Restore(args)) => restore::run(args, config, profile),
+        Some(Command::Simulate(args)) => simulate::run(args, config, profile),
+        Some(Command::Snapshot(args)) => snapshot::run(args, config, profile),
+        Some(Command::Status(args)) => status::run(args, config, profile),
+        Some(Command::Stop(args)) => stop::run(args, config, profile),
+        Some(Command::Supervise(args)) => supervise(args),
         None => run_devnet(args, config, profile),
     }
 }
@@ -50,20 +120,48 @@ pub fn run_devnet(args: Args, config: &RootConfig, profile: &ProfileConfig) -> m
 
     let ctx = crate::devnet::Context::from_wallet(&wallet);
 
-    let mut daemon = crate::devnet::start_daemon(&devnet, &ctx, args.background)?;
-
     if args.background {
-        println!("devnet started in background");
-    } else {
-        let status = daemon
-            .daemon
-            .wait()
-            .into_diagnostic()
-            .context("failed to wait for dolos devnet")?;
-
-        if !status.success() {
-            bail!("dolos devnet exited with code: {}", status);
-        }
+        let (home, _supervisor) = crate::devnet::start_supervised(&devnet, &ctx)?;
+        let manifest = manifest::write(&home, config, &devnet, &ctx.aliases)?;
+        println!("devnet started in background (stop it with `trix devnet stop`)");
+        println!(
+            "logs: {} (see `trix devnet logs`)",
+            crate::spawn::dolos::logs::log_dir(&home).display()
+        );
+        println!("manifest: {}", manifest.display());
+        return Ok(());
+    }
+
+    let mut daemon = crate::devnet::start_daemon(&devnet, &ctx, false)?;
+
+    manifest::write(&daemon.home, config, &devnet, &ctx.aliases)?;
+
+    let status = daemon
+        .daemon
+        .wait()
+        .into_diagnostic()
+        .context("failed to wait for dolos devnet")?;
+
+    if !status.success() {
+        bail!("dolos devnet exited with code: {}", status);
+    }
+
+    Ok(())
+}
+
+fn supervise(args: SuperviseArgs) -> miette::Result<()> {
+    let mut daemon = crate::spawn::dolos::daemon(&args.home, true)?;
+
+    let status = daemon
+        .wait()
+        .into_diagnostic()
+        .context("failed to wait for dolos devnet")?;
+
+    crate::devnet::clear_pid(&args.home);
+    crate::devnet::lock::release(&args.home, std::process::id());
+
+    if !status.success() {
+        bail!("dolos devnet exited with code: {}", status);
     }
 
     Ok(())
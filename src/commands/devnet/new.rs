@@ -8,8 +8,17 @@ pub struct Args {
     /// Path to save the devnet config file
     #[arg(long)]
     pub output: Option<PathBuf>,
+
+    /// Also write a docker-compose example next to it, with `trix devnet
+    /// probe` healthchecks
+    #[arg(long)]
+    pub compose: bool,
 }
 
+const COMPOSE_TEMPLATE: &str = include_str!("../../../templates/devnet/compose.yml");
+
+const COMPOSE_FILE: &str = "docker-compose.devnet.yml";
+
 const DEFAULT_DEVNET_WALLET_AMOUNT: u64 = 100_000_000_000;
 
 pub fn inquire_config(
@@ -33,11 +42,20 @@ pub fn inquire_config(
             crate::devnet::ExplicitUtxoSpec {
                 address: crate::devnet::AddressSpec::NamedWallet(identity_name.clone()),
                 value: balance,
+                assets: Default::default(),
+                datum_inline: None,
+                datum_hash: None,
+                script_ref: None,
+                script_language: None,
             },
         ));
     }
 
-    Ok(crate::devnet::Config { utxos })
+    Ok(crate::devnet::Config {
+        utxos,
+        ports: Default::default(),
+        chain: Default::default(),
+    })
 }
 
 pub fn run(
@@ -67,5 +85,24 @@ pub fn run(
             output_path.to_string_lossy()
         ))?;
 
+    if args.compose {
+        let compose_path = output_path
+            .parent()
+            .unwrap_or(std::path::Path::new("."))
+            .join(COMPOSE_FILE);
+
+        if compose_path.exists() {
+            eprintln!(
+                "warning: {} exists, leaving it as is",
+                compose_path.display()
+            );
+        } else {
+            std::fs::write(&compose_path, COMPOSE_TEMPLATE)
+                .into_diagnostic()
+                .with_context(|| format!("writing {}", compose_path.display()))?;
+            println!("wrote {}", compose_path.display());
+        }
+    }
+
     Ok(())
 }
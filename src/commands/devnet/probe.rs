@@ -0,0 +1,152 @@
+//! Container healthchecks for a running devnet:
+//!
+//! - `--liveness`: the devnet's supervisor process is alive.
+//! - `--readiness`: the TRP and U5C ports answer on localhost.
+//!
+//! Probes exit 0 or 1 and print nothing; with `--verbose` the reason is
+//! logged. They run before trix's startup work (update check, state
+//! migration), so a probe stays well under a second.
+
+use std::io::{Read as _, Write as _};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use clap::Args as ClapArgs;
+use tracing::info;
+
+use crate::config::RootConfig;
+use crate::spawn::shutdown;
+use crate::wallet::Derivation;
+
+/// Per-port budget; both readiness checks together stay under a second.
+const TIMEOUT: Duration = Duration::from_millis(300);
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Succeed while the devnet process is alive
+    #[arg(
+        long,
+        conflicts_with = "readiness",
+        required_unless_present = "readiness"
+    )]
+    liveness: bool,
+
+    /// Succeed once the TRP and U5C ports answer
+    #[arg(long)]
+    readiness: bool,
+
+    /// Devnet home to probe; skips looking up trix.toml
+    #[arg(long, value_name = "PATH")]
+    devnet_home: Option<PathBuf>,
+}
+
+/// The devnet home of the project in the current directory.
+fn project_home() -> miette::Result<PathBuf> {
+    let root = crate::dirs::protocol_root()?;
+    let config = RootConfig::load(&root.join("trix.toml"))?;
+
+    crate::devnet::home_dir(&Derivation::for_project(&config).tag())
+}
+
+fn alive(home: &Path) -> bool {
+    match crate::devnet::read_pid(home) {
+        Some(pid) if shutdown::group_alive(pid) => true,
+        Some(pid) => {
+            info!(pid, "devnet process is gone");
+            false
+        }
+        None => {
+            info!(home = %home.display(), "no devnet PID file");
+            false
+        }
+    }
+}
+
+/// Whether the TRP server answers a bare HTTP request with any response.
+fn trp_answers(port: u16) -> bool {
+    let target = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+
+    let Ok(mut stream) = TcpStream::connect_timeout(&target, TIMEOUT) else {
+        return false;
+    };
+
+    let _ = stream.set_read_timeout(Some(TIMEOUT));
+    let _ = stream.set_write_timeout(Some(TIMEOUT));
+
+    let request = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+    if stream.write_all(request.as_bytes()).is_err() {
+        return false;
+    }
+
+    let mut head = [0u8; 5];
+    stream.read_exact(&mut head).is_ok() && &head == b"HTTP/"
+}
+
+fn ready(home: &Path) -> bool {
+    let (Some(trp), Some(u5c)) = (
+        super::status::service_port(home, "trp"),
+        super::status::service_port(home, "grpc"),
+    ) else {
+        info!(home = %home.display(), "no dolos.toml with TRP and U5C ports");
+        return false;
+    };
+
+    if !trp_answers(trp) {
+        info!(port = trp, "TRP does not answer");
+        return false;
+    }
+
+    if !super::status::listening(u5c, TIMEOUT) {
+        info!(port = u5c, "U5C does not accept connections");
+        return false;
+    }
+
+    true
+}
+
+/// Run the probe and exit with its result.
+pub fn run(args: &Args) -> ! {
+    let home = match &args.devnet_home {
+        Some(home) => Ok(home.clone()),
+        None => project_home(),
+    };
+
+    let healthy = match home {
+        Ok(home) if args.liveness => alive(&home),
+        Ok(home) => ready(&home),
+        Err(err) => {
+            info!(%err, "can't locate the devnet home");
+            false
+        }
+    };
+
+    std::process::exit(if healthy { 0 } else { 1 });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trp_probe_needs_an_http_response() {
+        let listener = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 256];
+            let _ = stream.read(&mut buf);
+            stream
+                .write_all(b"HTTP/1.1 405 Method Not Allowed\r\n\r\n")
+                .unwrap();
+        });
+
+        assert!(trp_answers(port));
+        server.join().unwrap();
+
+        let home = tempfile::tempdir().unwrap();
+        assert!(!alive(home.path()));
+        assert!(!ready(home.path()));
+    }
+}
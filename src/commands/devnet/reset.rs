@@ -0,0 +1,152 @@
+use std::path::{Path, PathBuf};
+
+use clap::Args as ClapArgs;
+use miette::{Context as _, IntoDiagnostic as _, bail};
+
+use crate::config::{ProfileConfig, RootConfig};
+use crate::spawn::shutdown;
+use crate::wallet::Derivation;
+
+/// Prefix of the devnet homes older trix versions kept under the shared
+/// temp dir.
+const LEGACY_PREFIX: &str = "devnet_";
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Delete every devnet home of this project, and the `devnet_*` homes
+    /// older trix versions left in the shared temp dir
+    #[arg(long)]
+    all: bool,
+
+    /// Don't ask for confirmation with --all
+    #[arg(short, long, requires = "all")]
+    yes: bool,
+
+    /// Seconds to wait for a running devnet to exit
+    #[arg(long, default_value_t = 10)]
+    timeout: u64,
+}
+
+/// Every home `--all` removes: one per derivation mode under the project's
+/// `.tx3/dolos`, plus legacy homes in the shared temp dir.
+fn all_homes() -> miette::Result<Vec<PathBuf>> {
+    let mut homes = subdirs(&crate::dirs::target_dir("dolos")?, |_| true)?;
+
+    let tmp = crate::home::tmp_dir()?;
+    homes.extend(subdirs(&tmp, |name| name.starts_with(LEGACY_PREFIX))?);
+
+    Ok(homes)
+}
+
+fn subdirs(parent: &Path, keep: impl Fn(&str) -> bool) -> miette::Result<Vec<PathBuf>> {
+    let entries = match std::fs::read_dir(parent) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => {
+            return Err(e)
+                .into_diagnostic()
+                .with_context(|| format!("listing {}", parent.display()));
+        }
+    };
+
+    let mut dirs: Vec<_> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter(|entry| keep(&entry.file_name().to_string_lossy()))
+        .map(|entry| entry.path())
+        .collect();
+
+    dirs.sort();
+
+    Ok(dirs)
+}
+
+fn confirm(homes: &[PathBuf]) -> miette::Result<bool> {
+    if !crate::term::prompt::is_interactive() {
+        bail!(
+            help = "pass --yes to delete them without asking",
+            "refusing to delete {} devnet home(s) without confirmation",
+            homes.len()
+        );
+    }
+
+    for home in homes {
+        println!("  {}", home.display());
+    }
+
+    crate::term::prompt::confirm(&format!("Delete {} devnet home(s)?", homes.len()), false)
+}
+
+/// Stop the devnet running from `home`, if any, then delete it.
+fn reset(home: &Path, timeout: u64) -> miette::Result<()> {
+    if let Some(pid) = crate::devnet::read_pid(home).filter(|pid| shutdown::group_alive(*pid)) {
+        super::stop::terminate(pid, timeout)?;
+        println!("stopped devnet (pid {pid})");
+    }
+
+    std::fs::remove_dir_all(home)
+        .into_diagnostic()
+        .with_context(|| format!("removing {}", home.display()))?;
+
+    println!("removed {}", home.display());
+
+    Ok(())
+}
+
+pub fn run(args: Args, config: &RootConfig, _profile: &ProfileConfig) -> miette::Result<()> {
+    let homes = match args.all {
+        true => all_homes()?,
+        false => {
+            let home = crate::devnet::home_dir(&Derivation::for_project(config).tag())?;
+            match home.exists() {
+                true => vec![home],
+                false => vec![],
+            }
+        }
+    };
+
+    if homes.is_empty() {
+        println!("no devnet state to reset");
+        return Ok(());
+    }
+
+    if args.all && !args.yes && !confirm(&homes)? {
+        println!("nothing deleted");
+        return Ok(());
+    }
+
+    for home in &homes {
+        reset(home, args.timeout)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_only_matching_directories() {
+        let dir = tempfile::tempdir().unwrap();
+
+        for name in ["devnet_b", "devnet_a", "registry"] {
+            std::fs::create_dir(dir.path().join(name)).unwrap();
+        }
+        std::fs::write(dir.path().join("devnet_file"), "").unwrap();
+
+        let found: Vec<_> = subdirs(dir.path(), |name| name.starts_with(LEGACY_PREFIX))
+            .unwrap()
+            .into_iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+
+        assert_eq!(found, ["devnet_a", "devnet_b"]);
+
+        assert!(
+            subdirs(&dir.path().join("missing"), |_| true)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
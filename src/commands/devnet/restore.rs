@@ -0,0 +1,170 @@
+//! Start a devnet from a snapshot taken with `trix devnet snapshot`.
+//!
+//! The devnet home is re-initialized from the snapshot's UTxO set and
+//! genesis files, then dolos is started on it exactly as `trix devnet`
+//! would. Chain history is not kept: the restored devnet starts at a new
+//! genesis whose initial UTxOs are the snapshot's.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read as _;
+use std::path::Path;
+
+use clap::Args as ClapArgs;
+use miette::{Context as _, IntoDiagnostic as _, bail};
+
+use crate::config::{ProfileConfig, RootConfig};
+use crate::devnet::Config as DevnetConfig;
+use crate::wallet::Derivation;
+
+use super::snapshot::{self, GENESIS_FILES, MANIFEST_ENTRY, Manifest, UTXOS_ENTRY};
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Name of the snapshot to restore
+    name: String,
+
+    /// run devnet as a background process
+    #[arg(short, long, default_value_t = false)]
+    background: bool,
+}
+
+/// Entries of a snapshot archive, by file name.
+fn read_archive(path: &Path) -> miette::Result<BTreeMap<String, Vec<u8>>> {
+    let file = std::fs::File::open(path)
+        .into_diagnostic()
+        .with_context(|| format!("opening {}", path.display()))?;
+
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    let mut entries = BTreeMap::new();
+
+    for entry in archive.entries().into_diagnostic()? {
+        let mut entry = entry.into_diagnostic()?;
+
+        let name = entry
+            .path()
+            .into_diagnostic()?
+            .to_string_lossy()
+            .into_owned();
+
+        let mut bytes = vec![];
+        entry
+            .read_to_end(&mut bytes)
+            .into_diagnostic()
+            .with_context(|| format!("reading {name} from snapshot"))?;
+
+        entries.insert(name, bytes);
+    }
+
+    Ok(entries)
+}
+
+fn entry<'a>(entries: &'a BTreeMap<String, Vec<u8>>, name: &str) -> miette::Result<&'a [u8]> {
+    entries
+        .get(name)
+        .map(Vec::as_slice)
+        .ok_or_else(|| miette::miette!("snapshot is missing {name}"))
+}
+
+/// Re-initialize `home` with the snapshot's UTxOs and genesis files.
+fn hydrate(home: &Path, entries: &BTreeMap<String, Vec<u8>>) -> miette::Result<()> {
+    let utxos = std::str::from_utf8(entry(entries, UTXOS_ENTRY)?).into_diagnostic()?;
+
+    let devnet: DevnetConfig = toml::from_str(utxos)
+        .into_diagnostic()
+        .context("parsing snapshot utxos")?;
+
+    // Snapshot specs are raw output bytes, so there are no aliases to resolve.
+    let initial_utxos = crate::devnet::build_dolos_utxos(&devnet, &HashMap::new())?;
+
+    // Listen where the project's devnet.toml says, so `cardano-local`
+    // clients find the restored devnet.
+    let ports = crate::devnet::Ports::for_project()?;
+
+    // The snapshot's genesis files replace the rendered ones below, so its
+    // timing wins over the project's `[chain]`.
+    let chain = crate::devnet::Chain::default();
+
+    crate::spawn::dolos::initialize_config(home, initial_utxos, &ports, &chain)?;
+
+    for name in GENESIS_FILES {
+        let path = home.join(name);
+        std::fs::write(&path, entry(entries, name)?)
+            .into_diagnostic()
+            .with_context(|| format!("writing {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+pub fn run(args: Args, config: &RootConfig, _profile: &ProfileConfig) -> miette::Result<()> {
+    snapshot::validate_name(&args.name)?;
+
+    let path = snapshot::snapshot_path(&args.name)?;
+
+    if !path.exists() {
+        bail!(
+            help = format!("take one with `trix devnet snapshot {}`", args.name),
+            "no snapshot named `{}` at {}",
+            args.name,
+            path.display()
+        );
+    }
+
+    let derivation = Derivation::for_project(config).tag();
+    let home = crate::devnet::home_dir(&derivation)?;
+
+    let running = crate::devnet::read_pid(&home).is_some_and(crate::spawn::shutdown::group_alive);
+
+    if running {
+        bail!(
+            help = "stop it first with `trix devnet stop`",
+            "a background devnet is already running for this project"
+        );
+    }
+
+    let entries = read_archive(&path)?;
+
+    let manifest: Manifest = serde_json::from_slice(entry(&entries, MANIFEST_ENTRY)?)
+        .into_diagnostic()
+        .context("parsing snapshot manifest")?;
+
+    if manifest.derivation != derivation {
+        eprintln!(
+            "warning: snapshot `{}` was taken with `{}` identities, this project uses `{derivation}`; wallet addresses may not match",
+            manifest.name, manifest.derivation
+        );
+    }
+
+    let lock = crate::devnet::lock::HomeLock::acquire(&home)?;
+
+    hydrate(&home, &entries)?;
+
+    println!(
+        "restored snapshot `{}` ({} utxos, taken {})",
+        manifest.name, manifest.utxos, manifest.created_at
+    );
+
+    if args.background {
+        let supervisor = crate::devnet::supervise_home(&home)?;
+        lock.hand_over(supervisor.id())?;
+        println!("devnet started in background (stop it with `trix devnet stop`)");
+        println!(
+            "logs: {} (see `trix devnet logs`)",
+            crate::spawn::dolos::logs::log_dir(&home).display()
+        );
+        return Ok(());
+    }
+
+    let mut daemon = crate::spawn::dolos::daemon(&home, false)?;
+
+    let status = daemon
+        .wait()
+        .into_diagnostic()
+        .context("failed to wait for dolos devnet")?;
+
+    if !status.success() {
+        bail!("dolos devnet exited with code: {}", status);
+    }
+
+    Ok(())
+}
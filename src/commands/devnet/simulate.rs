@@ -0,0 +1,599 @@
+//! `trix devnet simulate`: keep a local devnet busy with randomized
+//! transactions, e.g. to load-test an indexer before a launch. Every
+//! transaction invokes one template through the same path as `trix invoke
+//! --output json`: parties get distinct random wallets and integer params a
+//! random value within their `--range`. Progress is reported every few
+//! seconds and summarized at the end, or on Ctrl-C.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::thread::sleep;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use clap::Args as ClapArgs;
+use miette::bail;
+
+use crate::builder;
+use crate::commands::invoke::args as invoke_args;
+use crate::commands::steps::{self, Transaction};
+use crate::config::{NetworkConfig, ProfileConfig, RootConfig};
+use crate::spawn::shutdown;
+use crate::wallet::WalletProxy;
+
+/// Lovelace bounds for integer params without a `--range`: 1 to 5 ADA.
+const DEFAULT_RANGE: (u64, u64) = (1_000_000, 5_000_000);
+
+/// Longest nap between checks for the next slot, a report or Ctrl-C.
+const TICK: Duration = Duration::from_millis(200);
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Target transactions per second
+    #[arg(long, default_value_t = 1.0, value_parser = parse_tps)]
+    tps: f64,
+
+    /// How long to run, e.g. `90s`, `5m` or `1h`; bare numbers are seconds
+    #[arg(long, default_value = "1m", value_parser = parse_duration)]
+    duration: Duration,
+
+    /// Template to invoke for every transaction
+    #[arg(long, default_value = "transfer")]
+    template: String,
+
+    /// Identities to fill parties from; defaults to all of the profile's
+    #[arg(long, value_delimiter = ',', value_name = "NAME,...")]
+    wallets: Vec<String>,
+
+    /// Party whose wallet signs each transaction
+    #[arg(long, default_value = "sender", value_name = "PARTY")]
+    signer_party: String,
+
+    /// Bounds for a random integer arg, e.g. `--range quantity=1000000..2000000`.
+    /// Integer params without one get 1 to 5 ADA. Repeatable.
+    #[arg(long = "range", value_name = "NAME=MIN..MAX", value_parser = parse_range)]
+    ranges: Vec<(String, (u64, u64))>,
+
+    /// Arg passed unchanged to every transaction, e.g. `--arg memo=load`.
+    /// Repeatable.
+    #[arg(long = "arg", value_name = "NAME=VALUE", value_parser = invoke_args::parse_arg)]
+    fixed: Vec<(String, serde_json::Value)>,
+
+    /// Seconds between progress reports
+    #[arg(long, default_value_t = 5, value_name = "SECS")]
+    report_every: u64,
+
+    /// Seed for the random choices, to replay the same sequence of args
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+fn parse_tps(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(tps) if tps.is_finite() && tps > 0.0 => Ok(tps),
+        _ => Err(format!("expected a positive rate, got '{s}'")),
+    }
+}
+
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let (digits, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(at) => s.split_at(at),
+        None => (s, "s"),
+    };
+
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        _ => return Err(format!("expected e.g. `90s`, `5m` or `1h`, got '{s}'")),
+    };
+
+    match digits.parse::<u64>() {
+        Ok(n) if n > 0 => Ok(Duration::from_secs(n * scale)),
+        _ => Err(format!("expected e.g. `90s`, `5m` or `1h`, got '{s}'")),
+    }
+}
+
+fn parse_range(s: &str) -> Result<(String, (u64, u64)), String> {
+    let invalid = || format!("expected NAME=MIN..MAX, got '{s}'");
+
+    let (name, bounds) = s.split_once('=').ok_or_else(invalid)?;
+    let (min, max) = bounds.split_once("..").ok_or_else(invalid)?;
+
+    let bound = |text: &str| text.replace('_', "").parse::<u64>().map_err(|_| invalid());
+    let (min, max) = (bound(min)?, bound(max)?);
+
+    if name.is_empty() || min > max {
+        return Err(invalid());
+    }
+
+    Ok((name.to_string(), (min, max)))
+}
+
+/// xorshift64*; plenty for picking wallets and amounts.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // The state must never be zero.
+        Self(seed | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn between(&mut self, (min, max): (u64, u64)) -> u64 {
+        match (max - min).checked_add(1) {
+            Some(span) => min + self.next() % span,
+            None => self.next(),
+        }
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.next() % (i as u64 + 1);
+            items.swap(i, j as usize);
+        }
+    }
+}
+
+/// How to fill the args of every simulated transaction.
+#[derive(Debug)]
+struct Plan {
+    template: String,
+    wallets: Vec<String>,
+    /// Party args to fill with a wallet, lowercased like invoke expects.
+    parties: Vec<String>,
+    ranges: BTreeMap<String, (u64, u64)>,
+    fixed: HashMap<String, serde_json::Value>,
+    signer_party: String,
+}
+
+impl Plan {
+    fn new(args: &Args, tii: &serde_json::Value, wallets: Vec<String>) -> miette::Result<Self> {
+        let Some(tx) = tii.pointer(&format!("/transactions/{}", args.template)) else {
+            let known: Vec<_> = tii
+                .get("transactions")
+                .and_then(|t| t.as_object())
+                .map(|t| t.keys().cloned().collect())
+                .unwrap_or_default();
+
+            bail!(
+                help = format!("templates: {}", known.join(", ")),
+                "protocol has no transaction template `{}`",
+                args.template
+            );
+        };
+
+        let fixed: HashMap<_, _> = args.fixed.iter().cloned().collect();
+        let mut ranges: BTreeMap<_, _> = args.ranges.iter().cloned().collect();
+
+        let mut parties: Vec<String> = tii
+            .get("parties")
+            .and_then(|p| p.as_object())
+            .map(|p| p.keys().map(|k| k.to_lowercase()).collect())
+            .unwrap_or_default();
+        parties.sort();
+        parties.retain(|party| !fixed.contains_key(party));
+
+        let params = tx
+            .pointer("/params/properties")
+            .and_then(|p| p.as_object())
+            .cloned()
+            .unwrap_or_default();
+
+        if let Some(name) = ranges.keys().find(|name| {
+            params
+                .get(*name)
+                .and_then(|schema| schema.get("type"))
+                .and_then(|t| t.as_str())
+                != Some("integer")
+        }) {
+            bail!(
+                "--range `{name}` is not an integer param of `{}`",
+                args.template
+            );
+        }
+
+        for (name, schema) in &params {
+            if fixed.contains_key(name) || parties.contains(name) || ranges.contains_key(name) {
+                continue;
+            }
+
+            match schema.get("type").and_then(|t| t.as_str()) {
+                Some("integer") => {
+                    ranges.insert(name.clone(), DEFAULT_RANGE);
+                }
+                _ => bail!(
+                    help = format!("pass a value with `--arg {name}=VALUE`"),
+                    "can't generate random values for `{name}` of `{}`",
+                    args.template
+                ),
+            }
+        }
+
+        let signer_party = args.signer_party.to_lowercase();
+        let fixed_signer = fixed
+            .get(&signer_party)
+            .and_then(|v| v.as_str())
+            .is_some_and(|v| v.starts_with('@'));
+
+        if !parties.contains(&signer_party) && !fixed_signer {
+            bail!(
+                help = "name the signing party with --signer-party, or pass it as `--arg PARTY=@wallet`",
+                "`{}` has no party `{}` to sign with",
+                args.template,
+                args.signer_party
+            );
+        }
+
+        if wallets.len() < parties.len() {
+            bail!(
+                help = "list more identities with --wallets",
+                "`{}` needs {} distinct wallets for its parties, but only {} are available",
+                args.template,
+                parties.len(),
+                wallets.len()
+            );
+        }
+
+        Ok(Self {
+            template: args.template.clone(),
+            wallets,
+            parties,
+            ranges,
+            fixed,
+            signer_party,
+        })
+    }
+
+    fn next(&self, rng: &mut Rng, n: u64) -> Transaction {
+        let mut wallets = self.wallets.clone();
+        rng.shuffle(&mut wallets);
+
+        let mut args = self.fixed.clone();
+
+        for (party, wallet) in self.parties.iter().zip(&wallets) {
+            args.insert(party.clone(), format!("@{wallet}").into());
+        }
+
+        for (name, range) in &self.ranges {
+            args.insert(name.clone(), rng.between(*range).into());
+        }
+
+        let signer = args
+            .get(&self.signer_party)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .trim_start_matches('@')
+            .to_string();
+
+        Transaction {
+            description: format!("simulated #{n}"),
+            template: self.template.clone(),
+            args,
+            signers: vec![signer],
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Stats {
+    submitted: u64,
+    failed: u64,
+    latency: Duration,
+    /// Most transactions that were due but not yet sent at once: how far
+    /// the devnet and cshell fall behind the target rate.
+    max_backlog: u64,
+    errors: BTreeMap<String, u64>,
+}
+
+impl Stats {
+    fn record(&mut self, result: miette::Result<()>, latency: Duration) {
+        self.latency += latency;
+
+        match result {
+            Ok(()) => self.submitted += 1,
+            Err(err) => {
+                self.failed += 1;
+
+                let message = err.to_string();
+                let first = message.lines().next().unwrap_or_default();
+                *self
+                    .errors
+                    .entry(first.chars().take(120).collect())
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    fn attempted(&self) -> u64 {
+        self.submitted + self.failed
+    }
+
+    fn avg_latency(&self) -> Duration {
+        match self.attempted() {
+            0 => Duration::ZERO,
+            n => self.latency / n as u32,
+        }
+    }
+}
+
+fn print_progress(stats: &Stats, elapsed: Duration, backlog: u64) {
+    println!(
+        "[{:>4}s] {} submitted ({:.2} tps), {} failed, backlog {backlog}, avg latency {}ms",
+        elapsed.as_secs(),
+        stats.submitted,
+        stats.submitted as f64 / elapsed.as_secs_f64().max(1.0),
+        stats.failed,
+        stats.avg_latency().as_millis()
+    );
+}
+
+fn print_summary(stats: &Stats, elapsed: Duration, target: f64, interrupted: bool) {
+    println!("\n== Summary ==\n");
+
+    if interrupted {
+        println!("interrupted after {}s", elapsed.as_secs());
+    } else {
+        println!("ran for {}s", elapsed.as_secs());
+    }
+
+    println!(
+        "submitted: {} ({:.2} tps, target {target})",
+        stats.submitted,
+        stats.submitted as f64 / elapsed.as_secs_f64().max(1.0)
+    );
+    println!("failed: {}", stats.failed);
+    println!("avg latency: {}ms", stats.avg_latency().as_millis());
+    println!("max backlog: {}", stats.max_backlog);
+
+    if !stats.errors.is_empty() {
+        println!("errors:");
+
+        for (message, count) in &stats.errors {
+            println!("  {count}x {message}");
+        }
+    }
+}
+
+/// Simulated traffic is for devnets: refuse a profile whose TRP endpoint is
+/// not on this machine.
+fn ensure_local(profile: &ProfileConfig, network: &NetworkConfig) -> miette::Result<()> {
+    let host = url::Url::parse(&network.trp.url)
+        .ok()
+        .and_then(|url| url.host_str().map(String::from));
+
+    if !matches!(
+        host.as_deref(),
+        Some("localhost" | "127.0.0.1" | "[::1]" | "::1")
+    ) {
+        bail!(
+            help = "run it against the local devnet, e.g. with `--profile local`",
+            "`trix devnet simulate` only targets a local devnet, but profile `{}` submits to {}",
+            profile.name,
+            network.trp.url
+        );
+    }
+
+    Ok(())
+}
+
+fn submit_one(
+    config: &RootConfig,
+    wallet: &WalletProxy,
+    tii_file: &Path,
+    transaction: &Transaction,
+    profile: &ProfileConfig,
+) -> miette::Result<()> {
+    let args = steps::define_args(transaction, wallet)?;
+    steps::submit(config, wallet, tii_file, transaction, &args, profile)?;
+    Ok(())
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    let network = config.resolve_profile_network(&profile.name)?;
+    ensure_local(profile, &network)?;
+
+    let wallet = crate::wallet::setup(config, profile)?;
+
+    let wallets = match args.wallets.is_empty() {
+        true => {
+            let mut names: Vec<_> = wallet.addresses.keys().cloned().collect();
+            names.sort();
+            names
+        }
+        false => args.wallets.clone(),
+    };
+
+    if let Some(unknown) = wallets.iter().find(|w| !wallet.addresses.contains_key(*w)) {
+        let mut known: Vec<_> = wallet.addresses.keys().cloned().collect();
+        known.sort();
+
+        bail!(
+            help = format!("wallets: {}", known.join(", ")),
+            "unknown wallet `{unknown}`"
+        );
+    }
+
+    let tii_file = builder::build_tii(config)?;
+    let tii = builder::load_tii(&tii_file)?;
+
+    let plan = Plan::new(&args, &tii, wallets)?;
+
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default()
+    });
+    let mut rng = Rng::new(seed);
+
+    let sample = plan.next(&mut Rng::new(seed), 0);
+    let sample = serde_json::to_value(&sample.args).unwrap_or_default();
+    invoke_args::validate(
+        sample.as_object().unwrap_or(&Default::default()),
+        &tii,
+        &plan.template,
+    )?;
+
+    println!(
+        "simulating `{}` at {} tps for {}s (seed {seed}, Ctrl-C to stop)",
+        plan.template,
+        args.tps,
+        args.duration.as_secs()
+    );
+
+    shutdown::catch_interrupt();
+
+    let interval = Duration::from_secs_f64(1.0 / args.tps);
+    let report_every = Duration::from_secs(args.report_every.max(1));
+
+    let mut stats = Stats::default();
+    let mut next_report = report_every;
+    let start = Instant::now();
+
+    while !shutdown::interrupted() && start.elapsed() < args.duration {
+        let elapsed = start.elapsed();
+        let due = (elapsed.as_secs_f64() * args.tps) as u64 + 1;
+        let backlog = due.saturating_sub(stats.attempted() + 1);
+
+        if elapsed >= next_report {
+            print_progress(&stats, elapsed, backlog);
+            next_report += report_every;
+        }
+
+        let slot = interval.mul_f64(stats.attempted() as f64);
+
+        if slot > elapsed {
+            sleep((slot - elapsed).min(TICK));
+            continue;
+        }
+
+        stats.max_backlog = stats.max_backlog.max(backlog);
+
+        let transaction = plan.next(&mut rng, stats.attempted() + 1);
+
+        let sent = Instant::now();
+        let result = submit_one(config, &wallet, &tii_file, &transaction, profile);
+
+        // A Ctrl-C kills the cshell call in flight; that's not a failure.
+        if shutdown::interrupted() {
+            break;
+        }
+
+        stats.record(result, sent.elapsed());
+    }
+
+    print_summary(&stats, start.elapsed(), args.tps, shutdown::interrupted());
+
+    if stats.submitted == 0 && stats.failed > 0 {
+        bail!("no simulated transaction was accepted, see the errors above");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tii() -> serde_json::Value {
+        serde_json::json!({
+            "parties": { "Sender": {}, "Receiver": {} },
+            "transactions": {
+                "transfer": {
+                    "params": {
+                        "properties": {
+                            "quantity": { "type": "integer" }
+                        }
+                    }
+                },
+                "tag": {
+                    "params": {
+                        "properties": {
+                            "label": { "$ref": "https://tx3.land/specs/v1beta0/core#Bytes" }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn args(extra: &[&str]) -> Args {
+        #[derive(clap::Parser)]
+        struct Cli {
+            #[command(flatten)]
+            args: Args,
+        }
+
+        let argv = std::iter::once("simulate").chain(extra.iter().copied());
+        <Cli as clap::Parser>::parse_from(argv).args
+    }
+
+    fn wallets() -> Vec<String> {
+        ["alice", "bob", "charlie"].map(String::from).to_vec()
+    }
+
+    #[test]
+    fn parses_rates_durations_and_ranges() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration("1h").unwrap(), Duration::from_secs(3600));
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("5 minutes").is_err());
+
+        assert_eq!(parse_tps("0.5").unwrap(), 0.5);
+        assert!(parse_tps("0").is_err());
+
+        assert_eq!(
+            parse_range("quantity=1_000_000..2_000_000").unwrap(),
+            ("quantity".to_string(), (1_000_000, 2_000_000))
+        );
+        assert!(parse_range("quantity=5..1").is_err());
+        assert!(parse_range("quantity=5").is_err());
+    }
+
+    #[test]
+    fn transfers_pick_distinct_wallets_and_amounts_in_range() {
+        let plan = Plan::new(&args(&["--range", "quantity=10..20"]), &tii(), wallets()).unwrap();
+        let mut rng = Rng::new(7);
+
+        for n in 1..=50 {
+            let tx = plan.next(&mut rng, n);
+
+            let sender = tx.args["sender"].as_str().unwrap();
+            let receiver = tx.args["receiver"].as_str().unwrap();
+            assert_ne!(sender, receiver);
+            assert_eq!(tx.signers, [sender.trim_start_matches('@')]);
+
+            let quantity = tx.args["quantity"].as_u64().unwrap();
+            assert!((10..=20).contains(&quantity), "{quantity}");
+        }
+    }
+
+    #[test]
+    fn plan_rejects_what_it_cannot_fill() {
+        let err = Plan::new(&args(&["--template", "tag"]), &tii(), wallets()).unwrap_err();
+        assert!(err.to_string().contains("`label`"), "{err}");
+
+        let plan = Plan::new(
+            &args(&["--template", "tag", "--arg", "label=6869"]),
+            &tii(),
+            wallets(),
+        );
+        assert!(plan.is_ok(), "{plan:?}");
+
+        let err = Plan::new(&args(&[]), &tii(), vec!["alice".to_string()]).unwrap_err();
+        assert!(
+            err.to_string().contains("needs 2 distinct wallets"),
+            "{err}"
+        );
+
+        let err = Plan::new(&args(&["--signer-party", "buyer"]), &tii(), wallets()).unwrap_err();
+        assert!(err.to_string().contains("no party `buyer`"), "{err}");
+    }
+}
@@ -0,0 +1,171 @@
+//! Save the running devnet's state under `.tx3/snapshots/<name>.tar.gz`.
+//!
+//! Dolos keeps the devnet ledger in memory, so there is no WAL on disk to
+//! copy. The snapshot instead captures the live UTxO set through u5c as
+//! `[[utxos]]` entries, next to the genesis files of the devnet home;
+//! `trix devnet restore` seeds a fresh devnet from them.
+
+use std::path::{Path, PathBuf};
+
+use clap::Args as ClapArgs;
+use miette::{Context as _, IntoDiagnostic as _, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ProfileConfig, RootConfig};
+use crate::wallet::Derivation;
+
+use super::dump;
+
+/// Name of the UTxO set inside the archive.
+pub const UTXOS_ENTRY: &str = "devnet.toml";
+
+/// Name of the manifest inside the archive.
+pub const MANIFEST_ENTRY: &str = "snapshot.json";
+
+/// Genesis files copied from the devnet home.
+pub const GENESIS_FILES: [&str; 4] = ["byron.json", "shelley.json", "alonzo.json", "conway.json"];
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Snapshot name; letters, digits, `-` and `_`
+    name: String,
+
+    /// Replace an existing snapshot with the same name
+    #[arg(long, default_value_t = false)]
+    force: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub name: String,
+    pub created_at: String,
+    /// Derivation tag of the devnet home the snapshot was taken from.
+    pub derivation: String,
+    pub utxos: usize,
+}
+
+pub fn validate_name(name: &str) -> miette::Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if !valid {
+        bail!(
+            help = "use letters, digits, `-` and `_`",
+            "invalid snapshot name `{name}`"
+        );
+    }
+
+    Ok(())
+}
+
+pub fn snapshot_path(name: &str) -> miette::Result<PathBuf> {
+    Ok(crate::dirs::target_dir("snapshots")?.join(format!("{name}.tar.gz")))
+}
+
+fn append_bytes(
+    archive: &mut tar::Builder<impl std::io::Write>,
+    name: &str,
+    bytes: &[u8],
+) -> miette::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(0);
+    header.set_cksum();
+
+    archive
+        .append_data(&mut header, name, bytes)
+        .into_diagnostic()
+        .with_context(|| format!("adding {name} to snapshot"))
+}
+
+fn write_archive(path: &Path, home: &Path, manifest: &Manifest, utxos: &str) -> miette::Result<()> {
+    let file = std::fs::File::create(path)
+        .into_diagnostic()
+        .with_context(|| format!("creating {}", path.display()))?;
+
+    let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    let mut archive = tar::Builder::new(encoder);
+
+    let manifest = serde_json::to_vec_pretty(manifest).into_diagnostic()?;
+    append_bytes(&mut archive, MANIFEST_ENTRY, &manifest)?;
+    append_bytes(&mut archive, UTXOS_ENTRY, utxos.as_bytes())?;
+
+    for name in GENESIS_FILES {
+        let bytes = std::fs::read(home.join(name))
+            .into_diagnostic()
+            .with_context(|| format!("reading {name} from {}", home.display()))?;
+        append_bytes(&mut archive, name, &bytes)?;
+    }
+
+    archive
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .into_diagnostic()
+        .context("finishing snapshot archive")?;
+
+    Ok(())
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    validate_name(&args.name)?;
+
+    let derivation = Derivation::for_project(config).tag();
+    let home = crate::devnet::home_dir(&derivation)?;
+
+    let running = crate::devnet::read_pid(&home).is_some_and(crate::spawn::shutdown::group_alive);
+
+    if !running {
+        bail!(
+            help = "start one with `trix devnet --background`",
+            "no background devnet is running for this project"
+        );
+    }
+
+    let path = snapshot_path(&args.name)?;
+
+    if path.exists() && !args.force {
+        bail!(
+            help = "pass --force to replace it",
+            "snapshot `{}` already exists at {}",
+            args.name,
+            path.display()
+        );
+    }
+
+    let network = config.resolve_profile_network(&profile.name)?;
+
+    let mut utxos = futures::executor::block_on(dump::fetch_all(&network.u5c))?;
+    utxos.sort_by(|a, b| a.r#ref.cmp(&b.r#ref));
+
+    let rendered = dump::render(&utxos, dump::Format::TomlSpec)?;
+
+    let manifest = Manifest {
+        name: args.name.clone(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        derivation,
+        utxos: utxos.len(),
+    };
+
+    write_archive(&path, &home, &manifest, &rendered)?;
+
+    println!("saved snapshot `{}` ({} utxos)", args.name, utxos.len());
+    println!("path: {}", path.display());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_names_are_plain_file_stems() {
+        assert!(validate_name("before-swap_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../escape").is_err());
+        assert!(validate_name("a b").is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use askama::Template;
+use clap::Args as ClapArgs;
+use miette::IntoDiagnostic as _;
+use serde::Serialize;
+
+use crate::config::{ProfileConfig, RootConfig};
+use crate::spawn::shutdown;
+use crate::term::OutputFormat;
+use crate::wallet::Derivation;
+
+const PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// dolos.toml `[serve.*]` sections the devnet listens on.
+const SERVICES: &[(&str, &str)] = &[("trp", "TRP"), ("grpc", "U5C"), ("minibf", "MiniBF")];
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    output: OutputFormat,
+}
+
+// ============================================================================
+// View Model
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PortView {
+    pub service: String,
+    pub port: u16,
+    pub listening: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusView {
+    pub running: bool,
+    pub home: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uptime_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ports: Vec<PortView>,
+    pub wallets: usize,
+}
+
+impl StatusView {
+    pub fn home_display(&self) -> String {
+        self.home.display().to_string()
+    }
+
+    pub fn uptime_display(&self) -> Option<String> {
+        let secs = self.uptime_secs?;
+        let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+        Some(match h {
+            0 => format!("{m}m {s:02}s"),
+            _ => format!("{h}h {m:02}m {s:02}s"),
+        })
+    }
+}
+
+// ============================================================================
+// Askama Template
+// ============================================================================
+
+#[derive(Template)]
+#[template(path = "devnet/status.md")]
+struct StatusTemplate<'a> {
+    view: &'a StatusView,
+}
+
+// ============================================================================
+// Command Entry Point
+// ============================================================================
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    let home = crate::devnet::home_dir(&Derivation::for_project(config).tag())?;
+
+    let pid = crate::devnet::read_pid(&home).filter(|pid| shutdown::group_alive(*pid));
+
+    let view = match pid {
+        Some(pid) => StatusView {
+            running: true,
+            pid: Some(pid),
+            uptime_secs: uptime(&home),
+            ports: ports(&home),
+            wallets: profile.identities.len(),
+            home,
+        },
+        None => StatusView {
+            running: false,
+            pid: None,
+            uptime_secs: None,
+            ports: vec![],
+            wallets: profile.identities.len(),
+            home,
+        },
+    };
+
+    match args.output {
+        OutputFormat::Human if !view.running => println!("no devnet running"),
+        OutputFormat::Human => {
+            let markdown = StatusTemplate { view: &view }
+                .render()
+                .expect("Template rendering failed");
+            crate::term::console::print_markdown(&markdown);
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&view).into_diagnostic()?);
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Probing
+// ============================================================================
+
+/// Time since the supervisor wrote its PID file, i.e. since the devnet
+/// started.
+fn uptime(home: &Path) -> Option<u64> {
+    let started = std::fs::metadata(crate::devnet::pid_file(home))
+        .and_then(|meta| meta.modified())
+        .ok()?;
+
+    SystemTime::now()
+        .duration_since(started)
+        .ok()
+        .map(|elapsed| elapsed.as_secs())
+}
+
+fn listen_port(address: &str) -> Option<u16> {
+    address.rsplit_once(':')?.1.parse().ok()
+}
+
+/// Port of the home's dolos.toml `[serve.<section>]`.
+pub(super) fn service_port(home: &Path, section: &str) -> Option<u16> {
+    let content = std::fs::read_to_string(home.join("dolos.toml")).ok()?;
+    let config = toml::from_str::<toml::Value>(&content).ok()?;
+
+    let address = config.get("serve")?.get(section)?.get("listen_address")?;
+    listen_port(address.as_str()?)
+}
+
+/// Whether something accepts connections on `port` on localhost.
+pub(super) fn listening(port: u16, timeout: Duration) -> bool {
+    let target = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    TcpStream::connect_timeout(&target, timeout).is_ok()
+}
+
+/// Ports from the home's dolos.toml, each probed on localhost.
+fn ports(home: &Path) -> Vec<PortView> {
+    SERVICES
+        .iter()
+        .filter_map(|(section, service)| {
+            let port = service_port(home, section)?;
+
+            Some(PortView {
+                service: service.to_string(),
+                port,
+                listening: listening(port, PROBE_TIMEOUT),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_ports_from_dolos_config() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::write(
+            home.path().join("dolos.toml"),
+            "[serve.grpc]\nlisten_address = \"[::]:5164\"\n\n[serve.trp]\nlisten_address = \"[::]:8164\"\n",
+        )
+        .unwrap();
+
+        let found: Vec<_> = ports(home.path())
+            .into_iter()
+            .map(|p| (p.service, p.port))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![("TRP".to_string(), 8164), ("U5C".to_string(), 5164)]
+        );
+    }
+}
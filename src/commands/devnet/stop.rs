@@ -0,0 +1,63 @@
+use std::time::{Duration, Instant};
+
+use clap::Args as ClapArgs;
+use miette::bail;
+
+use crate::config::{ProfileConfig, RootConfig};
+use crate::spawn::shutdown;
+use crate::wallet::Derivation;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Seconds to wait for the devnet to exit
+    #[arg(long, default_value_t = 10)]
+    timeout: u64,
+}
+
+pub fn run(args: Args, config: &RootConfig, _profile: &ProfileConfig) -> miette::Result<()> {
+    let home = crate::devnet::home_dir(&Derivation::for_project(config).tag())?;
+
+    let pid = crate::devnet::read_pid(&home).filter(|pid| shutdown::group_alive(*pid));
+
+    let Some(pid) = pid else {
+        // A PID file whose process is gone is left over from a devnet that
+        // exited on its own. Either way there is nothing left to stop.
+        crate::devnet::clear_pid(&home);
+        eprintln!(
+            "warning: no background devnet is running for this project (start one with `trix devnet --background`)"
+        );
+        return Ok(());
+    };
+
+    terminate(pid, args.timeout)?;
+
+    crate::devnet::clear_pid(&home);
+
+    println!("stopped devnet (pid {pid})");
+    println!("home: {}", home.display());
+
+    Ok(())
+}
+
+/// Terminate the devnet group led by `pid` and wait up to `timeout`
+/// seconds for it to exit.
+pub(super) fn terminate(pid: u32, timeout: u64) -> miette::Result<()> {
+    shutdown::terminate_group(pid);
+
+    let deadline = Instant::now() + Duration::from_secs(timeout);
+
+    while shutdown::group_alive(pid) {
+        if Instant::now() >= deadline {
+            bail!(
+                help = format!("kill it manually with `kill -9 -{pid}`"),
+                "devnet (pid {pid}) did not exit within {timeout}s"
+            );
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use askama::Template;
+use clap::Args as ClapArgs;
+
+use crate::home::{self, Location};
+
+/// Tools spawned by trix, in the order they're usually installed.
+const TOOLS: &[&str] = &["tx3c", "cshell", "dolos"];
+
+#[derive(ClapArgs)]
+pub struct Args {}
+
+// ============================================================================
+// View Model
+// ============================================================================
+
+#[derive(Debug, Clone)]
+pub struct PathView {
+    pub name: String,
+    pub path: String,
+    /// What decided the path, e.g. `TRIX_HOME` or `default`.
+    pub source: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ToolView {
+    pub name: String,
+    pub path: String,
+    pub source: String,
+    pub found: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct DoctorView {
+    pub state: Vec<PathView>,
+    pub config_file: String,
+    pub tools: Vec<ToolView>,
+    pub project: Option<PathView>,
+}
+
+#[derive(Template)]
+#[template(path = "doctor/paths.md")]
+struct DoctorTemplate<'a> {
+    view: &'a DoctorView,
+}
+
+// ============================================================================
+// View Building
+// ============================================================================
+
+fn build_tool_view(name: &str) -> miette::Result<ToolView> {
+    let (path, source) = match home::custom_tool_path(name)? {
+        Some(path) => (path, format!("TX3_{}_PATH", name.to_uppercase())),
+        None => (home::default_tool_location(name)?, "tx3up".to_string()),
+    };
+
+    Ok(ToolView {
+        name: name.to_string(),
+        found: path.is_file(),
+        path: path.display().to_string(),
+        source,
+    })
+}
+
+fn build_view() -> miette::Result<DoctorView> {
+    let state = Location::ALL
+        .iter()
+        .map(|location| {
+            let resolved = home::resolve(*location)?;
+            Ok(PathView {
+                name: location.to_string(),
+                path: resolved.path.display().to_string(),
+                source: resolved.source.to_string(),
+            })
+        })
+        .collect::<miette::Result<Vec<_>>>()?;
+
+    let config_file = home::resolve(Location::Config)?
+        .path
+        .join("config.toml")
+        .display()
+        .to_string();
+
+    let tools = TOOLS
+        .iter()
+        .map(|name| build_tool_view(name))
+        .collect::<miette::Result<Vec<_>>>()?;
+
+    let project = crate::dirs::protocol_root().ok().map(|root| PathView {
+        name: "project".to_string(),
+        path: root.join(".tx3").display().to_string(),
+        source: root.join("trix.toml").display().to_string(),
+    });
+
+    Ok(DoctorView {
+        state,
+        config_file,
+        tools,
+        project,
+    })
+}
+
+// ============================================================================
+// Command Entry Point
+// ============================================================================
+
+pub fn run(_args: Args) -> miette::Result<()> {
+    let view = build_view()?;
+
+    let markdown = DoctorTemplate { view: &view }
+        .render()
+        .expect("Template rendering failed");
+
+    crate::term::console::print_markdown(&markdown);
+
+    Ok(())
+}
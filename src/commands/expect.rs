@@ -5,117 +5,310 @@ use miette::Result;
 use crate::spawn::cshell;
 
 // Import Expect types from the `test` module
-use crate::commands::test::ExpectUtxo;
+use crate::commands::test::{DatumMatch, ExpectBalance, ExpectUtxo, Wallet};
 
 /// Resolve a `from` party reference to a cshell wallet name.
 ///
 /// Expectations name their party with the same `@`-prefixed placeholder the
 /// transactions use (e.g. `@bob`), but cshell wallets are named without the
 /// prefix (`bob`). The transaction path strips it in
-/// `test::replace_placeholder_args`; the expect path must do the same before
+/// `steps::replace_placeholder_args`; the expect path must do the same before
 /// querying utxos, or cshell errors with `failed to get wallet utxos`.
 fn wallet_name(from: &str) -> &str {
     from.trim_start_matches('@')
 }
 
-/// Run all checks for a slice of `ExpectUtxo` expectations.
-/// Returns `Ok(true)` when any expectation failed, `Ok(false)` when all passed.
-///
-///
-pub fn expect_utxo(expects: &[ExpectUtxo], test_home: &Path, provider: &str) -> Result<bool> {
-    let mut failed_any = false;
+/// One step of a `datum_matches` selector.
+#[derive(Debug, PartialEq)]
+pub enum Step {
+    Field(String),
+    Index(usize),
+}
+
+/// Parse a selector such as `$.fields[0].int` into its steps.
+pub fn parse_selector(path: &str) -> std::result::Result<Vec<Step>, String> {
+    let Some(rest) = path.strip_prefix('$') else {
+        return Err("must start with `$`".to_string());
+    };
 
-    for expect in expects.iter() {
-        let mut failed = false;
+    let mut steps = vec![];
+    let mut chars = rest.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                let mut name = String::new();
+
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_' || c == '-') {
+                        break;
+                    }
 
-        let utxos = cshell::wallet_utxos(test_home, wallet_name(&expect.from), provider)?;
+                    name.push(c);
+                    chars.next();
+                }
 
-        if expect.datum_equals.is_none() && expect.min_amount.is_empty() {
-            if utxos.is_empty() {
-                failed_any = true;
-                eprintln!("Test Failed: No UTXOs found for wallet `{}`.", expect.from);
+                if name.is_empty() {
+                    return Err("expected a field name after `.`".to_string());
+                }
+
+                steps.push(Step::Field(name));
+            }
+            '[' => {
+                let index: String = chars.by_ref().take_while(|c| *c != ']').collect();
+
+                let index = index
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("expected a list index in `[{index}]`"))?;
+
+                steps.push(Step::Index(index));
             }
-            continue;
+            other => return Err(format!("unexpected `{other}`")),
         }
+    }
 
-        // Find UTXOs that match the datum if specified
-        let matching_utxos: Vec<_> = if let Some(expected_datum) = &expect.datum_equals {
-            utxos
+    Ok(steps)
+}
+
+pub fn select<'a>(value: &'a serde_json::Value, steps: &[Step]) -> Option<&'a serde_json::Value> {
+    steps.iter().try_fold(value, |value, step| match step {
+        Step::Field(name) => value.get(name),
+        Step::Index(index) => value.get(index),
+    })
+}
+
+/// Integers too big for JSON numbers are decoded as decimal strings, so
+/// `equals = 42` also matches `"42"`.
+fn values_equal(expected: &serde_json::Value, actual: &serde_json::Value) -> bool {
+    match (expected, actual) {
+        (serde_json::Value::Number(n), serde_json::Value::String(s)) => n.to_string() == *s,
+        _ => expected == actual,
+    }
+}
+
+/// Evaluate one `datum_matches` check against a decoded datum, returning
+/// why it doesn't hold.
+fn datum_mismatch(datum: &serde_json::Value, check: &DatumMatch) -> Option<String> {
+    let steps = match parse_selector(&check.path) {
+        Ok(steps) => steps,
+        Err(err) => return Some(format!("`{}`: invalid path: {err}", check.path)),
+    };
+
+    let found = select(datum, &steps);
+    let path = &check.path;
+
+    match (&check.equals, check.exists, found) {
+        (Some(expected), _, Some(actual)) if !values_equal(expected, actual) => {
+            Some(format!("`{path}`: expected {expected}, found {actual}"))
+        }
+        (Some(expected), _, None) => Some(format!("`{path}`: expected {expected}, found no value")),
+        (_, Some(true), None) => Some(format!("`{path}`: expected a value, found none")),
+        (_, Some(false), Some(actual)) => {
+            Some(format!("`{path}`: expected no value, found {actual}"))
+        }
+        _ => None,
+    }
+}
+
+/// Check one `ExpectUtxo` expectation, returning the failure lines to
+/// print when it doesn't hold.
+pub fn utxo_failures(expect: &ExpectUtxo, test_home: &Path, provider: &str) -> Result<Vec<String>> {
+    let mut failure = vec![];
+
+    let utxos = cshell::wallet_utxos(test_home, wallet_name(&expect.from), provider)?;
+
+    if expect.datum_equals.is_none()
+        && expect.datum_matches.is_empty()
+        && expect.min_amount.is_empty()
+    {
+        if utxos.is_empty() {
+            failure.push(format!(
+                "Test Failed: No UTXOs found for wallet `{}`.",
+                expect.from
+            ));
+        }
+        return Ok(failure);
+    }
+
+    // Find UTXOs that match the datum if specified
+    let matching_utxos: Vec<_> = if let Some(expected_datum) = &expect.datum_equals {
+        utxos
+            .iter()
+            .filter(|utxo| {
+                if let Some(datum) = &utxo.datum {
+                    match expected_datum {
+                        serde_json::Value::String(s) => hex::encode(&datum.hash) == *s,
+                        _ => false,
+                    }
+                } else {
+                    false
+                }
+            })
+            .collect()
+    } else {
+        // If no datum_equals specified, consider all UTXOs
+        utxos.iter().collect()
+    };
+
+    let payloads: Vec<_> = matching_utxos
+        .iter()
+        .filter_map(|utxo| utxo.datum.as_ref()?.payload.as_ref())
+        .collect();
+
+    let mismatches = |payload: &serde_json::Value| -> Vec<String> {
+        expect
+            .datum_matches
+            .iter()
+            .filter_map(|check| datum_mismatch(payload, check))
+            .collect()
+    };
+
+    let matching_utxos: Vec<_> = matching_utxos
+        .into_iter()
+        .filter(|utxo| {
+            expect.datum_matches.is_empty()
+                || utxo
+                    .datum
+                    .as_ref()
+                    .and_then(|datum| datum.payload.as_ref())
+                    .is_some_and(|payload| mismatches(payload).is_empty())
+        })
+        .collect();
+
+    if !expect.datum_matches.is_empty() && matching_utxos.is_empty() {
+        failure.push(format!(
+            "Test Failed: no UTxO of wallet `{}` matches every `datum_matches` selector.",
+            expect.from
+        ));
+
+        if payloads.is_empty() {
+            failure.push("Found: no UTxO with an inline datum".to_string());
+        }
+
+        for (i, payload) in payloads.iter().enumerate() {
+            for mismatch in mismatches(payload) {
+                failure.push(format!("Datum #{}: {mismatch}", i + 1));
+            }
+        }
+    }
+
+    for min_req in &expect.min_amount {
+        let total_amount: u64 = if let (Some(policy), Some(name)) = (&min_req.policy, &min_req.name)
+        {
+            // Check for specific asset
+            matching_utxos
                 .iter()
-                .filter(|utxo| {
-                    if let Some(datum) = &utxo.datum {
-                        match expected_datum {
-                            serde_json::Value::String(s) => hex::encode(&datum.hash) == *s,
-                            _ => false,
-                        }
+                .flat_map(|utxo| utxo.assets.iter())
+                .map(|bal| {
+                    let policy_hex = hex::encode(&bal.policy_id);
+                    if policy_hex == *policy {
+                        bal.assets
+                            .iter()
+                            .filter(|asset| String::from_utf8_lossy(&asset.name) == *name)
+                            .map(|asset| asset.output_coin.parse::<u64>().unwrap_or(0))
+                            .sum::<u64>()
                     } else {
-                        false
+                        0u64
                     }
                 })
-                .collect()
+                .sum()
         } else {
-            // If no datum_equals specified, consider all UTXOs
-            utxos.iter().collect()
+            // Check for lovelace
+            matching_utxos
+                .iter()
+                .map(|utxo| utxo.coin.parse::<u64>().unwrap_or(0))
+                .sum()
         };
 
-        for min_req in &expect.min_amount {
-            let total_amount: u64 =
-                if let (Some(policy), Some(name)) = (&min_req.policy, &min_req.name) {
-                    // Check for specific asset
-                    matching_utxos
-                        .iter()
-                        .flat_map(|utxo| utxo.assets.iter())
-                        .map(|bal| {
-                            let policy_hex = hex::encode(&bal.policy_id);
-                            if policy_hex == *policy {
-                                bal.assets
-                                    .iter()
-                                    .filter(|asset| String::from_utf8_lossy(&asset.name) == *name)
-                                    .map(|asset| asset.output_coin.parse::<u64>().unwrap_or(0))
-                                    .sum::<u64>()
-                            } else {
-                                0u64
-                            }
-                        })
-                        .sum()
-                } else {
-                    // Check for lovelace
-                    matching_utxos
-                        .iter()
-                        .map(|utxo| utxo.coin.parse::<u64>().unwrap_or(0))
-                        .sum()
-                };
-
-            if total_amount < min_req.amount {
-                failed = true;
-
-                let asset_desc =
-                    if let (Some(policy), Some(name)) = (&min_req.policy, &min_req.name) {
-                        format!("asset {}.{}", policy, name)
-                    } else {
-                        "lovelace".to_string()
-                    };
-
-                eprintln!(
-                    "Test Failed: wallet `{}` with insufficient {}.",
-                    expect.from, asset_desc
-                );
-                eprintln!("Expected minimum: {}", min_req.amount);
-                eprintln!("Found: {}", total_amount);
-            }
-        }
+        if total_amount < min_req.amount {
+            let asset_desc = if let (Some(policy), Some(name)) = (&min_req.policy, &min_req.name) {
+                format!("asset {}.{}", policy, name)
+            } else {
+                "lovelace".to_string()
+            };
 
-        if failed {
-            failed_any = true;
+            failure.push(format!(
+                "Test Failed: wallet `{}` with insufficient {}.",
+                expect.from, asset_desc
+            ));
+            failure.push(format!("Expected minimum: {}", min_req.amount));
+            failure.push(format!("Found: {}", total_amount));
         }
     }
 
-    Ok(failed_any)
+    Ok(failure)
+}
+
+fn describe_range(min: Option<i64>, max: Option<i64>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("between {min} and {max}"),
+        (Some(min), None) => format!("at least {min}"),
+        (None, Some(max)) => format!("at most {max}"),
+        (None, None) => "any amount".to_string(),
+    }
+}
+
+/// Compare a wallet's `actual` balance with `expect`, returning the failure
+/// lines to print when it's out of range. `initial` is the balance declared
+/// for the wallet in the test's `wallets`.
+fn check_balance(expect: &ExpectBalance, initial: Option<u64>, actual: u64) -> Vec<String> {
+    let (measured, subject) = match (expect.delta_from_initial, initial) {
+        (true, Some(initial)) => (
+            actual as i64 - initial as i64,
+            format!("change from initial balance {initial}"),
+        ),
+        _ => (actual as i64, "balance".to_string()),
+    };
+
+    let below = expect.min.is_some_and(|min| measured < min);
+    let above = expect.max.is_some_and(|max| measured > max);
+
+    if !below && !above {
+        return vec![];
+    }
+
+    let mut lines = vec![
+        format!(
+            "Test Failed: wallet `{}` {subject} out of range.",
+            expect.wallet
+        ),
+        format!(
+            "Expected: {} lovelace",
+            describe_range(expect.min, expect.max)
+        ),
+        format!("Found: {measured} lovelace"),
+    ];
+
+    if expect.delta_from_initial {
+        lines.push(format!("Balance: {actual} lovelace"));
+    }
+
+    lines
+}
+
+/// Check one `ExpectBalance` expectation against the wallet's lovelace
+/// balance, returning the failure lines to print when it doesn't hold.
+pub fn balance_failures(
+    expect: &ExpectBalance,
+    wallets: &[Wallet],
+    test_home: &Path,
+) -> Result<Vec<String>> {
+    let name = wallet_name(&expect.wallet);
+
+    let balance = cshell::wallet_balance(test_home, name)?;
+
+    let initial = wallets
+        .iter()
+        .find(|wallet| wallet.name == name)
+        .map(|wallet| wallet.balance);
+
+    Ok(check_balance(expect, initial, balance.coin))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::wallet_name;
+    use super::*;
 
     #[test]
     fn wallet_name_strips_at_prefix() {
@@ -128,4 +321,136 @@ mod tests {
     fn wallet_name_passes_through_bare_name() {
         assert_eq!(wallet_name("bob"), "bob");
     }
+
+    fn check(path: &str, equals: Option<serde_json::Value>, exists: Option<bool>) -> DatumMatch {
+        DatumMatch {
+            path: path.to_string(),
+            equals,
+            exists,
+        }
+    }
+
+    #[test]
+    fn selector_parses_fields_and_indexes() {
+        assert_eq!(
+            parse_selector("$.fields[1].list[0]").unwrap(),
+            [
+                Step::Field("fields".to_string()),
+                Step::Index(1),
+                Step::Field("list".to_string()),
+                Step::Index(0),
+            ]
+        );
+        assert_eq!(parse_selector("$").unwrap(), []);
+
+        assert!(parse_selector("fields[0]").is_err());
+        assert!(parse_selector("$.fields[x]").is_err());
+        assert!(parse_selector("$..int").is_err());
+    }
+
+    #[test]
+    fn datum_match_selects_nested_constructor_fields() {
+        // Order { owner: bytes, deadline: Some(Int), prices: [1, 2] }
+        let datum = serde_json::json!({
+            "constructor": 0,
+            "fields": [
+                { "bytes": "abcd" },
+                { "constructor": 0, "fields": [{ "int": 1700000000 }] },
+                { "list": [{ "int": 1 }, { "int": "18446744073709551616" }] },
+            ]
+        });
+
+        let json = |v: serde_json::Value| Some(v);
+
+        for passing in [
+            check("$.fields[0].bytes", json("abcd".into()), None),
+            check("$.fields[1].constructor", json(0.into()), None),
+            check("$.fields[1].fields[0].int", json(1700000000.into()), None),
+            check(
+                "$.fields[2].list[1].int",
+                json("18446744073709551616".into()),
+                None,
+            ),
+            check("$.fields[1].fields[0]", None, Some(true)),
+            check("$.fields[3]", None, Some(false)),
+        ] {
+            assert_eq!(datum_mismatch(&datum, &passing), None, "{}", passing.path);
+        }
+
+        assert_eq!(
+            datum_mismatch(
+                &datum,
+                &check("$.fields[1].fields[0].int", json(42.into()), None)
+            ),
+            Some("`$.fields[1].fields[0].int`: expected 42, found 1700000000".to_string())
+        );
+        assert_eq!(
+            datum_mismatch(&datum, &check("$.fields[0]", None, Some(false))),
+            Some(r#"`$.fields[0]`: expected no value, found {"bytes":"abcd"}"#.to_string())
+        );
+    }
+
+    #[test]
+    fn datum_match_reports_missing_path() {
+        let datum = serde_json::json!({ "constructor": 1, "fields": [] });
+
+        assert_eq!(
+            datum_mismatch(&datum, &check("$.fields[0].int", Some(42.into()), None)),
+            Some("`$.fields[0].int`: expected 42, found no value".to_string())
+        );
+        assert_eq!(
+            datum_mismatch(&datum, &check("$.fields[0]", None, Some(true))),
+            Some("`$.fields[0]`: expected a value, found none".to_string())
+        );
+        assert_eq!(
+            datum_mismatch(&datum, &check("$.map", None, Some(false))),
+            None
+        );
+    }
+
+    fn expect(min: Option<i64>, max: Option<i64>, delta_from_initial: bool) -> ExpectBalance {
+        ExpectBalance {
+            wallet: "@alice".to_string(),
+            min,
+            max,
+            delta_from_initial,
+        }
+    }
+
+    #[test]
+    fn balance_within_bounds_passes() {
+        assert!(check_balance(&expect(Some(100), Some(200), false), None, 150).is_empty());
+        assert!(check_balance(&expect(Some(150), None, false), None, 150).is_empty());
+
+        // Sent 2 ADA plus 170_000 in fees out of 10 ADA.
+        let spent = expect(Some(-2_500_000), Some(-2_000_000), true);
+        assert!(check_balance(&spent, Some(10_000_000), 7_830_000).is_empty());
+    }
+
+    #[test]
+    fn balance_out_of_bounds_reports_range_and_actual() {
+        let failure = check_balance(&expect(None, Some(100), false), None, 150);
+
+        assert_eq!(
+            failure,
+            [
+                "Test Failed: wallet `@alice` balance out of range.",
+                "Expected: at most 100 lovelace",
+                "Found: 150 lovelace",
+            ]
+        );
+
+        let spent = expect(Some(-2_500_000), Some(-2_000_000), true);
+        let failure = check_balance(&spent, Some(10_000_000), 9_000_000);
+
+        assert_eq!(
+            failure,
+            [
+                "Test Failed: wallet `@alice` change from initial balance 10000000 out of range.",
+                "Expected: between -2500000 and -2000000 lovelace",
+                "Found: -1000000 lovelace",
+                "Balance: 9000000 lovelace",
+            ]
+        );
+    }
 }
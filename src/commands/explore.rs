@@ -1,14 +1,89 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
 use clap::Args as ClapArgs;
+use miette::{Context as _, IntoDiagnostic as _, bail};
 
 use crate::config::{ProfileConfig, RootConfig};
+use crate::spawn::shutdown;
 
 #[derive(ClapArgs)]
-pub struct Args {}
+pub struct Args {
+    /// Reload the profile's env file when it changes, re-resolving the
+    /// `env:` header references in the cshell config and restarting the
+    /// explorer to use them
+    #[arg(long)]
+    watch_env: bool,
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    if !args.watch_env {
+        let wallet = crate::wallet::setup(config, profile)?;
+        return wallet.explorer(profile.name.as_str());
+    }
+
+    explore_watching_env(config, profile)
+}
+
+/// Run the explorer while watching the profile's env file. A running
+/// explorer keeps the headers it read at startup, so each reload rewrites
+/// the cshell config and restarts the explorer.
+fn explore_watching_env(config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    let network = config.resolve_profile_network(&profile.name)?;
+    let target_dir = crate::dirs::target_dir("cshell")?;
+
+    // Pid of the explorer running, and whether a reload stopped it.
+    let running = Arc::new(Mutex::new(None::<u32>));
+    let restart = Arc::new(AtomicBool::new(false));
+
+    let _watch = {
+        let target_dir = target_dir.clone();
+        let profile_name = profile.name.clone();
+        let running = running.clone();
+        let restart = restart.clone();
+
+        let loaded = crate::envfile::loaded().clone();
+
+        crate::envfile::watch(&profile.env_file_path(), loaded, move |_| {
+            if let Err(err) =
+                crate::wallet::write_cshell_config(&target_dir, &profile_name, &network)
+            {
+                eprintln!("cshell config not updated: {err}");
+                return;
+            }
+
+            if let Some(pid) = *running.lock().unwrap() {
+                restart.store(true, Ordering::SeqCst);
+                shutdown::interrupt(pid);
+            }
+        })?
+    };
 
-pub fn run(_args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
     let wallet = crate::wallet::setup(config, profile)?;
+    let provider = crate::wallet::provider_name(&profile.name);
+
+    loop {
+        let mut child = crate::spawn::cshell::explorer(&wallet.target_dir, &provider)?;
+        let _tracked = shutdown::track(&child);
+
+        *running.lock().unwrap() = Some(child.id());
+
+        let status = child
+            .wait()
+            .into_diagnostic()
+            .context("failed to wait for cshell explorer")?;
+
+        *running.lock().unwrap() = None;
+
+        if restart.swap(false, Ordering::SeqCst) {
+            eprintln!("restarting the cshell explorer with the reloaded values");
+            continue;
+        }
 
-    wallet.explorer(profile.name.as_str())?;
+        if !status.success() {
+            bail!("cshell explorer exited with code: {}", status);
+        }
 
-    Ok(())
+        return Ok(());
+    }
 }
@@ -0,0 +1,648 @@
+//! CIP-57 blueprints (Aiken's `plutus.json`) to tx3: type definitions for
+//! a validator's datum and redeemer, plus skeleton transactions that lock
+//! funds at the validator and spend them back. Whatever the schema uses
+//! that tx3 can't express is reported as a warning, never dropped silently.
+
+use std::collections::BTreeMap;
+
+use convert_case::{Case, Casing as _};
+use miette::{Result, bail};
+use serde_json::Value;
+
+/// Generated tx3 source, ready to append to a protocol file.
+pub struct Scaffold {
+    pub source: String,
+    pub lock: String,
+    pub spend: String,
+    pub warnings: Vec<String>,
+}
+
+/// A blueprint validator handler, from a `module.validator.purpose` title.
+struct Handler<'a> {
+    module: &'a str,
+    name: &'a str,
+    purpose: &'a str,
+    entry: &'a Value,
+}
+
+fn handlers(blueprint: &Value) -> Vec<Handler<'_>> {
+    let validators = blueprint
+        .get("validators")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    validators
+        .iter()
+        .filter_map(|entry| {
+            let title = entry.get("title")?.as_str()?;
+            let mut parts = title.splitn(3, '.');
+
+            // Titles before Aiken 1.1 have no purpose; those validators
+            // always spend.
+            Some(Handler {
+                module: parts.next()?,
+                name: parts.next()?,
+                purpose: parts.next().unwrap_or("spend"),
+                entry,
+            })
+        })
+        .collect()
+}
+
+/// Tx3 identifiers can't carry the `$`, `/` or `~` of blueprint keys.
+fn identifier(text: &str, case: Case) -> String {
+    let cleaned: String = text
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c,
+            false => '_',
+        })
+        .collect();
+
+    cleaned.to_case(case)
+}
+
+/// The first constructor of a declared type: what a generated transaction
+/// builds its datum or redeemer with.
+struct Shape {
+    /// Set for types with several constructors.
+    variant: Option<String>,
+    fields: Vec<(String, String)>,
+}
+
+/// Renders blueprint schemas as tx3 types, declaring each named
+/// definition once.
+struct Types<'a> {
+    definitions: Option<&'a serde_json::Map<String, Value>>,
+    /// Definition key to the tx3 type declared for it.
+    declared: BTreeMap<String, String>,
+    shapes: BTreeMap<String, Shape>,
+    blocks: Vec<String>,
+    warnings: Vec<String>,
+}
+
+impl<'a> Types<'a> {
+    fn new(blueprint: &'a Value) -> Self {
+        Self {
+            definitions: blueprint.get("definitions").and_then(Value::as_object),
+            declared: BTreeMap::new(),
+            shapes: BTreeMap::new(),
+            blocks: vec![],
+            warnings: vec![],
+        }
+    }
+
+    fn lookup(&mut self, reference: &str) -> Option<(String, &'a Value)> {
+        let key = reference
+            .trim_start_matches("#/definitions/")
+            .replace("~1", "/")
+            .replace("~0", "~");
+
+        match self.definitions.and_then(|d| d.get(&key)) {
+            Some(definition) => Some((key, definition)),
+            None => {
+                self.warnings.push(format!(
+                    "`{reference}` is not in the blueprint's definitions"
+                ));
+                None
+            }
+        }
+    }
+
+    /// Name for a declared type: generic instances (`Option$Int`) by key,
+    /// everything else by title, numbered when two definitions share one.
+    fn type_name(&self, key: &str, definition: &Value) -> String {
+        let last = key.rsplit('/').next().unwrap_or(key);
+
+        let base = match definition.get("title").and_then(Value::as_str) {
+            Some(title) if !key.contains('$') => identifier(title, Case::Pascal),
+            _ => identifier(last, Case::Pascal),
+        };
+
+        let taken = |name: &str| self.declared.values().any(|n| n == name);
+
+        let mut name = base.clone();
+        let mut n = 2;
+
+        while taken(&name) {
+            name = format!("{base}{n}");
+            n += 1;
+        }
+
+        name
+    }
+
+    /// The tx3 type for `schema`, declaring the types it needs. `root`
+    /// names the type declared for a constructor schema instead of its
+    /// title, so datum and redeemer types get the validator's prefix.
+    fn resolve(&mut self, schema: &'a Value, root: Option<&str>, context: &str) -> String {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let Some((key, definition)) = self.lookup(reference) else {
+                return "Bytes".to_string();
+            };
+
+            if let Some(name) = self.declared.get(&key) {
+                return name.clone();
+            }
+
+            if key == "Bool" {
+                return "Bool".to_string();
+            }
+
+            if !is_constructed(definition) {
+                return self.resolve(definition, root, context);
+            }
+
+            let name = match root {
+                Some(root) => root.to_string(),
+                None => self.type_name(&key, definition),
+            };
+
+            self.declared.insert(key, name.clone());
+            self.declare(&name, definition, context);
+
+            return name;
+        }
+
+        if is_constructed(schema) {
+            let name = root.map(str::to_string).unwrap_or_else(|| {
+                identifier(
+                    schema
+                        .get("title")
+                        .and_then(Value::as_str)
+                        .unwrap_or(context),
+                    Case::Pascal,
+                )
+            });
+
+            self.declare(&name, schema, context);
+            return name;
+        }
+
+        match schema.get("dataType").and_then(Value::as_str) {
+            Some("bytes") => "Bytes".to_string(),
+            Some("integer") => "Int".to_string(),
+            Some("list") => match schema.get("items") {
+                Some(items) if items.is_object() => {
+                    format!("List<{}>", self.resolve(items, None, context))
+                }
+                _ => {
+                    self.warnings.push(format!(
+                        "{context}: tuples aren't supported in tx3; typed as `Bytes`"
+                    ));
+                    "Bytes".to_string()
+                }
+            },
+            Some("map") => match (schema.get("keys"), schema.get("values")) {
+                (Some(keys), Some(values)) => format!(
+                    "Map<{}, {}>",
+                    self.resolve(keys, None, context),
+                    self.resolve(values, None, context)
+                ),
+                _ => {
+                    self.warnings.push(format!(
+                        "{context}: map without key or value schema; typed as `Bytes`"
+                    ));
+                    "Bytes".to_string()
+                }
+            },
+            Some(other) => {
+                self.warnings.push(format!(
+                    "{context}: data type `{other}` has no tx3 equivalent; typed as `Bytes`"
+                ));
+                "Bytes".to_string()
+            }
+            None => {
+                self.warnings.push(format!(
+                    "{context}: schema is opaque `Data`; typed as `Bytes`"
+                ));
+                "Bytes".to_string()
+            }
+        }
+    }
+
+    fn declare(&mut self, name: &str, definition: &'a Value, context: &str) {
+        let mut constructors = constructors(definition);
+        constructors.sort_by_key(|c| c.get("index").and_then(Value::as_u64).unwrap_or(0));
+
+        let contiguous = constructors
+            .iter()
+            .enumerate()
+            .all(|(i, c)| c.get("index").and_then(Value::as_u64) == Some(i as u64));
+
+        if !contiguous {
+            self.warnings.push(format!(
+                "{name}: constructor indexes aren't 0, 1, 2...; tx3 numbers variants in declaration order"
+            ));
+        }
+
+        let single = constructors.len() == 1;
+        let mut block = format!("type {name} {{\n");
+
+        for constructor in constructors {
+            let fields = self.fields(constructor, &format!("{context} > {name}"));
+
+            let variant = (!single).then(|| {
+                let title = constructor
+                    .get("title")
+                    .and_then(Value::as_str)
+                    .unwrap_or("Variant");
+                identifier(title, Case::Pascal)
+            });
+
+            match &variant {
+                None => {
+                    for (field, ty) in &fields {
+                        block.push_str(&format!("    {field}: {ty},\n"));
+                    }
+                }
+                Some(variant) if fields.is_empty() => {
+                    block.push_str(&format!("    {variant},\n"));
+                }
+                Some(variant) => {
+                    block.push_str(&format!("    {variant} {{\n"));
+
+                    for (field, ty) in &fields {
+                        block.push_str(&format!("        {field}: {ty},\n"));
+                    }
+
+                    block.push_str("    },\n");
+                }
+            }
+
+            self.shapes
+                .entry(name.to_string())
+                .or_insert(Shape { variant, fields });
+        }
+
+        block.push_str("}\n");
+        self.blocks.push(block);
+    }
+
+    /// `(name, tx3 type)` of each field; positional fields are numbered.
+    fn fields(&mut self, constructor: &'a Value, context: &str) -> Vec<(String, String)> {
+        let fields = constructor
+            .get("fields")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let name = match field.get("title").and_then(Value::as_str) {
+                    Some(title) => identifier(title, Case::Snake),
+                    None => format!("field_{i}"),
+                };
+
+                let ty = self.resolve(field, None, &format!("{context}.{name}"));
+                (name, ty)
+            })
+            .collect()
+    }
+}
+
+/// Whether `schema` is a constructor, or a union of them, and so becomes
+/// a declared tx3 type.
+fn is_constructed(schema: &Value) -> bool {
+    schema.get("anyOf").is_some()
+        || schema.get("dataType").and_then(Value::as_str) == Some("constructor")
+}
+
+fn constructors(schema: &Value) -> Vec<&Value> {
+    match schema.get("anyOf").and_then(Value::as_array) {
+        Some(cases) => cases.iter().collect(),
+        None => vec![schema],
+    }
+}
+
+/// A tx3 value of type `ty` for a datum or redeemer, its fields taken
+/// from transaction parameters appended to `params`. A parameter whose
+/// name is in `taken` gets the type's name as prefix.
+fn construct(
+    types: &Types,
+    ty: &str,
+    param: &str,
+    taken: &[&str],
+    params: &mut Vec<(String, String)>,
+) -> String {
+    let Some(shape) = types.shapes.get(ty) else {
+        params.push((param.to_string(), ty.to_string()));
+        return param.to_string();
+    };
+
+    let mut value = match &shape.variant {
+        Some(variant) => format!("{ty}::{variant} {{\n"),
+        None => format!("{ty} {{\n"),
+    };
+
+    for (field, field_ty) in &shape.fields {
+        let param = match taken.contains(&field.as_str()) {
+            true => format!("{}_{field}", identifier(ty, Case::Snake)),
+            false => field.clone(),
+        };
+
+        value.push_str(&format!("            {field}: {param},\n"));
+        params.push((param, field_ty.clone()));
+    }
+
+    value.push_str("        }");
+    value
+}
+
+fn params_list(params: &[(String, String)]) -> String {
+    let params: Vec<String> = params
+        .iter()
+        .map(|(name, ty)| format!("    {name}: {ty}"))
+        .collect();
+
+    params.join(",\n")
+}
+
+/// Generate the tx3 for `validator`, given by name (`hello_world`) or
+/// module and name (`vesting.hello_world`).
+pub fn scaffold(blueprint: &Value, validator: &str) -> Result<Scaffold> {
+    let all = handlers(blueprint);
+
+    let matching: Vec<&Handler> = all
+        .iter()
+        .filter(|h| h.name == validator || format!("{}.{}", h.module, h.name) == validator)
+        .collect();
+
+    let Some(first) = matching.first() else {
+        let mut names: Vec<String> = all
+            .iter()
+            .map(|h| format!("{}.{}", h.module, h.name))
+            .collect();
+        names.dedup();
+
+        bail!(
+            help = format!("validators in the blueprint: {}", names.join(", ")),
+            "no validator `{validator}` in the blueprint"
+        );
+    };
+
+    if matching.iter().any(|h| h.module != first.module) {
+        bail!(
+            help = "qualify it with its module, e.g. `module.validator`",
+            "`{validator}` names validators in more than one module"
+        );
+    }
+
+    let Some(spend) = matching.iter().find(|h| h.purpose == "spend") else {
+        bail!(
+            "`{}.{}` has no spend handler; only spending validators can be scaffolded",
+            first.module,
+            first.name
+        );
+    };
+
+    let mut warnings = vec![];
+
+    for other in matching
+        .iter()
+        .filter(|h| !matches!(h.purpose, "spend" | "else"))
+    {
+        warnings.push(format!(
+            "the `{}` handler isn't scaffolded; write its transaction by hand",
+            other.purpose
+        ));
+    }
+
+    let Some(hash) = spend.entry.get("hash").and_then(Value::as_str) else {
+        bail!("validator `{}.{}` has no hash", spend.module, spend.name);
+    };
+
+    let prefix = identifier(spend.name, Case::Pascal);
+    let snake = identifier(spend.name, Case::Snake);
+    let party = format!("{prefix}User");
+
+    let mut types = Types::new(blueprint);
+
+    let datum = spend.entry.get("datum").and_then(|d| d.get("schema"));
+    let redeemer = spend.entry.get("redeemer").and_then(|r| r.get("schema"));
+
+    let datum_ty =
+        datum.map(|schema| types.resolve(schema, Some(&format!("{prefix}Datum")), "datum"));
+    let redeemer_ty = redeemer
+        .map(|schema| types.resolve(schema, Some(&format!("{prefix}Redeemer")), "redeemer"));
+
+    let mut lock_params = vec![("quantity".to_string(), "Int".to_string())];
+
+    let datum_line = match &datum_ty {
+        Some(ty) => {
+            let value = construct(&types, ty, "datum", &["quantity"], &mut lock_params);
+            format!(
+                "        // TODO: set the datum the validator expects.\n        datum: {value},\n"
+            )
+        }
+        None => {
+            warnings.push("the validator declares no datum; the lock output has none".to_string());
+            String::new()
+        }
+    };
+
+    let mut spend_params = vec![("locked_utxo".to_string(), "UtxoRef".to_string())];
+
+    let redeemer_value = match &redeemer_ty {
+        Some(ty) => construct(&types, ty, "redeemer", &["locked_utxo"], &mut spend_params),
+        None => {
+            warnings.push("the validator declares no redeemer; spending with `()`".to_string());
+            "()".to_string()
+        }
+    };
+
+    let lock = format!("lock_{snake}");
+    let spend_tx = format!("spend_{snake}");
+
+    let mut source = format!(
+        "// Generated by `trix init tx` from the `{module}.{name}` validator.\n\n\
+         party {party};\n\n\
+         policy {prefix} = 0x{hash};\n",
+        module = spend.module,
+        name = spend.name,
+    );
+
+    for block in &types.blocks {
+        source.push('\n');
+        source.push_str(block);
+    }
+
+    source.push_str(&format!(
+        "
+// TODO: check the amounts and parties against the validator's rules.
+tx {lock}(
+{lock_params}
+) {{
+    input source {{
+        from: {party},
+        min_amount: Ada(quantity) + fees,
+    }}
+
+    output {{
+        to: {prefix},
+        amount: Ada(quantity),
+{datum_line}    }}
+
+    output {{
+        to: {party},
+        amount: source - Ada(quantity) - fees,
+    }}
+}}
+
+// TODO: attach the validator script (e.g. from a reference input) and any
+// signers or validity range its rules check.
+tx {spend_tx}(
+{spend_params}
+) {{
+    input gas {{
+        from: {party},
+        min_amount: fees,
+    }}
+
+    input locked {{
+        ref: locked_utxo,
+        // TODO: set the redeemer the validator expects.
+        redeemer: {redeemer_value},
+    }}
+
+    output {{
+        to: {party},
+        amount: gas + locked - fees,
+    }}
+}}
+",
+        lock_params = params_list(&lock_params),
+        spend_params = params_list(&spend_params),
+    ));
+
+    warnings.append(&mut types.warnings);
+
+    Ok(Scaffold {
+        source,
+        lock,
+        spend: spend_tx,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vesting() -> Value {
+        serde_json::json!({
+            "validators": [
+                {
+                    "title": "vesting.hello_world.spend",
+                    "datum": { "schema": { "$ref": "#/definitions/vesting~1Datum" } },
+                    "redeemer": { "schema": { "$ref": "#/definitions/vesting~1Action" } },
+                    "hash": "2499e3a6"
+                },
+                { "title": "vesting.hello_world.mint", "redeemer": { "schema": {} }, "hash": "2499e3a6" },
+                { "title": "vesting.hello_world.else", "redeemer": { "schema": {} }, "hash": "2499e3a6" }
+            ],
+            "definitions": {
+                "ByteArray": { "dataType": "bytes" },
+                "Int": { "dataType": "integer" },
+                "Data": { "title": "Data", "description": "Any Plutus data." },
+                "aiken/crypto/VerificationKeyHash": { "title": "VerificationKeyHash", "dataType": "bytes" },
+                "List$Int": { "dataType": "list", "items": { "$ref": "#/definitions/Int" } },
+                "vesting/Datum": {
+                    "title": "Datum",
+                    "anyOf": [{
+                        "title": "Datum",
+                        "dataType": "constructor",
+                        "index": 0,
+                        "fields": [
+                            { "title": "owner", "$ref": "#/definitions/aiken~1crypto~1VerificationKeyHash" },
+                            { "title": "lockUntil", "$ref": "#/definitions/Int" },
+                            { "title": "amounts", "$ref": "#/definitions/List$Int" },
+                            { "title": "extra", "$ref": "#/definitions/Data" }
+                        ]
+                    }]
+                },
+                "vesting/Action": {
+                    "title": "Action",
+                    "anyOf": [
+                        {
+                            "title": "Claim",
+                            "dataType": "constructor",
+                            "index": 0,
+                            "fields": [{ "title": "quantity", "$ref": "#/definitions/Int" }]
+                        },
+                        { "title": "Cancel", "dataType": "constructor", "index": 1, "fields": [] }
+                    ]
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn declares_datum_and_redeemer_types() {
+        let scaffold = scaffold(&vesting(), "hello_world").unwrap();
+
+        assert!(scaffold.source.contains("policy HelloWorld = 0x2499e3a6;"));
+        assert!(scaffold.source.contains(
+            "type HelloWorldDatum {\n    owner: Bytes,\n    lock_until: Int,\n    amounts: List<Int>,\n    extra: Bytes,\n}\n"
+        ));
+        assert!(scaffold.source.contains(
+            "type HelloWorldRedeemer {\n    Claim {\n        quantity: Int,\n    },\n    Cancel,\n}\n"
+        ));
+        assert_eq!(scaffold.lock, "lock_hello_world");
+        assert_eq!(scaffold.spend, "spend_hello_world");
+    }
+
+    #[test]
+    fn fills_datum_and_redeemer_from_params() {
+        let scaffold = scaffold(&vesting(), "vesting.hello_world").unwrap();
+
+        assert!(scaffold.source.contains(
+            "tx lock_hello_world(\n    quantity: Int,\n    owner: Bytes,\n    lock_until: Int,\n    amounts: List<Int>,\n    extra: Bytes\n)"
+        ));
+        assert!(
+            scaffold
+                .source
+                .contains("datum: HelloWorldDatum {\n            owner: owner,")
+        );
+
+        // The redeemer's `quantity` doesn't clash with the lock parameter,
+        // which lives in another transaction.
+        assert!(
+            scaffold
+                .source
+                .contains("tx spend_hello_world(\n    locked_utxo: UtxoRef,\n    quantity: Int\n)")
+        );
+        assert!(
+            scaffold
+                .source
+                .contains("redeemer: HelloWorldRedeemer::Claim {\n            quantity: quantity,")
+        );
+    }
+
+    #[test]
+    fn warns_about_unmapped_schema() {
+        let scaffold = scaffold(&vesting(), "hello_world").unwrap();
+
+        assert_eq!(
+            scaffold.warnings,
+            [
+                "the `mint` handler isn't scaffolded; write its transaction by hand",
+                "datum > HelloWorldDatum.extra: schema is opaque `Data`; typed as `Bytes`",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_validator_lists_available_ones() {
+        let err = scaffold(&vesting(), "nope").unwrap_err();
+
+        assert!(err.to_string().contains("no validator `nope`"), "{err}");
+        assert!(
+            format!("{err:?}").contains("vesting.hello_world"),
+            "{err:?}"
+        );
+    }
+}
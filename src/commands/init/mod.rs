@@ -0,0 +1,450 @@
+use std::path::{Path, PathBuf};
+
+use crate::config::{
+    CodegenConfig, CodegenPlugin, KNOWN_CODEGEN_PLUGINS, KnownLedgerFamily, LedgerConfig,
+    ProfileConfig, ProtocolConfig, RootConfig, serde::NamedMap,
+};
+use crate::generated::{Guard, OverwritePolicy};
+use askama::Template;
+use clap::{Args as ClapArgs, Subcommand};
+use inquire::{MultiSelect, Text};
+use miette::{Context, IntoDiagnostic, bail};
+
+mod blueprint;
+pub mod tx;
+
+// Include template files at compile time
+const TEMPLATE_MAIN_TX3: &str = include_str!("../../../templates/tx3/main.tx3.tpl");
+const TEMPLATE_TEST_TOML: &str = include_str!("../../../templates/tx3/test.toml.tpl");
+const TEMPLATE_GITIGNORE: &str = include_str!("../../../templates/tx3/.gitignore.tpl");
+const TEMPLATE_STUB_TX3: &str = include_str!("../../../templates/tx3/stub.tx3.tpl");
+const DEFAULT_PROJECT_NAME: &str = "my-project";
+const DEFAULT_DEVNET_WALLET_AMOUNT: u64 = 100_000_000_000;
+
+/// Example protocol a new project starts from, with a test that exercises
+/// it. Without `--template`, projects start from a plain transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[clap(rename_all = "kebab-case")]
+pub enum ProjectTemplate {
+    /// A buyer deposits with an arbiter, who releases to the seller or refunds
+    Escrow,
+    /// Funds locked at a validator until a beneficiary may claim them
+    Vesting,
+    /// Mint a single token under a policy and send it on
+    NftMint,
+}
+
+/// `main.tx3` and `tests/basic.toml` a new project starts with.
+fn template_files(template: Option<ProjectTemplate>) -> (&'static str, &'static str) {
+    match template {
+        None => (TEMPLATE_MAIN_TX3, TEMPLATE_TEST_TOML),
+        Some(ProjectTemplate::Escrow) => (
+            include_str!("../../../templates/tx3/escrow/main.tx3.tpl"),
+            include_str!("../../../templates/tx3/escrow/test.toml.tpl"),
+        ),
+        Some(ProjectTemplate::Vesting) => (
+            include_str!("../../../templates/tx3/vesting/main.tx3.tpl"),
+            include_str!("../../../templates/tx3/vesting/test.toml.tpl"),
+        ),
+        Some(ProjectTemplate::NftMint) => (
+            include_str!("../../../templates/tx3/nft-mint/main.tx3.tpl"),
+            include_str!("../../../templates/tx3/nft-mint/test.toml.tpl"),
+        ),
+    }
+}
+
+fn infer_project_name() -> String {
+    let current_dir = match std::env::current_dir() {
+        Ok(dir) => dir,
+        Err(_) => return DEFAULT_PROJECT_NAME.to_string(),
+    };
+
+    let project_name = current_dir
+        .file_name()
+        .and_then(|f| f.to_str())
+        .map(|s| s.to_string());
+
+    project_name.unwrap_or_else(|| DEFAULT_PROJECT_NAME.to_string())
+}
+
+fn prompt<'a>(msg: &'a str, default: Option<&'a str>, initial: Option<&'a str>) -> Text<'a> {
+    let mut prompt = Text::new(msg);
+
+    if let Some(initial) = initial {
+        prompt = prompt.with_initial_value(initial);
+    } else if let Some(default) = default {
+        prompt = prompt.with_default(default);
+    }
+
+    prompt
+}
+
+fn infer_devnet(profile: &ProfileConfig) -> crate::devnet::Config {
+    let utxos = profile
+        .identities
+        .keys()
+        .map(|key| {
+            crate::devnet::UtxoSpec::Explicit(crate::devnet::ExplicitUtxoSpec {
+                address: crate::devnet::AddressSpec::NamedWallet(key.clone()),
+                value: DEFAULT_DEVNET_WALLET_AMOUNT,
+                assets: Default::default(),
+                datum_inline: None,
+                datum_hash: None,
+                script_ref: None,
+                script_language: None,
+            })
+        })
+        .collect();
+
+    crate::devnet::Config {
+        utxos,
+        ports: Default::default(),
+        chain: Default::default(),
+    }
+}
+
+/// Manifest of the template files `trix init` wrote, so a re-run can tell
+/// untouched templates from files the user has made their own.
+const INIT_MANIFEST: &str = ".tx3/init-generated.json";
+
+fn apply(
+    config: RootConfig,
+    devnet: Option<crate::devnet::Config>,
+    template: Option<ProjectTemplate>,
+    policy: OverwritePolicy,
+    force: bool,
+) -> miette::Result<()> {
+    let (main_tx3, test_toml) = template_files(template);
+
+    let mut guard = Guard::open(".", INIT_MANIFEST, policy, force)?;
+
+    if let Some(devnet) = devnet {
+        let devnet_toml = toml::to_string_pretty(&devnet).into_diagnostic()?;
+        guard.write(Path::new("devnet.toml"), devnet_toml.as_bytes())?;
+    }
+
+    guard.write(Path::new(".gitignore"), TEMPLATE_GITIGNORE.as_bytes())?;
+
+    guard.write(Path::new("main.tx3"), main_tx3.as_bytes())?;
+
+    guard.write(Path::new("tests/basic.toml"), test_toml.as_bytes())?;
+
+    guard.finish()?;
+
+    let trix_toml = toml::to_string_pretty(&config).into_diagnostic()?;
+
+    std::fs::write("trix.toml", &trix_toml)
+        .into_diagnostic()
+        .context("writing trix.toml")?;
+
+    Ok(())
+}
+
+/// Consumer-shape default config: a project that intends to *use* protocols
+/// rather than author one. Differs from [`default_config`] in that it omits
+/// the owner scope (consumers never publish) and starts with no codegen
+/// targets — `trix codegen --plugin <name>` seeds them on demand.
+fn consumer_default_config() -> RootConfig {
+    RootConfig {
+        config_schema_version: Some(crate::config::migrate::CURRENT_VERSION),
+        wallet_seed: None,
+        legacy_wallet_derivation: false,
+        protocol: ProtocolConfig {
+            name: infer_project_name(),
+            scope: None,
+            version: "0.1.0".into(),
+            description: None,
+            main: "main.tx3".into(),
+            sources: None,
+            readme: None,
+            logo: None,
+            repository: None,
+        },
+        ledger: LedgerConfig {
+            family: KnownLedgerFamily::Cardano,
+        },
+        toolchain: None,
+        codegen: Vec::new(),
+        profiles: NamedMap::default(),
+        networks: NamedMap::default(),
+        registry: None,
+        interfaces: NamedMap::default(),
+        invoke: None,
+        budgets: Default::default(),
+    }
+}
+
+/// Create a minimal consumer-shape project in the current directory. Writes
+/// only `trix.toml` — no `main.tx3`, no `tests/`, no `devnet.toml` — and
+/// prints a one-line notice so the side effect is visible. Returns the
+/// freshly-written config alongside the path it was saved to, so the caller
+/// can save further mutations (e.g. an `[interfaces.*]` pin from `trix use`)
+/// back to the same file.
+pub fn bootstrap_consumer_project() -> miette::Result<(RootConfig, PathBuf)> {
+    let cwd = std::env::current_dir().into_diagnostic()?;
+    let trix_toml = cwd.join("trix.toml");
+
+    let config = consumer_default_config();
+    config.save(&trix_toml)?;
+
+    eprintln!("No trix project found — created trix.toml here.");
+
+    Ok((config, trix_toml))
+}
+
+fn default_config() -> RootConfig {
+    RootConfig {
+        config_schema_version: Some(crate::config::migrate::CURRENT_VERSION),
+        wallet_seed: None,
+        legacy_wallet_derivation: false,
+        protocol: ProtocolConfig {
+            name: infer_project_name(),
+            scope: None,
+            version: "0.0.0".into(),
+            description: None,
+            main: "main.tx3".into(),
+            sources: None,
+            readme: None,
+            logo: None,
+            repository: None,
+        },
+        ledger: LedgerConfig {
+            family: KnownLedgerFamily::Cardano,
+        },
+        toolchain: None,
+        codegen: Vec::new(),
+        profiles: NamedMap::default(),
+        networks: NamedMap::default(),
+        registry: None,
+        interfaces: NamedMap::default(),
+        invoke: None,
+        budgets: Default::default(),
+    }
+}
+
+fn inquire_config(initial: &RootConfig) -> miette::Result<RootConfig> {
+    let protocol_name = prompt("Protocol name:", None, Some(&initial.protocol.name))
+        .prompt()
+        .into_diagnostic()?;
+
+    let owner_scope = prompt("Owner scope:", None, initial.protocol.scope.as_deref())
+        .prompt_skippable()
+        .into_diagnostic()?;
+
+    let description = prompt(
+        "Description:",
+        None,
+        initial.protocol.description.as_deref(),
+    )
+    .prompt_skippable()
+    .into_diagnostic()?;
+
+    let version = prompt("Version:", Some("0.0.0"), Some(&initial.protocol.version))
+        .prompt()
+        .into_diagnostic()?;
+
+    let generate_bindings =
+        MultiSelect::new("Generate bindings for:", KNOWN_CODEGEN_PLUGINS.to_vec())
+            .prompt()
+            .unwrap_or_default();
+
+    let config = RootConfig {
+        protocol: ProtocolConfig {
+            name: protocol_name,
+            scope: owner_scope,
+            version,
+            description,
+            main: "main.tx3".into(),
+            sources: None,
+            readme: None,
+            logo: None,
+            repository: None,
+        },
+        codegen: generate_bindings
+            .iter()
+            .map(|binding| CodegenConfig {
+                plugin: CodegenPlugin::Known(*binding),
+                job_id: None,
+                output_dir: None,
+                options: None,
+                verify_ignore: vec![],
+                vendored: false,
+            })
+            .collect(),
+        ..initial.clone()
+    };
+
+    Ok(config)
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Scaffold tx3 types and transactions for a validator of an Aiken
+    /// (CIP-57) blueprint
+    Tx(tx::Args),
+}
+
+#[derive(ClapArgs)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Use default configuration
+    #[arg(short, long)]
+    yes: bool,
+
+    /// Only write trix.toml, for adding trix to an existing codebase. No
+    /// example protocol, tests, devnet or .gitignore are created.
+    #[arg(long)]
+    bare: bool,
+
+    /// Protocol name written to trix.toml (bare mode)
+    #[arg(long, requires = "bare")]
+    name: Option<String>,
+
+    /// Existing main protocol file, relative to the project root (bare mode)
+    #[arg(long, value_name = "PATH", requires = "bare")]
+    main: Option<PathBuf>,
+
+    /// Start from an example protocol instead of a plain transfer
+    #[arg(long, value_enum, conflicts_with = "bare")]
+    template: Option<ProjectTemplate>,
+
+    /// What to do with template files edited since a previous `trix init`
+    #[arg(long, value_enum, default_value_t = OverwritePolicy::Ask)]
+    overwrite_policy: OverwritePolicy,
+
+    /// Replace existing files that `trix init` did not create
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Template)]
+#[template(path = "init/bare.md")]
+struct BareTemplate {
+    name: String,
+    main: String,
+    has_devnet: bool,
+}
+
+/// Make sure `main` exists, offering to create an empty stub when it
+/// doesn't. `--yes` accepts the stub without asking.
+fn ensure_main_file(main: &std::path::Path, yes: bool) -> miette::Result<()> {
+    if main.is_file() {
+        return Ok(());
+    }
+
+    let create = yes
+        || (crate::term::prompt::is_interactive()
+            && crate::term::prompt::confirm(
+                &format!("{} does not exist. Create an empty protocol stub?", main.display()),
+                true,
+            )?);
+
+    if !create {
+        bail!(
+            help = "point --main at an existing .tx3 file, or pass --yes to create a stub",
+            "main protocol file {} not found",
+            main.display()
+        );
+    }
+
+    if let Some(parent) = main.parent() {
+        std::fs::create_dir_all(parent).into_diagnostic()?;
+    }
+
+    std::fs::write(main, TEMPLATE_STUB_TX3)
+        .into_diagnostic()
+        .context(format!("writing protocol stub to {}", main.display()))
+}
+
+fn run_bare(args: Args, config: Option<&RootConfig>) -> miette::Result<()> {
+    let mut config = config.cloned().unwrap_or(default_config());
+
+    if let Some(name) = args.name {
+        config.protocol.name = name;
+    } else if !args.yes {
+        config.protocol.name = prompt("Protocol name:", None, Some(&config.protocol.name))
+            .prompt()
+            .into_diagnostic()?;
+    }
+
+    if let Some(main) = args.main {
+        config.protocol.main = main;
+    } else if !args.yes {
+        let current = config.protocol.main.to_string_lossy().to_string();
+        config.protocol.main = prompt("Main protocol file:", None, Some(&current))
+            .prompt()
+            .into_diagnostic()?
+            .into();
+    }
+
+    ensure_main_file(&config.protocol.main, args.yes)?;
+
+    let diagnostics = crate::spawn::tx3c::check(&config.protocol.main)?;
+    if let Some(first) = diagnostics.first() {
+        bail!(
+            help = "fix the protocol and re-run, or inspect it with `trix check`",
+            "{} does not parse: {}",
+            config.protocol.main.display(),
+            first.message
+        );
+    }
+
+    config.save(&PathBuf::from("trix.toml"))?;
+
+    let markdown = BareTemplate {
+        name: config.protocol.name.clone(),
+        main: config.protocol.main.display().to_string(),
+        has_devnet: std::path::Path::new("devnet.toml").exists(),
+    }
+    .render()
+    .expect("Template rendering failed");
+
+    crate::term::console::print_markdown(&markdown);
+
+    Ok(())
+}
+
+pub fn run(
+    mut args: Args,
+    config: Option<&RootConfig>,
+    config_path: Option<&Path>,
+) -> miette::Result<()> {
+    if let Some(Command::Tx(tx)) = args.command.take() {
+        let (Some(config), Some(config_path)) = (config, config_path) else {
+            bail!(
+                help = "run `trix init` first",
+                "`trix init tx` adds to an existing project, but no trix.toml was found"
+            );
+        };
+
+        return tx::run(tx, config, config_path);
+    }
+
+    if args.bare {
+        return run_bare(args, config);
+    }
+
+    let mut config = config.cloned().unwrap_or(default_config());
+
+    if !args.yes {
+        config = inquire_config(&config)?;
+    };
+
+    let devnet = config
+        .resolve_profile("local")
+        .ok()
+        .map(|x| infer_devnet(&x));
+
+    apply(
+        config,
+        devnet,
+        args.template,
+        args.overwrite_policy,
+        args.force,
+    )?;
+
+    Ok(())
+}
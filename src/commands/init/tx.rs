@@ -0,0 +1,161 @@
+use std::path::{Path, PathBuf};
+
+use clap::Args as ClapArgs;
+use miette::{Context as _, IntoDiagnostic as _, Result, bail};
+
+use crate::config::RootConfig;
+
+use super::blueprint;
+
+#[derive(ClapArgs)]
+pub struct Args {
+    /// CIP-57 blueprint to read, e.g. the `plutus.json` Aiken builds
+    #[arg(long, value_name = "PATH")]
+    from_blueprint: PathBuf,
+
+    /// Validator to scaffold, by name (`hello_world`) or module and name
+    /// (`vesting.hello_world`)
+    #[arg(long)]
+    validator: String,
+
+    /// Write to this new .tx3 file, added to `[protocol].sources`, instead
+    /// of appending to the main protocol file
+    #[arg(long, value_name = "PATH")]
+    out: Option<PathBuf>,
+}
+
+/// Whether `path`, relative to the project root, is already compiled as
+/// part of the protocol.
+fn is_source(config: &RootConfig, path: &Path) -> bool {
+    path == config.protocol.main
+        || config
+            .protocol
+            .sources
+            .iter()
+            .flatten()
+            .filter_map(|pattern| crate::builder::source_glob(pattern).ok())
+            .any(|glob| glob.is_match(path))
+}
+
+/// Where `--out`, given from `cwd`, points relative to the project root it
+/// must be inside of.
+fn out_source(out: &Path, cwd: &Path, root: &Path) -> Result<PathBuf> {
+    let out = cwd.join(out);
+
+    match out.strip_prefix(root) {
+        Ok(source) => Ok(source.to_path_buf()),
+        Err(_) => bail!(
+            help = "pick a path under {}",
+            root.display(),
+            "{} is outside the project",
+            out.display()
+        ),
+    }
+}
+
+/// Scaffold into the project whose trix.toml is at `config_path`. Paths in
+/// it are relative to its directory, not to where trix runs.
+pub fn run(args: Args, config: &RootConfig, config_path: &Path) -> Result<()> {
+    let json = std::fs::read_to_string(&args.from_blueprint)
+        .into_diagnostic()
+        .with_context(|| format!("reading blueprint {}", args.from_blueprint.display()))?;
+
+    let blueprint: serde_json::Value = serde_json::from_str(&json)
+        .into_diagnostic()
+        .with_context(|| format!("parsing blueprint {}", args.from_blueprint.display()))?;
+
+    let scaffold = blueprint::scaffold(&blueprint, &args.validator)?;
+
+    let root = config_path.parent().unwrap_or_else(|| Path::new("."));
+
+    let source = match &args.out {
+        Some(out) => out_source(out, &std::env::current_dir().into_diagnostic()?, root)?,
+        None => config.protocol.main.clone(),
+    };
+
+    let target = root.join(&source);
+
+    if args.out.is_some() && target.exists() {
+        bail!(
+            help = "pick a new file for --out, or leave it out to append to the main file",
+            "{} already exists",
+            target.display()
+        );
+    }
+
+    let existing = match target.exists() {
+        true => std::fs::read_to_string(&target)
+            .into_diagnostic()
+            .with_context(|| format!("reading {}", target.display()))?,
+        false => String::new(),
+    };
+
+    if existing.contains(&format!("tx {}(", scaffold.lock)) {
+        bail!(
+            "{} already has a `{}` transaction; was this validator scaffolded before?",
+            target.display(),
+            scaffold.lock
+        );
+    }
+
+    let content = match existing.trim_end() {
+        "" => scaffold.source.clone(),
+        kept => format!("{kept}\n\n{}", scaffold.source),
+    };
+
+    if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).into_diagnostic()?;
+    }
+
+    std::fs::write(&target, content)
+        .into_diagnostic()
+        .with_context(|| format!("writing {}", target.display()))?;
+
+    if !is_source(config, &source) {
+        let mut config = config.clone();
+
+        config
+            .protocol
+            .sources
+            .get_or_insert_default()
+            .push(source.to_string_lossy().to_string());
+
+        config.save(&config_path.to_path_buf())?;
+
+        println!("Added {} to `[protocol].sources`", source.display());
+    }
+
+    for warning in &scaffold.warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    println!(
+        "Added `{}` and `{}` to {}; fill in the TODOs, then run `trix check`",
+        scaffold.lock,
+        scaffold.spend,
+        source.display()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn out_is_relative_to_the_project_root() {
+        let root = Path::new("/work/project");
+
+        let cwd = root.join("src");
+
+        let source = out_source(Path::new("vesting.tx3"), &cwd, root);
+        assert_eq!(source.unwrap(), Path::new("src/vesting.tx3"));
+
+        let source = out_source(Path::new("/work/project/lib.tx3"), Path::new("/tmp"), root);
+        assert_eq!(source.unwrap(), Path::new("lib.tx3"));
+
+        let err = out_source(Path::new("../other.tx3"), root, root).unwrap_err();
+        assert!(err.to_string().contains("outside the project"), "{err}");
+    }
+}
@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use clap::{Args as ClapArgs, ValueEnum};
+use miette::IntoDiagnostic as _;
+
+use crate::config::RootConfig;
+use crate::spawn::tx3c;
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum Format {
+    /// Indented, one field per line
+    #[default]
+    Pretty,
+    /// A single line of JSON
+    Json,
+}
+
+#[derive(ClapArgs)]
+pub struct Args {
+    /// .tx3 file to parse; defaults to the project's protocol source
+    #[arg(long)]
+    file: Option<PathBuf>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+}
+
+fn render(ast: &serde_json::Value, format: Format) -> miette::Result<String> {
+    match format {
+        Format::Pretty => serde_json::to_string_pretty(ast).into_diagnostic(),
+        Format::Json => serde_json::to_string(ast).into_diagnostic(),
+    }
+}
+
+pub fn run(args: Args, config: &RootConfig) -> miette::Result<()> {
+    let source = match args.file {
+        Some(file) => file,
+        None => crate::builder::entry_source(config)?,
+    };
+
+    let ast = tx3c::ast_from_source(&source)?;
+
+    println!("{}", render(&ast, args.format)?);
+
+    Ok(())
+}
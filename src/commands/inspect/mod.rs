@@ -2,10 +2,14 @@ use clap::{Args as ClapArgs, Subcommand};
 
 use crate::config::RootConfig;
 
+mod ast;
 mod tir;
 
 #[derive(Subcommand)]
 pub enum Command {
+    /// Inspect the parsed syntax tree of a .tx3 file, before lowering
+    Ast(ast::Args),
+
     /// Inspect the intermediate representation of a transaction
     Tir(tir::Args),
 }
@@ -18,6 +22,7 @@ pub struct Args {
 
 pub fn run(args: Args, config: &RootConfig) -> miette::Result<()> {
     match args.command {
+        Command::Ast(args) => ast::run(args, config),
         Command::Tir(args) => tir::run(args, config),
     }
 }
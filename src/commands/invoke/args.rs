@@ -0,0 +1,446 @@
+//! Argument handling shared by every way of supplying invoke args: presets,
+//! `--args-json`, and `--arg` overrides all end up in one [`ArgMap`] that is
+//! resolved and validated here before it reaches cshell.
+
+use std::collections::BTreeMap;
+
+use miette::bail;
+use pallas::ledger::addresses::{Address, Network};
+
+use crate::config::NetworkConfig;
+use crate::wallet::WalletProxy;
+
+use super::ArgMap;
+
+/// Parse one `--arg NAME=VALUE`. The value is read as JSON when it parses
+/// (`42`, `true`, `"quoted"`), otherwise taken as a plain string, so
+/// `--arg to=@alice` needs no quoting.
+pub fn parse_arg(s: &str) -> Result<(String, serde_json::Value), String> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected NAME=VALUE, got '{s}'"))?;
+
+    if name.is_empty() {
+        return Err(format!("missing arg name in '{s}'"));
+    }
+
+    let value = serde_json::from_str(value)
+        .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+
+    Ok((name.to_string(), value))
+}
+
+/// Replace `@wallet` references with the identity's address and
+/// `@env:VAR` references with the variable's value.
+pub fn resolve_references(args: &mut ArgMap, wallet: &WalletProxy) -> miette::Result<()> {
+    for (name, value) in args.iter_mut() {
+        let serde_json::Value::String(text) = value else {
+            continue;
+        };
+
+        if let Some(var) = text.strip_prefix("@env:") {
+            let Ok(resolved) = std::env::var(var) else {
+                bail!("arg `{name}` references env var `{var}`, which is not set");
+            };
+            *text = resolved;
+        } else if let Some(identity) = text.strip_prefix('@') {
+            let Some(address) = wallet.addresses.get(identity) else {
+                bail!(
+                    help = "add the identity to the profile, or pass an address instead",
+                    "arg `{name}` references unknown wallet `@{identity}`"
+                );
+            };
+            *text = address.clone();
+        }
+    }
+
+    Ok(())
+}
+
+/// Human-readable type for a TII param schema.
+fn schema_type(schema: &serde_json::Value) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+        return reference.rsplit('#').next().unwrap_or(reference).to_string();
+    }
+
+    schema
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+fn matches_schema(value: &serde_json::Value, schema: &serde_json::Value) -> bool {
+    // `$ref` types (addresses, bytes, UTxO refs...) travel as strings.
+    if schema.get("$ref").is_some() {
+        return value.is_string();
+    }
+
+    match schema.get("type").and_then(|t| t.as_str()) {
+        Some("integer") => value.is_i64() || value.is_u64(),
+        Some("number") => value.is_number(),
+        Some("boolean") => value.is_boolean(),
+        Some("string") => value.is_string(),
+        Some("object") => value.is_object(),
+        Some("array") => value.is_array(),
+        _ => true,
+    }
+}
+
+/// Param schemas of `template`, by name.
+fn params(
+    tii: &serde_json::Value,
+    template: &str,
+) -> miette::Result<BTreeMap<String, serde_json::Value>> {
+    let Some(tx) = tii.pointer(&format!("/transactions/{template}")) else {
+        bail!("protocol has no transaction template `{template}`");
+    };
+
+    Ok(tx
+        .pointer("/params/properties")
+        .and_then(|p| p.as_object())
+        .map(|p| p.clone().into_iter().collect())
+        .unwrap_or_default())
+}
+
+/// Convert `value` to the type `schema` asks for when the conversion is
+/// lossless: `"42"` to an integer, `"true"` to a bool, and a number passed
+/// for a string or `$ref` type (`--arg item=1234`) back to a string.
+fn coerce_value(
+    value: &serde_json::Value,
+    schema: &serde_json::Value,
+) -> Option<serde_json::Value> {
+    use serde_json::Value;
+
+    let expects_string = schema.get("$ref").is_some()
+        || schema.get("type").and_then(|t| t.as_str()) == Some("string");
+
+    if expects_string {
+        return match value {
+            Value::Number(n) => Some(Value::String(n.to_string())),
+            Value::Bool(b) => Some(Value::String(b.to_string())),
+            _ => None,
+        };
+    }
+
+    let Value::String(text) = value else {
+        return None;
+    };
+
+    match schema.get("type").and_then(|t| t.as_str()) {
+        Some("integer") => text
+            .parse::<i64>()
+            .map(Value::from)
+            .or_else(|_| text.parse::<u64>().map(Value::from))
+            .ok(),
+        Some("number") => text
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number),
+        Some("boolean") => text.parse::<bool>().ok().map(Value::Bool),
+        _ => None,
+    }
+}
+
+/// Coerce every arg to the type of its TII param. Values that can't be
+/// converted are left alone for [`validate`] to report.
+pub fn coerce(args: &mut ArgMap, tii: &serde_json::Value, template: &str) -> miette::Result<()> {
+    let params = params(tii, template)?;
+
+    for (name, value) in args.iter_mut() {
+        if let Some(coerced) = params
+            .get(name)
+            .and_then(|schema| coerce_value(value, schema))
+        {
+            *value = coerced;
+        }
+    }
+
+    Ok(())
+}
+
+/// Params of `template` that `args` doesn't supply. The TII's `required`
+/// list is used when present, otherwise every param is required.
+pub fn missing(
+    args: &ArgMap,
+    tii: &serde_json::Value,
+    template: &str,
+) -> miette::Result<Vec<String>> {
+    let params = params(tii, template)?;
+
+    let required: Vec<String> = tii
+        .pointer(&format!("/transactions/{template}/params/required"))
+        .and_then(|r| r.as_array())
+        .map(|r| {
+            r.iter()
+                .filter_map(|n| n.as_str())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_else(|| params.keys().cloned().collect());
+
+    Ok(required
+        .into_iter()
+        .filter(|name| !args.contains_key(name))
+        .map(|name| match params.get(&name) {
+            Some(schema) => format!("{name} ({})", schema_type(schema)),
+            None => name,
+        })
+        .collect())
+}
+
+/// Every param of `template` as `name (type)`, sorted by name.
+pub fn describe_params(tii: &serde_json::Value, template: &str) -> miette::Result<Vec<String>> {
+    Ok(params(tii, template)?
+        .iter()
+        .map(|(name, schema)| format!("{name} ({})", schema_type(schema)))
+        .collect())
+}
+
+/// Check `args` against the TII params of `template`. Every problem is
+/// reported at once, so a preset with several bad values is fixed in one
+/// pass.
+pub fn validate(args: &ArgMap, tii: &serde_json::Value, template: &str) -> miette::Result<()> {
+    let params = params(tii, template)?;
+
+    let parties: Vec<String> = tii
+        .get("parties")
+        .and_then(|p| p.as_object())
+        .map(|p| p.keys().map(|k| k.to_lowercase()).collect())
+        .unwrap_or_default();
+
+    let mut problems = vec![];
+
+    for (name, value) in args {
+        match params.get(name) {
+            Some(schema) if !matches_schema(value, schema) => problems.push(format!(
+                "`{name}` expects {}, got {value}",
+                schema_type(schema)
+            )),
+            Some(_) => {}
+            None if parties.contains(&name.to_lowercase()) => {}
+            None => problems.push(format!("`{name}` is not a param of `{template}`")),
+        }
+    }
+
+    if !problems.is_empty() {
+        let known = params.keys().cloned().collect::<Vec<_>>().join(", ");
+        bail!(
+            help = format!("`{template}` takes: {known}"),
+            "invalid args for `{template}`:\n  {}",
+            problems.join("\n  ")
+        );
+    }
+
+    Ok(())
+}
+
+/// Network id in the header of a bech32 Cardano address. `None` for
+/// anything else, Byron addresses included: they carry no network id.
+fn address_network(text: &str) -> Option<Network> {
+    Address::from_bech32(text).ok()?.network()
+}
+
+fn describe_network(network: Network) -> String {
+    match network {
+        Network::Mainnet => "mainnet".to_string(),
+        Network::Testnet => "testnet".to_string(),
+        Network::Other(id) => format!("network id {id}"),
+    }
+}
+
+/// Reject address args meant for another network than `network`, before
+/// the TRP resolves a transaction that would fail or send funds where
+/// nobody expects them. Only the header's network id is compared, so a
+/// preprod address passes on preview: all testnets share id 0.
+pub fn check_networks(args: &ArgMap, network: &NetworkConfig, profile: &str) -> miette::Result<()> {
+    let expected = match network.is_testnet {
+        true => Network::Testnet,
+        false => Network::Mainnet,
+    };
+
+    let problems: Vec<String> = args
+        .iter()
+        .filter_map(|(name, value)| Some((name, address_network(value.as_str()?)?)))
+        .filter(|(_, found)| *found != expected)
+        .map(|(name, found)| format!("`{name}` is a {} address", describe_network(found)))
+        .collect();
+
+    if !problems.is_empty() {
+        bail!(
+            help = "pass addresses for the profile's network, or --allow-network-mismatch if this is deliberate",
+            "profile `{profile}` targets {} ({}), but:\n  {}",
+            network.name,
+            describe_network(expected),
+            problems.join("\n  ")
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tii() -> serde_json::Value {
+        serde_json::json!({
+            "parties": { "Bidder": {} },
+            "transactions": {
+                "place_bid": {
+                    "params": {
+                        "properties": {
+                            "amount": { "type": "integer" },
+                            "item": { "$ref": "https://tx3.land/specs/v1beta0/core#Bytes" }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn map(value: serde_json::Value) -> ArgMap {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn parse_arg_reads_json_then_falls_back_to_string() {
+        assert_eq!(parse_arg("amount=10").unwrap().1, serde_json::json!(10));
+        assert_eq!(parse_arg("to=@alice").unwrap().1, serde_json::json!("@alice"));
+        assert!(parse_arg("amount").is_err());
+    }
+
+    #[test]
+    fn coerce_converts_to_param_types() {
+        let mut tii = tii();
+        tii["transactions"]["place_bid"]["params"]["properties"]["sealed"] =
+            serde_json::json!({ "type": "boolean" });
+
+        let mut args = map(serde_json::json!({
+            "amount": "42",
+            "item": 1234,
+            "sealed": "true",
+            "bidder": "addr"
+        }));
+        coerce(&mut args, &tii, "place_bid").unwrap();
+
+        assert_eq!(args["amount"], serde_json::json!(42));
+        assert_eq!(args["item"], serde_json::json!("1234"));
+        assert_eq!(args["sealed"], serde_json::json!(true));
+        assert_eq!(args["bidder"], serde_json::json!("addr"));
+
+        let mut bad = map(serde_json::json!({ "amount": "lots" }));
+        coerce(&mut bad, &tii, "place_bid").unwrap();
+        assert!(validate(&bad, &tii, "place_bid").is_err());
+    }
+
+    #[test]
+    fn resolves_wallet_references() {
+        let wallet = WalletProxy {
+            target_dir: Default::default(),
+            addresses: [("alice".to_string(), "addr_test1alice".to_string())].into(),
+            derivation: crate::wallet::Derivation::Legacy,
+            address_only: Default::default(),
+        };
+
+        let mut args = map(serde_json::json!({ "bidder": "@alice", "amount": 5 }));
+        resolve_references(&mut args, &wallet).unwrap();
+        assert_eq!(args["bidder"], serde_json::json!("addr_test1alice"));
+
+        let mut args = map(serde_json::json!({ "bidder": "@bob" }));
+        let err = resolve_references(&mut args, &wallet).unwrap_err();
+        assert!(err.to_string().contains("unknown wallet `@bob`"), "{err}");
+    }
+
+    #[test]
+    fn missing_lists_unsupplied_params() {
+        let args = map(serde_json::json!({ "amount": 5 }));
+        assert_eq!(
+            missing(&args, &tii(), "place_bid").unwrap(),
+            ["item (Bytes)"]
+        );
+    }
+
+    #[test]
+    fn validate_accepts_params_and_parties() {
+        let args = map(serde_json::json!({ "amount": 5, "item": "abcd", "bidder": "addr" }));
+        validate(&args, &tii(), "place_bid").unwrap();
+    }
+
+    #[test]
+    fn validate_reports_every_problem() {
+        let args = map(serde_json::json!({ "amount": "lots", "colour": "red" }));
+        let err = validate(&args, &tii(), "place_bid").unwrap_err().to_string();
+
+        assert!(err.contains("`amount` expects integer"), "{err}");
+        assert!(err.contains("`colour` is not a param"), "{err}");
+    }
+
+    fn address(network: Network) -> String {
+        use pallas::ledger::addresses::{
+            ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart,
+        };
+
+        let payment = ShelleyPaymentPart::Key([7; 28].into());
+        let delegation = ShelleyDelegationPart::Null;
+
+        Address::Shelley(ShelleyAddress::new(network, payment, delegation))
+            .to_bech32()
+            .unwrap()
+    }
+
+    #[test]
+    fn detects_the_network_of_addresses() {
+        assert_eq!(
+            address_network(&address(Network::Mainnet)),
+            Some(Network::Mainnet)
+        );
+        assert_eq!(
+            address_network(&address(Network::Testnet)),
+            Some(Network::Testnet)
+        );
+        assert!(address(Network::Mainnet).starts_with("addr1"));
+        assert!(address(Network::Testnet).starts_with("addr_test1"));
+
+        assert_eq!(address_network("addr_test1alice"), None);
+        assert_eq!(address_network("@alice"), None);
+        assert_eq!(address_network("abcd"), None);
+    }
+
+    #[test]
+    fn rejects_addresses_for_another_network() {
+        use crate::config::KnownNetwork;
+
+        let preview = NetworkConfig::from(KnownNetwork::CardanoPreview);
+        let mainnet = NetworkConfig::from(KnownNetwork::CardanoMainnet);
+
+        let testnet_args = map(serde_json::json!({
+            "bidder": address(Network::Testnet),
+            "amount": 5,
+            "item": "abcd",
+        }));
+
+        check_networks(&testnet_args, &preview, "preview").unwrap();
+
+        let err = check_networks(&testnet_args, &mainnet, "mainnet")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("profile `mainnet` targets cardano-mainnet (mainnet)"),
+            "{err}"
+        );
+        assert!(err.contains("`bidder` is a testnet address"), "{err}");
+        assert!(!err.contains("`item`"), "{err}");
+
+        let mixed = map(serde_json::json!({
+            "bidder": address(Network::Mainnet),
+            "seller": address(Network::Other(3)),
+        }));
+        let err = check_networks(&mixed, &preview, "preview")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`bidder` is a mainnet address"), "{err}");
+        assert!(err.contains("`seller` is a network id 3 address"), "{err}");
+    }
+}
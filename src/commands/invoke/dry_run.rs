@@ -0,0 +1,238 @@
+//! `trix invoke --dry-run`: resolve the transaction through cshell without
+//! submitting it, then show what it would do. Input addresses come from the
+//! profile's U5C endpoint; when it can't be reached they're left out rather
+//! than failing the dry run.
+
+use askama::Template;
+use miette::{Context as _, IntoDiagnostic as _};
+use pallas::ledger::traverse::{MultiEraOutput, MultiEraTx};
+use serde::Serialize;
+use utxorpc::spec::query::TxoRef;
+
+use crate::config::U5cConfig;
+
+// ============================================================================
+// View Model
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InputView {
+    #[serde(rename = "ref")]
+    pub r#ref: String,
+    pub address: Option<String>,
+    pub lovelace: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputView {
+    pub address: String,
+    pub lovelace: u64,
+    /// Number of native assets besides ada.
+    pub assets: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TxSummary {
+    pub hash: String,
+    pub fee: Option<u64>,
+    pub inputs: Vec<InputView>,
+    pub outputs: Vec<OutputView>,
+    /// Key hashes the transaction requires signatures from.
+    pub required_signers: Vec<String>,
+}
+
+impl InputView {
+    pub fn ref_display(&self) -> &str {
+        &self.r#ref
+    }
+
+    pub fn address_display(&self) -> &str {
+        self.address.as_deref().unwrap_or("(unresolved)")
+    }
+}
+
+fn output_view(output: &MultiEraOutput) -> miette::Result<OutputView> {
+    let address = output
+        .address()
+        .into_diagnostic()?
+        .to_bech32()
+        .into_diagnostic()?;
+
+    let value = output.value();
+
+    Ok(OutputView {
+        address,
+        lovelace: value.coin(),
+        assets: value.assets().iter().map(|p| p.assets().len()).sum(),
+    })
+}
+
+/// Decode the hex transaction CBOR cshell reported.
+pub fn decode(cbor: &str) -> miette::Result<TxSummary> {
+    let bytes = hex::decode(cbor)
+        .into_diagnostic()
+        .context("transaction cbor is not hex")?;
+
+    let tx = MultiEraTx::decode(&bytes)
+        .into_diagnostic()
+        .context("decoding the resolved transaction")?;
+
+    let inputs = tx
+        .inputs()
+        .iter()
+        .map(|input| InputView {
+            r#ref: format!("{}#{}", input.hash(), input.index()),
+            address: None,
+            lovelace: None,
+        })
+        .collect();
+
+    let outputs = tx
+        .outputs()
+        .iter()
+        .map(output_view)
+        .collect::<miette::Result<_>>()?;
+
+    let required_signers = tx
+        .required_signers()
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(hex::encode)
+        .collect();
+
+    Ok(TxSummary {
+        hash: tx.hash().to_string(),
+        fee: tx.fee(),
+        inputs,
+        outputs,
+        required_signers,
+    })
+}
+
+async fn read_inputs(summary: &mut TxSummary, u5c: &U5cConfig) -> miette::Result<()> {
+    let mut client = crate::net::u5c_client(u5c).await?;
+
+    let refs = summary
+        .inputs
+        .iter()
+        .filter_map(|input| {
+            let (hash, index) = input.r#ref.split_once('#')?;
+            Some(TxoRef {
+                hash: hex::decode(hash).ok()?.into(),
+                index: index.parse().ok()?,
+            })
+        })
+        .collect();
+
+    let utxos = client.read_utxos(refs).await.into_diagnostic()?;
+
+    for utxo in utxos {
+        let Some(txo_ref) = utxo.txo_ref else {
+            continue;
+        };
+
+        let r#ref = format!("{}#{}", hex::encode(&txo_ref.hash), txo_ref.index);
+
+        let Some(input) = summary.inputs.iter_mut().find(|i| i.r#ref == r#ref) else {
+            continue;
+        };
+
+        let output = MultiEraOutput::decode(pallas::ledger::traverse::Era::Conway, &utxo.native)
+            .into_diagnostic()?;
+        let view = output_view(&output)?;
+
+        input.address = Some(view.address);
+        input.lovelace = Some(view.lovelace);
+    }
+
+    Ok(())
+}
+
+/// Fill in input addresses from the chain, best effort.
+pub fn resolve_inputs(summary: &mut TxSummary, u5c: &U5cConfig) {
+    if let Err(err) = futures::executor::block_on(read_inputs(summary, u5c)) {
+        eprintln!("warning: could not resolve input addresses: {err}");
+    }
+}
+
+// ============================================================================
+// Askama Template
+// ============================================================================
+
+#[derive(Template)]
+#[template(path = "invoke/dry_run.md")]
+struct DryRunTemplate<'a> {
+    view: &'a TxSummary,
+}
+
+pub fn print(summary: &TxSummary) {
+    let markdown = DryRunTemplate { view: summary }
+        .render()
+        .expect("Template rendering failed");
+
+    crate::term::console::print_markdown(&markdown);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Conway transaction spending `11..11#0` into one 2 ADA output at an
+    /// enterprise testnet address, with a 0.1728 ADA fee.
+    const TX: &str = concat!(
+        "84",
+        "a3",
+        "00",
+        "81",
+        "82",
+        "5820",
+        "1111111111111111111111111111111111111111111111111111111111111111",
+        "00",
+        "01",
+        "81",
+        "82",
+        "581d",
+        "60",
+        "07070707070707070707070707070707070707070707070707070707",
+        "1a001e8480",
+        "02",
+        "1a0002a300",
+        "a0",
+        "f5",
+        "f6",
+    );
+
+    fn address() -> String {
+        let mut bytes = vec![0x60];
+        bytes.extend([7u8; 28]);
+        pallas::ledger::addresses::Address::from_bytes(&bytes)
+            .unwrap()
+            .to_bech32()
+            .unwrap()
+    }
+
+    #[test]
+    fn decodes_fee_inputs_and_outputs() {
+        let summary = decode(TX).unwrap();
+
+        assert_eq!(summary.fee, Some(172_800));
+
+        assert_eq!(summary.inputs.len(), 1);
+        assert_eq!(summary.inputs[0].r#ref, format!("{}#0", "11".repeat(32)));
+        assert_eq!(summary.inputs[0].address, None);
+
+        assert_eq!(summary.outputs.len(), 1);
+        assert_eq!(summary.outputs[0].address, address());
+        assert_eq!(summary.outputs[0].lovelace, 2_000_000);
+        assert_eq!(summary.outputs[0].assets, 0);
+
+        assert!(summary.required_signers.is_empty());
+        assert_eq!(summary.hash.len(), 64);
+    }
+
+    #[test]
+    fn rejects_cbor_that_is_not_hex() {
+        let err = decode("not hex").unwrap_err().to_string();
+        assert!(err.contains("not hex"), "{err}");
+    }
+}
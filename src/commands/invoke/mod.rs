@@ -0,0 +1,454 @@
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+
+use clap::Args as ClapArgs;
+use miette::{Context as _, IntoDiagnostic, bail};
+use serde::Serialize;
+
+use crate::{
+    builder,
+    config::{InvokePreset, NetworkConfig, ProfileConfig, RootConfig},
+    interfaces::{self, ResolvedProtocol, Resolver},
+    refs::ProtocolRef,
+    term::OutputFormat,
+};
+
+pub(crate) mod args;
+mod dry_run;
+pub(crate) mod parties;
+mod presets;
+mod replay;
+pub(crate) mod resolve_error;
+mod script;
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Protocol to invoke against. Omit to use the project's own protocol;
+    /// pass a interface alias (e.g. `widget`) or a full registry reference
+    /// (e.g. `acme/widget:0.1.0`) to invoke one of its transactions. The
+    /// transaction is chosen interactively by the wallet unless `--preset`
+    /// names it.
+    #[arg(long, value_parser = parse_protocol)]
+    from: Option<ProtocolRef>,
+
+    /// Args for the TX3 transaction: a raw JSON object, a path to a JSON
+    /// file, or `-` to read it from stdin.
+    #[arg(long, value_name = "JSON|FILE|-")]
+    args_json: Option<String>,
+
+    /// Path to a JSON file with arguments for the TX3 transaction.
+    #[arg(long)]
+    args_json_path: Option<PathBuf>,
+
+    /// Override a single arg, e.g. `--arg quantity=5` or `--arg bidder=@alice`.
+    /// Applied after presets and JSON args. Repeatable.
+    #[arg(long = "arg", value_name = "NAME=VALUE", value_parser = args::parse_arg)]
+    arg_overrides: Vec<(String, serde_json::Value)>,
+
+    /// Start from a named preset in `[invoke.presets]`; it picks the
+    /// transaction template and supplies default args.
+    #[arg(long, value_name = "NAME", conflicts_with = "from_file")]
+    preset: Option<String>,
+
+    /// Transaction template to invoke. Overrides the preset's; required with
+    /// `--output json`, where there is no prompt to pick one.
+    #[arg(long, value_name = "NAME")]
+    template: Option<String>,
+
+    /// List the presets defined in trix.toml and exit.
+    #[arg(long, conflicts_with_all = ["preset", "from_file"])]
+    list_presets: bool,
+
+    /// Skip submitting the transaction.
+    #[arg(long)]
+    skip_submit: bool,
+
+    /// Resolve the transaction without submitting it and show its inputs,
+    /// outputs, fee and required signers. With `--output json` the raw
+    /// transaction is included as `tx_cbor`.
+    #[arg(long, conflicts_with_all = ["from_file", "export_unsigned"])]
+    dry_run: bool,
+
+    /// Resolve the transaction without submitting it and write the unsigned
+    /// envelope to this path, for external signing or a later `--from-file`.
+    #[arg(long, value_name = "PATH", conflicts_with = "from_file")]
+    export_unsigned: Option<PathBuf>,
+
+    /// Replay an envelope written by `--export-unsigned`: skip resolution,
+    /// sign with `--signer` and submit the exact original transaction.
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["args_json", "args_json_path", "skip_submit"])]
+    from_file: Option<PathBuf>,
+
+    /// Submit the transactions listed in a TOML or JSON file, in order,
+    /// stopping at the first failure. Entries take the fields of a `trix
+    /// test` transaction: description, template, args and signers.
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = [
+            "from_file", "args_json", "args_json_path", "arg_overrides", "preset",
+            "template", "list_presets", "skip_submit", "dry_run", "export_unsigned",
+            "signers", "output",
+        ]
+    )]
+    script: Option<PathBuf>,
+
+    /// Identity that signs a replayed envelope or a `--output json`
+    /// invoke. Repeat for multiple signers.
+    #[arg(long = "signer", value_name = "NAME")]
+    signers: Vec<String>,
+
+    /// Submit even when an address arg is for another network than the
+    /// profile's, e.g. for a deliberate cross-network test.
+    #[arg(long, conflicts_with_all = ["from_file", "script"])]
+    allow_network_mismatch: bool,
+
+    /// `json` invokes without prompts and prints the result as a JSON
+    /// document (tx hash, fee, whether it was submitted, resolved args).
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Human,
+        conflicts_with_all = ["from_file", "export_unsigned", "list_presets"]
+    )]
+    output: OutputFormat,
+}
+
+// ============================================================================
+// View Model
+// ============================================================================
+
+/// The `--output json` document. Its fields are stable for scripts.
+#[derive(Debug, Serialize)]
+pub struct InvokeView {
+    pub tx_hash: Option<String>,
+    pub fee: Option<u64>,
+    pub submitted: bool,
+    pub args: ArgMap,
+    /// Unsigned transaction CBOR, hex-encoded; set by `--dry-run`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_cbor: Option<String>,
+}
+
+/// Fee reported by cshell, which may write it as a number or a string.
+fn fee(output: &serde_json::Value) -> Option<u64> {
+    match output.get("fee")? {
+        serde_json::Value::Number(n) => n.as_u64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+impl InvokeView {
+    fn new(output: &serde_json::Value, submitted: bool, args: ArgMap) -> Self {
+        Self {
+            tx_hash: crate::spawn::cshell::tx_hash(output).map(str::to_string),
+            fee: fee(output),
+            submitted,
+            args,
+            tx_cbor: None,
+        }
+    }
+}
+
+fn parse_protocol(s: &str) -> Result<ProtocolRef, String> {
+    ProtocolRef::parse(s).map_err(|e| e.to_string())
+}
+
+pub type ArgMap = serde_json::Map<String, serde_json::Value>;
+
+fn merge_json_maps_mut(a: &mut ArgMap, b: &ArgMap) {
+    for (key, value) in b {
+        a.insert(key.clone(), value.clone());
+    }
+}
+
+fn string_to_json_map(s: &str) -> miette::Result<ArgMap> {
+    let value = serde_json::from_str::<serde_json::Value>(s).into_diagnostic()?;
+
+    let value = value
+        .as_object()
+        .ok_or(miette::miette!("json args should be an object"))?;
+
+    Ok(value.to_owned())
+}
+
+/// Contents of `--args-json`: inline JSON, `-` for stdin, or a file path.
+fn read_args_json(value: &str) -> miette::Result<String> {
+    if value == "-" {
+        let mut buf = String::new();
+        std::io::stdin()
+            .read_to_string(&mut buf)
+            .into_diagnostic()
+            .context("reading --args-json from stdin")?;
+        return Ok(buf);
+    }
+
+    if value.trim_start().starts_with('{') {
+        return Ok(value.to_string());
+    }
+
+    std::fs::read_to_string(value)
+        .into_diagnostic()
+        .with_context(|| format!("reading --args-json file {value}"))
+}
+
+/// Merge every arg source, later ones winning: preset defaults, then
+/// `--args-json`, then `--args-json-path`, then `--arg` overrides.
+fn load_args_json(args: &Args, preset: Option<&InvokePreset>) -> miette::Result<ArgMap> {
+    let mut all = serde_json::Map::new();
+
+    if let Some(preset) = preset {
+        all.extend(preset.args.clone());
+    }
+
+    if let Some(args_json) = &args.args_json {
+        let value = string_to_json_map(&read_args_json(args_json)?)?;
+        merge_json_maps_mut(&mut all, &value);
+    }
+
+    if let Some(path) = &args.args_json_path {
+        let args_json = std::fs::read_to_string(path).into_diagnostic()?;
+        let value = string_to_json_map(&args_json)?;
+        merge_json_maps_mut(&mut all, &value);
+    }
+
+    all.extend(args.arg_overrides.iter().cloned());
+
+    Ok(all)
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    if args.list_presets {
+        presets::print_list(config);
+        return Ok(());
+    }
+
+    let preset = args
+        .preset
+        .as_deref()
+        .map(|name| presets::find(config, name))
+        .transpose()?;
+
+    interfaces::validate(config)?;
+    interfaces::restore_all(config)?;
+
+    let wallet = crate::wallet::setup(config, profile)?;
+
+    let tii_file = resolve_tii_path(&args, config)?;
+
+    if let Some(path) = &args.script {
+        return script::run(path, &wallet, &tii_file, config, profile);
+    }
+
+    if let Some(path) = &args.from_file {
+        return replay::run(path, &args.signers, &wallet, &tii_file, config, profile);
+    }
+
+    if !args.signers.is_empty() && args.output == OutputFormat::Human {
+        bail!(
+            help = "the wallet asks for signers interactively; pass --output json to name them",
+            "--signer only applies to --from-file and --output json"
+        );
+    }
+
+    let mut args_json = load_args_json(&args, preset)?;
+    args::resolve_references(&mut args_json, &wallet)?;
+
+    let template = args
+        .template
+        .as_deref()
+        .or(preset.map(|preset| preset.template.as_str()));
+
+    let tii = builder::load_tii(&tii_file)?;
+
+    // The wallet would prompt for what's missing; without a terminal (or in
+    // JSON mode) that prompt never gets an answer.
+    let can_prompt = args.output == OutputFormat::Human && crate::term::prompt::is_interactive();
+
+    let network = match args.allow_network_mismatch {
+        true => None,
+        false => Some(config.resolve_profile_network(&profile.name)?),
+    };
+
+    check_args(
+        &mut args_json,
+        &tii_file,
+        &tii,
+        template,
+        profile,
+        can_prompt,
+        network.as_ref(),
+    )?;
+
+    let skip_submit = args.skip_submit || args.dry_run || args.export_unsigned.is_some();
+
+    if args.output == OutputFormat::Json {
+        let Some(template) = template else {
+            bail!(
+                help = "name it with --template or --preset",
+                "--output json needs a transaction template"
+            );
+        };
+
+        if args.signers.is_empty() && !skip_submit {
+            bail!(
+                help = "name the signing identity with --signer, or pass --skip-submit",
+                "--output json needs a signer to submit the transaction"
+            );
+        }
+
+        let signers = args.signers.iter().map(String::as_str).collect();
+
+        let output = wallet
+            .invoke_json(
+                &tii_file,
+                template,
+                &serde_json::Value::Object(args_json.clone()),
+                signers,
+                &profile.name,
+                skip_submit,
+            )
+            .map_err(|err| {
+                let project = args.from.is_none().then_some(config);
+                resolve_error::explain(err, project, template)
+            })?;
+
+        if !skip_submit {
+            let network = config.resolve_profile_network(&profile.name)?;
+            crate::submissions::record(&output, &profile.name, &network, Some(template));
+        }
+
+        let mut view = InvokeView::new(&output, !skip_submit, args_json);
+
+        if args.dry_run {
+            view.tx_cbor = output
+                .get("cbor")
+                .and_then(|c| c.as_str())
+                .map(String::from);
+        }
+
+        println!("{}", serde_json::to_string_pretty(&view).into_diagnostic()?);
+
+        return Ok(());
+    }
+
+    let output = wallet.invoke_interactive(
+        &tii_file,
+        template,
+        &serde_json::Value::Object(args_json),
+        &profile.name,
+        skip_submit,
+    )?;
+
+    if let Some(path) = &args.export_unsigned {
+        let output = output
+            .ok_or_else(|| miette::miette!("cshell did not report the resolved transaction"))?;
+        return replay::export(&output, &tii_file, template, config, profile, path);
+    }
+
+    if args.dry_run {
+        let cbor = output
+            .as_ref()
+            .and_then(|output| output.get("cbor"))
+            .and_then(|c| c.as_str())
+            .ok_or_else(|| miette::miette!("cshell did not report the resolved transaction"))?;
+
+        let mut summary = dry_run::decode(cbor)?;
+        let network = config.resolve_profile_network(&profile.name)?;
+        dry_run::resolve_inputs(&mut summary, &network.u5c);
+
+        dry_run::print(&summary);
+        return Ok(());
+    }
+
+    if let Some(output) = output
+        && !skip_submit
+    {
+        let network = config.resolve_profile_network(&profile.name)?;
+        crate::wallet::print_tx_link(&output, &network);
+        crate::submissions::record(&output, &profile.name, &network, template);
+    }
+
+    Ok(())
+}
+
+/// Check resolved args before they reach the wallet: against `template`'s
+/// params and, for the parties it refers to, against the profile's
+/// identities. With a `network`, addresses are checked against it too.
+/// Missing args only fail the check when the wallet can't prompt for them.
+/// A template the wallet will ask for is only known once picked, so its
+/// args and parties go unchecked.
+pub(crate) fn check_args(
+    args_json: &mut ArgMap,
+    tii_file: &Path,
+    tii: &serde_json::Value,
+    template: Option<&str>,
+    profile: &ProfileConfig,
+    can_prompt: bool,
+    network: Option<&NetworkConfig>,
+) -> miette::Result<()> {
+    if let Some(template) = template {
+        args::coerce(args_json, tii, template)?;
+        args::validate(args_json, tii, template)?;
+
+        let missing = args::missing(args_json, tii, template)?;
+
+        if !missing.is_empty() && !can_prompt {
+            bail!(
+                help = "pass them with --arg NAME=VALUE or --args-json",
+                "missing args for `{template}`:\n  {}",
+                missing.join("\n  ")
+            );
+        }
+
+        let parties = parties::of_template(tii_file, tii, template)?;
+        parties::ensure_satisfied(template, &parties, args_json, profile)?;
+    }
+
+    if let Some(network) = network {
+        args::check_networks(args_json, network, &profile.name)?;
+    }
+
+    Ok(())
+}
+
+fn resolve_tii_path(args: &Args, config: &RootConfig) -> miette::Result<PathBuf> {
+    let Some(from) = &args.from else {
+        return builder::build_tii(config);
+    };
+
+    let resolver = Resolver::new(config);
+    match resolver.resolve_protocol(from)? {
+        ResolvedProtocol::Project => builder::build_tii(config),
+        ResolvedProtocol::Interface(entry) => {
+            let paths = interfaces::cache_paths(entry)?;
+            Ok(paths.tii)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn view_reads_hash_and_fee_from_cshell_output() {
+        let args = ArgMap::from_iter([("quantity".to_string(), serde_json::json!(5))]);
+
+        let output = serde_json::json!({ "hash": "ab".repeat(32), "fee": "171573" });
+        let view = InvokeView::new(&output, true, args.clone());
+
+        assert_eq!(view.tx_hash.as_deref(), Some("ab".repeat(32).as_str()));
+        assert_eq!(view.fee, Some(171573));
+
+        let view = InvokeView::new(&serde_json::json!({ "fee": 9 }), false, args);
+        assert_eq!(view.tx_hash, None);
+        assert_eq!(view.fee, Some(9));
+
+        let json = serde_json::to_value(&view).unwrap();
+        assert_eq!(json["submitted"], false);
+        assert_eq!(json["args"]["quantity"], 5);
+        assert!(json.get("tx_cbor").is_none());
+    }
+}
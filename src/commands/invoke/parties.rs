@@ -0,0 +1,203 @@
+//! Up-front check that every party a transaction template uses can be
+//! filled, either by an arg or by an identity of the active profile.
+//! Without it a missing party only surfaces when cshell signs, after every
+//! prompt has been answered.
+
+use std::path::Path;
+
+use miette::bail;
+
+use crate::config::ProfileConfig;
+
+use super::ArgMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unsatisfied {
+    /// Party name as declared in the protocol.
+    pub party: String,
+    pub reason: String,
+}
+
+/// Arg key that carries `party`, matched case-insensitively the same way
+/// [`super::args::validate`] accepts party args.
+fn party_arg<'a>(args: &'a ArgMap, party: &str) -> Option<&'a serde_json::Value> {
+    args.iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(party))
+        .map(|(_, value)| value)
+}
+
+/// Collect the names of every `Address` param in `tir`, the TIR's JSON
+/// form, where a party reference ends up once lowered.
+fn address_params<'a>(tir: &'a serde_json::Value, out: &mut Vec<&'a str>) {
+    match tir {
+        serde_json::Value::Object(map) => {
+            if let Some(serde_json::Value::Array(param)) = map.get("ExpectValue")
+                && let [serde_json::Value::String(name), ty] = param.as_slice()
+                && ty == "Address"
+            {
+                out.push(name);
+            }
+
+            map.values().for_each(|value| address_params(value, out));
+        }
+        serde_json::Value::Array(items) => {
+            items.iter().for_each(|item| address_params(item, out));
+        }
+        _ => {}
+    }
+}
+
+/// Protocol parties the template lowered to `tir` refers to, sorted.
+pub fn referenced(tii: &serde_json::Value, tir: &serde_json::Value) -> Vec<String> {
+    let mut params = vec![];
+    address_params(tir, &mut params);
+
+    let mut parties: Vec<String> = tii
+        .get("parties")
+        .and_then(|p| p.as_object())
+        .map(|p| {
+            p.keys()
+                .filter(|party| params.iter().any(|param| param.eq_ignore_ascii_case(party)))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    parties.sort();
+
+    parties
+}
+
+/// Parties `template` of the TII at `tii_file` refers to. tx3c decodes its
+/// TIR.
+pub fn of_template(
+    tii_file: &Path,
+    tii: &serde_json::Value,
+    template: &str,
+) -> miette::Result<Vec<String>> {
+    let tir = crate::spawn::tx3c::decode_tir(tii_file, template)?;
+
+    Ok(referenced(tii, &tir))
+}
+
+/// Which of `parties` neither an arg nor an identity satisfies. An arg
+/// still holding an `@name` reference counts only if `name` is a known
+/// identity; any other value is taken as an address.
+pub fn unsatisfied(
+    parties: &[String],
+    args: &ArgMap,
+    has_identity: impl Fn(&str) -> bool,
+) -> Vec<Unsatisfied> {
+    let mut out = vec![];
+
+    for party in parties {
+        let reason = match party_arg(args, party) {
+            Some(serde_json::Value::String(text)) => match text.strip_prefix('@') {
+                Some(identity) if !has_identity(identity) => {
+                    format!("arg references unknown identity `@{identity}`")
+                }
+                _ => continue,
+            },
+            Some(_) => continue,
+            None if has_identity(&party.to_lowercase()) => continue,
+            None => "no arg and no identity with this name".to_string(),
+        };
+
+        out.push(Unsatisfied {
+            party: party.clone(),
+            reason,
+        });
+    }
+
+    out
+}
+
+/// Fail with the unsatisfied parties of `template` and a snippet for each
+/// way to fix them.
+pub fn ensure_satisfied(
+    template: &str,
+    parties: &[String],
+    args: &ArgMap,
+    profile: &ProfileConfig,
+) -> miette::Result<()> {
+    let missing = unsatisfied(parties, args, |name| profile.identities.contains_key(name));
+
+    let Some(first) = missing.first() else {
+        return Ok(());
+    };
+
+    let list = missing
+        .iter()
+        .map(|m| format!("  - {}: {}", m.party, m.reason))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let name = first.party.to_lowercase();
+
+    bail!(
+        help = format!(
+            "add an identity to trix.toml:\n\n  [profiles.{}.identities.{name}]\n  type = \"RandomKey\"\n  random_key = true\n\nor pass the address as an arg: `--arg {name}=<address>`",
+            profile.name
+        ),
+        "profile `{}` cannot satisfy every party of `{template}`:\n{list}",
+        profile.name
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parties() -> Vec<String> {
+        vec!["Buyer".to_string(), "Treasury".to_string()]
+    }
+
+    fn args(value: serde_json::Value) -> ArgMap {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn identity_or_arg_satisfies_party() {
+        let found = unsatisfied(
+            &parties(),
+            &args(serde_json::json!({ "treasury": "addr_test1xyz" })),
+            |name| name == "buyer",
+        );
+
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn reports_missing_party_and_unknown_reference() {
+        let found = unsatisfied(
+            &parties(),
+            &args(serde_json::json!({ "buyer": "@mallory" })),
+            |name| name == "alice",
+        );
+
+        let parties: Vec<_> = found.iter().map(|m| m.party.as_str()).collect();
+        assert_eq!(parties, vec!["Buyer", "Treasury"]);
+        assert!(found[0].reason.contains("@mallory"));
+    }
+
+    #[test]
+    fn only_parties_the_template_refers_to() {
+        let tii = serde_json::json!({
+            "parties": { "Buyer": {}, "Seller": {}, "Treasury": {} },
+        });
+
+        // `buy` pays the seller from the buyer's inputs; the treasury is
+        // only used by other templates.
+        let tir = serde_json::json!({
+            "inputs": [{
+                "name": "source",
+                "address": { "EvalParam": { "ExpectValue": ["buyer", "Address"] } },
+            }],
+            "outputs": [{
+                "address": { "EvalParam": { "ExpectValue": ["seller", "Address"] } },
+                "amount": { "EvalParam": { "ExpectValue": ["quantity", "Int"] } },
+            }],
+        });
+
+        assert_eq!(referenced(&tii, &tir), ["Buyer", "Seller"]);
+    }
+}
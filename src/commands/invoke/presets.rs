@@ -0,0 +1,110 @@
+//! Named arg sets from `[invoke.presets]` in `trix.toml`.
+
+use askama::Template;
+use miette::bail;
+
+use crate::config::{InvokePreset, RootConfig};
+
+pub struct PresetView {
+    pub name: String,
+    pub template: String,
+    pub description: Option<String>,
+    pub args: Vec<(String, String)>,
+}
+
+#[derive(Template)]
+#[template(path = "invoke/presets.md")]
+struct PresetsTemplate {
+    presets: Vec<PresetView>,
+}
+
+fn all(config: &RootConfig) -> impl Iterator<Item = (&String, &InvokePreset)> {
+    config.invoke.iter().flat_map(|invoke| invoke.presets.iter())
+}
+
+/// Levenshtein distance; preset names are short, so the quadratic table is
+/// fine.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(current)
+            };
+            previous = current;
+        }
+    }
+
+    row[b.len()]
+}
+
+fn close_matches<'a>(name: &str, candidates: impl Iterator<Item = &'a String>) -> Vec<&'a str> {
+    candidates
+        .filter(|candidate| distance(name, candidate) <= 2 || candidate.contains(name))
+        .map(String::as_str)
+        .collect()
+}
+
+/// Look up a preset, suggesting close names when there's no exact match.
+pub fn find<'a>(config: &'a RootConfig, name: &str) -> miette::Result<&'a InvokePreset> {
+    if let Some((_, preset)) = all(config).find(|(key, _)| *key == name) {
+        return Ok(preset);
+    }
+
+    let suggestions = close_matches(name, all(config).map(|(key, _)| key));
+
+    if suggestions.is_empty() {
+        bail!(
+            help = "run `trix invoke --list-presets` to see the defined presets",
+            "unknown invoke preset `{name}`"
+        );
+    }
+
+    bail!(
+        help = format!("did you mean {}?", suggestions.join(", ")),
+        "unknown invoke preset `{name}`"
+    );
+}
+
+pub fn print_list(config: &RootConfig) {
+    let presets = all(config)
+        .map(|(name, preset)| PresetView {
+            name: name.clone(),
+            template: preset.template.clone(),
+            description: preset.description.clone(),
+            args: preset
+                .args
+                .iter()
+                .map(|(k, v)| (k.clone(), v.to_string()))
+                .collect(),
+        })
+        .collect();
+
+    let markdown = PresetsTemplate { presets }
+        .render()
+        .expect("Template rendering failed");
+
+    crate::term::console::print_markdown(&markdown);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggests_close_names() {
+        let names = ["small-bid".to_string(), "large-bid".to_string(), "cancel".to_string()];
+
+        assert_eq!(close_matches("smal-bid", names.iter()), vec!["small-bid"]);
+        assert_eq!(close_matches("bid", names.iter()), vec!["small-bid", "large-bid"]);
+        assert!(close_matches("withdraw", names.iter()).is_empty());
+    }
+}
@@ -0,0 +1,279 @@
+//! Export and replay of resolved transactions.
+//!
+//! `--export-unsigned` writes the transaction cshell resolved (without
+//! submitting it) to an envelope file; `--from-file` takes that envelope
+//! back, skips resolution entirely, signs and submits the exact same
+//! transaction. Re-running a plain `invoke` after a transient failure would
+//! re-resolve and may pick different inputs.
+
+use std::path::Path;
+
+use miette::{Context as _, IntoDiagnostic as _, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ProfileConfig, RootConfig, U5cConfig};
+use crate::wallet::WalletProxy;
+
+/// A resolved, unsigned transaction plus enough context to check it still
+/// belongs to the project it's replayed against.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TxEnvelope {
+    /// `[protocol].name` of the project that resolved the transaction.
+    pub protocol: String,
+
+    /// TII schema version (`tii.version`) the transaction was resolved from.
+    pub tii_version: String,
+
+    /// Profile the transaction was resolved against.
+    pub profile: String,
+
+    /// Network of that profile; the transaction is only valid there.
+    pub network: String,
+
+    /// Template the transaction was resolved from, when it was named
+    /// rather than picked in cshell's prompt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+
+    /// Transaction hash, hex-encoded.
+    pub hash: String,
+
+    /// Unsigned transaction CBOR, hex-encoded.
+    pub cbor: String,
+}
+
+fn read_tii(tii_file: &Path) -> miette::Result<serde_json::Value> {
+    let bytes = std::fs::read(tii_file).into_diagnostic()?;
+
+    serde_json::from_slice(&bytes)
+        .into_diagnostic()
+        .context("parsing tii")
+}
+
+fn tii_version(tii: &serde_json::Value) -> miette::Result<String> {
+    tii.pointer("/tii/version")
+        .and_then(|v| v.as_str())
+        .map(String::from)
+        .ok_or_else(|| miette::miette!("tii has no `tii.version` field"))
+}
+
+/// Build the envelope for a `--skip-submit` cshell result of `template`
+/// and write it to `path`.
+pub fn export(
+    output: &serde_json::Value,
+    tii_file: &Path,
+    template: Option<&str>,
+    config: &RootConfig,
+    profile: &ProfileConfig,
+    path: &Path,
+) -> miette::Result<()> {
+    let hash = crate::spawn::cshell::tx_hash(output)
+        .ok_or_else(|| miette::miette!("cshell did not report a transaction hash"))?;
+
+    let cbor = output
+        .get("cbor")
+        .and_then(|c| c.as_str())
+        .ok_or_else(|| miette::miette!("cshell did not report the transaction cbor"))?;
+
+    let envelope = TxEnvelope {
+        protocol: config.protocol.name.clone(),
+        tii_version: tii_version(&read_tii(tii_file)?)?,
+        profile: profile.name.clone(),
+        network: config.resolve_profile_network(&profile.name)?.name,
+        template: template.map(str::to_string),
+        hash: hash.to_string(),
+        cbor: cbor.to_string(),
+    };
+
+    let json = serde_json::to_string_pretty(&envelope).into_diagnostic()?;
+
+    std::fs::write(path, json)
+        .into_diagnostic()
+        .context(format!("writing envelope to {}", path.display()))?;
+
+    println!(
+        "Unsigned transaction {} exported to {}",
+        envelope.hash,
+        path.display()
+    );
+
+    Ok(())
+}
+
+pub fn load(path: &Path) -> miette::Result<TxEnvelope> {
+    let content = std::fs::read_to_string(path)
+        .into_diagnostic()
+        .context(format!("reading envelope {}", path.display()))?;
+
+    serde_json::from_str(&content)
+        .into_diagnostic()
+        .context(format!("parsing envelope {}", path.display()))
+}
+
+/// Reject an envelope resolved for a different protocol, TII schema or
+/// network than the current project and profile use, or from a template
+/// the project no longer has.
+fn validate(
+    envelope: &TxEnvelope,
+    config: &RootConfig,
+    tii_file: &Path,
+    network: &str,
+) -> miette::Result<()> {
+    if envelope.protocol != config.protocol.name {
+        bail!(
+            "envelope was resolved for protocol '{}', but this project is '{}'",
+            envelope.protocol,
+            config.protocol.name
+        );
+    }
+
+    let tii = read_tii(tii_file)?;
+    let current = tii_version(&tii)?;
+
+    if envelope.tii_version != current {
+        bail!(
+            "envelope was resolved from tii {}, but this project builds tii {}",
+            envelope.tii_version,
+            current
+        );
+    }
+
+    if envelope.network != network {
+        bail!(
+            help = "replay it with a profile on that network",
+            "envelope was resolved for network '{}', but the profile submits to '{network}'",
+            envelope.network
+        );
+    }
+
+    if let Some(template) = &envelope.template
+        && tii.pointer(&format!("/transactions/{template}")).is_none()
+    {
+        bail!("envelope was resolved from template '{template}', which this project no longer has");
+    }
+
+    Ok(())
+}
+
+async fn is_on_chain(u5c: &U5cConfig, hash: &str) -> miette::Result<bool> {
+    let mut client = crate::net::u5c_client(u5c).await?;
+
+    let hash = hex::decode(hash).into_diagnostic()?;
+
+    let tx = client.read_tx(hash.into()).await.into_diagnostic()?;
+
+    Ok(tx.is_some())
+}
+
+pub fn run(
+    path: &Path,
+    signers: &[String],
+    wallet: &WalletProxy,
+    tii_file: &Path,
+    config: &RootConfig,
+    profile: &ProfileConfig,
+) -> miette::Result<()> {
+    let envelope = load(path)?;
+
+    let network = config.resolve_profile_network(&profile.name)?;
+
+    validate(&envelope, config, tii_file, &network.name)?;
+
+    if envelope.profile != profile.name {
+        eprintln!(
+            "warning: envelope was resolved against profile '{}', submitting to '{}'",
+            envelope.profile, profile.name
+        );
+    }
+
+    if signers.is_empty() {
+        bail!("replaying an envelope needs at least one `--signer`");
+    }
+
+    if futures::executor::block_on(is_on_chain(&network.u5c, &envelope.hash))? {
+        println!(
+            "Transaction {} is already on-chain, nothing to do",
+            envelope.hash
+        );
+        return Ok(());
+    }
+
+    let signers: Vec<&str> = signers.iter().map(String::as_str).collect();
+
+    let output = wallet.sign_and_submit(&envelope.cbor, signers, &profile.name)?;
+
+    crate::wallet::print_tx_link(&output, &network);
+    crate::submissions::record(
+        &output,
+        &profile.name,
+        &network,
+        envelope.template.as_deref(),
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RootConfig {
+        toml::from_str(
+            "[protocol]\nname = \"demo\"\nversion = \"0.0.0\"\nmain = \"main.tx3\"\n\
+             [ledger]\nfamily = \"cardano\"\n",
+        )
+        .unwrap()
+    }
+
+    fn write_tii(path: &Path, templates: serde_json::Value) {
+        let tii = serde_json::json!({
+            "tii": { "version": "v1beta0" },
+            "transactions": templates,
+        });
+        std::fs::write(path, tii.to_string()).unwrap();
+    }
+
+    /// Export a resolved `transfer` for the `local` profile into `dir` and
+    /// load it back, along with the TII it was resolved from.
+    fn round_trip(dir: &Path) -> (TxEnvelope, std::path::PathBuf) {
+        let tii_file = dir.join("main.tii");
+        write_tii(&tii_file, serde_json::json!({ "transfer": {} }));
+
+        let config = config();
+        let profile = config.resolve_profile("local").unwrap();
+
+        let output = serde_json::json!({ "hash": "ab".repeat(32), "cbor": "84a400" });
+        let path = dir.join("tx.json");
+
+        let template = Some("transfer");
+        export(&output, &tii_file, template, &config, &profile, &path).unwrap();
+
+        (load(&path).unwrap(), tii_file)
+    }
+
+    #[test]
+    fn exported_envelope_loads_and_validates() {
+        let dir = tempfile::tempdir().unwrap();
+        let (envelope, tii_file) = round_trip(dir.path());
+
+        assert_eq!(envelope.hash, "ab".repeat(32));
+        assert_eq!(envelope.cbor, "84a400");
+        assert_eq!(envelope.template.as_deref(), Some("transfer"));
+
+        validate(&envelope, &config(), &tii_file, &envelope.network).unwrap();
+    }
+
+    #[test]
+    fn mismatched_network_or_template_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let (envelope, tii_file) = round_trip(dir.path());
+
+        let err = validate(&envelope, &config(), &tii_file, "cardano-preview").unwrap_err();
+        assert!(err.to_string().contains("for network"), "{err}");
+
+        write_tii(&tii_file, serde_json::json!({ "send": {} }));
+
+        let err = validate(&envelope, &config(), &tii_file, &envelope.network).unwrap_err();
+        assert!(err.to_string().contains("no longer has"), "{err}");
+    }
+}
@@ -0,0 +1,334 @@
+//! Explain a TRP resolve failure in terms of the protocol's tx3 source.
+//!
+//! The TRP endpoint reports what it couldn't resolve by its IR name: an
+//! argument (`quantity`, or `sender` for party `Sender`) or an input block
+//! (`source`). cshell passes the JSON-RPC error body through on stderr; this
+//! module picks it out, finds the declaration it names in the template and
+//! labels it in a snippet of the .tx3 file. trix links no tx3 parser, so
+//! declarations are located by scanning the source text.
+
+use std::path::{Path, PathBuf};
+
+use miette::{Diagnostic, NamedSource, Report, SourceSpan};
+use thiserror::Error;
+
+use crate::config::RootConfig;
+use crate::spawn::cshell::InvokeFailed;
+
+/// What the endpoint couldn't resolve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Subject {
+    Arg(String),
+    Input(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TrpError {
+    subject: Option<Subject>,
+    reason: String,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("TRP could not resolve `{template}`: {reason}")]
+struct ResolveDiagnostic {
+    template: String,
+    reason: String,
+    #[source_code]
+    src: NamedSource<String>,
+    #[label("{label}")]
+    span: SourceSpan,
+    label: String,
+    #[help]
+    help: Option<String>,
+}
+
+/// The first JSON object in `text`, ignoring whatever cshell printed
+/// around it.
+fn json_body(text: &str) -> Option<serde_json::Value> {
+    text.match_indices('{').find_map(|(start, _)| {
+        serde_json::Deserializer::from_str(&text[start..])
+            .into_iter::<serde_json::Value>()
+            .next()?
+            .ok()
+            .filter(|value| value.is_object())
+    })
+}
+
+fn parse(stderr: &str) -> Option<TrpError> {
+    let body = json_body(stderr)?;
+
+    // A full JSON-RPC response, or just its `error` member.
+    let error = body.get("error").unwrap_or(&body);
+    let message = error.get("message")?.as_str()?;
+    let data = error.get("data");
+
+    let field = |key: &str| data?.get(key)?.as_str().map(String::from);
+
+    let subject = field("arg")
+        .or_else(|| field("key"))
+        .or_else(|| field("param"))
+        .map(Subject::Arg)
+        .or_else(|| field("input").map(Subject::Input));
+
+    let reason = match (field("expected"), field("got")) {
+        (Some(expected), Some(got)) => format!("{message} (expected {expected}, got {got})"),
+        (Some(expected), None) => format!("{message} (expected {expected})"),
+        _ => message.to_string(),
+    };
+
+    Some(TrpError { subject, reason })
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Offset of `word` in `source[from..to]` as a whole identifier.
+fn find_word(source: &str, word: &str, from: usize, to: usize) -> Option<usize> {
+    source[from..to].match_indices(word).find_map(|(i, _)| {
+        let start = from + i;
+        let end = start + word.len();
+
+        let before = source[..start].chars().next_back();
+        let after = source[end..].chars().next();
+
+        let whole = !before.is_some_and(is_ident) && !after.is_some_and(is_ident);
+        whole.then_some(start)
+    })
+}
+
+/// The identifier after keyword `keyword` equals `name` (ignoring case if
+/// asked); returns the identifier's offset.
+fn find_declaration(
+    source: &str,
+    keyword: &str,
+    name: &str,
+    from: usize,
+    to: usize,
+    ignore_case: bool,
+) -> Option<usize> {
+    let mut cursor = from;
+
+    while let Some(at) = find_word(source, keyword, cursor, to) {
+        let rest = &source[at + keyword.len()..to];
+        let ident_start = at + keyword.len() + (rest.len() - rest.trim_start().len());
+        let ident: String = source[ident_start..to]
+            .chars()
+            .take_while(|c| is_ident(*c))
+            .collect();
+
+        let matches = match ignore_case {
+            true => ident.eq_ignore_ascii_case(name),
+            false => ident == name,
+        };
+
+        if matches && !ident.is_empty() {
+            return Some(ident_start);
+        }
+
+        cursor = at + keyword.len();
+    }
+
+    None
+}
+
+/// Offset just past the bracket closing the one at `open`.
+fn closing(source: &str, open: usize, pair: (char, char)) -> Option<usize> {
+    let mut depth = 0;
+
+    for (i, c) in source[open..].char_indices() {
+        if c == pair.0 {
+            depth += 1;
+        } else if c == pair.1 {
+            depth -= 1;
+
+            if depth == 0 {
+                return Some(open + i + 1);
+            }
+        }
+    }
+
+    None
+}
+
+/// Span of the declaration `subject` refers to within template `template`,
+/// or of the party an argument stands for.
+fn locate(source: &str, template: &str, subject: &Subject) -> Option<(usize, usize)> {
+    let tx = find_declaration(source, "tx", template, 0, source.len(), false);
+
+    let found = tx.and_then(|tx| {
+        let params_open = tx + source[tx..].find('(')?;
+        let params_close = closing(source, params_open, ('(', ')'))?;
+        let body_open = params_close + source[params_close..].find('{')?;
+        let body_close = closing(source, body_open, ('{', '}'))?;
+
+        match subject {
+            Subject::Arg(name) => find_word(source, name, params_open, params_close)
+                .filter(|at| source[at + name.len()..].trim_start().starts_with(':')),
+            Subject::Input(name) => {
+                find_declaration(source, "input", name, body_open, body_close, false)
+            }
+        }
+    });
+
+    let (name, found) = match subject {
+        Subject::Arg(name) => (
+            name,
+            found.or_else(|| find_declaration(source, "party", name, 0, source.len(), true)),
+        ),
+        Subject::Input(name) => (name, found),
+    };
+
+    found.map(|at| (at, name.len()))
+}
+
+fn diagnose(error: &TrpError, template: &str, sources: &[PathBuf]) -> Option<ResolveDiagnostic> {
+    let subject = error.subject.as_ref()?;
+
+    let (path, content, (offset, len)) = sources.iter().find_map(|path| {
+        let content = std::fs::read_to_string(path).ok()?;
+        let span = locate(&content, template, subject)?;
+        Some((path, content, span))
+    })?;
+
+    let (label, help) = match subject {
+        Subject::Arg(name) => (
+            format!("argument `{name}`"),
+            Some(format!("check the value passed for `{name}`")),
+        ),
+        Subject::Input(name) => (
+            format!("input `{name}`"),
+            Some("no UTxO on chain satisfies this input's query".to_string()),
+        ),
+    };
+
+    Some(ResolveDiagnostic {
+        template: template.to_string(),
+        reason: error.reason.clone(),
+        src: NamedSource::new(display_path(path), content),
+        span: (offset, len).into(),
+        label,
+        help,
+    })
+}
+
+fn display_path(path: &Path) -> String {
+    crate::dirs::protocol_root()
+        .ok()
+        .and_then(|root| path.strip_prefix(root).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+/// Rewrite a failed invoke of `template` as a diagnostic pointing at the
+/// offending tx3 code. `config` is the project whose source resolved the
+/// transaction; `None` (e.g. for an interface's TII) keeps the raw body.
+pub(crate) fn explain(err: Report, config: Option<&RootConfig>, template: &str) -> Report {
+    let Some(failed) = err.downcast_ref::<InvokeFailed>() else {
+        return err;
+    };
+
+    let Some(error) = parse(&failed.stderr) else {
+        return err;
+    };
+
+    let sources = config
+        .and_then(|config| {
+            let root = crate::dirs::protocol_root().ok()?;
+            crate::builder::source_files(config, &root).ok()
+        })
+        .unwrap_or_default();
+
+    match diagnose(&error, template, &sources) {
+        Some(diagnostic) => Report::new(diagnostic),
+        None => err,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROTOCOL: &str = r#"party Sender;
+
+party Receiver;
+
+tx transfer(
+    quantity: Int
+) {
+    input source {
+        from: Sender,
+        min_amount: Ada(quantity) + fees,
+    }
+
+    output {
+        to: Receiver,
+        amount: Ada(quantity),
+    }
+}
+
+tx lock(quantity: Int, deadline: Int) {
+    input source {
+        from: Sender,
+        min_amount: Ada(quantity),
+    }
+}
+"#;
+
+    fn snippet(template: &str, subject: Subject) -> Option<String> {
+        let (offset, len) = locate(PROTOCOL, template, &subject)?;
+        let line = PROTOCOL[..offset].lines().count();
+        Some(format!("{line}:{}", &PROTOCOL[offset..offset + len]))
+    }
+
+    #[test]
+    fn maps_trp_errors_to_source_spans() {
+        let cases = [
+            (
+                r#"error: {"jsonrpc":"2.0","id":1,"error":{"code":-32602,"message":"missing argument","data":{"arg":"quantity","type":"Int"}}}"#,
+                "transfer",
+                Some("6:quantity"),
+                "missing argument",
+            ),
+            (
+                r#"{"code":-32602,"message":"invalid argument type","data":{"key":"deadline","expected":"Int","got":"Bytes"}}"#,
+                "lock",
+                Some("19:deadline"),
+                "invalid argument type (expected Int, got Bytes)",
+            ),
+            (
+                r#"{"code":-32602,"message":"invalid address","data":{"arg":"sender"}}"#,
+                "transfer",
+                Some("1:Sender"),
+                "invalid address",
+            ),
+            (
+                r#"TRP error {"code":-32001,"message":"input query not satisfied","data":{"input":"source","query":{"min_amount":"1000000"}}} while resolving"#,
+                "lock",
+                Some("20:source"),
+                "input query not satisfied",
+            ),
+            (
+                r#"{"code":-32603,"message":"internal error"}"#,
+                "transfer",
+                None,
+                "internal error",
+            ),
+        ];
+
+        for (body, template, expected, reason) in cases {
+            let error = parse(body).unwrap_or_else(|| panic!("unparsed: {body}"));
+            assert_eq!(error.reason, reason);
+
+            let located = error.subject.and_then(|subject| snippet(template, subject));
+            assert_eq!(located.as_deref(), expected, "{body}");
+        }
+
+        assert_eq!(parse("connection refused"), None);
+        assert_eq!(
+            snippet("transfer", Subject::Input("missing".to_string())),
+            None
+        );
+    }
+}
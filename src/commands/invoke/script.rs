@@ -0,0 +1,193 @@
+//! `trix invoke --script`: submit a fixed sequence of transactions, e.g. to
+//! replay a demo (fund, lock, unlock) against the devnet. The file lists
+//! them like the `transactions` of a `trix test` file, in TOML or JSON:
+//!
+//! ```toml
+//! [[transactions]]
+//! description = "Lock funds"
+//! template = "lock"
+//! signers = ["alice"]
+//! args = { owner = "@alice", quantity = 1000000 }
+//! ```
+//!
+//! Steps run in order and the script stops at the first failure.
+
+use std::path::Path;
+
+use miette::{Context as _, IntoDiagnostic as _, bail};
+use serde::Deserialize;
+
+use crate::commands::steps::{self, Transaction};
+use crate::config::{ProfileConfig, RootConfig};
+use crate::wallet::WalletProxy;
+
+#[derive(Debug, Deserialize)]
+pub struct Script {
+    #[serde(default)]
+    pub transactions: Vec<Transaction>,
+}
+
+impl Script {
+    /// Load a script, as JSON when the file ends in `.json` and as TOML
+    /// otherwise.
+    pub fn load(path: &Path) -> miette::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .into_diagnostic()
+            .with_context(|| format!("reading script {}", path.display()))?;
+
+        let script: Self = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&content).into_diagnostic(),
+            _ => toml::from_str(&content).into_diagnostic(),
+        }
+        .with_context(|| format!("parsing script {}", path.display()))?;
+
+        if script.transactions.is_empty() {
+            bail!("{} lists no transactions", path.display());
+        }
+
+        Ok(script)
+    }
+}
+
+enum Outcome {
+    Submitted(Option<String>),
+    Failed(String),
+    NotRun,
+}
+
+fn print_summary(script: &Script, outcomes: &[Outcome]) {
+    println!("== Summary ==\n");
+
+    for (i, transaction) in script.transactions.iter().enumerate() {
+        let outcome = outcomes.get(i).unwrap_or(&Outcome::NotRun);
+
+        let (status, detail) = match outcome {
+            Outcome::Submitted(hash) => ("ok", hash.clone().unwrap_or_default()),
+            Outcome::Failed(err) => ("FAILED", err.clone()),
+            Outcome::NotRun => ("not run", String::new()),
+        };
+
+        println!(
+            "{}. [{status}] {} ({}) {detail}",
+            i + 1,
+            transaction.description,
+            transaction.template
+        );
+    }
+
+    println!();
+}
+
+fn run_step(
+    config: &RootConfig,
+    wallet: &WalletProxy,
+    tii_file: &Path,
+    transaction: &Transaction,
+    profile: &ProfileConfig,
+) -> miette::Result<Option<String>> {
+    let args = steps::define_args(transaction, wallet)?;
+
+    let output = steps::submit(config, wallet, tii_file, transaction, &args, profile)?;
+
+    let network = config.resolve_profile_network(&profile.name)?;
+    crate::wallet::print_tx_link(&output, &network);
+    crate::submissions::record(
+        &output,
+        &profile.name,
+        &network,
+        Some(&transaction.template),
+    );
+
+    Ok(crate::spawn::cshell::tx_hash(&output).map(str::to_string))
+}
+
+pub fn run(
+    path: &Path,
+    wallet: &WalletProxy,
+    tii_file: &Path,
+    config: &RootConfig,
+    profile: &ProfileConfig,
+) -> miette::Result<()> {
+    let script = Script::load(path)?;
+
+    let mut outcomes = vec![];
+
+    for (i, transaction) in script.transactions.iter().enumerate() {
+        if i > 0 {
+            steps::wait_next_block();
+        }
+
+        println!("--- Running transaction: {} ---", transaction.description);
+
+        match run_step(config, wallet, tii_file, transaction, profile) {
+            Ok(hash) => outcomes.push(Outcome::Submitted(hash)),
+            Err(err) => {
+                eprintln!("{err:?}");
+                outcomes.push(Outcome::Failed(err.to_string()));
+                break;
+            }
+        }
+    }
+
+    print_summary(&script, &outcomes);
+
+    if let Some(Outcome::Failed(_)) = outcomes.last() {
+        let failed = &script.transactions[outcomes.len() - 1];
+
+        bail!(
+            "script {} stopped at step {} (`{}`)",
+            path.display(),
+            outcomes.len(),
+            failed.description
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_toml_and_json_scripts() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let toml_path = dir.path().join("demo.toml");
+        std::fs::write(
+            &toml_path,
+            r#"
+            [[transactions]]
+            description = "Lock funds"
+            template = "lock"
+            signers = ["alice"]
+            args = { owner = "@alice", quantity = 1000000 }
+
+            [[transactions]]
+            description = "Unlock funds"
+            template = "unlock"
+            signers = ["alice"]
+            args = {}
+            "#,
+        )
+        .unwrap();
+
+        let script = Script::load(&toml_path).unwrap();
+        let templates: Vec<_> = script.transactions.iter().map(|t| &t.template).collect();
+        assert_eq!(templates, ["lock", "unlock"]);
+        assert_eq!(script.transactions[0].args["quantity"], 1000000);
+
+        let json_path = dir.path().join("demo.json");
+        std::fs::write(
+            &json_path,
+            r#"{"transactions": [{"description": "Fund", "template": "transfer", "signers": ["alice"], "args": {"receiver": "@bob"}}]}"#,
+        )
+        .unwrap();
+
+        let script = Script::load(&json_path).unwrap();
+        assert_eq!(script.transactions[0].args["receiver"], "@bob");
+
+        std::fs::write(&json_path, r#"{"transactions": []}"#).unwrap();
+        assert!(Script::load(&json_path).is_err());
+    }
+}
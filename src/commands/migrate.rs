@@ -0,0 +1,74 @@
+//! `trix migrate`: bring a trix.toml written for an older trix up to the
+//! current config schema, one migration at a time. The file is saved after
+//! each step with its new `config_schema_version`, so a failing step
+//! leaves it at the last version that applied cleanly.
+
+use std::path::Path;
+
+use clap::Args as ClapArgs;
+use miette::Context as _;
+
+use crate::config::RootConfig;
+use crate::config::migrate::{self, CURRENT_VERSION};
+
+#[derive(ClapArgs)]
+pub struct Args {
+    /// List the pending migrations without changing trix.toml
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Apply them without asking
+    #[arg(short, long)]
+    yes: bool,
+}
+
+pub fn run(args: Args, config_path: &Path) -> miette::Result<()> {
+    let mut doc = migrate::parse(config_path)?;
+
+    let pending = migrate::pending(&doc)?;
+
+    if pending.is_empty() {
+        if migrate::recorded_version(&doc)?.is_none() && !args.dry_run {
+            migrate::stamp(&mut doc);
+            crate::fsutil::write_atomic(config_path, doc.to_string())?;
+        }
+
+        println!(
+            "{} is up to date (config schema {CURRENT_VERSION})",
+            config_path.display()
+        );
+
+        return Ok(());
+    }
+
+    println!(
+        "pending migrations for {}:\n{}",
+        config_path.display(),
+        migrate::describe(&pending)
+    );
+
+    if args.dry_run {
+        return Ok(());
+    }
+
+    let proceed = args.yes
+        || !crate::term::prompt::is_interactive()
+        || crate::term::prompt::confirm("Apply them?", true)?;
+
+    if !proceed {
+        println!("nothing changed");
+        return Ok(());
+    }
+
+    for migration in pending {
+        migrate::apply(&mut doc, migration)?;
+        crate::fsutil::write_atomic(config_path, doc.to_string())?;
+
+        println!("migrated to config schema {}", migration.version);
+    }
+
+    RootConfig::load(&config_path.to_path_buf())
+        .context("the migrated trix.toml still doesn't load; finish it by hand")?;
+
+    Ok(())
+}
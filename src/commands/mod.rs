@@ -1,15 +1,24 @@
+pub mod alias;
 pub mod build;
 pub mod check;
 pub mod codegen;
+pub mod config;
 pub mod devnet;
+pub mod doctor;
 pub mod expect;
 pub mod explore;
 pub mod identities;
 pub mod init;
 pub mod inspect;
 pub mod invoke;
+pub mod migrate;
 pub mod profile;
 pub mod publish;
+pub mod secret;
+pub mod shell;
+pub mod steps;
+pub mod submissions;
 pub mod telemetry;
 pub mod test;
 pub mod use_cmd;
+pub mod wallet;
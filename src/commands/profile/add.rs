@@ -0,0 +1,286 @@
+use miette::bail;
+
+use crate::config::{NetworkOption, ProfileConfig, RootConfig, TrpConfig};
+use crate::secrets::SecretRef;
+use crate::term::prompt;
+
+use super::AddArgs;
+
+/// Everything a new profile needs, from flags or prompts.
+#[derive(Debug, Clone)]
+struct Answers {
+    name: String,
+    network: String,
+    trp_url: Option<String>,
+    api_key: Option<String>,
+}
+
+/// Sorted so prompts and errors list networks in a stable order.
+fn network_names(config: &RootConfig) -> Vec<String> {
+    let mut names: Vec<_> = config.available_networks().into_iter().collect();
+    names.sort();
+    names
+}
+
+fn from_flags(args: &AddArgs, config: &RootConfig) -> miette::Result<Answers> {
+    let mut missing = vec![];
+
+    if args.name.is_none() {
+        missing.push("NAME");
+    }
+
+    if args.network.is_none() {
+        missing.push("--network");
+    }
+
+    if !missing.is_empty() {
+        bail!(
+            help = format!("networks: {}", network_names(config).join(", ")),
+            "missing {} (required without a terminal or with --non-interactive)",
+            missing.join(", ")
+        );
+    }
+
+    Ok(Answers {
+        name: args.name.clone().unwrap_or_default(),
+        network: args.network.clone().unwrap_or_default(),
+        trp_url: args.trp_url.clone(),
+        api_key: args.api_key.clone(),
+    })
+}
+
+/// Ask for every field not given as a flag. Blank optional answers keep the
+/// network's own endpoint and headers.
+fn from_prompts(args: &AddArgs, config: &RootConfig) -> miette::Result<Answers> {
+    let name = match &args.name {
+        Some(name) => name.clone(),
+        None => prompt::text("Profile name:")?.trim().to_string(),
+    };
+
+    let network = match &args.network {
+        Some(network) => network.clone(),
+        None => prompt::select("Network:", network_names(config))?,
+    };
+
+    let trp_url = match &args.trp_url {
+        Some(url) => Some(url.clone()),
+        None => Some(prompt::text("TRP URL (blank keeps the network's):")?)
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty()),
+    };
+
+    let api_key = match &args.api_key {
+        Some(key) => Some(key.clone()),
+        None => Some(prompt::password(
+            "API key, or an env:VAR / keyring:service/account reference (blank for none):",
+        )?)
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty()),
+    };
+
+    Ok(Answers {
+        name,
+        network,
+        trp_url,
+        api_key,
+    })
+}
+
+/// Whether an existing explicit profile may be replaced.
+fn confirm_overwrite(args: &AddArgs, name: &str, interactive: bool) -> miette::Result<bool> {
+    eprintln!("warning: trix.toml already defines a profile named `{name}`");
+
+    if args.force {
+        return Ok(true);
+    }
+
+    if !interactive {
+        bail!(
+            help = "pass --force to replace it, or pick another name",
+            "profile `{name}` already exists"
+        );
+    }
+
+    prompt::confirm(&format!("Overwrite profile `{name}`?"), false)
+}
+
+/// Add the profile to `config`. A custom TRP URL or API key can't live on a
+/// shared network, so it gets a network of its own named after the profile,
+/// starting from the chosen network's settings.
+fn apply(config: &mut RootConfig, answers: &Answers, header: &str) -> miette::Result<()> {
+    if answers.name.is_empty() {
+        bail!("the profile name can't be empty");
+    }
+
+    if !config.available_networks().contains(&answers.network) {
+        bail!(
+            help = format!("networks: {}", network_names(config).join(", ")),
+            "unknown network `{}`",
+            answers.network
+        );
+    }
+
+    let mut network = answers.network.clone();
+
+    if answers.trp_url.is_some() || answers.api_key.is_some() {
+        // Replacing a profile added this way may replace its network too.
+        let own_network = config
+            .profiles
+            .get(&answers.name)
+            .is_some_and(|profile| profile.network == answers.name);
+        let shadows_network = !own_network && config.available_networks().contains(&answers.name);
+
+        if shadows_network {
+            bail!(
+                help = "pick a profile name that isn't already a network name",
+                "a custom endpoint is stored as network `{}`, which already exists",
+                answers.name
+            );
+        }
+
+        let mut custom = config.resolve_network(&answers.network)?;
+        custom.name = answers.name.clone();
+
+        if let Some(url) = &answers.trp_url {
+            custom.trp = TrpConfig {
+                url: url.clone(),
+                headers: custom.trp.headers,
+            };
+        }
+
+        if let Some(key) = &answers.api_key {
+            if matches!(SecretRef::parse(key)?, SecretRef::Literal(_)) {
+                eprintln!(
+                    "warning: the API key is written to trix.toml as is; pass an `env:VAR` or `keyring:service/account` reference to keep it out of the file"
+                );
+            }
+
+            custom.trp.headers.insert(header.to_string(), key.clone());
+        }
+
+        config
+            .networks
+            .insert(answers.name.clone(), NetworkOption::Custom(custom));
+        network = answers.name.clone();
+    }
+
+    config.profiles.insert(
+        answers.name.clone(),
+        ProfileConfig {
+            name: answers.name.clone(),
+            extends: None,
+            network,
+            env_file: None,
+            identities: Default::default(),
+        },
+    );
+
+    Ok(())
+}
+
+pub fn run(args: AddArgs, config: &RootConfig, _profile: &ProfileConfig) -> miette::Result<()> {
+    let interactive = !args.non_interactive && prompt::is_interactive();
+
+    let answers = match interactive {
+        true => from_prompts(&args, config)?,
+        false => from_flags(&args, config)?,
+    };
+
+    if config.profiles.contains_key(&answers.name)
+        && !confirm_overwrite(&args, &answers.name, interactive)?
+    {
+        println!("profile `{}` left unchanged", answers.name);
+        return Ok(());
+    }
+
+    let mut next = config.clone();
+    apply(&mut next, &answers, &args.api_key_header)?;
+
+    next.save(&crate::dirs::protocol_root()?.join("trix.toml"))?;
+
+    println!(
+        "added profile `{}` (network `{}`)",
+        answers.name, next.profiles[&answers.name].network
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE_TOML: &str = r#"
+        [protocol]
+        name = "demo"
+        version = "0.0.0"
+        main = "main.tx3"
+
+        [ledger]
+        family = "cardano"
+    "#;
+
+    fn answers(name: &str, network: &str) -> Answers {
+        Answers {
+            name: name.to_string(),
+            network: network.to_string(),
+            trp_url: None,
+            api_key: None,
+        }
+    }
+
+    fn round_trip(config: &RootConfig) -> RootConfig {
+        toml::from_str(&toml::to_string_pretty(config).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn plain_profile_round_trips() {
+        let mut config: RootConfig = toml::from_str(BASE_TOML).unwrap();
+        apply(
+            &mut config,
+            &answers("staging", "cardano-preview"),
+            "dmtr-api-key",
+        )
+        .unwrap();
+
+        let config = round_trip(&config);
+
+        assert_eq!(config.profiles["staging"].network, "cardano-preview");
+        assert!(config.networks.is_empty());
+    }
+
+    #[test]
+    fn custom_endpoint_gets_its_own_network() {
+        let mut config: RootConfig = toml::from_str(BASE_TOML).unwrap();
+
+        let answers = Answers {
+            trp_url: Some("https://trp.example".to_string()),
+            api_key: Some("env:STAGING_KEY".to_string()),
+            ..answers("staging", "cardano-preview")
+        };
+        apply(&mut config, &answers, "dmtr-api-key").unwrap();
+
+        let config = round_trip(&config);
+        let profile = config.resolve_profile("staging").unwrap();
+        let network = config.resolve_network(&profile.network).unwrap();
+
+        assert_eq!(profile.network, "staging");
+        assert!(network.is_testnet);
+        assert_eq!(network.trp.url, "https://trp.example");
+        assert_eq!(network.trp.headers["dmtr-api-key"], "env:STAGING_KEY");
+    }
+
+    #[test]
+    fn rejects_unknown_network_and_shadowing() {
+        let mut config: RootConfig = toml::from_str(BASE_TOML).unwrap();
+
+        let err = apply(&mut config, &answers("staging", "nowhere"), "x").unwrap_err();
+        assert!(err.to_string().contains("unknown network"), "{err}");
+
+        let shadowing = Answers {
+            trp_url: Some("https://trp.example".to_string()),
+            ..answers("cardano-mainnet", "cardano-preview")
+        };
+        assert!(apply(&mut config, &shadowing, "x").is_err());
+    }
+}
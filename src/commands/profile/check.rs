@@ -0,0 +1,269 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use askama::Template;
+use miette::{IntoDiagnostic as _, bail};
+use serde::Serialize;
+
+use crate::config::{CodegenConfig, ProfileConfig, RootConfig};
+use crate::term::OutputFormat;
+
+use super::load_env_vars;
+
+// ============================================================================
+// View Model
+// ============================================================================
+
+/// One env var a codegen job expects at runtime, and the option that
+/// declares it.
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvRef {
+    pub var: String,
+    pub job_id: String,
+    pub option: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileEnvReport {
+    pub profile: String,
+    pub env_file: String,
+    pub env_file_found: bool,
+    /// Declared by a codegen job but absent from the env file.
+    pub missing: Vec<EnvRef>,
+    /// In the env file but read by no codegen job.
+    pub unreferenced: Vec<String>,
+    /// Declared and in the env file, but overridden by the process env.
+    pub shadowed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvCheckView {
+    pub profiles: Vec<ProfileEnvReport>,
+}
+
+impl EnvCheckView {
+    fn missing(&self) -> usize {
+        self.profiles.iter().map(|p| p.missing.len()).sum()
+    }
+}
+
+// ============================================================================
+// Askama Template
+// ============================================================================
+
+#[derive(Template)]
+#[template(path = "profile/check.md")]
+struct EnvCheckTemplate<'a> {
+    view: &'a EnvCheckView,
+}
+
+// ============================================================================
+// Command Entry Point
+// ============================================================================
+
+pub fn run(
+    args: super::CheckArgs,
+    config: &RootConfig,
+    _profile: &ProfileConfig,
+) -> miette::Result<()> {
+    let names: Vec<String> = match args.name {
+        Some(name) => vec![name],
+        None => config
+            .available_profiles()
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect(),
+    };
+
+    let declared = declared_vars(&config.codegen);
+
+    let mut profiles = vec![];
+    for name in names {
+        let profile = config.resolve_profile(&name)?;
+        profiles.push(build_report(&profile, &declared)?);
+    }
+
+    let view = EnvCheckView { profiles };
+
+    match args.output {
+        OutputFormat::Human => {
+            let markdown = EnvCheckTemplate { view: &view }
+                .render()
+                .expect("Template rendering failed");
+            crate::term::console::print_markdown(&markdown);
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&view).into_diagnostic()?);
+        }
+    }
+
+    let missing = view.missing();
+    if missing > 0 {
+        bail!("{missing} env variables declared by codegen are missing from profile env files");
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// View Building (Materialization)
+// ============================================================================
+
+fn push_names(value: &serde_json::Value, job_id: &str, option: String, out: &mut Vec<EnvRef>) {
+    match value {
+        serde_json::Value::String(var) => out.push(EnvRef {
+            var: var.clone(),
+            job_id: job_id.to_string(),
+            option,
+        }),
+        serde_json::Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                push_names(item, job_id, format!("{option}[{i}]"), out);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for (key, item) in map {
+                push_names(item, job_id, format!("{option}.{key}"), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Env var names read by the generated bindings: the `trp.env` mapping
+/// and every `env_*` option of each `[[codegen]]` job.
+pub fn declared_vars(codegen: &[CodegenConfig]) -> Vec<EnvRef> {
+    let mut out = vec![];
+
+    for job in codegen {
+        let Some(options) = &job.options else {
+            continue;
+        };
+
+        let job_id = job.job_id();
+
+        if let Some(env) = options.get("trp").and_then(|trp| trp.get("env")) {
+            push_names(env, &job_id, "trp.env".into(), &mut out);
+        }
+
+        for (key, value) in options {
+            if key.starts_with("env_") {
+                push_names(value, &job_id, key.clone(), &mut out);
+            }
+        }
+    }
+
+    out.sort_by(|a, b| (&a.var, &a.job_id).cmp(&(&b.var, &b.job_id)));
+    out
+}
+
+fn compare(
+    profile: &str,
+    env_file: String,
+    vars: Option<&BTreeMap<String, String>>,
+    declared: &[EnvRef],
+    process_env: impl Fn(&str) -> Option<String>,
+) -> ProfileEnvReport {
+    let empty = BTreeMap::new();
+    let present = vars.unwrap_or(&empty);
+
+    let referenced: BTreeSet<&str> = declared.iter().map(|d| d.var.as_str()).collect();
+
+    let missing = declared
+        .iter()
+        .filter(|d| !present.contains_key(&d.var))
+        .cloned()
+        .collect();
+
+    let unreferenced = present
+        .keys()
+        .filter(|key| !referenced.contains(key.as_str()))
+        .cloned()
+        .collect();
+
+    let shadowed = present
+        .iter()
+        .filter(|(key, value)| {
+            referenced.contains(key.as_str())
+                && process_env(key).is_some_and(|env| env != **value)
+        })
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    ProfileEnvReport {
+        profile: profile.to_string(),
+        env_file,
+        env_file_found: vars.is_some(),
+        missing,
+        unreferenced,
+        shadowed,
+    }
+}
+
+fn build_report(profile: &ProfileConfig, declared: &[EnvRef]) -> miette::Result<ProfileEnvReport> {
+    let path = profile.env_file_path();
+
+    let vars = if path.is_file() {
+        Some(load_env_vars(&path)?)
+    } else {
+        None
+    };
+
+    Ok(compare(
+        &profile.name,
+        path.display().to_string(),
+        vars.as_ref(),
+        declared,
+        |key| std::env::var(key).ok(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codegen(options: serde_json::Value) -> Vec<CodegenConfig> {
+        vec![CodegenConfig {
+            job_id: Some("web".into()),
+            plugin: crate::config::CodegenPlugin::Known(crate::config::KnownCodegenPlugin::TsClient),
+            output_dir: None,
+            options: Some(serde_json::from_value(options).unwrap()),
+            verify_ignore: vec![],
+            vendored: false,
+        }]
+    }
+
+    #[test]
+    fn collects_trp_env_and_env_options() {
+        let declared = declared_vars(&codegen(serde_json::json!({
+            "trp": { "env": { "url": "TRP_URL", "key": "TRP_KEY" } },
+            "env_network": "NETWORK",
+            "other": "NOT_AN_ENV",
+        })));
+
+        let vars: Vec<_> = declared.iter().map(|d| d.var.as_str()).collect();
+        assert_eq!(vars, vec!["NETWORK", "TRP_KEY", "TRP_URL"]);
+        assert_eq!(declared[1].option, "trp.env.key");
+    }
+
+    #[test]
+    fn reports_missing_unreferenced_and_shadowed() {
+        let declared = declared_vars(&codegen(serde_json::json!({
+            "trp": { "env": { "url": "TRP_URL", "key": "TRP_KEY" } },
+        })));
+
+        let vars = BTreeMap::from([
+            ("TRP_URL".to_string(), "http://a".to_string()),
+            ("LEFTOVER".to_string(), "x".to_string()),
+        ]);
+
+        let report = compare("preview", ".env.preview".into(), Some(&vars), &declared, |key| {
+            (key == "TRP_URL").then(|| "http://b".to_string())
+        });
+
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].var, "TRP_KEY");
+        assert_eq!(report.unreferenced, vec!["LEFTOVER".to_string()]);
+        assert_eq!(report.shadowed, vec!["TRP_URL".to_string()]);
+    }
+}
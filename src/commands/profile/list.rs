@@ -1,5 +1,4 @@
 use askama::Template;
-use termimad::MadSkin;
 
 use crate::config::RootConfig;
 
@@ -90,6 +89,5 @@ fn build_profile_list_view(config: &RootConfig) -> miette::Result<ProfileListVie
 
 fn render_profile_list_view(view: &ProfileListView) {
     let markdown = ProfileListTemplate::render_view(view);
-    let skin = MadSkin::default();
-    skin.print_text(&markdown);
+    crate::term::console::print_markdown(&markdown);
 }
@@ -4,9 +4,13 @@ use std::path::Path;
 
 use crate::config::RootConfig;
 
+pub mod add;
+pub mod check;
 pub mod list;
 pub mod show;
 
+pub use add::run as run_add;
+pub use check::run as run_check;
 pub use list::run as run_list;
 pub use show::run as run_show;
 
@@ -16,6 +20,10 @@ pub enum Command {
     List,
     /// Show effective configuration for a specific profile
     Show(ShowArgs),
+    /// Compare env files against the env vars codegen jobs read
+    Check(CheckArgs),
+    /// Add a named profile to trix.toml
+    Add(AddArgs),
 }
 
 #[derive(ClapArgs)]
@@ -27,6 +35,47 @@ pub struct ShowArgs {
     pub name: String,
 }
 
+#[derive(ClapArgs)]
+pub struct CheckArgs {
+    /// Profile to check; all profiles when omitted
+    pub name: Option<String>,
+
+    /// Report format; `json` is meant for CI annotations
+    #[arg(long, value_enum, default_value_t = crate::term::OutputFormat::Human)]
+    pub output: crate::term::OutputFormat,
+}
+
+#[derive(ClapArgs)]
+pub struct AddArgs {
+    /// Name of the new profile; prompted for when omitted
+    pub name: Option<String>,
+
+    /// Network the profile targets (see `trix profile list`)
+    #[arg(long)]
+    pub network: Option<String>,
+
+    /// TRP endpoint to use instead of the network's
+    #[arg(long, value_name = "URL")]
+    pub trp_url: Option<String>,
+
+    /// API key sent to the TRP endpoint; an `env:VAR` or
+    /// `keyring:service/account` reference keeps it out of trix.toml
+    #[arg(long)]
+    pub api_key: Option<String>,
+
+    /// Header that carries the API key
+    #[arg(long, value_name = "NAME", default_value = "dmtr-api-key")]
+    pub api_key_header: String,
+
+    /// Replace a profile of the same name without asking
+    #[arg(long)]
+    pub force: bool,
+
+    /// Never prompt; NAME and --network must be given as arguments
+    #[arg(long)]
+    pub non_interactive: bool,
+}
+
 #[derive(ClapArgs)]
 pub struct Args {
     #[clap(subcommand)]
@@ -41,6 +90,8 @@ pub fn run(
     match args.command {
         Command::List => run_list(ListArgs, config, profile),
         Command::Show(args) => run_show(args, config, profile),
+        Command::Check(args) => run_check(args, config, profile),
+        Command::Add(args) => run_add(args, config, profile),
     }
 }
 
@@ -63,6 +114,27 @@ impl std::fmt::Display for ConfigSource {
     }
 }
 
+/// Which profile in an `extends` chain a field's effective value comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldOrigin {
+    /// Set by the profile itself.
+    Own,
+    /// Inherited from the named ancestor.
+    Inherited(String),
+    /// Set nowhere in the chain; the convention applies.
+    Default,
+}
+
+impl std::fmt::Display for FieldOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldOrigin::Own => write!(f, "overridden here"),
+            FieldOrigin::Inherited(parent) => write!(f, "inherited from {parent}"),
+            FieldOrigin::Default => write!(f, "default"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum EnvFileStatus {
     Found,
@@ -90,11 +162,13 @@ pub struct NetworkView {
 pub struct IdentityView {
     pub name: String,
     pub kind: String,
+    pub origin: FieldOrigin,
 }
 
 #[derive(Debug, Clone)]
 pub struct EnvFileView {
     pub file_name: String,
+    pub origin: FieldOrigin,
     pub status: EnvFileStatus,
     pub variables: Vec<(String, String)>,
 }
@@ -103,6 +177,9 @@ pub struct EnvFileView {
 pub struct ProfileView {
     pub name: String,
     pub source: ConfigSource,
+    /// Ancestors from `extends`, nearest first.
+    pub extends: Vec<String>,
+    pub network_origin: FieldOrigin,
     pub network: NetworkView,
     pub identities: Vec<IdentityView>,
     pub env_file: EnvFileView,
@@ -171,15 +248,19 @@ pub(crate) fn should_mask_env_var(key: &str) -> bool {
         || lower.contains("private")
 }
 
-pub(crate) fn load_and_mask_env_vars(path: &Path) -> miette::Result<Vec<(String, String)>> {
+pub(crate) fn load_env_vars(path: &Path) -> miette::Result<BTreeMap<String, String>> {
     use miette::{Context, IntoDiagnostic};
 
     let content = std::fs::read_to_string(path)
         .into_diagnostic()
         .context("Failed to read env file")?;
 
-    let parsed: BTreeMap<String, String> = dotenv_parser::parse_dotenv(&content)
-        .map_err(|e| miette::miette!("Failed to parse env file: {}", e))?;
+    dotenv_parser::parse_dotenv(&content)
+        .map_err(|e| miette::miette!("Failed to parse env file: {}", e))
+}
+
+pub(crate) fn load_and_mask_env_vars(path: &Path) -> miette::Result<Vec<(String, String)>> {
+    let parsed = load_env_vars(path)?;
 
     Ok(parsed
         .into_iter()
@@ -1,11 +1,11 @@
 use askama::Template;
-use termimad::MadSkin;
 
 use crate::config::{NetworkConfig, ProfileConfig, RootConfig};
 
 use super::{
     load_and_mask_env_vars, mask_value, resolve_network_source, resolve_profile_source,
-    ConfigSource, EndpointView, EnvFileStatus, EnvFileView, IdentityView, NetworkView, ProfileView,
+    ConfigSource, EndpointView, EnvFileStatus, EnvFileView, FieldOrigin, IdentityView, NetworkView,
+    ProfileView,
 };
 
 // ============================================================================
@@ -45,6 +45,7 @@ pub fn run(
 // ============================================================================
 
 fn build_profile_view(config: &RootConfig, profile_name: &str) -> miette::Result<ProfileView> {
+    let chain = config.profile_chain(profile_name)?;
     let profile = config.resolve_profile(profile_name)?;
     let network = config.resolve_profile_network(profile_name)?;
 
@@ -54,12 +55,24 @@ fn build_profile_view(config: &RootConfig, profile_name: &str) -> miette::Result
     Ok(ProfileView {
         name: profile.name.clone(),
         source: profile_source,
+        extends: chain.iter().skip(1).map(|p| p.name.clone()).collect(),
+        network_origin: origin_of(&chain, |p| !p.network.is_empty()),
         network: build_network_view(&network, network_source),
-        identities: build_identities_view(&profile),
-        env_file: build_env_file_view(&profile),
+        identities: build_identities_view(&profile, &chain),
+        env_file: build_env_file_view(&profile, origin_of(&chain, |p| p.env_file.is_some())),
     })
 }
 
+/// Nearest profile in `chain` (the profile itself first) that `sets` the
+/// field.
+fn origin_of(chain: &[ProfileConfig], sets: impl Fn(&ProfileConfig) -> bool) -> FieldOrigin {
+    match chain.iter().position(sets) {
+        Some(0) => FieldOrigin::Own,
+        Some(i) => FieldOrigin::Inherited(chain[i].name.clone()),
+        None => FieldOrigin::Default,
+    }
+}
+
 fn build_network_view(network: &NetworkConfig, source: ConfigSource) -> NetworkView {
     NetworkView {
         name: network.name.clone(),
@@ -76,7 +89,7 @@ fn build_endpoint_view(
     source: ConfigSource,
 ) -> EndpointView {
     EndpointView {
-        url: url.to_string(),
+        url: crate::term::console::truncate_middle(url, crate::term::console::value_width(12)),
         url_source: source,
         headers: headers
             .iter()
@@ -85,7 +98,7 @@ fn build_endpoint_view(
     }
 }
 
-fn build_identities_view(profile: &ProfileConfig) -> Vec<IdentityView> {
+fn build_identities_view(profile: &ProfileConfig, chain: &[ProfileConfig]) -> Vec<IdentityView> {
     use crate::config::serde::Named;
 
     profile
@@ -96,12 +109,14 @@ fn build_identities_view(profile: &ProfileConfig) -> Vec<IdentityView> {
             kind: match identity {
                 crate::config::IdentityConfig::RandomKey(_) => "random-key".to_string(),
                 crate::config::IdentityConfig::ExplicitKey(_) => "explicit-key".to_string(),
+                crate::config::IdentityConfig::Mnemonic(_) => "mnemonic".to_string(),
             },
+            origin: origin_of(chain, |p| p.identities.contains_key(&identity.name())),
         })
         .collect()
 }
 
-fn build_env_file_view(profile: &ProfileConfig) -> EnvFileView {
+fn build_env_file_view(profile: &ProfileConfig, origin: FieldOrigin) -> EnvFileView {
     let env_file_path = profile.env_file_path();
     let file_name = env_file_path
         .file_name()
@@ -112,6 +127,7 @@ fn build_env_file_view(profile: &ProfileConfig) -> EnvFileView {
     if !env_file_path.is_file() {
         return EnvFileView {
             file_name,
+            origin,
             status: EnvFileStatus::NotFound,
             variables: vec![],
         };
@@ -120,11 +136,13 @@ fn build_env_file_view(profile: &ProfileConfig) -> EnvFileView {
     match load_and_mask_env_vars(&env_file_path) {
         Ok(vars) => EnvFileView {
             file_name,
+            origin,
             status: EnvFileStatus::Found,
             variables: vars,
         },
         Err(e) => EnvFileView {
             file_name,
+            origin,
             status: EnvFileStatus::Error(e.to_string()),
             variables: vec![],
         },
@@ -137,6 +155,5 @@ fn build_env_file_view(profile: &ProfileConfig) -> EnvFileView {
 
 fn render_profile_view(view: &ProfileView) {
     let markdown = ProfileShowTemplate::render_view(view);
-    let skin = MadSkin::default();
-    skin.print_text(&markdown);
+    crate::term::console::print_markdown(&markdown);
 }
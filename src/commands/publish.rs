@@ -9,7 +9,12 @@ use clap::Args as ClapArgs;
 use miette::IntoDiagnostic as _;
 
 #[derive(ClapArgs)]
-pub struct Args {}
+pub struct Args {
+    /// Validate the package and print what would be uploaded, without
+    /// pushing anything to the registry
+    #[arg(long)]
+    dry_run: bool,
+}
 
 fn get_image_url(config: &RootConfig) -> String {
     let registry_url = config.registry_url();
@@ -38,7 +43,19 @@ fn capture_commit_sha() -> Option<String> {
     if s.is_empty() { None } else { Some(s) }
 }
 
-pub async fn run(_args: Args, config: &RootConfig) -> miette::Result<()> {
+/// A package that passed every local check, with the layers `trix publish`
+/// pushes.
+pub struct Package {
+    pub scope: String,
+    pub repository_short: String,
+    pub repository_url: String,
+    pub layers: Vec<oci_client::client::ImageLayer>,
+}
+
+/// Run every check `trix publish` makes before uploading: the identity
+/// fields of `trix.toml`, the protocol source (parsed and built into a
+/// TII), and the readme and logo when set.
+pub fn validate_package(config: &RootConfig) -> miette::Result<Package> {
     let Some(scope) = config.protocol.scope.clone() else {
         return Err(miette::miette!("No scope found in trix.toml"));
     };
@@ -60,18 +77,16 @@ pub async fn run(_args: Args, config: &RootConfig) -> miette::Result<()> {
             parsed.owner
         ));
     }
-    let repository_short = parsed.short();
-    let repository_url = parsed.url();
 
-    let name = config.protocol.name.clone();
-    let version = config.protocol.version.clone();
-    let published_date = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs() as i64;
-    let commit_sha = capture_commit_sha();
+    let main = &config.protocol.main;
+    if !main.is_file() {
+        return Err(miette::miette!(
+            "`[protocol].main` file '{}' not found",
+            main.display()
+        ));
+    }
 
-    let protocol = std::fs::read_to_string(config.protocol.main.clone()).into_diagnostic()?;
+    let protocol = std::fs::read_to_string(main).into_diagnostic()?;
 
     let tii_path = crate::builder::build_tii(config)?;
     let tii_content = std::fs::read_to_string(&tii_path).into_diagnostic()?;
@@ -88,9 +103,14 @@ pub async fn run(_args: Args, config: &RootConfig) -> miette::Result<()> {
         None,
     ));
 
-    if config.protocol.readme.is_some() {
-        let readme =
-            std::fs::read_to_string(config.protocol.readme.clone().unwrap()).into_diagnostic()?;
+    if let Some(readme_path) = config.protocol.readme.clone() {
+        if !readme_path.is_file() {
+            return Err(miette::miette!(
+                "`[protocol].readme` file '{}' not found",
+                readme_path.display()
+            ));
+        }
+        let readme = std::fs::read_to_string(&readme_path).into_diagnostic()?;
         image_layers.push(oci_client::client::ImageLayer::new(
             readme.as_bytes().to_vec(),
             MARKDOWN_MEDIA_TYPE.to_string(),
@@ -126,6 +146,55 @@ pub async fn run(_args: Args, config: &RootConfig) -> miette::Result<()> {
         ));
     }
 
+    Ok(Package {
+        scope,
+        repository_short: parsed.short(),
+        repository_url: parsed.url(),
+        layers: image_layers,
+    })
+}
+
+fn print_dry_run(config: &RootConfig, package: &Package) {
+    println!(
+        "Would publish {}/{}@{} to {}",
+        package.scope,
+        config.protocol.name,
+        config.protocol.version,
+        config.registry_url()
+    );
+    println!("Repository: {}", package.repository_url);
+    println!("Layers:");
+
+    for layer in &package.layers {
+        println!("  {} ({} bytes)", layer.media_type, layer.data.len());
+    }
+
+    println!("Dry run, nothing was uploaded.");
+}
+
+pub async fn run(args: Args, config: &RootConfig) -> miette::Result<()> {
+    let package = validate_package(config)?;
+
+    if args.dry_run {
+        print_dry_run(config, &package);
+        return Ok(());
+    }
+
+    let Package {
+        scope,
+        repository_short,
+        repository_url,
+        layers: image_layers,
+    } = package;
+
+    let name = config.protocol.name.clone();
+    let version = config.protocol.version.clone();
+    let published_date = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let commit_sha = capture_commit_sha();
+
     let image_config = oci_client::client::Config {
         data: serde_json::to_vec(&ImageMetadata {
             name: name.clone(),
@@ -189,7 +258,7 @@ pub async fn run(_args: Args, config: &RootConfig) -> miette::Result<()> {
         version: Some(version.clone()),
     };
     let image_reference = oci::reference_for(&registry_url, &protocol_ref)?;
-    let oci_client = oci::client_for(&registry_url);
+    let oci_client = oci::client_for(&registry_url)?;
 
     let digest = oci_client
         .push(
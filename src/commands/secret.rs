@@ -0,0 +1,57 @@
+use clap::{Args as ClapArgs, Subcommand};
+
+use crate::secrets::{self, keyring};
+
+#[derive(ClapArgs)]
+pub struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Store a secret, read from stdin or a hidden prompt
+    Set(KeyArgs),
+    /// Print a stored secret
+    Get(KeyArgs),
+    /// Remove a stored secret
+    Delete(KeyArgs),
+}
+
+#[derive(ClapArgs)]
+pub struct KeyArgs {
+    /// Entry as `service/account`; reference it from trix.toml as
+    /// `keyring:service/account`
+    key: String,
+}
+
+/// The secret to store: piped stdin when there is one, so scripts never
+/// put it in argv, otherwise a hidden prompt.
+fn read_secret(key: &str) -> miette::Result<String> {
+    crate::term::prompt::secret(
+        &format!("Secret for {key}:"),
+        &format!("secret for `{key}`"),
+    )
+}
+
+pub fn run(args: Args) -> miette::Result<()> {
+    match args.command {
+        Command::Set(args) => {
+            let (service, account) = secrets::parse_keyring_key(&args.key)?;
+            let secret = read_secret(&args.key)?;
+            keyring::set(service, account, &secret)?;
+            eprintln!("stored `{}` in {}", args.key, keyring::BACKEND);
+        }
+        Command::Get(args) => {
+            let (service, account) = secrets::parse_keyring_key(&args.key)?;
+            println!("{}", keyring::get(service, account)?);
+        }
+        Command::Delete(args) => {
+            let (service, account) = secrets::parse_keyring_key(&args.key)?;
+            keyring::delete(service, account)?;
+            eprintln!("deleted `{}` from {}", args.key, keyring::BACKEND);
+        }
+    }
+
+    Ok(())
+}
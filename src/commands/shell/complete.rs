@@ -0,0 +1,167 @@
+//! Tab completion for `trix shell`: command names first, then template
+//! names after `invoke` followed by the template's params, and wallet names
+//! wherever a wallet goes, including `@wallet` arg values.
+
+use std::collections::BTreeMap;
+
+use rustyline::completion::Completer;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+
+use super::line::COMMANDS;
+
+pub struct Completions {
+    /// Param names of every template.
+    templates: BTreeMap<String, Vec<String>>,
+    wallets: Vec<String>,
+}
+
+impl Completions {
+    pub fn new(tii: &serde_json::Value, wallets: Vec<String>) -> Self {
+        let templates = tii
+            .get("transactions")
+            .and_then(|t| t.as_object())
+            .map(|transactions| {
+                transactions
+                    .iter()
+                    .map(|(name, tx)| {
+                        let params = tx
+                            .pointer("/params/properties")
+                            .and_then(|p| p.as_object())
+                            .map(|p| p.keys().cloned().collect())
+                            .unwrap_or_default();
+
+                        (name.clone(), params)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self { templates, wallets }
+    }
+
+    /// Words that could replace the last one of `line`, and where it starts.
+    fn candidates(&self, line: &str) -> (usize, Vec<String>) {
+        let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let word = &line[start..];
+        let before: Vec<&str> = line[..start].split_whitespace().collect();
+
+        let options: Vec<String> = match before.as_slice() {
+            [] => COMMANDS.iter().map(|(name, ..)| name.to_string()).collect(),
+            ["invoke"] => self.templates.keys().cloned().collect(),
+            ["balance" | "utxos"] => self.wallets.clone(),
+            ["invoke", .., "--signer"] => self.wallets.clone(),
+            ["invoke", template, ..] => match word.split_once('=') {
+                Some((name, _)) => self
+                    .wallets
+                    .iter()
+                    .map(|wallet| format!("{name}=@{wallet}"))
+                    .collect(),
+                None => self
+                    .templates
+                    .get(*template)
+                    .into_iter()
+                    .flatten()
+                    .map(|param| format!("{param}="))
+                    .chain(["--signer".to_string()])
+                    .collect(),
+            },
+            _ => vec![],
+        };
+
+        let matching = options
+            .into_iter()
+            .filter(|option| option.starts_with(word))
+            .collect();
+
+        (start, matching)
+    }
+}
+
+impl Completer for Completions {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(&line[..pos]))
+    }
+}
+
+impl Hinter for Completions {
+    type Hint = String;
+}
+
+impl Highlighter for Completions {}
+
+impl Validator for Completions {}
+
+impl Helper for Completions {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completions() -> Completions {
+        let tii = serde_json::json!({
+            "transactions": {
+                "transfer": {
+                    "params": {
+                        "properties": {
+                            "quantity": { "type": "integer" },
+                            "receiver": { "type": "string" }
+                        }
+                    }
+                },
+                "tag": {}
+            }
+        });
+
+        Completions::new(&tii, vec!["alice".to_string(), "bob".to_string()])
+    }
+
+    #[test]
+    fn completes_commands_templates_and_wallets() {
+        let completions = completions();
+
+        assert_eq!(
+            completions.candidates("ba"),
+            (0, vec!["balance".to_string()])
+        );
+        assert_eq!(
+            completions.candidates("invoke t"),
+            (7, vec!["tag".to_string(), "transfer".to_string()])
+        );
+        assert_eq!(
+            completions.candidates("utxos "),
+            (6, vec!["alice".to_string(), "bob".to_string()])
+        );
+    }
+
+    #[test]
+    fn completes_params_and_wallet_values_of_a_template() {
+        let completions = completions();
+
+        assert_eq!(
+            completions.candidates("invoke transfer quantity=5 r").1,
+            ["receiver="]
+        );
+        assert_eq!(
+            completions.candidates("invoke transfer receiver=@a").1,
+            ["receiver=@alice"]
+        );
+        assert_eq!(
+            completions.candidates("invoke transfer --signer b").1,
+            ["bob"]
+        );
+        assert_eq!(
+            completions.candidates("invoke transfer ").1,
+            ["quantity=", "receiver=", "--signer"]
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! The commands `trix shell` reads. Words are split on whitespace, so an
+//! arg value can't contain a space; otherwise args are written as they are
+//! for `trix invoke --arg`.
+
+use miette::bail;
+
+use crate::commands::invoke::args;
+
+/// Every command as `help` lists it: name, usage and what it does.
+pub const COMMANDS: &[(&str, &str, &str)] = &[
+    (
+        "invoke",
+        "<template> [NAME=VALUE...] [--signer NAME]",
+        "submit a transaction; the wallet asks for the signer unless named",
+    ),
+    ("balance", "<wallet>", "show the lovelace a wallet holds"),
+    (
+        "utxos",
+        "<wallet|address>",
+        "list the UTxOs of a wallet or an address",
+    ),
+    (
+        "advance",
+        "<slots>",
+        "wait for the devnet to produce the slots",
+    ),
+    ("templates", "", "list the protocol's transaction templates"),
+    ("help", "", "show this list"),
+    ("quit", "", "leave the shell (also `exit` or Ctrl-D)"),
+];
+
+#[derive(Debug, PartialEq)]
+pub enum Line {
+    Invoke {
+        template: String,
+        args: Vec<(String, serde_json::Value)>,
+        signer: Option<String>,
+    },
+    Balance(String),
+    Utxos(String),
+    Advance(u64),
+    Templates,
+    Help,
+    Quit,
+}
+
+fn parse_invoke(template: &str, words: &[&str]) -> miette::Result<Line> {
+    let mut args = vec![];
+    let mut signer = None;
+    let mut words = words.iter();
+
+    while let Some(word) = words.next() {
+        if *word == "--signer" {
+            let Some(name) = words.next() else {
+                bail!("--signer needs an identity name");
+            };
+
+            signer = Some(name.trim_start_matches('@').to_string());
+            continue;
+        }
+
+        args.push(args::parse_arg(word).map_err(|err| miette::miette!("{err}"))?);
+    }
+
+    Ok(Line::Invoke {
+        template: template.to_string(),
+        args,
+        signer,
+    })
+}
+
+/// Parse one line of input; a blank line is `None`.
+pub fn parse(line: &str) -> miette::Result<Option<Line>> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+
+    let Some((command, rest)) = words.split_first() else {
+        return Ok(None);
+    };
+
+    let line = match (*command, rest) {
+        ("invoke", [template, rest @ ..]) => parse_invoke(template, rest)?,
+        ("balance", [wallet]) => Line::Balance(wallet.to_string()),
+        ("utxos", [target]) => Line::Utxos(target.to_string()),
+        ("advance", [slots]) => match slots.parse::<u64>() {
+            Ok(slots) if slots > 0 => Line::Advance(slots),
+            _ => bail!("`{slots}` is not a positive number of slots"),
+        },
+        ("templates", []) => Line::Templates,
+        ("help", []) => Line::Help,
+        ("quit" | "exit", []) => Line::Quit,
+        (command, _) => match COMMANDS.iter().find(|(name, ..)| *name == command) {
+            Some((name, usage, _)) => bail!("usage: {}", format!("{name} {usage}").trim_end()),
+            None => bail!(
+                help = "`help` lists the commands",
+                "unknown command `{command}`"
+            ),
+        },
+    };
+
+    Ok(Some(line))
+}
+
+pub fn help() -> String {
+    let width = COMMANDS
+        .iter()
+        .map(|(name, usage, _)| name.len() + usage.len() + 1)
+        .max()
+        .unwrap_or_default();
+
+    COMMANDS
+        .iter()
+        .map(|(name, usage, summary)| {
+            let command = format!("{name} {usage}");
+            format!("  {:<width$}  {summary}", command.trim_end())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invoke_takes_args_and_a_signer() {
+        let line = parse("invoke transfer quantity=5 receiver=@bob --signer @alice")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            line,
+            Line::Invoke {
+                template: "transfer".to_string(),
+                args: vec![
+                    ("quantity".to_string(), serde_json::json!(5)),
+                    ("receiver".to_string(), serde_json::json!("@bob")),
+                ],
+                signer: Some("alice".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn blank_lines_and_bad_usage() {
+        assert_eq!(parse("   ").unwrap(), None);
+        assert_eq!(parse("advance 10").unwrap(), Some(Line::Advance(10)));
+        assert_eq!(parse("exit").unwrap(), Some(Line::Quit));
+
+        let err = parse("balance").unwrap_err().to_string();
+        assert_eq!(err, "usage: balance <wallet>");
+
+        let err = parse("advance soon").unwrap_err().to_string();
+        assert!(err.contains("not a positive number"), "{err}");
+
+        let err = parse("invoke transfer quantity").unwrap_err().to_string();
+        assert!(err.contains("expected NAME=VALUE"), "{err}");
+
+        let err = parse("deploy").unwrap_err().to_string();
+        assert_eq!(err, "unknown command `deploy`");
+    }
+}
@@ -0,0 +1,331 @@
+//! `trix shell`: a prompt for exploring the protocol without losing context
+//! between commands. The protocol is built, the wallet set up and the
+//! devnet started (or the running one attached to) once, when the shell
+//! opens; each command then goes through the same functions as `trix
+//! invoke`, `trix wallet balance` and `trix wallet utxos`.
+//!
+//! A devnet can't skip ahead in time, so `advance` waits for the slots to
+//! pass at the slot length devnet.toml sets.
+
+use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::Duration;
+
+use clap::Args as ClapArgs;
+use miette::{IntoDiagnostic as _, bail};
+use rustyline::Editor;
+use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+
+use crate::builder;
+use crate::commands::devnet::{copy, dump};
+use crate::commands::invoke::{self, args as invoke_args, resolve_error};
+use crate::commands::wallet::{balance, utxos};
+use crate::config::{KnownNetwork, NetworkConfig, ProfileConfig, RootConfig};
+use crate::devnet::{Config as DevnetConfig, DevnetDaemon};
+use crate::spawn::shutdown;
+use crate::wallet::WalletProxy;
+
+mod complete;
+mod line;
+
+use complete::Completions;
+use line::Line;
+
+const PROMPT: &str = "trix> ";
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {}
+
+/// The devnet a session runs against. Holds the daemon when the shell
+/// started it, so it stops with the shell.
+struct Devnet {
+    slot_length: Duration,
+    daemon: Option<DevnetDaemon>,
+}
+
+impl Devnet {
+    /// Attach to the project's running devnet, or start one.
+    fn open(wallet: &WalletProxy) -> miette::Result<Self> {
+        let devnet = DevnetConfig::load(crate::dirs::protocol_root()?.join("devnet.toml"))?;
+        let slot_length = devnet.chain.slot_length();
+
+        let ctx = crate::devnet::Context::from_wallet(wallet);
+        let home = crate::devnet::home_dir(&ctx.derivation_tag)?;
+
+        let running = crate::devnet::lock::holder(&home).filter(|h| shutdown::alive(h.pid));
+
+        if let Some(holder) = running {
+            println!("attached to the devnet running as pid {}", holder.pid);
+
+            return Ok(Self {
+                slot_length,
+                daemon: None,
+            });
+        }
+
+        let daemon = crate::devnet::start_daemon(&devnet, &ctx, true)?;
+        sleep(Duration::from_secs(
+            crate::commands::test::DOLOS_SPAWN_DELAY_SECONDS,
+        ));
+
+        println!("devnet started; it stops when the shell ends");
+
+        Ok(Self {
+            slot_length,
+            daemon: Some(daemon),
+        })
+    }
+}
+
+/// Everything the commands share, set up once.
+struct Session<'a> {
+    config: &'a RootConfig,
+    profile: &'a ProfileConfig,
+    network: NetworkConfig,
+    wallet: WalletProxy,
+    tii_file: PathBuf,
+    tii: serde_json::Value,
+    /// Only for profiles on the local devnet.
+    devnet: Option<Devnet>,
+}
+
+impl<'a> Session<'a> {
+    fn open(config: &'a RootConfig, profile: &'a ProfileConfig) -> miette::Result<Self> {
+        crate::interfaces::validate(config)?;
+        crate::interfaces::restore_all(config)?;
+
+        let network = config.resolve_profile_network(&profile.name)?;
+        let wallet = crate::wallet::setup(config, profile)?;
+
+        let tii_file = builder::build_tii(config)?;
+        let tii = builder::load_tii(&tii_file)?;
+
+        let devnet = match network.name == KnownNetwork::CardanoLocal.as_network_name() {
+            true => Some(Devnet::open(&wallet)?),
+            false => None,
+        };
+
+        Ok(Self {
+            config,
+            profile,
+            network,
+            wallet,
+            tii_file,
+            tii,
+            devnet,
+        })
+    }
+
+    fn wallet_names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.wallet.addresses.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    fn run(&self, line: Line) -> miette::Result<()> {
+        match line {
+            Line::Invoke {
+                template,
+                args,
+                signer,
+            } => self.invoke(&template, args, signer.as_deref()),
+            Line::Balance(name) => {
+                balance::print_table(&balance::query(&self.wallet, self.profile, &name)?);
+                Ok(())
+            }
+            Line::Utxos(target) => self.utxos(&target),
+            Line::Advance(slots) => self.advance(slots),
+            Line::Templates => self.templates(),
+            Line::Help => {
+                println!("{}", line::help());
+                Ok(())
+            }
+            Line::Quit => Ok(()),
+        }
+    }
+
+    /// Submit `template` like `trix invoke --template` does, or like
+    /// `--output json --signer` when a signer is named.
+    fn invoke(
+        &self,
+        template: &str,
+        args: Vec<(String, serde_json::Value)>,
+        signer: Option<&str>,
+    ) -> miette::Result<()> {
+        let mut args_json: invoke::ArgMap = args.into_iter().collect();
+        invoke_args::resolve_references(&mut args_json, &self.wallet)?;
+
+        invoke::check_args(
+            &mut args_json,
+            &self.tii_file,
+            &self.tii,
+            Some(template),
+            self.profile,
+            signer.is_none(),
+            Some(&self.network),
+        )?;
+
+        let args_json = serde_json::Value::Object(args_json);
+
+        let output = match signer {
+            Some(signer) => Some(
+                self.wallet
+                    .invoke_json(
+                        &self.tii_file,
+                        template,
+                        &args_json,
+                        vec![signer],
+                        &self.profile.name,
+                        false,
+                    )
+                    .map_err(|err| resolve_error::explain(err, Some(self.config), template))?,
+            ),
+            None => self.wallet.invoke_interactive(
+                &self.tii_file,
+                Some(template),
+                &args_json,
+                &self.profile.name,
+                false,
+            )?,
+        };
+
+        if let Some(output) = output {
+            crate::wallet::print_tx_link(&output, &self.network);
+            crate::submissions::record(&output, &self.profile.name, &self.network, Some(template));
+        }
+
+        Ok(())
+    }
+
+    /// UTxOs of a wallet, through cshell, or of any address, searched for
+    /// by address as `trix devnet copy --all` does.
+    fn utxos(&self, target: &str) -> miette::Result<()> {
+        let wallet = target.trim_start_matches('@');
+
+        if target.starts_with('@') || self.wallet.addresses.contains_key(wallet) {
+            utxos::print_table(&utxos::query(&self.wallet, self.profile, wallet)?);
+            return Ok(());
+        }
+
+        let found = futures::executor::block_on(copy::fetch_at(&self.network.u5c, target))?;
+
+        let mut views = found
+            .into_iter()
+            .map(|(r#ref, native)| dump::decode_utxo(r#ref, &native).map(utxos::UtxoView::from))
+            .collect::<miette::Result<Vec<_>>>()?;
+        views.sort_by(|a, b| a.r#ref.cmp(&b.r#ref));
+
+        utxos::print_table(&views);
+
+        Ok(())
+    }
+
+    fn advance(&self, slots: u64) -> miette::Result<()> {
+        let Some(devnet) = &self.devnet else {
+            bail!(
+                help = "open the shell with a profile on the local devnet, e.g. `--profile local`",
+                "profile `{}` is not on the local devnet, so it has no slots to wait for",
+                self.profile.name
+            );
+        };
+
+        let wait = devnet.slot_length * u32::try_from(slots).into_diagnostic()?;
+
+        println!("waiting {:.1}s for {slots} slots...", wait.as_secs_f64());
+        sleep(wait);
+
+        Ok(())
+    }
+
+    fn templates(&self) -> miette::Result<()> {
+        let mut names: Vec<&String> = self
+            .tii
+            .get("transactions")
+            .and_then(|t| t.as_object())
+            .map(|t| t.keys().collect())
+            .unwrap_or_default();
+        names.sort();
+
+        if names.is_empty() {
+            println!("(no templates)");
+        }
+
+        for name in names {
+            let params = invoke_args::describe_params(&self.tii, name)?;
+            println!("  {name}  {}", params.join(", "));
+        }
+
+        Ok(())
+    }
+
+    /// Stop the devnet if the shell started it.
+    fn close(self) -> miette::Result<()> {
+        let Some(Devnet {
+            daemon: Some(mut daemon),
+            ..
+        }) = self.devnet
+        else {
+            return Ok(());
+        };
+
+        daemon.daemon.kill().into_diagnostic()?;
+        println!("devnet stopped");
+
+        Ok(())
+    }
+}
+
+/// Read and run lines until `quit` or Ctrl-D. A failing command is
+/// reported and the shell carries on.
+fn repl(editor: &mut Editor<Completions, DefaultHistory>, session: &Session) -> miette::Result<()> {
+    loop {
+        let input = match editor.readline(PROMPT) {
+            Ok(input) => input,
+            // Ctrl-C drops the line being typed, as in other shells.
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => return Ok(()),
+            Err(err) => return Err(err).into_diagnostic(),
+        };
+
+        if !input.trim().is_empty() {
+            let _ = editor.add_history_entry(input.as_str());
+        }
+
+        let result = match line::parse(&input) {
+            Ok(Some(Line::Quit)) => return Ok(()),
+            Ok(Some(line)) => session.run(line),
+            Ok(None) => Ok(()),
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = result {
+            eprintln!("{err:?}");
+        }
+    }
+}
+
+pub fn run(_args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    crate::term::prompt::ensure_interactive("trix shell")?;
+
+    let session = Session::open(config, profile)?;
+
+    let mut editor = Editor::new().into_diagnostic()?;
+    editor.set_helper(Some(Completions::new(&session.tii, session.wallet_names())));
+
+    // History is kept per project; there is none before the first session.
+    let history = crate::dirs::target_dir("shell")?.join("history");
+    let _ = editor.load_history(&history);
+
+    println!(
+        "{} on profile `{}`; `help` lists the commands",
+        config.protocol.name, profile.name
+    );
+
+    let result = repl(&mut editor, &session);
+
+    let _ = editor.save_history(&history);
+    session.close()?;
+
+    result
+}
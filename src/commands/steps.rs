@@ -0,0 +1,115 @@
+//! Transactions listed in a file and submitted one after another, shared by
+//! `trix test` and `trix invoke --script`.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::thread::sleep;
+use std::time::Duration;
+
+use miette::{IntoDiagnostic, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::commands::invoke::{args, resolve_error};
+use crate::config::{ProfileConfig, RootConfig};
+use crate::wallet::WalletProxy;
+
+const BLOCK_PRODUCTION_INTERVAL_SECONDS: u64 = 5;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Transaction {
+    pub description: String,
+    pub template: String,
+    pub args: HashMap<String, serde_json::Value>,
+    pub signers: Vec<String>,
+}
+
+/// The args to invoke `transaction` with, `@wallet` and `@env:VAR`
+/// references resolved as for `trix invoke --arg`.
+pub fn define_args(transaction: &Transaction, wallet: &WalletProxy) -> Result<serde_json::Value> {
+    let explicit = serde_json::to_value(&transaction.args).into_diagnostic()?;
+
+    let mut all = explicit.as_object().cloned().unwrap_or_default();
+
+    args::resolve_references(&mut all, wallet)?;
+
+    Ok(serde_json::json!(all))
+}
+
+/// Resolve, sign and submit `transaction`, returning cshell's output.
+pub fn submit(
+    config: &RootConfig,
+    wallet: &WalletProxy,
+    tii_file: &Path,
+    transaction: &Transaction,
+    args: &serde_json::Value,
+    profile: &ProfileConfig,
+) -> Result<serde_json::Value> {
+    let signer = match transaction.signers.len() {
+        1 => transaction.signers[0].clone(),
+        _ => {
+            bail!("only one signer is supported at the moment")
+        }
+    };
+
+    wallet
+        .invoke_json(
+            tii_file,
+            &transaction.template,
+            args,
+            vec![&signer],
+            &profile.name,
+            false,
+        )
+        .map_err(|err| resolve_error::explain(err, Some(config), &transaction.template))
+}
+
+/// Give the devnet time to produce a block, so the next transaction can
+/// spend the outputs of the previous one.
+pub fn wait_next_block() {
+    println!("Waiting next block...");
+    sleep(Duration::from_secs(BLOCK_PRODUCTION_INTERVAL_SECONDS));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::Derivation;
+
+    fn wallet() -> WalletProxy {
+        WalletProxy {
+            target_dir: Default::default(),
+            addresses: HashMap::from([("alice".to_string(), "addr_test1alice".to_string())]),
+            derivation: Derivation::Legacy,
+            address_only: Default::default(),
+        }
+    }
+
+    fn transaction(args: serde_json::Value) -> Transaction {
+        Transaction {
+            description: "Transfer".to_string(),
+            template: "transfer".to_string(),
+            args: serde_json::from_value(args).unwrap(),
+            signers: vec!["alice".to_string()],
+        }
+    }
+
+    #[test]
+    fn resolves_wallet_references() {
+        let tx = transaction(serde_json::json!({ "sender": "@alice", "quantity": 5 }));
+
+        let args = define_args(&tx, &wallet()).unwrap();
+
+        assert_eq!(args["sender"], "addr_test1alice");
+        assert_eq!(args["quantity"], 5);
+
+        let tx = transaction(serde_json::json!({ "receiver": "@carol" }));
+        let err = define_args(&tx, &wallet()).unwrap_err();
+
+        assert!(err.to_string().contains("unknown wallet `@carol`"), "{err}");
+
+        let tx = transaction(serde_json::json!({ "memo": "@env:TRIX_STEPS_UNSET" }));
+        let err = define_args(&tx, &wallet()).unwrap_err();
+
+        assert!(err.to_string().contains("which is not set"), "{err}");
+    }
+}
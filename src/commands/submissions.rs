@@ -0,0 +1,143 @@
+use clap::Args as ClapArgs;
+use miette::IntoDiagnostic as _;
+use serde::Serialize;
+
+use crate::config::{NetworkConfig, ProfileConfig, RootConfig};
+use crate::submissions::Submission;
+use crate::term::OutputFormat;
+
+#[derive(ClapArgs)]
+pub struct Args {
+    /// Only the latest N submissions
+    #[arg(long)]
+    limit: Option<usize>,
+
+    /// `json` prints the submissions as a JSON array instead of a table
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    output: OutputFormat,
+}
+
+#[derive(Debug, Serialize)]
+struct SubmissionView {
+    #[serde(flatten)]
+    submission: Submission,
+    /// `None` when the network has no explorer template.
+    url: Option<String>,
+}
+
+/// Submissions to `network`, oldest first, keeping the latest `limit`.
+fn select(
+    submissions: Vec<Submission>,
+    network: &NetworkConfig,
+    limit: Option<usize>,
+) -> Vec<SubmissionView> {
+    let mut views: Vec<_> = submissions
+        .into_iter()
+        .filter(|submission| submission.network == network.name)
+        .map(|submission| SubmissionView {
+            url: network.explorer_tx_url(&submission.hash),
+            submission,
+        })
+        .collect();
+
+    if let Some(limit) = limit {
+        views.drain(..views.len().saturating_sub(limit));
+    }
+
+    views
+}
+
+fn print_table(views: &[SubmissionView]) {
+    if views.is_empty() {
+        println!("(no submissions)");
+        return;
+    }
+
+    let profile_width = views
+        .iter()
+        .map(|v| v.submission.profile.len())
+        .fold("PROFILE".len(), usize::max);
+    let template_width = views
+        .iter()
+        .map(|v| v.submission.template.as_deref().unwrap_or("-").len())
+        .fold("TEMPLATE".len(), usize::max);
+    let time_width = views
+        .iter()
+        .map(|v| v.submission.submitted_at.len())
+        .fold("SUBMITTED".len(), usize::max);
+
+    println!(
+        "{:<time_width$}  {:<profile_width$}  {:<template_width$}  TX",
+        "SUBMITTED", "PROFILE", "TEMPLATE"
+    );
+
+    for view in views {
+        let submission = &view.submission;
+        let tx = match &view.url {
+            Some(url) => crate::term::hyperlink(url, &submission.hash),
+            None => submission.hash.clone(),
+        };
+
+        println!(
+            "{:<time_width$}  {:<profile_width$}  {:<template_width$}  {tx}",
+            submission.submitted_at,
+            submission.profile,
+            submission.template.as_deref().unwrap_or("-"),
+        );
+    }
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    let network = config.resolve_profile_network(&profile.name)?;
+    let submissions = crate::submissions::read(&crate::submissions::log_path()?)?;
+
+    let views = select(submissions, &network, args.limit);
+
+    match args.output {
+        OutputFormat::Human => print_table(&views),
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&views).into_diagnostic()?
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::KnownNetwork;
+
+    fn submission(network: &str, hash: &str) -> Submission {
+        Submission {
+            submitted_at: "2025-01-01T00:00:00+00:00".to_string(),
+            profile: "preview".to_string(),
+            network: network.to_string(),
+            template: Some("transfer".to_string()),
+            hash: hash.to_string(),
+        }
+    }
+
+    #[test]
+    fn keeps_the_latest_on_the_network_with_links() {
+        let network = NetworkConfig::from(KnownNetwork::CardanoPreview);
+        let submissions = vec![
+            submission("cardano-preview", "aa"),
+            submission("cardano-local", "bb"),
+            submission("cardano-preview", "cc"),
+            submission("cardano-preview", "dd"),
+        ];
+
+        let views = select(submissions, &network, Some(2));
+
+        let hashes: Vec<_> = views.iter().map(|v| v.submission.hash.as_str()).collect();
+        assert_eq!(hashes, ["cc", "dd"]);
+        assert_eq!(
+            views[0].url.as_deref(),
+            Some("https://preview.cexplorer.io/tx/cc")
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use clap::{Args as ClapArgs, Subcommand};
+
+use crate::global::TelemetryCategory;
+
+pub mod status;
+
+#[derive(ClapArgs)]
+pub struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Enable telemetry (anonymous usage data collection)
+    On,
+    /// Disable telemetry
+    Off,
+    /// Report one more category of data, turning telemetry on if it's off
+    Enable(CategoryArgs),
+    /// Stop reporting one category of data
+    Disable(CategoryArgs),
+    /// Show the current telemetry status, endpoint and fingerprint
+    Status(status::Args),
+}
+
+#[derive(ClapArgs)]
+pub struct CategoryArgs {
+    /// `performance` and `errors` are only sent along with `usage`
+    #[arg(value_enum)]
+    category: TelemetryCategory,
+}
+
+pub fn run(args: Args) -> miette::Result<()> {
+    let mut global_config = crate::global::read_config()?;
+
+    match args.command {
+        Command::On => {
+            global_config.telemetry.enabled = true;
+            crate::global::save_config(&global_config)?;
+            status::print(&global_config);
+        }
+        Command::Off => {
+            global_config.telemetry.enabled = false;
+            crate::global::save_config(&global_config)?;
+            crate::telemetry::spool::clear();
+            status::print(&global_config);
+        }
+        Command::Enable(args) => {
+            set_category(&mut global_config, args.category, true);
+            global_config.telemetry.enabled = true;
+            crate::global::save_config(&global_config)?;
+            status::print(&global_config);
+        }
+        Command::Disable(args) => {
+            set_category(&mut global_config, args.category, false);
+            crate::global::save_config(&global_config)?;
+            status::print(&global_config);
+        }
+        Command::Status(args) => {
+            status::run(args, &global_config)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Toggle `category`, writing out the categories in full so a config from
+/// before they existed stops meaning "all of them".
+fn set_category(config: &mut crate::global::Config, category: TelemetryCategory, on: bool) {
+    let mut categories = config.telemetry.categories();
+    categories.set(category, on);
+    config.telemetry.categories = Some(categories);
+}
@@ -0,0 +1,169 @@
+//! `trix telemetry status`: what telemetry reports, where it sends it and
+//! the fingerprint it is reported under. The fingerprint is masked; it is
+//! only read, so checking the status never creates one.
+
+use clap::Args as ClapArgs;
+use miette::IntoDiagnostic as _;
+use serde::Serialize;
+
+use crate::commands::profile::mask_value;
+use crate::global::{Config, TelemetryCategory, print_telemetry_info};
+use crate::term::OutputFormat;
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    output: OutputFormat,
+}
+
+// ============================================================================
+// View Model
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryView {
+    pub name: &'static str,
+    pub on: bool,
+    pub covers: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusView {
+    pub enabled: bool,
+    pub categories: Vec<CategoryView>,
+    pub otlp_endpoint: String,
+    pub sample_rate: f64,
+    /// Masked. Absent until the first report stores one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+}
+
+impl StatusView {
+    fn new(config: &Config, fingerprint: Option<&str>) -> Self {
+        let telemetry = &config.telemetry;
+
+        Self {
+            enabled: telemetry.enabled,
+            categories: TelemetryCategory::ALL
+                .into_iter()
+                .map(|category| CategoryView {
+                    name: category.name(),
+                    on: telemetry.allows(category),
+                    covers: category.covers(),
+                })
+                .collect(),
+            otlp_endpoint: telemetry.otlp_endpoint.clone(),
+            sample_rate: telemetry.sample_rate,
+            fingerprint: fingerprint.map(|f| mask_value(f.trim())),
+        }
+    }
+}
+
+fn render_human(view: &StatusView) -> String {
+    let mut out = String::new();
+
+    match view.enabled {
+        true => out.push_str("Telemetry: ON\n"),
+        false => out.push_str("Telemetry: OFF\n"),
+    }
+
+    for category in &view.categories {
+        let state = match category.on {
+            true => "on",
+            false => "off",
+        };
+
+        out.push_str(&format!(
+            "  {:<11}  {state:<3}  {}\n",
+            category.name, category.covers
+        ));
+    }
+
+    out.push_str(&format!("Endpoint:    {}\n", view.otlp_endpoint));
+    out.push_str(&format!(
+        "Fingerprint: {}\n",
+        view.fingerprint.as_deref().unwrap_or("none yet")
+    ));
+
+    out
+}
+
+/// Print the status as a human reads it, as every telemetry command does
+/// after changing the config.
+pub fn print(config: &Config) {
+    let fingerprint = crate::telemetry::load_stored_fingerprint();
+    let view = StatusView::new(config, fingerprint.as_deref());
+
+    if view.enabled {
+        print_telemetry_info();
+    }
+
+    print!("{}", render_human(&view));
+}
+
+// ============================================================================
+// Command Entry Point
+// ============================================================================
+
+pub fn run(args: Args, config: &Config) -> miette::Result<()> {
+    match args.output {
+        OutputFormat::Human => print(config),
+        OutputFormat::Json => {
+            let fingerprint = crate::telemetry::load_stored_fingerprint();
+            let view = StatusView::new(config, fingerprint.as_deref());
+            println!("{}", serde_json::to_string_pretty(&view).into_diagnostic()?);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FINGERPRINT: &str = "8f3a1c2be4d59076";
+
+    fn config() -> Config {
+        let config: Config = toml::from_str(
+            "[telemetry]\nenabled = true\notlp_endpoint = \"https://otlp.example.com\"\n\
+             categories = { usage = true }\n",
+        )
+        .unwrap();
+
+        // Round-trip it the way `trix telemetry` saves it.
+        toml::from_str(&toml::to_string(&config).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn json_status_has_endpoint_and_masked_fingerprint() {
+        let view = StatusView::new(&config(), Some(FINGERPRINT));
+        let json = serde_json::to_value(&view).unwrap();
+
+        assert_eq!(json["enabled"], true);
+        assert_eq!(json["otlp_endpoint"], "https://otlp.example.com");
+        assert_eq!(json["fingerprint"], "8f3a...9076");
+        assert_eq!(json["categories"][0]["name"], "usage");
+        assert_eq!(json["categories"][0]["on"], true);
+        assert_eq!(json["categories"][1]["on"], false);
+
+        let view = StatusView::new(&config(), None);
+        let json = serde_json::to_value(&view).unwrap();
+        assert!(json.get("fingerprint").is_none(), "{json}");
+    }
+
+    #[test]
+    fn human_status_never_shows_the_full_fingerprint() {
+        let view = StatusView::new(&config(), Some(FINGERPRINT));
+        let out = render_human(&view);
+
+        assert!(out.contains("Telemetry: ON"), "{out}");
+        assert!(out.contains("usage        on"), "{out}");
+        assert!(
+            out.contains("Endpoint:    https://otlp.example.com"),
+            "{out}"
+        );
+        assert!(out.contains("Fingerprint: 8f3a...9076"), "{out}");
+        assert!(!out.contains(FINGERPRINT), "{out}");
+    }
+}
@@ -0,0 +1,93 @@
+//! Template coverage of a `trix test` run: which of the protocol's
+//! transaction templates a transaction of the test file submitted
+//! successfully, and which were never exercised.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use miette::{Context as _, IntoDiagnostic as _};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Coverage {
+    pub total: usize,
+    pub covered: Vec<String>,
+    pub untested: Vec<String>,
+    pub percent: f64,
+}
+
+impl Coverage {
+    /// Compare the templates in `tii` against the ones `executed`.
+    pub fn new(tii: &serde_json::Value, executed: &BTreeSet<String>) -> Self {
+        let templates: BTreeSet<String> = tii
+            .get("transactions")
+            .and_then(|t| t.as_object())
+            .map(|t| t.keys().cloned().collect())
+            .unwrap_or_default();
+
+        let (covered, untested): (Vec<_>, Vec<_>) = templates
+            .iter()
+            .cloned()
+            .partition(|name| executed.contains(name));
+
+        let percent = match templates.len() {
+            0 => 100.0,
+            total => covered.len() as f64 * 100.0 / total as f64,
+        };
+
+        Self {
+            total: templates.len(),
+            covered,
+            untested,
+            percent,
+        }
+    }
+
+    pub fn print(&self) {
+        println!("== Coverage ==\n");
+        println!(
+            "templates covered {}/{} ({:.0}%)",
+            self.covered.len(),
+            self.total,
+            self.percent
+        );
+
+        if !self.untested.is_empty() {
+            println!("untested:");
+
+            for name in &self.untested {
+                println!("  - {name}");
+            }
+        }
+
+        println!();
+    }
+
+    pub fn write(&self, path: &Path) -> miette::Result<()> {
+        let json = serde_json::to_string_pretty(self).into_diagnostic()?;
+
+        std::fs::write(path, json)
+            .into_diagnostic()
+            .with_context(|| format!("writing coverage report to {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_untested_templates() {
+        let tii = serde_json::json!({
+            "transactions": { "mint": {}, "burn": {}, "transfer": {}, "lock": {} }
+        });
+        let executed: BTreeSet<String> = ["mint", "transfer", "unknown"].map(String::from).into();
+
+        let coverage = Coverage::new(&tii, &executed);
+
+        assert_eq!(coverage.total, 4);
+        assert_eq!(coverage.covered, ["mint", "transfer"]);
+        assert_eq!(coverage.untested, ["burn", "lock"]);
+        assert_eq!(coverage.percent, 50.0);
+    }
+}
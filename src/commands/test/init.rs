@@ -0,0 +1,229 @@
+use std::path::PathBuf;
+
+use askama::Template;
+use clap::Args as ClapArgs;
+use miette::{bail, Context as _, IntoDiagnostic as _, Result};
+
+use crate::{
+    builder,
+    config::{ProfileConfig, RootConfig},
+};
+
+const DEFAULT_WALLET_BALANCE: u64 = 10_000_000;
+
+#[derive(ClapArgs, Debug)]
+pub struct Args {
+    /// Name of the test file to create under `tests/` (without extension)
+    #[arg(long, default_value = "basic")]
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct WalletView {
+    pub name: String,
+    pub party: String,
+    pub balance: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArgView {
+    pub name: String,
+    /// TOML literal used as the placeholder value.
+    pub value: String,
+    pub kind: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransactionView {
+    pub template: String,
+    pub signer: String,
+    pub args: Vec<ArgView>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TestInitView {
+    pub protocol: PathBuf,
+    pub wallets: Vec<WalletView>,
+    pub transactions: Vec<TransactionView>,
+}
+
+#[derive(Template)]
+#[template(path = "test/init.toml.askama")]
+struct TestInitTemplate<'a> {
+    view: &'a TestInitView,
+}
+
+/// Placeholder TOML literal + human-readable type for one param schema, as
+/// emitted in the TII `transactions.<name>.params.properties` map.
+fn placeholder_for(schema: &serde_json::Value) -> (String, String) {
+    if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+        let kind = reference.rsplit('#').next().unwrap_or(reference).to_string();
+        return ("\"\"".to_string(), kind);
+    }
+
+    match schema.get("type").and_then(|t| t.as_str()) {
+        Some("integer") | Some("number") => ("0".to_string(), "integer".to_string()),
+        Some("boolean") => ("false".to_string(), "boolean".to_string()),
+        Some("string") => ("\"\"".to_string(), "string".to_string()),
+        Some(other) => ("\"\"".to_string(), other.to_string()),
+        None => ("\"\"".to_string(), "unknown".to_string()),
+    }
+}
+
+/// Pair each declared party with a local identity so the generated `@name`
+/// placeholders resolve out of the box. Parties beyond the available
+/// identities keep their own (lowercased) name and need an identity added.
+fn assign_wallets(parties: &[String], profile: &ProfileConfig) -> Vec<WalletView> {
+    let mut identities: Vec<_> = profile.identities.keys().cloned().collect();
+    identities.sort();
+
+    parties
+        .iter()
+        .enumerate()
+        .map(|(i, party)| WalletView {
+            name: identities
+                .get(i)
+                .cloned()
+                .unwrap_or_else(|| party.to_lowercase()),
+            party: party.clone(),
+            balance: DEFAULT_WALLET_BALANCE,
+        })
+        .collect()
+}
+
+fn build_view(config: &RootConfig, tii: &serde_json::Value, profile: &ProfileConfig) -> TestInitView {
+    let mut parties: Vec<String> = tii
+        .get("parties")
+        .and_then(|p| p.as_object())
+        .map(|p| p.keys().cloned().collect())
+        .unwrap_or_default();
+    parties.sort();
+
+    let wallets = assign_wallets(&parties, profile);
+
+    let signer = wallets
+        .first()
+        .map(|w| w.name.clone())
+        .unwrap_or_default();
+
+    let transactions = tii
+        .get("transactions")
+        .and_then(|t| t.as_object())
+        .map(|txs| {
+            txs.iter()
+                .map(|(name, tx)| {
+                    let params = tx
+                        .pointer("/params/properties")
+                        .and_then(|p| p.as_object())
+                        .cloned()
+                        .unwrap_or_default();
+
+                    let mut args: Vec<ArgView> = params
+                        .iter()
+                        .map(|(param, schema)| {
+                            let (value, kind) = placeholder_for(schema);
+                            ArgView {
+                                name: param.clone(),
+                                value,
+                                kind,
+                            }
+                        })
+                        .collect();
+
+                    args.extend(wallets.iter().map(|w| ArgView {
+                        name: w.party.to_lowercase(),
+                        value: format!("\"@{}\"", w.name),
+                        kind: format!("party {}", w.party),
+                    }));
+
+                    TransactionView {
+                        template: name.clone(),
+                        signer: signer.clone(),
+                        args,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    TestInitView {
+        protocol: config.protocol.main.clone(),
+        wallets,
+        transactions,
+    }
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> Result<()> {
+    let output = PathBuf::from("tests").join(format!("{}.toml", args.name));
+
+    if output.exists() {
+        bail!(
+            help = "pick another name with `--name`",
+            "{} already exists",
+            output.display()
+        );
+    }
+
+    let tii = builder::load_tii(&builder::build_tii(config)?)?;
+
+    let view = build_view(config, &tii, profile);
+
+    let content = TestInitTemplate { view: &view }
+        .render()
+        .into_diagnostic()?;
+
+    std::fs::create_dir_all("tests").into_diagnostic()?;
+
+    std::fs::write(&output, content)
+        .into_diagnostic()
+        .context(format!("writing {}", output.display()))?;
+
+    println!("Created {}", output.display());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test::Test;
+    use crate::config::KnownProfile;
+
+    const BASE_TOML: &str = r#"
+        [protocol]
+        name = "demo"
+        version = "0.0.0"
+        main = "main.tx3"
+
+        [ledger]
+        family = "cardano"
+    "#;
+
+    #[test]
+    fn generated_file_parses_as_test() {
+        let config: RootConfig = toml::from_str(BASE_TOML).unwrap();
+        let profile = ProfileConfig::from(KnownProfile::Local);
+
+        let tii = serde_json::json!({
+            "parties": { "Receiver": {}, "Sender": {} },
+            "transactions": {
+                "transfer": {
+                    "params": {
+                        "properties": { "quantity": { "type": "integer" } },
+                        "type": "object"
+                    }
+                }
+            }
+        });
+
+        let view = build_view(&config, &tii, &profile);
+        let rendered = TestInitTemplate { view: &view }.render().unwrap();
+
+        let parsed: Test = toml::from_str(&rendered).expect("generated test parses");
+        assert_eq!(parsed.wallets.len(), 2);
+        assert_eq!(parsed.transactions.len(), 1);
+        assert_eq!(parsed.transactions[0].args["quantity"], serde_json::json!(0));
+        assert_eq!(parsed.transactions[0].args["sender"], serde_json::json!("@bob"));
+        assert_eq!(parsed.expect.len(), 2);
+    }
+}
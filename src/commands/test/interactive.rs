@@ -0,0 +1,179 @@
+//! Step-through mode for `trix test --interactive`.
+//!
+//! The runner pauses before each transaction and after each failure with the
+//! devnet still up, so chain state can be inspected before teardown.
+
+use miette::{IntoDiagnostic as _, Result};
+
+use crate::config::ProfileConfig;
+use crate::term::prompt;
+use crate::wallet::WalletProxy;
+
+use super::Transaction;
+
+/// What the runner should do with the current step.
+pub enum Step {
+    Run,
+    Skip,
+    Abort,
+}
+
+#[derive(Clone, Copy)]
+enum Action {
+    Continue,
+    Skip,
+    Rerun,
+    Balances,
+    Utxos,
+    Explorer,
+    Cshell,
+    Abort,
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Continue => write!(f, "continue"),
+            Action::Skip => write!(f, "skip this step"),
+            Action::Rerun => write!(f, "re-run this step"),
+            Action::Balances => write!(f, "show wallet balances"),
+            Action::Utxos => write!(f, "show wallet UTxOs"),
+            Action::Explorer => write!(f, "open explorer"),
+            Action::Cshell => write!(f, "run a cshell command"),
+            Action::Abort => write!(f, "abort the test"),
+        }
+    }
+}
+
+const BEFORE_STEP: &[Action] = &[
+    Action::Continue,
+    Action::Skip,
+    Action::Balances,
+    Action::Utxos,
+    Action::Explorer,
+    Action::Cshell,
+    Action::Abort,
+];
+
+const AFTER_FAILURE: &[Action] = &[
+    Action::Continue,
+    Action::Rerun,
+    Action::Balances,
+    Action::Utxos,
+    Action::Explorer,
+    Action::Cshell,
+    Action::Abort,
+];
+
+pub struct Session<'a> {
+    wallet: &'a WalletProxy,
+    profile: &'a ProfileConfig,
+}
+
+impl<'a> Session<'a> {
+    pub fn new(wallet: &'a WalletProxy, profile: &'a ProfileConfig) -> Self {
+        Self { wallet, profile }
+    }
+
+    /// Pause before `transaction` runs. `args` are the substituted args
+    /// about to be sent.
+    pub fn before(&self, transaction: &Transaction, args: &serde_json::Value) -> Result<Step> {
+        println!("  template: {}", transaction.template);
+        println!("  signers:  {}", transaction.signers.join(", "));
+        println!(
+            "  args:     {}",
+            serde_json::to_string_pretty(args).into_diagnostic()?
+        );
+
+        loop {
+            match prompt::select("Next step:", BEFORE_STEP.to_vec())? {
+                Action::Continue => return Ok(Step::Run),
+                Action::Skip => return Ok(Step::Skip),
+                Action::Abort => return Ok(Step::Abort),
+                other => self.inspect(other)?,
+            }
+        }
+    }
+
+    /// Pause after a failed attempt. `Step::Run` re-runs the step,
+    /// `Step::Skip` moves on with the step counted as failed.
+    pub fn after_failure(&self) -> Result<Step> {
+        loop {
+            match prompt::select("Step failed:", AFTER_FAILURE.to_vec())? {
+                Action::Rerun => return Ok(Step::Run),
+                Action::Continue => return Ok(Step::Skip),
+                Action::Abort => return Ok(Step::Abort),
+                other => self.inspect(other)?,
+            }
+        }
+    }
+
+    /// Inspection actions report their own errors and return to the menu;
+    /// a failed balance query shouldn't end the session.
+    fn inspect(&self, action: Action) -> Result<()> {
+        let result = match action {
+            Action::Balances => self.show_balances(),
+            Action::Utxos => self.show_utxos(),
+            Action::Explorer => self.wallet.explorer(&self.profile.name),
+            Action::Cshell => self.cshell(),
+            _ => Ok(()),
+        };
+
+        if let Err(err) = result {
+            eprintln!("Error: {err}\n");
+        }
+
+        Ok(())
+    }
+
+    fn wallet_names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.wallet.addresses.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    fn show_balances(&self) -> Result<()> {
+        for name in self.wallet_names() {
+            let balance = crate::spawn::cshell::wallet_balance(&self.wallet.target_dir, &name)?;
+            println!("  {name}: {} lovelace", balance.coin);
+        }
+
+        Ok(())
+    }
+
+    fn show_utxos(&self) -> Result<()> {
+        let name = prompt::select("Wallet:", self.wallet_names())?;
+        let provider = crate::wallet::provider_name(&self.profile.name);
+
+        let utxos = crate::spawn::cshell::wallet_utxos(&self.wallet.target_dir, &name, &provider)?;
+
+        if utxos.is_empty() {
+            println!("  (no utxos)");
+        }
+
+        for utxo in utxos {
+            let assets: usize = utxo.assets.iter().map(|p| p.assets.len()).sum();
+            let datum = utxo
+                .datum
+                .map(|d| hex::encode(d.hash))
+                .unwrap_or_else(|| "-".to_string());
+
+            println!("  {} lovelace, {assets} assets, datum {datum}", utxo.coin);
+        }
+
+        Ok(())
+    }
+
+    fn cshell(&self) -> Result<()> {
+        let line = prompt::text("cshell")?;
+        let args: Vec<&str> = line.split_whitespace().collect();
+
+        let status = crate::spawn::cshell::passthrough(&self.wallet.target_dir, &args)?;
+
+        if !status.success() {
+            eprintln!("cshell exited with {status}");
+        }
+
+        Ok(())
+    }
+}
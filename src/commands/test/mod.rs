@@ -0,0 +1,1166 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use clap::{Args as ClapArgs, Subcommand};
+use miette::{bail, Context as _, IntoDiagnostic, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    builder,
+    commands::{expect, invoke::parties, steps},
+    config::{ProfileConfig, RootConfig},
+    devnet::{Config as DevnetConfig, Ports},
+    wallet::WalletProxy,
+};
+
+mod coverage;
+pub mod init;
+mod interactive;
+mod parallel;
+mod refs;
+mod report;
+mod suite;
+
+pub use crate::commands::steps::Transaction;
+
+pub(crate) const DOLOS_SPAWN_DELAY_SECONDS: u64 = 2;
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Generate a starter test file from the protocol's transactions
+    Init(init::Args),
+}
+
+#[derive(ClapArgs, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Test toml file, or a directory to run every `*.toml` under; defaults
+    /// to the project's `tests/` directory
+    path: Option<PathBuf>,
+
+    /// Run every test file under `tests/`, same as passing no path
+    #[arg(long, conflicts_with = "path")]
+    all: bool,
+
+    /// When running a directory, only run test files whose path contains
+    /// this text
+    #[arg(long, value_name = "TEXT")]
+    only: Option<String>,
+
+    /// Only validate the test file against the test schema; nothing is
+    /// built, spawned or submitted.
+    #[arg(long)]
+    dry_parse: bool,
+
+    /// Pause before each transaction and after each failure to inspect the
+    /// devnet, which stays up while paused. Requires a terminal.
+    #[arg(long, conflicts_with = "dry_parse")]
+    interactive: bool,
+
+    /// Only run transactions whose description contains this text
+    /// (case-insensitive)
+    #[arg(long, value_name = "PATTERN")]
+    filter: Option<String>,
+
+    /// Print which transactions would run, without starting a devnet or
+    /// invoking anything
+    #[arg(long, conflicts_with_all = ["dry_parse", "interactive"])]
+    dry_run: bool,
+
+    /// Write the template coverage of the run to this JSON file
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["dry_parse", "dry_run"])]
+    coverage_out: Option<PathBuf>,
+
+    /// Fail when fewer than this percentage of the protocol's templates
+    /// were submitted successfully
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        conflicts_with_all = ["dry_parse", "dry_run"]
+    )]
+    min_template_coverage: Option<u8>,
+
+    /// Write a report of every transaction and expectation run, one suite
+    /// per test file, to `--report-out`
+    #[arg(
+        long,
+        value_enum,
+        requires = "report_out",
+        conflicts_with_all = ["dry_parse", "dry_run"]
+    )]
+    report: Option<report::Format>,
+
+    /// File to write the `--report` to; written even when tests fail
+    #[arg(long, value_name = "PATH", requires = "report")]
+    report_out: Option<PathBuf>,
+
+    /// Run up to N test files at once, each against a devnet of its own on
+    /// free ports
+    #[arg(
+        long,
+        short = 'j',
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u16).range(1..),
+        conflicts_with = "interactive"
+    )]
+    jobs: u16,
+
+    /// Stop at the first failing test file; files not started yet are
+    /// reported as not run
+    #[arg(long)]
+    fail_fast: bool,
+
+    /// Ports of the devnet a `--jobs` worker runs its file against
+    #[arg(long, hide = true, value_name = "TRP,GRPC,MINIBF", value_parser = parallel::parse_ports)]
+    worker_ports: Option<Ports>,
+
+    /// TII the parent `trix test` already built, for a `--jobs` worker
+    #[arg(long, hide = true, requires = "worker_ports")]
+    worker_tii: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Context {
+    pub protocol: PathBuf,
+    pub devnet: PathBuf,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self {
+            protocol: PathBuf::from("./main.tx3"),
+            devnet: PathBuf::from("./devnet.toml"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Test {
+    #[serde(default)]
+    pub context: Context,
+
+    #[serde(default)]
+    pub wallets: Vec<Wallet>,
+
+    #[serde(default)]
+    pub transactions: Vec<Transaction>,
+
+    #[serde(default)]
+    pub expect: Vec<ExpectUtxo>,
+
+    #[serde(default)]
+    pub expect_balance: Vec<ExpectBalance>,
+}
+
+impl Test {
+    /// Load a test configuration from a TOML file
+    pub fn load(path: impl AsRef<std::path::Path>) -> miette::Result<Self> {
+        let content = std::fs::read_to_string(&path).into_diagnostic()?;
+        let test: Self = toml::from_str(&content).into_diagnostic()?;
+        test.check_expect_balance()?;
+        test.check_datum_matches()?;
+        test.check_tx_refs()?;
+        Ok(test)
+    }
+
+    /// Reject balance expectations that can't be evaluated, before any
+    /// devnet is started for them.
+    fn check_expect_balance(&self) -> miette::Result<()> {
+        for expect in &self.expect_balance {
+            let name = expect.wallet.trim_start_matches('@');
+
+            if expect.min.is_none() && expect.max.is_none() {
+                bail!(
+                    "expect_balance for `{}` sets neither `min` nor `max`",
+                    expect.wallet
+                );
+            }
+
+            if expect.delta_from_initial && !self.wallets.iter().any(|w| w.name == name) {
+                bail!(
+                    help = "declare its starting balance in a `[[wallets]]` entry",
+                    "expect_balance for `{}` uses `delta_from_initial`, but the wallet has no initial balance",
+                    expect.wallet
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reject datum selectors that don't parse or don't say what to check.
+    fn check_datum_matches(&self) -> miette::Result<()> {
+        for expect in &self.expect {
+            for check in &expect.datum_matches {
+                if let Err(err) = crate::commands::expect::parse_selector(&check.path) {
+                    bail!(
+                        help = "selectors look like `$.fields[0].int`",
+                        "datum_matches for `{}`: invalid path `{}`: {err}",
+                        expect.from,
+                        check.path
+                    );
+                }
+
+                if check.equals.is_some() == check.exists.is_some() {
+                    bail!(
+                        "datum_matches for `{}` at `{}` must set exactly one of `equals` or `exists`",
+                        expect.from,
+                        check.path
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reject `$tx[N]` placeholders that don't parse or point at a
+    /// transaction that doesn't run before the one using them.
+    fn check_tx_refs(&self) -> miette::Result<()> {
+        for (position, transaction) in self.transactions.iter().enumerate() {
+            let placeholders = match refs::find(transaction) {
+                Ok(placeholders) => placeholders,
+                Err(err) => bail!(
+                    help = "placeholders look like `$tx[0].outputs[1].ref`",
+                    "transaction `{}`: {err}",
+                    transaction.description
+                ),
+            };
+
+            for placeholder in placeholders {
+                if placeholder.index >= position {
+                    bail!(
+                        help = "`$tx[N]` counts the transactions of the file from zero",
+                        "transaction `{}`: `{}` refers to transaction #{}, which doesn't run before it",
+                        transaction.description,
+                        placeholder.text,
+                        placeholder.index
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether any transaction takes an arg from an earlier one.
+    fn uses_tx_refs(&self) -> bool {
+        self.transactions
+            .iter()
+            .any(|tx| refs::find(tx).is_ok_and(|found| !found.is_empty()))
+    }
+
+    /// Keep only the transactions whose description contains `pattern`,
+    /// ignoring case, and return the ones dropped.
+    pub fn filter_transactions(&mut self, pattern: &str) -> Vec<Transaction> {
+        let pattern = pattern.to_lowercase();
+
+        let (kept, dropped): (Vec<_>, Vec<_>) = std::mem::take(&mut self.transactions)
+            .into_iter()
+            .partition(|tx| tx.description.to_lowercase().contains(&pattern));
+
+        self.transactions = kept;
+        dropped
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Wallet {
+    pub name: String,
+    pub balance: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExpectUtxo {
+    pub from: String,
+    pub datum_equals: Option<serde_json::Value>,
+    #[serde(default)]
+    pub datum_matches: Vec<DatumMatch>,
+    #[serde(default)]
+    pub min_amount: Vec<ExpectMinAmount>,
+}
+
+/// A check on one value of a UTxO's inline datum, decoded to JSON in the
+/// cardano-cli detailed schema. `path` selects it with `$`, `.field` and
+/// `[index]` steps, e.g. `$.fields[0].int`; the value must then equal
+/// `equals`, or be present or absent per `exists`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DatumMatch {
+    pub path: String,
+    pub equals: Option<serde_json::Value>,
+    pub exists: Option<bool>,
+}
+
+/// Bounds, in lovelace, for a wallet's total balance at the end of the run.
+/// With `delta_from_initial`, `min` and `max` bound the change from the
+/// balance declared for the wallet in `wallets` instead, so a payment of 2
+/// ADA plus fees reads `min = -2_500_000, max = -2_000_000`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExpectBalance {
+    pub wallet: String,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    #[serde(default)]
+    pub delta_from_initial: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExpectMinAmount {
+    pub policy: Option<String>,
+    pub name: Option<String>,
+    pub amount: u64,
+}
+
+/// Every party or signer the test's transactions can't fill from the
+/// profile, as `description: problem` lines.
+fn unsatisfied_parties(
+    test: &Test,
+    template_parties: &BTreeMap<String, Vec<String>>,
+    has_identity: impl Fn(&str) -> bool,
+) -> Result<Vec<String>> {
+    let mut out = vec![];
+
+    for transaction in &test.transactions {
+        let args = serde_json::to_value(&transaction.args).into_diagnostic()?;
+        let args = args.as_object().cloned().unwrap_or_default();
+
+        let parties = template_parties
+            .get(&transaction.template)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        for missing in parties::unsatisfied(parties, &args, &has_identity) {
+            out.push(format!(
+                "{}: party `{}`: {}",
+                transaction.description, missing.party, missing.reason
+            ));
+        }
+
+        for signer in &transaction.signers {
+            if !has_identity(signer) {
+                out.push(format!(
+                    "{}: signer `{signer}` is not an identity of the profile",
+                    transaction.description
+                ));
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+fn trigger_transaction(
+    config: &RootConfig,
+    wallet: &WalletProxy,
+    tii_file: &Path,
+    transaction: &Transaction,
+    args: &serde_json::Value,
+    profile: &ProfileConfig,
+) -> Result<serde_json::Value> {
+    let output = steps::submit(config, wallet, tii_file, transaction, args, profile)?;
+
+    println!("Invoke output: {:#?}", output);
+
+    let network = config.resolve_profile_network(&profile.name)?;
+    crate::wallet::print_tx_link(&output, &network);
+
+    Ok(output)
+}
+
+/// Run every transaction in order and report whether any failed. Errors
+/// are reserved for the session itself (e.g. an aborted prompt), so the
+/// caller can still tear the devnet down. Templates submitted successfully
+/// are added to `executed`, and each transaction to `cases`. `$tx[N]`
+/// placeholders in args are filled from the outputs of earlier ones.
+#[allow(clippy::too_many_arguments)]
+fn run_steps(
+    config: &RootConfig,
+    wallet: &WalletProxy,
+    tii_file: &Path,
+    transactions: &[Transaction],
+    profile: &ProfileConfig,
+    session: Option<&interactive::Session>,
+    executed: &mut BTreeSet<String>,
+    cases: &mut Vec<report::Case>,
+) -> Result<bool> {
+    let mut failed = false;
+
+    // cshell output of each transaction run so far, `None` if it failed or
+    // was skipped.
+    let mut outputs: Vec<Option<serde_json::Value>> = vec![];
+
+    for transaction in transactions {
+        println!("--- Running transaction: {} ---", transaction.description);
+
+        let name = &transaction.description;
+        let kind = report::Kind::Transaction;
+        let started = Instant::now();
+
+        let args = steps::define_args(transaction, wallet).and_then(|mut args| {
+            refs::resolve(&mut args, transactions, &outputs)?;
+            Ok(args)
+        });
+
+        let args = match args {
+            Ok(args) => args,
+            Err(err) => {
+                eprintln!("Transaction `{}` failed.\n", transaction.description);
+                eprintln!("Error: {err}\n");
+                failed = true;
+                outputs.push(None);
+
+                cases.push(report::Case::failed(
+                    name,
+                    kind,
+                    started.elapsed(),
+                    err.to_string(),
+                ));
+                continue;
+            }
+        };
+
+        if let Some(session) = session {
+            match session.before(transaction, &args)? {
+                interactive::Step::Run => {}
+                interactive::Step::Skip => {
+                    cases.push(report::Case::skipped(name, kind));
+                    outputs.push(None);
+                    continue;
+                }
+                interactive::Step::Abort => return Ok(true),
+            }
+        }
+
+        let started = Instant::now();
+
+        loop {
+            let result = trigger_transaction(config, wallet, tii_file, transaction, &args, profile);
+
+            let err = match result {
+                Ok(output) => {
+                    executed.insert(transaction.template.clone());
+                    outputs.push(Some(output));
+
+                    cases.push(report::Case::passed(name, kind, started.elapsed()));
+                    break;
+                }
+                Err(err) => err,
+            };
+
+            eprintln!("Transaction `{}` failed.\n", transaction.description);
+            eprintln!("Error: {err}\n");
+            failed = true;
+
+            let next = session.map(|s| s.after_failure()).transpose()?;
+
+            // A retried transaction is reported once, with its last outcome.
+            if !matches!(next, Some(interactive::Step::Run)) {
+                cases.push(report::Case::failed(
+                    name,
+                    kind,
+                    started.elapsed(),
+                    err.to_string(),
+                ));
+            }
+
+            match next {
+                Some(interactive::Step::Run) => continue,
+                Some(interactive::Step::Abort) => return Ok(true),
+                Some(interactive::Step::Skip) | None => {
+                    outputs.push(None);
+                    break;
+                }
+            }
+        }
+
+        steps::wait_next_block();
+    }
+
+    Ok(failed)
+}
+
+/// Check every expectation of `test` against the cshell store at
+/// `test_home`, printing what fails, and report whether any did. Each
+/// expectation is added to `cases`.
+fn check_expectations(
+    test: &Test,
+    test_home: &Path,
+    provider: &str,
+    cases: &mut Vec<report::Case>,
+) -> Result<bool> {
+    let mut failed = false;
+
+    let mut record = |name: String, started: Instant, failure: Vec<String>| {
+        for line in &failure {
+            eprintln!("{line}");
+        }
+
+        let kind = report::Kind::Expect;
+
+        cases.push(match failure.is_empty() {
+            true => report::Case::passed(name, kind, started.elapsed()),
+            false => report::Case::failed(name, kind, started.elapsed(), failure.join("\n")),
+        });
+
+        failed |= !failure.is_empty();
+    };
+
+    for expect in &test.expect {
+        let started = Instant::now();
+        let failure = expect::utxo_failures(expect, test_home, provider)?;
+        record(
+            format!("expect utxo from {}", expect.from),
+            started,
+            failure,
+        );
+    }
+
+    for expect in &test.expect_balance {
+        let started = Instant::now();
+        let failure = expect::balance_failures(expect, &test.wallets, test_home)?;
+        record(
+            format!("expect balance of {}", expect.wallet),
+            started,
+            failure,
+        );
+    }
+
+    Ok(failed)
+}
+
+fn print_plan(test: &Test, skipped: &[Transaction]) {
+    let total = test.transactions.len() + skipped.len();
+    println!(
+        "would run {} of {total} transaction(s):",
+        test.transactions.len()
+    );
+
+    for transaction in &test.transactions {
+        println!("  - {} ({})", transaction.description, transaction.template);
+    }
+
+    if !skipped.is_empty() {
+        println!("skipped:");
+
+        for transaction in skipped {
+            println!("  - {}", transaction.description);
+        }
+    }
+}
+
+/// Start a devnet of its own for `test`, run its transactions and
+/// expectations, and tear the devnet down again. `ports` overrides the
+/// ones in the devnet config. Templates submitted successfully are added to
+/// `executed` and every transaction and expectation to `cases`; returns
+/// whether anything failed.
+#[allow(clippy::too_many_arguments)]
+fn run_file(
+    path: &Path,
+    test: &Test,
+    config: &RootConfig,
+    profile: &ProfileConfig,
+    wallet: &WalletProxy,
+    tii_file: &Path,
+    ports: Option<Ports>,
+    interactive: bool,
+    executed: &mut BTreeSet<String>,
+    cases: &mut Vec<report::Case>,
+) -> Result<bool> {
+    let mut devnet = DevnetConfig::load(&test.context.devnet)?;
+
+    if let Some(ports) = ports {
+        devnet.ports = ports;
+    }
+
+    let mut ctx = crate::devnet::Context::from_wallet(wallet);
+    ctx.derivation_tag = format!("{}-{}", ctx.derivation_tag, suite::home_suffix(path));
+
+    let mut devnet = crate::devnet::start_daemon(&devnet, &ctx, true)?;
+
+    println!("Dolos daemon started");
+
+    sleep(Duration::from_secs(DOLOS_SPAWN_DELAY_SECONDS));
+
+    let session = interactive.then(|| interactive::Session::new(wallet, profile));
+
+    let steps = run_steps(
+        config,
+        wallet,
+        tii_file,
+        &test.transactions,
+        profile,
+        session.as_ref(),
+        executed,
+        cases,
+    );
+
+    // Query utxos from the cshell store that actually holds the wallets and the
+    // provider (`wallet.target_dir`) — the same home the invoke path submits
+    // against. `devnet.home` is the *dolos* store and has neither.
+    let provider = crate::wallet::provider_name(&profile.name);
+    let expect_outcome = match &steps {
+        Ok(_) => check_expectations(test, &wallet.target_dir, &provider, cases),
+        Err(_) => Ok(false),
+    };
+
+    // Tear down the devnet unconditionally — even when the expect phase errors,
+    // so a failed or early-exiting test never leaves a Dolos daemon running.
+    devnet
+        .daemon
+        .kill()
+        .into_diagnostic()
+        .context("failed to stop dolos devnet in background")?;
+
+    let mut failed = steps?;
+    failed |= expect_outcome?;
+
+    Ok(failed)
+}
+
+pub fn run(mut args: Args, config: &RootConfig, profile: &ProfileConfig) -> Result<()> {
+    if let Some(Command::Init(init)) = args.command.take() {
+        return init::run(init, config, profile);
+    }
+
+    // A single file keeps the original output; a directory, or no path at
+    // all, runs every test file found and ends with a summary table.
+    let (files, suite) = if args.all || args.path.is_none() {
+        let dir = crate::dirs::protocol_root()?.join("tests");
+        (suite::discover(&dir, args.only.as_deref())?, true)
+    } else if let Some(dir) = args.path.as_ref().filter(|path| path.is_dir()) {
+        (suite::discover(dir, args.only.as_deref())?, true)
+    } else {
+        (vec![args.path.clone().unwrap_or_default()], false)
+    };
+
+    if args.dry_parse {
+        for path in &files {
+            Test::load(path).context(format!("parsing test file {}", path.display()))?;
+            println!("{}: test file is valid", path.display());
+        }
+
+        return Ok(());
+    }
+
+    if args.interactive {
+        crate::term::prompt::ensure_interactive("`trix test --interactive`")?;
+    }
+
+    let mut tests = vec![];
+
+    for path in &files {
+        let mut test = Test::load(path)?;
+
+        let skipped = match &args.filter {
+            Some(pattern) => {
+                let skipped = test.filter_transactions(pattern);
+
+                if !skipped.is_empty() && test.uses_tx_refs() {
+                    bail!(
+                        help = "`$tx[N]` counts the transactions of the whole file; run it without --filter",
+                        "--filter would skip transactions of {}, which passes outputs between its transactions",
+                        path.display()
+                    );
+                }
+
+                if test.transactions.is_empty() && !suite {
+                    bail!(
+                        help = "the filter matches transaction descriptions, ignoring case",
+                        "no transaction in {} matches `{pattern}`",
+                        path.display()
+                    );
+                }
+
+                skipped
+            }
+            None => vec![],
+        };
+
+        tests.push((path, test, skipped));
+    }
+
+    if args.dry_run {
+        for (path, test, skipped) in &tests {
+            if suite {
+                println!("{}:", path.display());
+            }
+
+            print_plan(test, skipped);
+        }
+
+        return Ok(());
+    }
+
+    // A `--jobs` worker gets a cshell home of its own, pointed at its
+    // devnet's ports, and reuses the TII its parent built.
+    let wallet = match &args.worker_ports {
+        Some(ports) => {
+            crate::wallet::setup_isolated(config, profile, &suite::home_suffix(&files[0]), ports)?
+        }
+        None => crate::wallet::setup(config, profile)?,
+    };
+
+    let tii_file = match &args.worker_tii {
+        Some(tii_file) => tii_file.clone(),
+        None => builder::build_tii(config)?,
+    };
+
+    let tii = builder::load_tii(&tii_file)?;
+
+    // The parties of every template the tests use, decoded once.
+    let mut template_parties = BTreeMap::new();
+
+    for (_, test, _) in &tests {
+        for transaction in &test.transactions {
+            let template = &transaction.template;
+
+            if template_parties.contains_key(template)
+                || tii.pointer(&format!("/transactions/{template}")).is_none()
+            {
+                continue;
+            }
+
+            let parties = parties::of_template(&tii_file, &tii, template)?;
+            template_parties.insert(template.clone(), parties);
+        }
+    }
+
+    for (path, test, _) in &tests {
+        let missing = unsatisfied_parties(test, &template_parties, |name| {
+            profile.identities.contains_key(name)
+        })?;
+
+        if !missing.is_empty() {
+            bail!(
+                help = format!(
+                    "add the identities under `[profiles.{}.identities]` in trix.toml, or pass addresses in the transaction args",
+                    profile.name
+                ),
+                "{} references parties the profile can't satisfy:\n  - {}",
+                path.display(),
+                missing.join("\n  - ")
+            );
+        }
+    }
+
+    let mut executed = BTreeSet::new();
+    let mut reports = vec![];
+
+    let filtered_out =
+        |test: &Test, skipped: &[Transaction]| test.transactions.is_empty() && !skipped.is_empty();
+
+    let skipped_cases = |skipped: &[Transaction]| -> Vec<report::Case> {
+        skipped
+            .iter()
+            .map(|tx| report::Case::skipped(&tx.description, report::Kind::Transaction))
+            .collect()
+    };
+
+    let skipped_report = |path: &Path, skipped: &[Transaction]| suite::FileReport {
+        path: path.to_path_buf(),
+        outcome: suite::Outcome::Skipped,
+        duration: Duration::ZERO,
+        cases: skipped_cases(skipped),
+    };
+
+    let write_report = |reports: &[suite::FileReport]| match (args.report, &args.report_out) {
+        (Some(format), Some(out)) => report::Report::new(reports).write(format, out),
+        _ => Ok(()),
+    };
+
+    if suite && args.jobs > 1 {
+        let runnable: Vec<PathBuf> = tests
+            .iter()
+            .filter(|(_, test, skipped)| !filtered_out(test, skipped))
+            .map(|(path, ..)| path.to_path_buf())
+            .collect();
+
+        let options = parallel::Options {
+            jobs: usize::from(args.jobs),
+            fail_fast: args.fail_fast,
+            profile: &profile.name,
+            filter: args.filter.as_deref(),
+            tii_file: &tii_file,
+        };
+
+        let mut ran = parallel::run(&runnable, &options, &mut executed)?.into_iter();
+
+        for (path, test, skipped) in &tests {
+            match filtered_out(test, skipped) {
+                true => reports.push(skipped_report(path, skipped)),
+                false => reports.extend(ran.next()),
+            }
+        }
+    } else {
+        for (path, test, skipped) in &tests {
+            if suite {
+                println!("=== {} ===\n", path.display());
+            }
+
+            if filtered_out(test, skipped) {
+                println!("no transaction matches the filter, skipping\n");
+                reports.push(skipped_report(path, skipped));
+                continue;
+            }
+
+            let stopped = args.fail_fast
+                && reports
+                    .iter()
+                    .any(|r| matches!(r.outcome, suite::Outcome::Failed));
+
+            if stopped {
+                println!("an earlier test file failed, not running\n");
+                reports.push(suite::FileReport {
+                    path: path.to_path_buf(),
+                    outcome: suite::Outcome::NotRun,
+                    duration: Duration::ZERO,
+                    cases: skipped_cases(skipped),
+                });
+                continue;
+            }
+
+            println!("== Starting tests ==\n");
+
+            for transaction in skipped {
+                println!("--- Skipping transaction: {} ---", transaction.description);
+            }
+
+            let started = Instant::now();
+            let mut cases = skipped_cases(skipped);
+
+            let result = run_file(
+                path,
+                test,
+                config,
+                profile,
+                &wallet,
+                &tii_file,
+                args.worker_ports,
+                args.interactive,
+                &mut executed,
+                &mut cases,
+            );
+
+            if let Err(err) = &result {
+                cases.push(report::Case::failed(
+                    "setup",
+                    report::Kind::Setup,
+                    started.elapsed(),
+                    err.to_string(),
+                ));
+            }
+
+            reports.push(suite::FileReport {
+                path: path.to_path_buf(),
+                outcome: match result {
+                    Ok(false) => suite::Outcome::Passed,
+                    _ => suite::Outcome::Failed,
+                },
+                duration: started.elapsed(),
+                cases,
+            });
+
+            // In a suite, one broken file (e.g. a bad devnet.toml) is reported
+            // and the next one still runs.
+            match result {
+                Err(err) if suite => eprintln!("{err:?}"),
+                Err(err) => {
+                    write_report(&reports)?;
+                    return Err(err);
+                }
+                Ok(_) => {}
+            }
+        }
+    }
+
+    let coverage = coverage::Coverage::new(&tii, &executed);
+    coverage.print();
+
+    if let Some(path) = &args.coverage_out {
+        coverage.write(path)?;
+    }
+
+    if suite {
+        suite::print_summary(&reports);
+    }
+
+    write_report(&reports)?;
+
+    let failed = reports
+        .iter()
+        .filter(|r| matches!(r.outcome, suite::Outcome::Failed))
+        .count();
+
+    if failed > 0 {
+        match suite {
+            true => bail!("{failed} of {} test files failed", reports.len()),
+            false => bail!("Test failed, see output above for details."),
+        }
+    }
+
+    if let Some(min) = args.min_template_coverage
+        && coverage.percent < f64::from(min)
+    {
+        bail!(
+            help = format!("add transactions for: {}", coverage.untested.join(", ")),
+            "template coverage {:.0}% is below the required {min}%",
+            coverage.percent
+        );
+    }
+
+    println!("Test Passed\n");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn parse_expect_utxo_toml() {
+        let toml = r#"
+            [context]
+            protocol = "./main.tx3"
+            devnet = "./devnet.toml"
+            
+            [[transactions]]
+            description = "Simple Oracle"
+            template = "create"
+            signers = ["operator"]
+            args = { rate = 42, operator = "@operator", oracle = "@oracle" }
+
+            [[expect]]
+            from = "@oracle"
+            datum_equals = 42
+
+            [[expect.min_amount]]
+            amount = 123
+
+            [[expect.min_amount]]
+            policy = "xyz"
+            name = "abc"
+            amount = 456
+        "#;
+
+        let parsed: Test = toml::from_str(toml).expect("parse toml");
+
+        assert_eq!(parsed.context.protocol, PathBuf::from("./main.tx3"));
+        assert_eq!(parsed.context.devnet, PathBuf::from("./devnet.toml"));
+
+        assert_eq!(parsed.transactions.len(), 1);
+
+        assert_eq!(parsed.expect.len(), 1);
+        let e = &parsed.expect[0];
+        assert_eq!(e.from, "@oracle");
+
+        assert!(e.datum_equals.is_some());
+        let datum = e.datum_equals.as_ref().unwrap();
+        match datum {
+            serde_json::Value::Number(n) => {
+                assert_eq!(n.as_i64(), Some(42));
+            }
+            other => panic!("unexpected datum kind: {other:?}"),
+        }
+
+        let mins = &e.min_amount;
+        assert_eq!(mins.len(), 2);
+
+        assert_eq!(mins[0].amount, 123);
+        assert!(mins[0].policy.is_none() && mins[0].name.is_none());
+
+        assert_eq!(mins[1].policy.as_ref().unwrap(), "xyz");
+        assert_eq!(mins[1].name.as_ref().unwrap(), "abc");
+        assert_eq!(mins[1].amount, 456);
+    }
+
+    const MISSING_PARTY_TOML: &str = r#"
+        [[transactions]]
+        description = "Buy"
+        template = "buy"
+        signers = ["buyer"]
+        args = { quantity = 1, buyer = "@buyer" }
+    "#;
+
+    fn template_parties() -> BTreeMap<String, Vec<String>> {
+        BTreeMap::from([(
+            "buy".to_string(),
+            vec!["Buyer".to_string(), "Treasury".to_string()],
+        )])
+    }
+
+    #[test]
+    fn prechecks_missing_party() {
+        let test: Test = toml::from_str(MISSING_PARTY_TOML).unwrap();
+
+        let missing =
+            unsatisfied_parties(&test, &template_parties(), |name| name == "buyer").unwrap();
+
+        assert_eq!(missing.len(), 1);
+        assert!(missing[0].starts_with("Buy: party `Treasury`"));
+    }
+
+    #[test]
+    fn prechecks_unknown_signer_and_reference() {
+        let test: Test = toml::from_str(MISSING_PARTY_TOML).unwrap();
+
+        let missing =
+            unsatisfied_parties(&test, &template_parties(), |name| name == "treasury").unwrap();
+
+        assert_eq!(missing.len(), 2);
+        assert!(missing[0].contains("`@buyer`"));
+        assert!(missing[1].contains("signer `buyer`"));
+    }
+
+    #[test]
+    fn expect_balance_delta_needs_declared_wallet() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("balance.toml");
+
+        let declared = r#"
+            [[wallets]]
+            name = "alice"
+            balance = 10000000
+
+            [[expect_balance]]
+            wallet = "@alice"
+            min = -2500000
+            max = -2000000
+            delta_from_initial = true
+        "#;
+        std::fs::write(&path, declared).unwrap();
+
+        let test = Test::load(&path).unwrap();
+        assert_eq!(test.expect_balance[0].min, Some(-2_500_000));
+        assert!(test.expect_balance[0].delta_from_initial);
+
+        std::fs::write(&path, declared.replace("\"alice\"\n", "\"bob\"\n")).unwrap();
+        let err = Test::load(&path).unwrap_err();
+        assert!(err.to_string().contains("no initial balance"), "{err}");
+    }
+
+    #[test]
+    fn datum_matches_rejects_bad_selectors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("datum.toml");
+
+        let valid = r#"
+            [[expect]]
+            from = "@oracle"
+            datum_matches = [
+                { path = "$.fields[0].int", equals = 42 },
+                { path = "$.fields[1]", exists = false },
+            ]
+        "#;
+        std::fs::write(&path, valid).unwrap();
+
+        let test = Test::load(&path).unwrap();
+        assert_eq!(test.expect[0].datum_matches.len(), 2);
+        assert!(test.expect[0].min_amount.is_empty());
+
+        std::fs::write(&path, valid.replace("$.fields[0]", "fields[0]")).unwrap();
+        let err = Test::load(&path).unwrap_err();
+        assert!(
+            err.to_string().contains("invalid path `fields[0].int`"),
+            "{err}"
+        );
+
+        std::fs::write(
+            &path,
+            valid.replace("equals = 42", "equals = 42, exists = true"),
+        )
+        .unwrap();
+        let err = Test::load(&path).unwrap_err();
+        assert!(err.to_string().contains("exactly one of"), "{err}");
+    }
+
+    #[test]
+    fn tx_refs_must_point_at_earlier_transactions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("refs.toml");
+
+        let valid = r#"
+            [[transactions]]
+            description = "lock funds"
+            template = "transfer"
+            signers = ["alice"]
+            args = { quantity = 5000000, sender = "@alice", receiver = "@bob" }
+
+            [[transactions]]
+            description = "reclaim funds"
+            template = "reclaim"
+            signers = ["bob"]
+            args = { locked_utxo = "$tx[0].outputs[0].ref", sender = "@alice" }
+        "#;
+        std::fs::write(&path, valid).unwrap();
+
+        let mut test = Test::load(&path).unwrap();
+        assert!(test.uses_tx_refs());
+
+        test.filter_transactions("reclaim");
+        assert!(test.uses_tx_refs());
+
+        std::fs::write(&path, valid.replace("$tx[0]", "$tx[1]")).unwrap();
+        let err = Test::load(&path).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("`$tx[1].outputs[0].ref` refers to transaction #1"),
+            "{err}"
+        );
+
+        std::fs::write(&path, valid.replace("$tx[0]", "$tx[-1]")).unwrap();
+        let err = Test::load(&path).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("transaction `reclaim funds`: invalid placeholder"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn filter_keeps_matching_transactions_case_insensitively() {
+        let mut test: Test = toml::from_str(
+            r#"
+            [[transactions]]
+            description = "Mint tokens"
+            template = "mint"
+            signers = ["alice"]
+            args = {}
+
+            [[transactions]]
+            description = "Transfer to Bob"
+            template = "transfer"
+            signers = ["alice"]
+            args = {}
+
+            [[transactions]]
+            description = "Burn minted tokens"
+            template = "burn"
+            signers = ["alice"]
+            args = {}
+            "#,
+        )
+        .unwrap();
+
+        let skipped = test.filter_transactions("MINT");
+
+        let kept: Vec<_> = test
+            .transactions
+            .iter()
+            .map(|t| t.template.as_str())
+            .collect();
+        assert_eq!(kept, ["mint", "burn"]);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].template, "transfer");
+    }
+}
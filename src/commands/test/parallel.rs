@@ -0,0 +1,332 @@
+//! `trix test --jobs N`: run several test files at once. Each file runs in
+//! a `trix test` child of its own (a worker), against a devnet on free
+//! ports with cshell and dolos homes of its own, so nothing is shared but
+//! the TII the parent built. Worker output is passed through line by line,
+//! prefixed with the file it belongs to.
+
+use std::collections::{BTreeSet, VecDeque};
+use std::io::{BufRead as _, BufReader, Read};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use miette::{Context as _, IntoDiagnostic as _, Result};
+
+use crate::devnet::Ports;
+use crate::spawn::shutdown;
+
+use super::report::{Case, Kind, Report};
+use super::suite::{FileReport, Outcome};
+
+/// Parse the hidden `--worker-ports TRP,GRPC,MINIBF` a worker is started
+/// with.
+pub fn parse_ports(s: &str) -> Result<Ports, String> {
+    let ports: Vec<u16> = s
+        .split(',')
+        .map(|port| port.trim().parse::<u16>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("expected TRP,GRPC,MINIBF ports, got '{s}'"))?;
+
+    let [trp, grpc, minibf] = ports[..] else {
+        return Err(format!("expected TRP,GRPC,MINIBF ports, got '{s}'"));
+    };
+
+    Ok(Ports {
+        trp: Some(trp),
+        grpc: Some(grpc),
+        minibf: Some(minibf),
+    })
+}
+
+/// Three distinct ports nobody listens on right now. They are released
+/// before dolos binds them, so another process could still take one in
+/// between; dolos then fails to start and the file is reported failed.
+fn free_ports() -> Result<Ports> {
+    let listeners = (0..3)
+        .map(|_| TcpListener::bind(("127.0.0.1", 0)))
+        .collect::<Result<Vec<_>, _>>()
+        .into_diagnostic()
+        .context("finding free ports for a test devnet")?;
+
+    let port =
+        |i: usize| -> Result<u16> { Ok(listeners[i].local_addr().into_diagnostic()?.port()) };
+
+    Ok(Ports {
+        trp: Some(port(0)?),
+        grpc: Some(port(1)?),
+        minibf: Some(port(2)?),
+    })
+}
+
+pub struct Options<'a> {
+    pub jobs: usize,
+    pub fail_fast: bool,
+    pub profile: &'a str,
+    pub filter: Option<&'a str>,
+    pub tii_file: &'a Path,
+}
+
+/// A worker's outcome plus the templates it covered.
+struct Finished {
+    report: FileReport,
+    covered: Vec<String>,
+}
+
+fn pump<R: Read>(pipe: R, label: &str, to_stderr: bool) {
+    for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+        match to_stderr {
+            true => eprintln!("[{label}] {line}"),
+            false => println!("[{label}] {line}"),
+        }
+    }
+}
+
+fn covered_templates(path: &Path) -> Vec<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str::<super::coverage::Coverage>(&json).ok())
+        .map(|coverage| coverage.covered)
+        .unwrap_or_default()
+}
+
+struct Pool<'a> {
+    options: &'a Options<'a>,
+    queue: Mutex<VecDeque<PathBuf>>,
+    cancelled: AtomicBool,
+    running: Mutex<Vec<u32>>,
+    finished: Mutex<Vec<Finished>>,
+    scratch: tempfile::TempDir,
+}
+
+impl Pool<'_> {
+    fn worker_command(
+        &self,
+        path: &Path,
+        ports: &Ports,
+        coverage: &Path,
+        report: &Path,
+    ) -> Result<Command> {
+        let exe = std::env::current_exe()
+            .into_diagnostic()
+            .context("locating the trix executable")?;
+
+        let mut cmd = Command::new(exe);
+
+        cmd.arg("--no-alias")
+            .arg("test")
+            .arg(path)
+            .args(["--profile", self.options.profile])
+            .arg("--worker-ports")
+            .arg(format!(
+                "{},{},{}",
+                ports.trp(),
+                ports.grpc(),
+                ports.minibf()
+            ))
+            .arg("--worker-tii")
+            .arg(self.options.tii_file)
+            .arg("--coverage-out")
+            .arg(coverage)
+            .args(["--report", "json", "--report-out"])
+            .arg(report);
+
+        if let Some(filter) = self.options.filter {
+            cmd.args(["--filter", filter]);
+        }
+
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        Ok(cmd)
+    }
+
+    fn run_one(&self, index: usize, path: &Path) -> Result<Finished> {
+        let label = crate::dirs::protocol_root()
+            .ok()
+            .and_then(|root| path.strip_prefix(root).ok().map(Path::to_path_buf))
+            .unwrap_or_else(|| path.to_path_buf())
+            .display()
+            .to_string();
+
+        let coverage = self.scratch.path().join(format!("coverage-{index}.json"));
+        let report = self.scratch.path().join(format!("report-{index}.json"));
+        let ports = free_ports()?;
+
+        let started = Instant::now();
+
+        let mut child = self
+            .worker_command(path, &ports, &coverage, &report)?
+            .spawn()
+            .into_diagnostic()
+            .with_context(|| format!("starting a worker for {label}"))?;
+
+        let pid = child.id();
+        self.running.lock().unwrap().push(pid);
+
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+
+        let status = std::thread::scope(|scope| {
+            if let Some(stdout) = stdout {
+                scope.spawn(|| pump(stdout, &label, false));
+            }
+
+            if let Some(stderr) = stderr {
+                scope.spawn(|| pump(stderr, &label, true));
+            }
+
+            child.wait()
+        });
+
+        self.running.lock().unwrap().retain(|p| *p != pid);
+
+        let passed = status.is_ok_and(|status| status.success());
+
+        Ok(Finished {
+            report: FileReport {
+                path: path.to_path_buf(),
+                outcome: match passed {
+                    true => Outcome::Passed,
+                    false => Outcome::Failed,
+                },
+                duration: started.elapsed(),
+                cases: Report::read_cases(&report),
+            },
+            covered: covered_templates(&coverage),
+        })
+    }
+
+    /// Stop the workers still running, letting each tear its devnet down.
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+
+        for pid in self.running.lock().unwrap().iter() {
+            shutdown::interrupt(*pid);
+        }
+    }
+
+    fn work(&self, next_index: &Mutex<usize>) {
+        loop {
+            let Some(path) = self.queue.lock().unwrap().pop_front() else {
+                return;
+            };
+
+            if self.cancelled.load(Ordering::SeqCst) {
+                self.finished.lock().unwrap().push(Finished {
+                    report: FileReport {
+                        path,
+                        outcome: Outcome::NotRun,
+                        duration: Default::default(),
+                        cases: vec![],
+                    },
+                    covered: vec![],
+                });
+                continue;
+            }
+
+            let index = {
+                let mut next = next_index.lock().unwrap();
+                *next += 1;
+                *next
+            };
+
+            let finished = self.run_one(index, &path).unwrap_or_else(|err| {
+                eprintln!("{err:?}");
+
+                Finished {
+                    report: FileReport {
+                        path,
+                        outcome: Outcome::Failed,
+                        duration: Default::default(),
+                        cases: vec![Case::failed(
+                            "setup",
+                            Kind::Setup,
+                            Default::default(),
+                            err.to_string(),
+                        )],
+                    },
+                    covered: vec![],
+                }
+            });
+
+            let failed = matches!(finished.report.outcome, Outcome::Failed);
+
+            // A worker stopped by a sibling's failure is not run, not failed.
+            let finished = match failed && self.cancelled.load(Ordering::SeqCst) {
+                true => Finished {
+                    report: FileReport {
+                        outcome: Outcome::NotRun,
+                        ..finished.report
+                    },
+                    ..finished
+                },
+                false => finished,
+            };
+
+            if failed && self.options.fail_fast && !self.cancelled.load(Ordering::SeqCst) {
+                self.cancel();
+            }
+
+            self.finished.lock().unwrap().push(finished);
+        }
+    }
+}
+
+/// Run `files` on up to `options.jobs` workers at once. Reports come back
+/// in the order of `files`; templates the workers covered are added to
+/// `executed`.
+pub fn run(
+    files: &[PathBuf],
+    options: &Options,
+    executed: &mut BTreeSet<String>,
+) -> Result<Vec<FileReport>> {
+    let pool = Pool {
+        options,
+        queue: Mutex::new(files.iter().cloned().collect()),
+        cancelled: AtomicBool::new(false),
+        running: Mutex::new(vec![]),
+        finished: Mutex::new(vec![]),
+        scratch: tempfile::TempDir::new().into_diagnostic()?,
+    };
+
+    let next_index = Mutex::new(0);
+
+    std::thread::scope(|scope| {
+        for _ in 0..options.jobs.min(files.len()) {
+            scope.spawn(|| pool.work(&next_index));
+        }
+    });
+
+    let mut finished = pool.finished.into_inner().unwrap();
+
+    for run in &finished {
+        executed.extend(run.covered.iter().cloned());
+    }
+
+    finished.sort_by_key(|run| files.iter().position(|f| f == &run.report.path));
+
+    Ok(finished.into_iter().map(|run| run.report).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn worker_ports_round_trip() {
+        let ports = free_ports().unwrap();
+        let rendered = format!("{},{},{}", ports.trp(), ports.grpc(), ports.minibf());
+
+        assert_eq!(parse_ports(&rendered).unwrap(), ports);
+
+        let distinct: BTreeSet<_> = [ports.trp(), ports.grpc(), ports.minibf()].into();
+        assert_eq!(distinct.len(), 3);
+
+        assert!(parse_ports("8164,5164").is_err());
+        assert!(parse_ports("8164,5164,x").is_err());
+    }
+}
@@ -0,0 +1,248 @@
+//! `$tx[N]` placeholders in transaction args, e.g. `"$tx[0].outputs[1].ref"`
+//! for a UTxO an earlier transaction produced. N counts the transactions of
+//! the test file from zero. A placeholder is filled from the cshell output
+//! of that transaction once it was submitted:
+//! `.outputs[i].ref` is built from its hash, any other path selects from
+//! the output JSON itself, e.g. `$tx[0].hash`.
+
+use miette::{Result, bail};
+use pallas::ledger::traverse::MultiEraTx;
+use serde_json::Value;
+
+use crate::commands::expect::{self, Step};
+use crate::commands::steps::Transaction;
+use crate::spawn::cshell;
+
+#[derive(Debug, PartialEq)]
+pub struct Placeholder {
+    pub text: String,
+    /// Position of the referenced transaction in the file.
+    pub index: usize,
+    pub path: Vec<Step>,
+}
+
+impl Placeholder {
+    /// Parse `text` as a placeholder, or `Ok(None)` if it isn't one.
+    pub fn parse(text: &str) -> std::result::Result<Option<Self>, String> {
+        let Some(rest) = text.strip_prefix("$tx") else {
+            return Ok(None);
+        };
+
+        let mut path = expect::parse_selector(&format!("${rest}"))?;
+
+        let index = match path.first() {
+            Some(Step::Index(index)) => *index,
+            _ => return Err("must start with `$tx[N]`".to_string()),
+        };
+
+        path.remove(0);
+
+        Ok(Some(Self {
+            text: text.to_string(),
+            index,
+            path,
+        }))
+    }
+
+    /// The `i` of an `.outputs[i].ref` path.
+    fn output_ref(&self) -> Option<usize> {
+        match self.path.as_slice() {
+            [Step::Field(outputs), Step::Index(i), Step::Field(field)]
+                if outputs == "outputs" && field == "ref" =>
+            {
+                Some(*i)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Every string in `value`, at any depth.
+fn strings_mut(value: &mut Value) -> Vec<&mut Value> {
+    if value.is_string() {
+        return vec![value];
+    }
+
+    match value {
+        Value::Array(items) => items.iter_mut().flat_map(strings_mut).collect(),
+        Value::Object(fields) => fields.values_mut().flat_map(strings_mut).collect(),
+        _ => vec![],
+    }
+}
+
+/// Every placeholder in the args of `transaction`, or why one is malformed.
+pub fn find(transaction: &Transaction) -> std::result::Result<Vec<Placeholder>, String> {
+    let mut args = serde_json::to_value(&transaction.args).map_err(|err| err.to_string())?;
+
+    strings_mut(&mut args)
+        .into_iter()
+        .filter_map(|value| value.as_str().map(str::to_string))
+        .filter_map(|text| {
+            Placeholder::parse(&text)
+                .map_err(|err| format!("invalid placeholder `{text}`: {err}"))
+                .transpose()
+        })
+        .collect()
+}
+
+/// Number of outputs of the transaction cshell reported, when it included
+/// the CBOR.
+fn output_count(output: &Value) -> Option<usize> {
+    let cbor = output.get("cbor")?.as_str()?;
+    let bytes = hex::decode(cbor).ok()?;
+
+    MultiEraTx::decode(&bytes).ok().map(|tx| tx.outputs().len())
+}
+
+fn value_of(
+    placeholder: &Placeholder,
+    transactions: &[Transaction],
+    outputs: &[Option<Value>],
+) -> Result<Value> {
+    let text = &placeholder.text;
+    let index = placeholder.index;
+
+    let Some(referenced) = transactions.get(index) else {
+        bail!(
+            "`{text}`: there is no transaction #{index}, the file has {}",
+            transactions.len()
+        );
+    };
+
+    let description = &referenced.description;
+
+    let output = match outputs.get(index) {
+        Some(Some(output)) => output,
+        Some(None) => bail!(
+            "`{text}`: transaction #{index} (`{description}`) failed or was skipped, so it has no outputs"
+        ),
+        None => bail!("`{text}`: transaction #{index} (`{description}`) hasn't been executed yet"),
+    };
+
+    if let Some(i) = placeholder.output_ref() {
+        let Some(hash) = cshell::tx_hash(output) else {
+            bail!("`{text}`: cshell reported no hash for transaction #{index} (`{description}`)");
+        };
+
+        if let Some(count) = output_count(output)
+            && i >= count
+        {
+            bail!(
+                "`{text}`: output index out of range, transaction #{index} (`{description}`) has {count} output(s)"
+            );
+        }
+
+        return Ok(Value::String(format!("{hash}#{i}")));
+    }
+
+    match expect::select(output, &placeholder.path) {
+        Some(value) => Ok(value.clone()),
+        None => bail!(
+            "`{text}`: the output of transaction #{index} (`{description}`) has no value at that path"
+        ),
+    }
+}
+
+/// Replace the placeholders in `args` with their values. `outputs[n]` is
+/// the cshell output of the nth of `transactions`, `None` if it failed or
+/// was skipped.
+pub fn resolve(
+    args: &mut Value,
+    transactions: &[Transaction],
+    outputs: &[Option<Value>],
+) -> Result<()> {
+    for value in strings_mut(args) {
+        let text = value.as_str().unwrap_or_default();
+
+        let placeholder = match Placeholder::parse(text) {
+            Ok(Some(placeholder)) => placeholder,
+            Ok(None) => continue,
+            Err(err) => bail!("invalid placeholder `{text}`: {err}"),
+        };
+
+        *value = value_of(&placeholder, transactions, outputs)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(description: &str, args: Value) -> Transaction {
+        Transaction {
+            description: description.to_string(),
+            template: "transfer".to_string(),
+            args: serde_json::from_value(args).unwrap(),
+            signers: vec!["alice".to_string()],
+        }
+    }
+
+    fn run() -> Vec<Transaction> {
+        vec![
+            transaction("lock funds", serde_json::json!({ "quantity": 5 })),
+            transaction("cancel order", serde_json::json!({})),
+            transaction("claim", serde_json::json!({})),
+        ]
+    }
+
+    #[test]
+    fn parses_placeholders() {
+        let placeholder = Placeholder::parse("$tx[0].outputs[1].ref")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(placeholder.index, 0);
+        assert_eq!(placeholder.output_ref(), Some(1));
+
+        assert_eq!(Placeholder::parse("@alice").unwrap(), None);
+        assert!(Placeholder::parse("$tx.outputs").is_err());
+        assert!(Placeholder::parse("$tx[first]").is_err());
+    }
+
+    #[test]
+    fn resolves_refs_and_output_fields() {
+        let outputs = vec![Some(serde_json::json!({ "hash": "ab12", "fee": 171573 }))];
+
+        let mut args = serde_json::json!({
+            "order": "$tx[0].outputs[1].ref",
+            "refs": ["$tx[0].hash"],
+            "fee": "$tx[0].fee",
+            "sender": "@alice",
+        });
+
+        resolve(&mut args, &run(), &outputs).unwrap();
+
+        assert_eq!(args["order"], "ab12#1");
+        assert_eq!(args["refs"][0], "ab12");
+        assert_eq!(args["fee"], 171573);
+        assert_eq!(args["sender"], "@alice");
+    }
+
+    #[test]
+    fn errors_name_placeholder_and_transaction() {
+        let outputs = vec![Some(serde_json::json!({ "hash": "ab12" })), None];
+
+        let err = |placeholder: &str| {
+            let mut args = serde_json::json!({ "order": placeholder });
+            resolve(&mut args, &run(), &outputs)
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            err("$tx[1].outputs[0].ref"),
+            "`$tx[1].outputs[0].ref`: transaction #1 (`cancel order`) failed or was skipped, so it has no outputs"
+        );
+        assert_eq!(
+            err("$tx[2].hash"),
+            "`$tx[2].hash`: transaction #2 (`claim`) hasn't been executed yet"
+        );
+        assert_eq!(
+            err("$tx[7].hash"),
+            "`$tx[7].hash`: there is no transaction #7, the file has 3"
+        );
+        assert!(err("$tx[0].cbor").contains("has no value at that path"));
+    }
+}
@@ -0,0 +1,278 @@
+//! `trix test --report`: every transaction and expectation of the run as
+//! a test case, one suite per test file, written as JUnit XML for CI that
+//! only renders that, or as JSON of the same shape for custom tooling.
+
+use std::path::Path;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use miette::{Context as _, IntoDiagnostic as _, Result};
+use serde::{Deserialize, Serialize};
+
+use super::suite::FileReport;
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Format {
+    /// JUnit XML, as read by GitLab and most CI servers
+    Junit,
+    /// The same suites and cases as JSON
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    Transaction,
+    Expect,
+    /// The file couldn't be run at all, e.g. its devnet failed to start.
+    Setup,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Case {
+    pub name: String,
+    pub kind: Kind,
+    pub status: Status,
+    /// Seconds.
+    pub time: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl Case {
+    pub fn passed(name: impl Into<String>, kind: Kind, duration: Duration) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            status: Status::Passed,
+            time: duration.as_secs_f64(),
+            message: None,
+        }
+    }
+
+    pub fn failed(
+        name: impl Into<String>,
+        kind: Kind,
+        duration: Duration,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            status: Status::Failed,
+            time: duration.as_secs_f64(),
+            message: Some(message.into()),
+        }
+    }
+
+    pub fn skipped(name: impl Into<String>, kind: Kind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            status: Status::Skipped,
+            time: 0.0,
+            message: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Suite {
+    /// The test file, as given or discovered.
+    pub name: String,
+    pub tests: usize,
+    pub failures: usize,
+    pub skipped: usize,
+    pub time: f64,
+    pub cases: Vec<Case>,
+}
+
+impl Suite {
+    fn new(report: &FileReport) -> Self {
+        let count = |status: Status| report.cases.iter().filter(|c| c.status == status).count();
+
+        Self {
+            name: report.path.display().to_string(),
+            tests: report.cases.len(),
+            failures: count(Status::Failed),
+            skipped: count(Status::Skipped),
+            time: report.duration.as_secs_f64(),
+            cases: report.cases.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    pub suites: Vec<Suite>,
+}
+
+impl Report {
+    pub fn new(reports: &[FileReport]) -> Self {
+        Self {
+            suites: reports.iter().map(Suite::new).collect(),
+        }
+    }
+
+    /// The cases of the first suite in the JSON report at `path`, or none
+    /// when it is missing or unreadable.
+    pub fn read_cases(path: &Path) -> Vec<Case> {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<Report>(&json).ok())
+            .and_then(|report| report.suites.into_iter().next())
+            .map(|suite| suite.cases)
+            .unwrap_or_default()
+    }
+
+    pub fn render(&self, format: Format) -> Result<String> {
+        match format {
+            Format::Junit => Ok(self.junit()),
+            Format::Json => serde_json::to_string_pretty(self).into_diagnostic(),
+        }
+    }
+
+    pub fn write(&self, format: Format, path: &Path) -> Result<()> {
+        std::fs::write(path, self.render(format)?)
+            .into_diagnostic()
+            .with_context(|| format!("writing test report to {}", path.display()))
+    }
+
+    fn junit(&self) -> String {
+        let total = |count: fn(&Suite) -> usize| self.suites.iter().map(count).sum::<usize>();
+        let time: f64 = self.suites.iter().map(|s| s.time).sum();
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+        xml.push_str(&format!(
+            "<testsuites tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{time:.3}\">\n",
+            total(|s| s.tests),
+            total(|s| s.failures),
+            total(|s| s.skipped),
+        ));
+
+        for suite in &self.suites {
+            let name = escape(&suite.name);
+
+            xml.push_str(&format!(
+                "  <testsuite name=\"{name}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">\n",
+                suite.tests, suite.failures, suite.skipped, suite.time
+            ));
+
+            for case in &suite.cases {
+                let open = format!(
+                    "    <testcase name=\"{}\" classname=\"{name}\" time=\"{:.3}\"",
+                    escape(&case.name),
+                    case.time
+                );
+
+                match case.status {
+                    Status::Passed => xml.push_str(&format!("{open}/>\n")),
+                    Status::Skipped => {
+                        xml.push_str(&format!("{open}>\n      <skipped/>\n    </testcase>\n"))
+                    }
+                    Status::Failed => {
+                        let message = case.message.as_deref().unwrap_or_default();
+                        let summary = message.lines().next().unwrap_or_default();
+
+                        xml.push_str(&format!(
+                            "{open}>\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+                            escape(summary),
+                            escape(message)
+                        ));
+                    }
+                }
+            }
+
+            xml.push_str("  </testsuite>\n");
+        }
+
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+}
+
+/// Escape `text` for an XML attribute or text node, dropping the control
+/// characters (e.g. terminal colour codes) XML 1.0 doesn't allow.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\n' | '\t' | '\r' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::commands::test::suite::Outcome;
+
+    fn report() -> Report {
+        Report::new(&[FileReport {
+            path: PathBuf::from("tests/swap.toml"),
+            outcome: Outcome::Failed,
+            duration: Duration::from_millis(4500),
+            cases: vec![
+                Case::passed("alice pays bob", Kind::Transaction, Duration::from_secs(2)),
+                Case::skipped("burn <all>", Kind::Transaction),
+                Case::failed(
+                    "expect balance of @bob",
+                    Kind::Expect,
+                    Duration::from_millis(250),
+                    "Test Failed: wallet `@bob` out of range.\nFound: \"0\" \u{1b}[31mlovelace",
+                ),
+            ],
+        }])
+    }
+
+    #[test]
+    fn junit_counts_and_escapes_cases() {
+        let xml = report().render(Format::Junit).unwrap();
+
+        assert!(xml.contains(r#"<testsuites tests="3" failures="1" skipped="1" time="4.500">"#));
+        assert!(xml.contains(
+            r#"<testsuite name="tests/swap.toml" tests="3" failures="1" skipped="1" time="4.500">"#
+        ));
+        assert!(xml.contains(
+            r#"<testcase name="alice pays bob" classname="tests/swap.toml" time="2.000"/>"#
+        ));
+        assert!(xml.contains(r#"<testcase name="burn &lt;all&gt;""#));
+        assert!(xml.contains(
+            r#"<failure message="Test Failed: wallet `@bob` out of range.">Test Failed: wallet `@bob` out of range.
+Found: &quot;0&quot; [31mlovelace</failure>"#
+        ));
+    }
+
+    #[test]
+    fn json_round_trips_worker_cases() {
+        let report = report();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.json");
+
+        report.write(Format::Json, &path).unwrap();
+
+        assert_eq!(Report::read_cases(&path), report.suites[0].cases);
+        assert!(Report::read_cases(&dir.path().join("missing.json")).is_empty());
+    }
+}
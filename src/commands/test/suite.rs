@@ -0,0 +1,149 @@
+//! Running several test files in one `trix test`: discovery under a
+//! directory and the per-file summary printed at the end.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use miette::{Result, bail};
+
+use super::report::Case;
+
+/// Every `*.toml` under `dir`, at any depth, in path order. With `select`,
+/// only paths containing it.
+pub fn discover(dir: &Path, select: Option<&str>) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        bail!(
+            help = "pass a test file or a directory of them, e.g. `trix test tests/basic.toml`",
+            "no test directory at {}",
+            dir.display()
+        );
+    }
+
+    let files: Vec<PathBuf> = crate::fsutil::files_under(dir)?
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .filter(|path| select.is_none_or(|s| path.to_string_lossy().contains(s)))
+        .collect();
+
+    if files.is_empty() {
+        match select {
+            Some(select) => bail!("no test file under {} matches `{select}`", dir.display()),
+            None => bail!("no test files (*.toml) under {}", dir.display()),
+        }
+    }
+
+    Ok(files)
+}
+
+/// Suffix for the devnet home of one test file, so each file runs against
+/// a ledger of its own.
+pub fn home_suffix(path: &Path) -> String {
+    let slug: String = path
+        .with_extension("")
+        .to_string_lossy()
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_lowercase(),
+            false => '-',
+        })
+        .collect();
+
+    format!("test-{}", slug.trim_matches('-'))
+}
+
+pub enum Outcome {
+    Passed,
+    Failed,
+    /// `--filter` left no transaction to run.
+    Skipped,
+    /// Cancelled by `--fail-fast` after another file failed.
+    NotRun,
+}
+
+pub struct FileReport {
+    pub path: PathBuf,
+    pub outcome: Outcome,
+    pub duration: Duration,
+    /// Its transactions and expectations, for `--report`.
+    pub cases: Vec<Case>,
+}
+
+pub fn print_summary(reports: &[FileReport]) {
+    println!("== Test summary ==\n");
+
+    let width = reports
+        .iter()
+        .map(|r| r.path.display().to_string().len())
+        .max()
+        .unwrap_or_default();
+
+    for report in reports {
+        let status = match report.outcome {
+            Outcome::Passed => "passed",
+            Outcome::Failed => "FAILED",
+            Outcome::Skipped => "skipped",
+            Outcome::NotRun => "not run",
+        };
+
+        println!(
+            "  {status:<7}  {:<width$}  {:>6.1}s",
+            report.path.display(),
+            report.duration.as_secs_f64()
+        );
+    }
+
+    let count =
+        |outcome: fn(&Outcome) -> bool| reports.iter().filter(|r| outcome(&r.outcome)).count();
+
+    let not_run = match count(|o| matches!(o, Outcome::NotRun)) {
+        0 => String::new(),
+        n => format!(", {n} not run"),
+    };
+
+    println!(
+        "\n{} passed, {} failed, {} skipped{not_run}\n",
+        count(|o| matches!(o, Outcome::Passed)),
+        count(|o| matches!(o, Outcome::Failed)),
+        count(|o| matches!(o, Outcome::Skipped))
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discovers_nested_toml_files_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+
+        for file in ["b.toml", "a.toml", "nested/c.toml", "notes.md"] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+
+        let names = |files: Vec<PathBuf>| -> Vec<String> {
+            files
+                .iter()
+                .map(|f| f.strip_prefix(dir.path()).unwrap().display().to_string())
+                .collect()
+        };
+
+        let all = discover(dir.path(), None).unwrap();
+        assert_eq!(names(all), ["a.toml", "b.toml", "nested/c.toml"]);
+
+        let nested = discover(dir.path(), Some("nested")).unwrap();
+        assert_eq!(names(nested), ["nested/c.toml"]);
+
+        let err = discover(dir.path(), Some("missing")).unwrap_err();
+        assert!(err.to_string().contains("matches `missing`"), "{err}");
+    }
+
+    #[test]
+    fn home_suffix_is_a_single_path_segment() {
+        assert_eq!(
+            home_suffix(Path::new("tests/Nested/swap.toml")),
+            "test-tests-nested-swap"
+        );
+    }
+}
@@ -1,5 +1,4 @@
 use askama::Template;
-use termimad::MadSkin;
 
 #[derive(Debug, Clone)]
 pub struct UseView {
@@ -22,6 +21,5 @@ pub fn render(view: &UseView) {
     let markdown = UseTemplate { view }
         .render()
         .expect("Template rendering failed");
-    let skin = MadSkin::default();
-    skin.print_text(&markdown);
+    crate::term::console::print_markdown(&markdown);
 }
@@ -0,0 +1,77 @@
+use clap::Args as ClapArgs;
+use miette::IntoDiagnostic as _;
+use serde::Serialize;
+
+use crate::config::{ProfileConfig, RootConfig};
+use crate::term::OutputFormat;
+use crate::wallet::WalletProxy;
+
+#[derive(ClapArgs)]
+pub struct Args {
+    /// Identity to query, e.g. `alice` or `@alice`
+    name: String,
+
+    /// `json` prints the balance as a JSON object instead of a table
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    output: OutputFormat,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BalanceView {
+    pub wallet: String,
+    pub address: String,
+    pub lovelace: u64,
+}
+
+/// Lovelace identity `name` holds, queried through `wallet`.
+pub fn query(
+    wallet: &WalletProxy,
+    profile: &ProfileConfig,
+    name: &str,
+) -> miette::Result<BalanceView> {
+    let (name, address) = super::resolve_identity(wallet, profile, name)?;
+
+    let balance = crate::spawn::cshell::wallet_balance(&wallet.target_dir, name)?;
+
+    Ok(BalanceView {
+        wallet: name.to_string(),
+        address: address.to_string(),
+        lovelace: balance.coin,
+    })
+}
+
+pub fn print_table(view: &BalanceView) {
+    let name_width = view.wallet.len().max("WALLET".len());
+    let address_width = view.address.len();
+
+    println!(
+        "{:<name_width$}  {:<address_width$}  LOVELACE",
+        "WALLET", "ADDRESS"
+    );
+    println!(
+        "{:<name_width$}  {}  {}",
+        view.wallet, view.address, view.lovelace
+    );
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    let wallet = crate::wallet::setup(config, profile)?;
+
+    let view = query(&wallet, profile, &args.name)?;
+
+    match args.output {
+        OutputFormat::Human => {
+            print_table(&view);
+
+            let network = config.resolve_profile_network(&profile.name)?;
+            if let Some(url) = network.explorer_address_url(&view.address) {
+                println!("\nExplorer: {}", crate::term::hyperlink(&url, &url));
+            }
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&view).into_diagnostic()?);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use clap::Args as ClapArgs;
+use miette::{Context as _, IntoDiagnostic as _, bail};
+
+use crate::config::{ProfileConfig, RootConfig};
+
+#[derive(ClapArgs)]
+pub struct Args {
+    /// Identity to export, e.g. `alice` or `@alice`
+    name: String,
+
+    /// File to write the cardano-cli signing key envelope to
+    #[arg(long, value_name = "PATH")]
+    out: PathBuf,
+
+    /// Replace the file at --out if it exists
+    #[arg(long)]
+    force: bool,
+}
+
+/// Write `contents` to `path`, readable by the owner only when the file is
+/// created.
+fn write_key(path: &Path, contents: &str) -> miette::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt as _;
+        options.mode(0o600);
+    }
+
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .into_diagnostic()
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    if args.out.exists() && !args.force {
+        bail!(
+            help = "pick another path, or pass --force to replace it",
+            "{} already exists",
+            args.out.display()
+        );
+    }
+
+    let wallet = crate::wallet::setup(config, profile)?;
+
+    let name = args.name.trim_start_matches('@');
+
+    let Some(address) = wallet.addresses.get(name) else {
+        let mut available: Vec<_> = wallet.addresses.keys().map(String::as_str).collect();
+        available.sort();
+
+        bail!(
+            help = format!("available identities: {}", available.join(", ")),
+            "profile `{}` has no identity named `{name}`",
+            profile.name
+        );
+    };
+
+    let key = crate::wallet::identity_signing_key(profile, &wallet.derivation, name)?;
+
+    // The address comes from cshell; a key that doesn't pay to it would
+    // sign for some other wallet.
+    if crate::wallet::payment_key_hash(address) != Some(key.key_hash()) {
+        bail!(
+            "the key derived for `{name}` doesn't match its address {address}; refusing to export it"
+        );
+    }
+
+    write_key(&args.out, &(key.to_envelope()? + "\n"))?;
+
+    println!("exported `{name}` to {}", args.out.display());
+    println!("address: {address}");
+    eprintln!(
+        "warning: {} holds the unencrypted signing key; keep it out of version control",
+        args.out.display()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,289 @@
+//! `trix wallet fund`: send lovelace from a funded identity to another
+//! wallet on the running local devnet, without writing a transfer by hand.
+//! The transfer comes from a small built-in protocol, built with tx3c and
+//! submitted through cshell like any `trix invoke`, so the devnet keeps
+//! running and the funds show up in the next block.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::Duration;
+
+use clap::Args as ClapArgs;
+use miette::bail;
+use pallas::ledger::addresses::Address;
+
+use crate::config::{KnownNetwork, NetworkConfig, ProfileConfig, RootConfig};
+use crate::spawn::cshell;
+use crate::wallet::WalletProxy;
+
+const FAUCET_TX3: &str = include_str!("../../../templates/wallet/faucet.tx3");
+
+/// Checks for the transfer in the recipient's balance before giving up.
+const BALANCE_CHECKS: u32 = 10;
+
+const BALANCE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(ClapArgs)]
+pub struct Args {
+    /// Wallet to fund, e.g. `carol` or `@carol`, or a bech32 address
+    #[arg(long, value_name = "NAME|ADDRESS")]
+    to: String,
+
+    /// Lovelace to send
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    amount: u64,
+
+    /// Identity paying for the transfer; defaults to the first one
+    /// devnet.toml funds
+    #[arg(long, value_name = "NAME")]
+    from: Option<String>,
+}
+
+/// Funding is for the devnet: refuse a profile on any other network.
+fn ensure_devnet(profile: &ProfileConfig, network: &NetworkConfig) -> miette::Result<()> {
+    let devnet = KnownNetwork::CardanoLocal.as_network_name();
+
+    if network.name != devnet {
+        bail!(
+            help = "run it against the local devnet, e.g. with `--profile local`",
+            "`trix wallet fund` only targets the `{devnet}` network, but profile `{}` uses `{}`",
+            profile.name,
+            network.name
+        );
+    }
+
+    Ok(())
+}
+
+/// The wallet name (if `to` names one) and address to send to.
+fn recipient<'a>(
+    addresses: &'a HashMap<String, String>,
+    to: &'a str,
+) -> miette::Result<(Option<&'a str>, &'a str)> {
+    if Address::from_bech32(to).is_ok() {
+        return Ok((None, to));
+    }
+
+    let name = to.trim_start_matches('@');
+
+    match addresses.get_key_value(name) {
+        Some((name, address)) => Ok((Some(name), address)),
+        None => {
+            let mut available: Vec<_> = addresses.keys().map(String::as_str).collect();
+            available.sort();
+
+            bail!(
+                help = format!("pass a bech32 address, or one of: {}", available.join(", ")),
+                "`{to}` is neither an address nor an identity of the profile"
+            );
+        }
+    }
+}
+
+/// The first identity, by name, that devnet.toml seeds with lovelace and
+/// cshell can sign for, other than `recipient`.
+fn default_sender(
+    profile: &ProfileConfig,
+    devnet: &crate::devnet::Config,
+    addresses: &HashMap<String, String>,
+    recipient: Option<&str>,
+) -> Option<String> {
+    let mut funded: Vec<_> = addresses
+        .iter()
+        .filter(|(name, _)| Some(name.as_str()) != recipient)
+        .filter(|(name, _)| {
+            profile
+                .identities
+                .get(*name)
+                .is_some_and(crate::wallet::in_cshell)
+        })
+        .filter(|(name, address)| {
+            crate::devnet::manifest::initial_lovelace(devnet, name, address) > 0
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    funded.sort();
+    funded.into_iter().next()
+}
+
+/// Build the faucet protocol's TII next to the project's other artifacts.
+fn build_faucet(config: &RootConfig) -> miette::Result<PathBuf> {
+    let dir = crate::dirs::target_dir("faucet")?;
+
+    let source = dir.join("faucet.tx3");
+    crate::fsutil::write_atomic(&source, FAUCET_TX3)?;
+
+    let tii = dir.join("faucet.tii");
+    crate::spawn::tx3c::build_tii(&source, &tii, config)?;
+
+    Ok(tii)
+}
+
+/// Balance of wallet `name` once it reaches `expected`, or the last one
+/// seen when the devnet hasn't included the transfer in time.
+fn await_balance(home: &Path, name: &str, expected: u64) -> miette::Result<u64> {
+    let mut coin = 0;
+
+    for _ in 0..BALANCE_CHECKS {
+        sleep(BALANCE_CHECK_INTERVAL);
+
+        coin = cshell::wallet_balance(home, name)?.coin;
+
+        if coin >= expected {
+            return Ok(coin);
+        }
+    }
+
+    eprintln!("warning: the transfer hasn't shown up in @{name}'s balance yet");
+
+    Ok(coin)
+}
+
+fn sender(
+    args: &Args,
+    profile: &ProfileConfig,
+    wallet: &WalletProxy,
+    recipient: Option<&str>,
+) -> miette::Result<String> {
+    if let Some(from) = &args.from {
+        let (name, _) = super::resolve_identity(wallet, profile, from)?;
+        return Ok(name.to_string());
+    }
+
+    let path = crate::dirs::protocol_root()?.join("devnet.toml");
+
+    if !path.is_file() {
+        bail!(
+            help = "name the paying identity with --from",
+            "no devnet.toml to pick a funded identity from"
+        );
+    }
+
+    let devnet = crate::devnet::Config::load(&path)?;
+
+    match default_sender(profile, &devnet, &wallet.addresses, recipient) {
+        Some(name) => Ok(name),
+        None => bail!(
+            help = "name the paying identity with --from",
+            "devnet.toml funds no identity that could pay for the transfer"
+        ),
+    }
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    let network = config.resolve_profile_network(&profile.name)?;
+    ensure_devnet(profile, &network)?;
+
+    let wallet = crate::wallet::setup(config, profile)?;
+
+    let (to_name, to_address) = recipient(&wallet.addresses, &args.to)?;
+
+    let from = sender(&args, profile, &wallet, to_name)?;
+
+    if wallet.addresses.get(&from).map(String::as_str) == Some(to_address) {
+        bail!("`{from}` can't fund its own address");
+    }
+
+    // Only a wallet cshell holds has a balance to read; the transfer to any
+    // other identity is reported by its tx hash alone.
+    let watched = to_name.filter(|name| {
+        profile
+            .identities
+            .get(*name)
+            .is_some_and(crate::wallet::in_cshell)
+    });
+
+    let before = match watched {
+        Some(name) => Some(cshell::wallet_balance(&wallet.target_dir, name)?.coin),
+        None => None,
+    };
+
+    let tii_file = build_faucet(config)?;
+
+    let tx_args = serde_json::json!({
+        "sender": wallet.addresses[&from],
+        "receiver": to_address,
+        "quantity": args.amount,
+    });
+
+    let output = wallet.invoke_json(
+        &tii_file,
+        "transfer",
+        &tx_args,
+        vec![&from],
+        &profile.name,
+        false,
+    )?;
+
+    println!("sent {} lovelace from @{from} to {}", args.amount, args.to);
+
+    if let Some(hash) = cshell::tx_hash(&output) {
+        println!("tx: {hash}");
+    }
+
+    if let (Some(name), Some(before)) = (watched, before) {
+        let balance = await_balance(&wallet.target_dir, name, before + args.amount)?;
+        println!("balance of @{name}: {balance} lovelace");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAROL: &str = "addr_test1vqqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgasfzjt";
+
+    fn addresses() -> HashMap<String, String> {
+        ["alice", "bob", "carol", "treasury"]
+            .into_iter()
+            .map(|name| (name.to_string(), format!("addr_test1{name}")))
+            .collect()
+    }
+
+    fn profile() -> ProfileConfig {
+        toml::from_str(
+            "network = \"cardano-local\"\n\n\
+             [identities.treasury]\ntype = \"ExplicitKey\"\nkey_path = \"treasury.skey\"\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn sender_defaults_to_the_first_funded_identity() {
+        let devnet: crate::devnet::Config = toml::from_str(
+            "[[utxos]]\naddress = \"@treasury\"\nvalue = 9000000\n\n\
+             [[utxos]]\naddress = \"@bob\"\nvalue = 5000000\n\n\
+             [[utxos]]\naddress = \"@carol\"\nvalue = 5000000\n",
+        )
+        .unwrap();
+
+        let addresses = addresses();
+
+        assert_eq!(
+            default_sender(&profile(), &devnet, &addresses, None).as_deref(),
+            Some("bob")
+        );
+        assert_eq!(
+            default_sender(&profile(), &devnet, &addresses, Some("bob")).as_deref(),
+            Some("carol")
+        );
+    }
+
+    #[test]
+    fn recipient_is_an_identity_or_an_address() {
+        let addresses = addresses();
+
+        assert_eq!(
+            recipient(&addresses, "@carol").unwrap(),
+            (Some("carol"), "addr_test1carol")
+        );
+        assert_eq!(recipient(&addresses, CAROL).unwrap(), (None, CAROL));
+
+        let err = recipient(&addresses, "dave").unwrap_err().to_string();
+        assert!(err.contains("`dave`"), "{err}");
+    }
+}
@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+
+use clap::Args as ClapArgs;
+use miette::{Context as _, bail};
+
+use crate::config::{
+    ExplicitKeyIdentityConfig, IdentityConfig, MnemonicIdentityConfig, ProfileConfig, RootConfig,
+};
+use crate::secrets::SecretRef;
+
+#[derive(ClapArgs)]
+pub struct Args {
+    /// Identity name, referenced as `@name` in devnet.toml and tests
+    name: String,
+
+    /// `env:VAR` or `keyring:service/account` reference to a 24-word
+    /// BIP-39 phrase, which keeps the phrase out of trix.toml. Without it
+    /// (or --key-path) the phrase is read from stdin or a hidden prompt.
+    #[arg(long, conflicts_with = "key_path")]
+    mnemonic: Option<String>,
+
+    /// ed25519 signing key file (cardano-cli envelope or CBOR hex), e.g.
+    /// one written by `trix wallet export`
+    #[arg(long, visible_alias = "key")]
+    key_path: Option<PathBuf>,
+
+    /// Replace an identity of the same name
+    #[arg(long)]
+    force: bool,
+}
+
+fn identity(args: &Args) -> miette::Result<IdentityConfig> {
+    if let Some(key_path) = &args.key_path {
+        crate::wallet::read_signing_key(key_path)?;
+
+        return Ok(IdentityConfig::ExplicitKey(ExplicitKeyIdentityConfig {
+            name: args.name.clone(),
+            key_path: key_path.clone(),
+        }));
+    }
+
+    let mnemonic = match &args.mnemonic {
+        Some(mnemonic) => {
+            let reference = SecretRef::parse(mnemonic)?;
+
+            if matches!(reference, SecretRef::Literal(_)) {
+                bail!(
+                    help = "leave out --mnemonic to pipe the phrase on stdin or type it at a hidden prompt",
+                    "--mnemonic takes an `env:` or `keyring:` reference; a phrase on the command line ends up in shell history and the process list"
+                );
+            }
+
+            let phrase = reference.resolve().context("resolving --mnemonic")?;
+            crate::wallet::parse_mnemonic(&phrase)?;
+
+            mnemonic.clone()
+        }
+        None => {
+            let phrase = crate::term::prompt::secret("Mnemonic (24 words):", "mnemonic")?;
+            crate::wallet::parse_mnemonic(&phrase)?;
+
+            eprintln!(
+                "warning: the mnemonic is written to trix.toml as is; store it with `trix secret set` and import a `keyring:` reference to keep it out of the file"
+            );
+
+            phrase
+        }
+    };
+
+    Ok(IdentityConfig::Mnemonic(MnemonicIdentityConfig {
+        name: args.name.clone(),
+        mnemonic: Some(mnemonic),
+        mnemonic_env: None,
+        account_index: None,
+    }))
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    if profile.identities.contains_key(&args.name) && !args.force {
+        bail!(
+            help = "pick another name, or pass --force to replace it",
+            "profile `{}` already has an identity named `{}`",
+            profile.name,
+            args.name
+        );
+    }
+
+    let identity = identity(&args)?;
+
+    let mut next = config.clone();
+
+    // A built-in profile gets an explicit entry carrying its inherited
+    // network and identities, since an explicit profile replaces it.
+    let entry = next
+        .profiles
+        .entry(profile.name.clone())
+        .or_insert_with(|| profile.clone());
+
+    entry.identities.insert(args.name.clone(), identity);
+
+    let resolved = next.resolve_profile(&profile.name)?;
+
+    // Register with cshell before saving, so a key cshell rejects never
+    // reaches trix.toml.
+    let wallet = crate::wallet::setup(&next, &resolved)?;
+
+    next.save(&crate::dirs::protocol_root()?.join("trix.toml"))?;
+
+    println!("imported `{}` into profile `{}`", args.name, profile.name);
+
+    if let Some(address) = wallet.addresses.get(&args.name) {
+        println!("address: {address}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use clap::Args as ClapArgs;
+use miette::IntoDiagnostic as _;
+use serde::Serialize;
+
+use crate::config::{IdentityConfig, ProfileConfig, RootConfig};
+use crate::term::OutputFormat;
+
+#[derive(ClapArgs)]
+pub struct Args {
+    /// `json` prints the wallets as a JSON array instead of a table
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    output: OutputFormat,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WalletView {
+    pub name: String,
+    pub r#type: &'static str,
+    /// `None` when cshell couldn't set up the wallets.
+    pub address: Option<String>,
+}
+
+fn kind(identity: &IdentityConfig) -> &'static str {
+    match identity {
+        IdentityConfig::RandomKey(_) => "random-key",
+        IdentityConfig::ExplicitKey(_) => "signing-key",
+        IdentityConfig::Mnemonic(_) => "mnemonic",
+    }
+}
+
+/// The table for `wallets`; without addresses only the name and type
+/// columns are shown.
+fn render_table(wallets: &[WalletView], with_addresses: bool) -> String {
+    if wallets.is_empty() {
+        return "(no wallets)\n".to_string();
+    }
+
+    let name_width = wallets
+        .iter()
+        .map(|w| w.name.len())
+        .fold("NAME".len(), usize::max);
+    let type_width = wallets
+        .iter()
+        .map(|w| w.r#type.len())
+        .fold("TYPE".len(), usize::max);
+
+    let mut out = String::new();
+
+    let row = |out: &mut String, name: &str, kind: &str, address: Option<&str>| {
+        let line = match address {
+            Some(address) => format!("{name:<name_width$}  {kind:<type_width$}  {address}"),
+            None => format!("{name:<name_width$}  {kind}"),
+        };
+        out.push_str(line.trim_end());
+        out.push('\n');
+    };
+
+    row(
+        &mut out,
+        "NAME",
+        "TYPE",
+        with_addresses.then_some("ADDRESS"),
+    );
+
+    for wallet in wallets {
+        let address = wallet.address.as_deref().unwrap_or("-");
+        row(
+            &mut out,
+            &wallet.name,
+            wallet.r#type,
+            with_addresses.then_some(address),
+        );
+    }
+
+    out
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    // Addresses come from the wallets cshell derives; without cshell the
+    // identities are still worth listing from the config alone.
+    let addresses = match crate::wallet::setup(config, profile) {
+        Ok(wallet) => Some(wallet.addresses),
+        Err(err) => {
+            eprintln!("note: addresses unavailable, cshell couldn't set up the wallets: {err}");
+            None
+        }
+    };
+
+    let mut wallets: Vec<_> = profile
+        .identities
+        .iter()
+        .map(|(name, identity)| WalletView {
+            name: name.clone(),
+            r#type: kind(identity),
+            address: addresses
+                .as_ref()
+                .and_then(|addresses| addresses.get(name).cloned()),
+        })
+        .collect();
+
+    wallets.sort_by(|a, b| a.name.cmp(&b.name));
+
+    match args.output {
+        OutputFormat::Human => print!("{}", render_table(&wallets, addresses.is_some())),
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&wallets).into_diagnostic()?
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wallets() -> Vec<WalletView> {
+        vec![
+            WalletView {
+                name: "alice".to_string(),
+                r#type: "random-key",
+                address: Some("addr_test1alice".to_string()),
+            },
+            WalletView {
+                name: "treasury".to_string(),
+                r#type: "signing-key",
+                address: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn renders_addresses_when_resolved() {
+        assert_eq!(
+            render_table(&wallets(), true),
+            "NAME      TYPE         ADDRESS\n\
+             alice     random-key   addr_test1alice\n\
+             treasury  signing-key  -\n"
+        );
+    }
+
+    #[test]
+    fn renders_name_and_type_without_cshell() {
+        assert_eq!(
+            render_table(&wallets(), false),
+            "NAME      TYPE\n\
+             alice     random-key\n\
+             treasury  signing-key\n"
+        );
+    }
+
+    #[test]
+    fn renders_empty_profile() {
+        assert_eq!(render_table(&[], true), "(no wallets)\n");
+    }
+}
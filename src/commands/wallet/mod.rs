@@ -0,0 +1,78 @@
+use clap::{Args as ClapArgs, Subcommand};
+use miette::bail;
+
+use crate::config::{IdentityConfig, ProfileConfig, RootConfig};
+use crate::wallet::WalletProxy;
+
+pub mod balance;
+pub mod export;
+pub mod fund;
+pub mod import;
+pub mod list;
+pub mod reconcile;
+pub mod utxos;
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Show the lovelace an identity holds on the profile's network
+    Balance(balance::Args),
+    /// Write an identity's signing key as a cardano-cli key file
+    Export(export::Args),
+    /// Send lovelace to a wallet on the local devnet
+    Fund(fund::Args),
+    /// Add an existing wallet (mnemonic or signing key) as an identity
+    Import(import::Args),
+    /// List the profile's identities with their type and address
+    List(list::Args),
+    /// Report identities whose address changed with the derivation mode
+    Reconcile(reconcile::Args),
+    /// List the UTxOs an identity holds on the profile's network
+    Utxos(utxos::Args),
+}
+
+#[derive(ClapArgs)]
+pub struct Args {
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+/// The cshell wallet of identity `name` (`@` optional) and its address.
+fn resolve_identity<'a>(
+    wallet: &'a WalletProxy,
+    profile: &ProfileConfig,
+    name: &'a str,
+) -> miette::Result<(&'a str, &'a str)> {
+    let name = name.trim_start_matches('@');
+
+    let Some(address) = wallet.addresses.get(name) else {
+        let mut available: Vec<_> = wallet.addresses.keys().map(String::as_str).collect();
+        available.sort();
+
+        bail!(
+            help = format!("available identities: {}", available.join(", ")),
+            "profile `{}` has no identity named `{name}`",
+            profile.name
+        );
+    };
+
+    if let Some(IdentityConfig::ExplicitKey(_)) = profile.identities.get(name) {
+        bail!(
+            help = format!("look its address up in an explorer instead: {address}"),
+            "identity `{name}` is a signing key file, which cshell can't query"
+        );
+    }
+
+    Ok((name, address))
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    match args.command {
+        Command::Balance(args) => balance::run(args, config, profile),
+        Command::Export(args) => export::run(args, config, profile),
+        Command::Fund(args) => fund::run(args, config, profile),
+        Command::Import(args) => import::run(args, config, profile),
+        Command::List(args) => list::run(args, config, profile),
+        Command::Reconcile(args) => reconcile::run(args, config, profile),
+        Command::Utxos(args) => utxos::run(args, config, profile),
+    }
+}
@@ -0,0 +1,132 @@
+use askama::Template;
+use clap::Args as ClapArgs;
+use miette::{Context as _, IntoDiagnostic as _};
+
+use crate::config::{IdentityConfig, ProfileConfig, RootConfig};
+use crate::wallet::Derivation;
+
+#[derive(ClapArgs)]
+pub struct Args {}
+
+// ============================================================================
+// View Model
+// ============================================================================
+
+#[derive(Debug, Clone)]
+pub struct IdentityChangeView {
+    pub name: String,
+    pub current: String,
+    pub other: String,
+}
+
+impl IdentityChangeView {
+    pub fn changed(&self) -> bool {
+        self.current != self.other
+    }
+
+    /// Both addresses share one line, so each gets half the width left
+    /// after the name and labels.
+    fn fit(&self, address: &str) -> String {
+        use crate::term::console::{truncate_middle, value_width};
+
+        let overhead = self.name.len() + 24;
+        truncate_middle(address, value_width(overhead) / 2)
+    }
+
+    pub fn current_display(&self) -> String {
+        self.fit(&self.current)
+    }
+
+    pub fn other_display(&self) -> String {
+        self.fit(&self.other)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReconcileView {
+    pub profile: String,
+    pub current_mode: String,
+    pub other_mode: String,
+    pub identities: Vec<IdentityChangeView>,
+    pub changed: usize,
+    pub legacy: bool,
+}
+
+#[derive(Template)]
+#[template(path = "wallet/reconcile.md")]
+struct ReconcileTemplate<'a> {
+    view: &'a ReconcileView,
+}
+
+// ============================================================================
+// Command Entry Point
+// ============================================================================
+
+fn describe(derivation: &Derivation) -> String {
+    match derivation {
+        Derivation::Legacy => "legacy (name only)".to_string(),
+        Derivation::Namespaced(namespace) => format!("namespaced by `{namespace}`"),
+    }
+}
+
+/// The derivation a project would use with `legacy_wallet_derivation`
+/// flipped.
+fn other_derivation(config: &RootConfig) -> Derivation {
+    let mut flipped = config.clone();
+    flipped.legacy_wallet_derivation = !config.legacy_wallet_derivation;
+    Derivation::for_project(&flipped)
+}
+
+pub fn run(_args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    let wallet = crate::wallet::setup(config, profile)?;
+
+    let other = other_derivation(config);
+
+    // Restore the alternate keys into a scratch cshell store so the
+    // project's own store keeps the current addresses.
+    let scratch = tempfile::tempdir().into_diagnostic()?;
+
+    std::fs::copy(
+        wallet.target_dir.join("cshell.toml"),
+        scratch.path().join("cshell.toml"),
+    )
+    .into_diagnostic()
+    .context("preparing scratch cshell config")?;
+
+    let mut identities = vec![];
+
+    for (name, ident) in profile.identities.iter() {
+        let IdentityConfig::RandomKey(ident) = ident else {
+            continue;
+        };
+
+        let current = wallet.addresses.get(name).cloned().unwrap_or_default();
+
+        let other = crate::wallet::setup_wallet_key(scratch.path(), &ident.name, &other)?;
+
+        identities.push(IdentityChangeView {
+            name: name.clone(),
+            current,
+            other,
+        });
+    }
+
+    identities.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let view = ReconcileView {
+        profile: profile.name.clone(),
+        current_mode: describe(&wallet.derivation),
+        other_mode: describe(&other),
+        changed: identities.iter().filter(|i| i.changed()).count(),
+        identities,
+        legacy: config.legacy_wallet_derivation,
+    };
+
+    let markdown = ReconcileTemplate { view: &view }
+        .render()
+        .expect("Template rendering failed");
+
+    crate::term::console::print_markdown(&markdown);
+
+    Ok(())
+}
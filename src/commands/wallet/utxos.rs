@@ -0,0 +1,154 @@
+use clap::Args as ClapArgs;
+use miette::IntoDiagnostic as _;
+use serde::Serialize;
+
+use crate::commands::devnet::dump::DumpUtxo;
+use crate::config::{ProfileConfig, RootConfig};
+use crate::spawn::cshell::UTxO;
+use crate::term::OutputFormat;
+use crate::wallet::WalletProxy;
+
+#[derive(ClapArgs)]
+pub struct Args {
+    /// Identity to query, e.g. `alice` or `@alice`
+    name: String,
+
+    /// `json` prints the UTxOs as a JSON array instead of a table
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    output: OutputFormat,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssetView {
+    pub policy: String,
+    /// Asset name, hex encoded.
+    pub name: String,
+    pub quantity: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UtxoView {
+    pub r#ref: Option<String>,
+    /// Decimal string, as cshell reports it.
+    pub lovelace: String,
+    pub assets: Vec<AssetView>,
+    /// Hash of the datum, if the UTxO has one.
+    pub datum: Option<String>,
+    /// The inline datum in the cardano-cli detailed schema.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub datum_value: Option<serde_json::Value>,
+}
+
+impl From<UTxO> for UtxoView {
+    fn from(utxo: UTxO) -> Self {
+        let assets = utxo
+            .assets
+            .iter()
+            .flat_map(|policy| {
+                policy.assets.iter().map(|asset| AssetView {
+                    policy: hex::encode(&policy.policy_id),
+                    name: hex::encode(&asset.name),
+                    quantity: asset.output_coin.clone(),
+                })
+            })
+            .collect();
+
+        Self {
+            r#ref: utxo.txo_ref,
+            lovelace: utxo.coin,
+            assets,
+            datum: utxo.datum.as_ref().map(|d| hex::encode(&d.hash)),
+            datum_value: utxo.datum.and_then(|d| d.payload),
+        }
+    }
+}
+
+/// A UTxO from a u5c query of the whole set. The query only decodes inline
+/// datums to CBOR, so `datum` is set for hashed datums alone.
+impl From<DumpUtxo> for UtxoView {
+    fn from(utxo: DumpUtxo) -> Self {
+        Self {
+            r#ref: Some(utxo.r#ref),
+            lovelace: utxo.value.to_string(),
+            assets: utxo
+                .assets
+                .into_iter()
+                .map(|asset| AssetView {
+                    policy: asset.policy,
+                    name: asset.name,
+                    quantity: asset.amount.to_string(),
+                })
+                .collect(),
+            datum: utxo.datum_hash,
+            datum_value: None,
+        }
+    }
+}
+
+pub fn print_table(utxos: &[UtxoView]) {
+    if utxos.is_empty() {
+        println!("(no utxos)");
+        return;
+    }
+
+    let refs: Vec<_> = utxos
+        .iter()
+        .map(|u| u.r#ref.clone().unwrap_or_else(|| "-".to_string()))
+        .collect();
+
+    let ref_width = refs.iter().map(String::len).fold("REF".len(), usize::max);
+    let coin_width = utxos
+        .iter()
+        .map(|u| u.lovelace.len())
+        .fold("LOVELACE".len(), usize::max);
+
+    println!(
+        "{:<ref_width$}  {:>coin_width$}  ASSETS  DATUM",
+        "REF", "LOVELACE"
+    );
+
+    for (utxo, txo_ref) in utxos.iter().zip(&refs) {
+        println!(
+            "{txo_ref:<ref_width$}  {:>coin_width$}  {:>6}  {}",
+            utxo.lovelace,
+            utxo.assets.len(),
+            utxo.datum.as_deref().unwrap_or("-")
+        );
+    }
+}
+
+/// UTxOs identity `name` holds, queried through `wallet`.
+pub fn query(
+    wallet: &WalletProxy,
+    profile: &ProfileConfig,
+    name: &str,
+) -> miette::Result<Vec<UtxoView>> {
+    let (name, _) = super::resolve_identity(wallet, profile, name)?;
+
+    let provider = crate::wallet::provider_name(&profile.name);
+
+    Ok(
+        crate::spawn::cshell::wallet_utxos(&wallet.target_dir, name, &provider)?
+            .into_iter()
+            .map(UtxoView::from)
+            .collect(),
+    )
+}
+
+pub fn run(args: Args, config: &RootConfig, profile: &ProfileConfig) -> miette::Result<()> {
+    let wallet = crate::wallet::setup(config, profile)?;
+
+    let utxos = query(&wallet, profile, &args.name)?;
+
+    match args.output {
+        OutputFormat::Human => print_table(&utxos),
+        OutputFormat::Json => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&utxos).into_diagnostic()?
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -3,7 +3,7 @@ use std::{
     path::PathBuf,
 };
 
-use miette::Result;
+use miette::{bail, Result};
 
 use crate::config::serde::NamedMap;
 
@@ -71,6 +71,27 @@ impl ProfileConfig {
             .clone()
             .unwrap_or_else(|| PathBuf::from(&format!(".env.{}", self.name)))
     }
+
+    /// `self` with every field `child` sets replaced by the child's value.
+    /// Identities merge, the child winning on a name clash.
+    fn overlay(mut self, child: &ProfileConfig) -> Self {
+        self.name = child.name.clone();
+        self.extends = child.extends.clone();
+
+        if !child.network.is_empty() {
+            self.network = child.network.clone();
+        }
+
+        if child.env_file.is_some() {
+            self.env_file = child.env_file.clone();
+        }
+
+        for (name, identity) in child.identities.iter() {
+            self.identities.insert(name.clone(), identity.clone());
+        }
+
+        self
+    }
 }
 
 const LOCAL_IDENTITIES: &[&str] = &[
@@ -91,6 +112,7 @@ impl From<KnownProfile> for ProfileConfig {
     fn from(profile: KnownProfile) -> Self {
         Self {
             name: profile.as_profile_name().to_string(),
+            extends: None,
             network: KnownNetwork::from(profile).as_network_name().to_string(),
             env_file: None,
             identities: match profile {
@@ -223,6 +245,26 @@ impl From<KnownNetwork> for U5cConfig {
     }
 }
 
+impl KnownNetwork {
+    pub fn default_explorer_url_template(&self) -> Option<&'static str> {
+        match self {
+            KnownNetwork::CardanoMainnet => Some("https://cexplorer.io/tx/{hash}"),
+            KnownNetwork::CardanoPreview => Some("https://preview.cexplorer.io/tx/{hash}"),
+            KnownNetwork::CardanoPreprod => Some("https://preprod.cexplorer.io/tx/{hash}"),
+            KnownNetwork::CardanoLocal => None,
+        }
+    }
+
+    pub fn default_explorer_address_url_template(&self) -> Option<&'static str> {
+        match self {
+            KnownNetwork::CardanoMainnet => Some("https://cexplorer.io/address/{address}"),
+            KnownNetwork::CardanoPreview => Some("https://preview.cexplorer.io/address/{address}"),
+            KnownNetwork::CardanoPreprod => Some("https://preprod.cexplorer.io/address/{address}"),
+            KnownNetwork::CardanoLocal => None,
+        }
+    }
+}
+
 impl From<KnownNetwork> for NetworkConfig {
     fn from(network: KnownNetwork) -> Self {
         Self {
@@ -230,10 +272,42 @@ impl From<KnownNetwork> for NetworkConfig {
             trp: TrpConfig::from(network),
             u5c: U5cConfig::from(network),
             is_testnet: !matches!(network, KnownNetwork::CardanoMainnet),
+            explorer_url_template: network.default_explorer_url_template().map(String::from),
+            explorer_address_url_template: network
+                .default_explorer_address_url_template()
+                .map(String::from),
         }
     }
 }
 
+fn expand_explorer_url(template: Option<&str>, placeholder: &str, value: &str) -> Option<String> {
+    let template = template?;
+
+    if !template.contains(placeholder) {
+        return None;
+    }
+
+    Some(template.replace(placeholder, value))
+}
+
+impl NetworkConfig {
+    /// Explorer URL for a transaction, if the network's tx template takes a
+    /// `{hash}`.
+    pub fn explorer_tx_url(&self, hash: &str) -> Option<String> {
+        expand_explorer_url(self.explorer_url_template.as_deref(), "{hash}", hash)
+    }
+
+    /// Explorer URL for a wallet address, if the network's address
+    /// template takes an `{address}`.
+    pub fn explorer_address_url(&self, address: &str) -> Option<String> {
+        expand_explorer_url(
+            self.explorer_address_url_template.as_deref(),
+            "{address}",
+            address,
+        )
+    }
+}
+
 impl From<NetworkOption> for NetworkConfig {
     fn from(network: NetworkOption) -> Self {
         match network {
@@ -325,21 +399,25 @@ impl From<KnownCodegenPlugin> for CodegenPluginConfig {
                 // When web-sdk get updated, we need to change this path to bindgen/client-lib when we update the ref
                 path: ".trix/client-lib".to_string(),
                 r#ref: Some(CURRENT_CODEGEN_VERSION.to_string()),
+                local_path: None,
             },
             KnownCodegenPlugin::RustClient => CodegenPluginConfig {
                 repo: "tx3-lang/rust-sdk".to_string(),
                 path: ".trix/client-lib".to_string(),
                 r#ref: Some(CURRENT_CODEGEN_VERSION.to_string()),
+                local_path: None,
             },
             KnownCodegenPlugin::PythonClient => CodegenPluginConfig {
                 repo: "tx3-lang/python-sdk".to_string(),
                 path: ".trix/client-lib".to_string(),
                 r#ref: Some(CURRENT_CODEGEN_VERSION.to_string()),
+                local_path: None,
             },
             KnownCodegenPlugin::GoClient => CodegenPluginConfig {
                 repo: "tx3-lang/go-sdk".to_string(),
                 path: ".trix/client-lib".to_string(),
                 r#ref: Some(CURRENT_CODEGEN_VERSION.to_string()),
+                local_path: None,
             },
         }
     }
@@ -358,7 +436,16 @@ impl CodegenPlugin {
     pub fn name(&self) -> String {
         match self {
             CodegenPlugin::Known(plugin) => plugin.to_string(),
-            CodegenPlugin::Custom(plugin) => format!("custom-{}", plugin.repo),
+            CodegenPlugin::Custom(plugin) => match &plugin.local_path {
+                Some(local) if plugin.repo.is_empty() => format!(
+                    "custom-{}",
+                    local
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "local".to_string())
+                ),
+                _ => format!("custom-{}", plugin.repo),
+            },
         }
     }
 }
@@ -409,7 +496,14 @@ impl RootConfig {
             .find(|n| n.as_network_name() == network);
 
         if let Some(implicit) = implicit {
-            return Ok(NetworkConfig::from(*implicit));
+            let mut config = NetworkConfig::from(*implicit);
+
+            // The local devnet may listen on ports from devnet.toml.
+            if matches!(implicit, KnownNetwork::CardanoLocal) {
+                crate::devnet::Ports::for_project()?.apply(&mut config);
+            }
+
+            return Ok(config);
         }
 
         Err(miette::miette!("Network not found"))
@@ -426,7 +520,66 @@ impl RootConfig {
         explicit.into_iter().chain(implicit).collect()
     }
 
+    /// `profile` followed by every profile it extends, nearest first.
+    pub fn profile_chain(&self, profile: &str) -> Result<Vec<ProfileConfig>> {
+        let mut chain: Vec<ProfileConfig> = vec![];
+        let mut next = profile.to_string();
+
+        loop {
+            if chain.iter().any(|p| p.name == next) {
+                let cycle = chain
+                    .iter()
+                    .map(|p| p.name.as_str())
+                    .chain([next.as_str()])
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+
+                bail!(
+                    help = "remove `extends` from one of these profiles",
+                    "profile inheritance cycle: {cycle}"
+                );
+            }
+
+            let found = match chain.last() {
+                None => self.lookup_profile(&next)?,
+                Some(child) => self.lookup_profile(&next).map_err(|_| {
+                    miette::miette!(
+                        "profile `{}` extends `{next}`, which is not in config",
+                        child.name
+                    )
+                })?,
+            };
+
+            let parent = found.extends.clone();
+            chain.push(found);
+
+            match parent {
+                Some(parent) => next = parent,
+                None => return Ok(chain),
+            }
+        }
+    }
+
+    /// The effective profile: its `extends` chain merged from the root
+    /// ancestor down.
     pub fn resolve_profile(&self, profile: &str) -> Result<ProfileConfig> {
+        let mut chain = self.profile_chain(profile)?.into_iter().rev();
+
+        let root = chain.next().expect("chain holds at least the profile itself");
+        let resolved = chain.fold(root, |parent, child| parent.overlay(&child));
+
+        if resolved.network.is_empty() {
+            bail!(
+                help = "set `network`, or `extends` a profile that has one",
+                "profile `{profile}` has no network"
+            );
+        }
+
+        Ok(resolved)
+    }
+
+    /// The profile as written, explicit or built-in, without inheritance.
+    fn lookup_profile(&self, profile: &str) -> Result<ProfileConfig> {
         let explicit = self.profiles.get(profile);
 
         if let Some(explicit) = explicit {
@@ -451,10 +604,8 @@ impl RootConfig {
 
         Ok(network)
     }
-
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -497,6 +648,26 @@ mod tests {
         assert!(err.contains("ts-client") && err.contains("rust-client"));
     }
 
+    #[test]
+    fn known_testnets_link_tx_hashes_and_addresses() {
+        let network = NetworkConfig::from(KnownNetwork::CardanoPreview);
+        assert_eq!(
+            network.explorer_tx_url("abc").as_deref(),
+            Some("https://preview.cexplorer.io/tx/abc")
+        );
+        assert_eq!(
+            network.explorer_address_url("addr_test1").as_deref(),
+            Some("https://preview.cexplorer.io/address/addr_test1")
+        );
+    }
+
+    #[test]
+    fn network_without_template_has_no_links() {
+        let network = NetworkConfig::from(KnownNetwork::CardanoLocal);
+        assert!(network.explorer_tx_url("abc").is_none());
+        assert!(network.explorer_address_url("addr_test1").is_none());
+    }
+
     #[test]
     fn registry_url_prefers_explicit() {
         let toml = r#"
@@ -514,4 +685,89 @@ mod tests {
         let config: RootConfig = toml::from_str(toml).unwrap();
         assert_eq!(config.registry_url(), "https://example.test");
     }
+
+    const INHERITANCE_TOML: &str = r#"
+        [protocol]
+        name = "demo"
+        version = "0.0.0"
+        main = "main.tx3"
+
+        [ledger]
+        family = "cardano"
+
+        [profiles.base]
+        extends = "preprod"
+        env_file = ".env.shared"
+
+        [profiles.base.identities.treasury]
+        type = "RandomKey"
+        random_key = true
+
+        [profiles.staging]
+        extends = "base"
+        network = "cardano-preview"
+
+        [profiles.staging.identities.ops]
+        type = "RandomKey"
+        random_key = true
+    "#;
+
+    #[test]
+    fn profile_inherits_across_levels() {
+        let config: RootConfig = toml::from_str(INHERITANCE_TOML).unwrap();
+
+        let chain: Vec<_> = config
+            .profile_chain("staging")
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(chain, vec!["staging", "base", "preprod"]);
+
+        let staging = config.resolve_profile("staging").unwrap();
+        assert_eq!(staging.name, "staging");
+        assert_eq!(staging.network, "cardano-preview");
+        assert_eq!(staging.env_file, Some(PathBuf::from(".env.shared")));
+        assert!(staging.identities.contains_key("treasury"));
+        assert!(staging.identities.contains_key("ops"));
+
+        let base = config.resolve_profile("base").unwrap();
+        assert_eq!(base.network, "cardano-preprod");
+        assert!(!base.identities.contains_key("ops"));
+    }
+
+    #[test]
+    fn profile_inheritance_cycle_is_an_error() {
+        let toml = r#"
+            [protocol]
+            name = "demo"
+            version = "0.0.0"
+            main = "main.tx3"
+
+            [ledger]
+            family = "cardano"
+
+            [profiles.a]
+            extends = "b"
+
+            [profiles.b]
+            extends = "c"
+
+            [profiles.c]
+            extends = "a"
+        "#;
+        let config: RootConfig = toml::from_str(toml).unwrap();
+
+        let err = config.resolve_profile("a").unwrap_err().to_string();
+        assert!(err.contains("a -> b -> c -> a"), "{err}");
+    }
+
+    #[test]
+    fn profile_extending_unknown_profile_is_an_error() {
+        let mut config: RootConfig = toml::from_str(INHERITANCE_TOML).unwrap();
+        config.profiles.get_mut("base").unwrap().extends = Some("nope".into());
+
+        let err = config.resolve_profile("staging").unwrap_err().to_string();
+        assert!(err.contains("`base` extends `nope`"), "{err}");
+    }
 }
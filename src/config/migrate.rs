@@ -0,0 +1,366 @@
+//! Upgrades of trix.toml between schema versions. A project records the
+//! schema it was written for in `config_schema_version`; every change to
+//! what trix.toml means adds a [`Migration`] that rewrites a file of the
+//! previous version, and bumps [`CURRENT_VERSION`].
+//!
+//! Migrations edit the document in place with `toml_edit`, so comments,
+//! ordering and formatting the user chose survive `trix migrate`.
+//!
+//! A trix.toml without the field predates it. It is only held back when
+//! one of the migrations would actually change it; otherwise it loads as
+//! is and gets the field the next time `trix migrate` runs.
+
+use std::path::Path;
+
+use miette::{Context as _, IntoDiagnostic as _, bail};
+use toml_edit::{DocumentMut, Item, Table};
+
+use super::{KNOWN_NETWORKS, KnownNetwork, NetworkConfig};
+
+pub const CURRENT_VERSION: u32 = 1;
+
+const VERSION_KEY: &str = "config_schema_version";
+
+pub struct Migration {
+    /// Schema version a file is at once this migration ran.
+    pub version: u32,
+    pub summary: &'static str,
+    apply: fn(&mut DocumentMut) -> miette::Result<()>,
+}
+
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    summary: "profiles name their network; endpoints move to `[networks.<profile>]`",
+    apply: split_profile_networks,
+}];
+
+/// Network settings a profile could hold before networks were declared on
+/// their own.
+const PROFILE_NETWORK_KEYS: &[&str] = &[
+    "is_testnet",
+    "trp",
+    "u5c",
+    "explorer_url_template",
+    "explorer_address_url_template",
+];
+
+/// Network a profile's `chain` or `network` value names.
+fn known_network(name: &str) -> Option<KnownNetwork> {
+    match name {
+        "CardanoDevnet" | "CardanoLocal" => Some(KnownNetwork::CardanoLocal),
+        "CardanoPreview" => Some(KnownNetwork::CardanoPreview),
+        "CardanoPreprod" => Some(KnownNetwork::CardanoPreprod),
+        "CardanoMainnet" => Some(KnownNetwork::CardanoMainnet),
+        _ => KNOWN_NETWORKS
+            .iter()
+            .copied()
+            .find(|network| network.as_network_name() == name),
+    }
+}
+
+/// Settings of `network` that `overrides` leaves out, inlined so they stay
+/// next to the profile's own.
+fn fill_from(overrides: &mut Table, network: KnownNetwork) -> miette::Result<()> {
+    let defaults: DocumentMut = toml::to_string(&NetworkConfig::from(network))
+        .into_diagnostic()?
+        .parse()
+        .into_diagnostic()?;
+
+    for (key, item) in defaults.iter() {
+        if overrides.contains_key(key) {
+            continue;
+        }
+
+        let item = match item.clone().into_value() {
+            Ok(value) => Item::Value(value),
+            Err(item) => item,
+        };
+
+        overrides.insert(key, item);
+    }
+
+    Ok(())
+}
+
+/// Profiles used to pick a network with `chain = "CardanoPreview"` and to
+/// override its endpoints inline, under `network` or directly in the
+/// profile. Name the network instead, and move overridden endpoints to a
+/// network named after the profile, completed from the one it overrode.
+fn split_profile_networks(doc: &mut DocumentMut) -> miette::Result<()> {
+    let Some(profiles) = doc.get_mut("profiles").and_then(Item::as_table_like_mut) else {
+        return Ok(());
+    };
+
+    let mut moved = vec![];
+
+    for (name, profile) in profiles.iter_mut() {
+        let name = name.get().to_string();
+
+        let Some(profile) = profile.as_table_like_mut() else {
+            continue;
+        };
+
+        let mut base = match profile.remove("chain") {
+            Some(chain) => {
+                let chain = chain.as_str().unwrap_or_default();
+
+                match known_network(chain) {
+                    Some(network) => Some(network),
+                    None => bail!("profile `{name}` uses unknown chain `{chain}`"),
+                }
+            }
+            None => None,
+        };
+
+        let inline = profile.get("network").is_some_and(Item::is_table_like);
+
+        let mut network = match inline {
+            true => profile
+                .remove("network")
+                .and_then(|item| item.into_table().ok())
+                .unwrap_or_default(),
+            false => {
+                let named = profile.get("network").and_then(Item::as_str);
+                base = base.or_else(|| named.and_then(known_network));
+                Table::new()
+            }
+        };
+
+        for key in PROFILE_NETWORK_KEYS {
+            if let Some(item) = profile.remove(key) {
+                network.insert(key, item);
+            }
+        }
+
+        if network.is_empty() {
+            if let Some(base) = base {
+                profile.insert("network", toml_edit::value(base.as_network_name()));
+            }
+
+            continue;
+        }
+
+        if let Some(base) = base {
+            fill_from(&mut network, base)?;
+        }
+
+        profile.insert("network", toml_edit::value(name.as_str()));
+        moved.push((name, network));
+    }
+
+    if moved.is_empty() {
+        return Ok(());
+    }
+
+    let networks = doc
+        .entry("networks")
+        .or_insert_with(|| {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        })
+        .as_table_like_mut()
+        .ok_or_else(|| miette::miette!("`networks` is not a table"))?;
+
+    for (name, network) in moved {
+        if networks.contains_key(&name) {
+            bail!(
+                help = "rename the profile or the network, then run it again",
+                "profile `{name}` has its own network settings, but `[networks.{name}]` already exists"
+            );
+        }
+
+        networks.insert(&name, Item::Table(network));
+    }
+
+    Ok(())
+}
+
+pub fn parse(path: &Path) -> miette::Result<DocumentMut> {
+    let contents = std::fs::read_to_string(path)
+        .into_diagnostic()
+        .with_context(|| format!("reading {}", path.display()))?;
+
+    contents
+        .parse()
+        .into_diagnostic()
+        .with_context(|| format!("parsing {}", path.display()))
+}
+
+pub fn recorded_version(doc: &DocumentMut) -> miette::Result<Option<u32>> {
+    let Some(item) = doc.get(VERSION_KEY) else {
+        return Ok(None);
+    };
+
+    match item.as_integer().map(u32::try_from) {
+        Some(Ok(version)) => Ok(Some(version)),
+        _ => bail!("`{VERSION_KEY}` must be a non-negative integer"),
+    }
+}
+
+fn changes(migration: &Migration, doc: &DocumentMut) -> bool {
+    let mut copy = doc.clone();
+
+    // A migration that fails has something to do; running it reports why.
+    (migration.apply)(&mut copy).is_err() || copy.to_string() != doc.to_string()
+}
+
+/// Migrations `doc` needs, oldest first.
+pub fn pending(doc: &DocumentMut) -> miette::Result<Vec<&'static Migration>> {
+    match recorded_version(doc)? {
+        Some(version) if version > CURRENT_VERSION => bail!(
+            help = "upgrade trix to work on this project",
+            "trix.toml uses config schema {version}, but this trix only knows up to {CURRENT_VERSION}"
+        ),
+        Some(version) => Ok(MIGRATIONS.iter().filter(|m| m.version > version).collect()),
+        None => Ok(MIGRATIONS.iter().filter(|m| changes(m, doc)).collect()),
+    }
+}
+
+/// Run `migration` and record its version in the document.
+pub fn apply(doc: &mut DocumentMut, migration: &Migration) -> miette::Result<()> {
+    (migration.apply)(doc).with_context(|| {
+        format!(
+            "migrating trix.toml to schema {}: {}",
+            migration.version, migration.summary
+        )
+    })?;
+
+    doc[VERSION_KEY] = toml_edit::value(i64::from(migration.version));
+
+    Ok(())
+}
+
+/// Record [`CURRENT_VERSION`] in a document that needs no migration.
+pub fn stamp(doc: &mut DocumentMut) {
+    doc[VERSION_KEY] = toml_edit::value(i64::from(CURRENT_VERSION));
+}
+
+pub fn describe(migrations: &[&Migration]) -> String {
+    migrations
+        .iter()
+        .map(|m| format!("  {}. {}", m.version, m.summary))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Refuse a trix.toml the current schema can't read as intended, naming
+/// what `trix migrate` would do to it. Files that don't parse are left for
+/// the config loader to report.
+pub fn ensure_current(path: &Path) -> miette::Result<()> {
+    let Ok(doc) = parse(path) else {
+        return Ok(());
+    };
+
+    let pending = pending(&doc)?;
+
+    if !pending.is_empty() {
+        bail!(
+            help = "run `trix migrate` to upgrade it",
+            "{} was written for an older trix; pending migrations:\n{}",
+            path.display(),
+            describe(&pending)
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY: &str = r#"[protocol]
+name = "demo"
+version = "0.0.0"
+main = "main.tx3"
+
+[ledger]
+family = "cardano"
+
+[profiles.local]
+chain = "CardanoDevnet"
+
+# our own preview node
+[profiles.preview]
+chain = "CardanoPreview"
+
+[profiles.preview.u5c]
+url = "http://localhost:50051"
+
+[profiles.custom]
+env_file = ".env.custom"
+
+[profiles.custom.network]
+is_testnet = true
+trp = { url = "http://localhost:3000/trp", headers = {} }
+u5c = { url = "http://localhost:3000/u5c" }
+"#;
+
+    fn migrate(src: &str) -> DocumentMut {
+        let mut doc: DocumentMut = src.parse().unwrap();
+
+        for migration in pending(&doc).unwrap() {
+            apply(&mut doc, migration).unwrap();
+        }
+
+        doc
+    }
+
+    #[test]
+    fn profiles_name_their_network_after_the_split() {
+        let doc: DocumentMut = LEGACY.parse().unwrap();
+        assert_eq!(pending(&doc).unwrap().len(), 1);
+
+        let migrated = migrate(LEGACY).to_string();
+        assert!(migrated.contains("# our own preview node"), "{migrated}");
+        assert!(migrated.contains("config_schema_version = 1"), "{migrated}");
+
+        let config: crate::config::RootConfig = toml::from_str(&migrated).unwrap();
+        assert_eq!(config.config_schema_version, Some(1));
+        assert_eq!(config.profiles["local"].network, "cardano-local");
+        assert_eq!(config.profiles["preview"].network, "preview");
+        assert_eq!(config.profiles["custom"].network, "custom");
+
+        let preview = config.resolve_profile_network("preview").unwrap();
+        let builtin = NetworkConfig::from(KnownNetwork::CardanoPreview);
+        assert_eq!(preview.u5c.url, "http://localhost:50051");
+        assert_eq!(preview.trp.url, builtin.trp.url);
+        assert!(preview.is_testnet);
+
+        let custom = config.resolve_profile_network("custom").unwrap();
+        assert_eq!(custom.trp.url, "http://localhost:3000/trp");
+    }
+
+    #[test]
+    fn unversioned_file_with_nothing_to_migrate_is_current() {
+        let src = "[protocol]\nname = \"demo\"\n\n[profiles.preview]\nnetwork = \"cardano-preview\"\n";
+        let doc: DocumentMut = src.parse().unwrap();
+
+        assert!(pending(&doc).unwrap().is_empty());
+    }
+
+    #[test]
+    fn versioned_file_runs_every_later_migration() {
+        let doc: DocumentMut = "config_schema_version = 0\n".parse().unwrap();
+        assert_eq!(pending(&doc).unwrap().len(), MIGRATIONS.len());
+
+        let doc: DocumentMut = format!("config_schema_version = {CURRENT_VERSION}\n")
+            .parse()
+            .unwrap();
+        assert!(pending(&doc).unwrap().is_empty());
+
+        let doc: DocumentMut = "config_schema_version = 99\n".parse().unwrap();
+        let err = pending(&doc).unwrap_err().to_string();
+        assert!(err.contains("only knows up to"), "{err}");
+    }
+
+    #[test]
+    fn existing_network_of_the_same_name_is_kept() {
+        let src = format!("{LEGACY}\n[networks.custom]\nis_testnet = false\n");
+        let mut doc: DocumentMut = src.parse().unwrap();
+
+        let err = split_profile_networks(&mut doc).unwrap_err().to_string();
+        assert!(err.contains("`[networks.custom]` already exists"), "{err}");
+    }
+}
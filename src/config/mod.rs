@@ -1,6 +1,7 @@
 use miette::IntoDiagnostic as _;
 
 pub mod convention;
+pub mod migrate;
 pub mod model;
 pub mod serde;
 
@@ -19,7 +20,7 @@ impl RootConfig {
 
     pub fn save(&self, path: &PathBuf) -> miette::Result<()> {
         let contents = toml::to_string_pretty(self).into_diagnostic()?;
-        std::fs::write(path, contents).into_diagnostic()?;
+        crate::fsutil::write_atomic(path, contents)?;
         Ok(())
     }
 }
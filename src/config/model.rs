@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
 
 use crate::config::serde::{KnownOrCustom, Named, NamedMap};
 use crate::refs::ProtocolRef;
@@ -11,6 +14,13 @@ pub struct ProtocolConfig {
     pub version: String,
     pub description: Option<String>,
     pub main: PathBuf,
+
+    /// Extra source globs (relative to `trix.toml`) for protocols split
+    /// across files. `main` stays the entry point; every matched file is
+    /// compiled along with it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sources: Option<Vec<String>>,
+
     pub readme: Option<PathBuf>,
 
     /// Optional path to a PNG logo (relative to `trix.toml`). When set,
@@ -61,11 +71,34 @@ pub struct RandomKeyIdentityConfig {
     pub random_key: bool,
 }
 
+/// A wallet restored from a 24-word BIP-39 phrase, as added by `trix wallet
+/// import`. `mnemonic` can be an `env:` or `keyring:` reference, so the
+/// phrase itself needn't be written to trix.toml. Alternatively,
+/// `mnemonic_env` names a variable of the profile's env file holding it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MnemonicIdentityConfig {
+    #[serde(skip)]
+    pub name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mnemonic: Option<String>,
+
+    /// Variable holding the phrase, looked up in the profile's env file and
+    /// then the process environment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mnemonic_env: Option<String>,
+
+    /// CIP-1852 account to derive the payment key from; 0 when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_index: Option<u32>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "type")]
 pub enum IdentityConfig {
     RandomKey(RandomKeyIdentityConfig),
     ExplicitKey(ExplicitKeyIdentityConfig),
+    Mnemonic(MnemonicIdentityConfig),
 }
 
 impl Named for IdentityConfig {
@@ -73,6 +106,7 @@ impl Named for IdentityConfig {
         match self {
             IdentityConfig::RandomKey(config) => config.name.clone(),
             IdentityConfig::ExplicitKey(config) => config.name.clone(),
+            IdentityConfig::Mnemonic(config) => config.name.clone(),
         }
     }
 
@@ -80,6 +114,7 @@ impl Named for IdentityConfig {
         match self {
             IdentityConfig::RandomKey(config) => config.name = name,
             IdentityConfig::ExplicitKey(config) => config.name = name,
+            IdentityConfig::Mnemonic(config) => config.name = name,
         }
     }
 }
@@ -98,6 +133,13 @@ pub struct ProfileConfig {
     #[serde(skip)]
     pub name: String,
 
+    /// Another profile (explicit or built-in) this one starts from. Fields
+    /// left unset here are inherited; identities merge by name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+
+    /// Required unless inherited through `extends`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub network: String,
 
     #[serde(default)]
@@ -149,6 +191,18 @@ pub struct NetworkConfig {
     pub is_testnet: bool,
     pub trp: TrpConfig,
     pub u5c: U5cConfig,
+
+    /// Block explorer link for a transaction on this network, e.g.
+    /// `https://preview.cexplorer.io/tx/{hash}`; `{hash}` expands to the
+    /// transaction hash. Networks without one print bare hashes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explorer_url_template: Option<String>,
+
+    /// Block explorer link for a wallet address, e.g.
+    /// `https://preview.cexplorer.io/address/{address}`; `{address}` expands
+    /// to the address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explorer_address_url_template: Option<String>,
 }
 
 pub type NetworkOption = KnownOrCustom<KnownNetwork, NetworkConfig>;
@@ -171,9 +225,16 @@ impl Named for NetworkOption {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CodegenPluginConfig {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub repo: String,
+    #[serde(default)]
     pub path: String,
     pub r#ref: Option<String>, // default: main
+
+    /// Template directory on disk, used instead of downloading `repo`.
+    /// Relative paths resolve against the trix.toml location.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_path: Option<PathBuf>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
@@ -194,6 +255,16 @@ pub struct CodegenConfig {
     pub plugin: CodegenPlugin,
     pub output_dir: Option<PathBuf>,
     pub options: Option<HashMap<String, serde_json::Value>>,
+    /// Globs that `trix codegen verify` leaves out of the comparison. Each
+    /// protocol is generated into `output_dir/<protocol-name>/`; a glob
+    /// matches there (e.g. `node_modules/**`) or from `output_dir` itself
+    /// (e.g. `my-protocol/node_modules/**`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub verify_ignore: Vec<String>,
+    /// Load templates only from the copy `trix codegen vendor` stored in
+    /// `vendor/trix-templates/<job_id>/`, with no network access.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub vendored: bool,
 }
 
 /// Publisher trust tier. Mirrors the `land.tx3.protocol.publisher.kind`
@@ -337,8 +408,65 @@ pub struct ToolchainConfig {
     pub tx3c: Option<String>,
 }
 
+/// A named, reusable argument set for one transaction template, from
+/// `[invoke.presets.<name>]` in `trix.toml`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InvokePreset {
+    pub template: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Default args. Accept the same `@wallet` / `@env:VAR` references as
+    /// args given on the command line.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub args: BTreeMap<String, serde_json::Value>,
+}
+
+/// Limits for one transaction template, from `[budgets.<template>]` in
+/// `trix.toml`, checked by `trix build --check-budgets`. Execution units
+/// are summed over the transaction's redeemers.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BudgetConfig {
+    /// Lovelace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_ex_mem: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_ex_steps: Option<u64>,
+
+    /// Args to evaluate the template with instead of placeholders. Accept
+    /// `@wallet` references like preset args.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub args: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct InvokeConfig {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub presets: BTreeMap<String, InvokePreset>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RootConfig {
+    /// Schema this file was written for; see [`crate::config::migrate`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_schema_version: Option<u32>,
+
+    /// Extra seed mixed into random-key identity derivation. Defaults to the
+    /// protocol's `scope/name`, so identities are unique per project.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wallet_seed: Option<String>,
+
+    /// Derive random-key identities from their name alone, as trix did
+    /// before per-project namespacing. Keeps pre-existing addresses stable
+    /// at the cost of sharing keys with every other legacy project.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub legacy_wallet_derivation: bool,
+
     pub protocol: ProtocolConfig,
 
     pub ledger: LedgerConfig,
@@ -360,4 +488,10 @@ pub struct RootConfig {
 
     #[serde(default, skip_serializing_if = "NamedMap::is_empty")]
     pub interfaces: NamedMap<InterfaceEntry>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invoke: Option<InvokeConfig>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub budgets: BTreeMap<String, BudgetConfig>,
 }
@@ -0,0 +1,114 @@
+//! Native assets for seeded UTxOs, written in devnet.toml as
+//! `assets = { "<policy id>.<asset name hex>" = <amount> }`.
+
+use std::collections::BTreeMap;
+
+use miette::{bail, miette};
+use pallas::codec::utils::{Bytes, NonEmptyKeyValuePairs};
+use pallas::crypto::hash::Hash;
+use pallas::ledger::primitives::conway::{PositiveCoin, Value};
+
+/// `(policy id hex, asset name hex, amount)`
+pub type Asset = (String, String, u64);
+
+/// Split `assets` table keys into policy and asset name. A key without a
+/// `.` names the policy's empty asset name.
+pub fn parse_table(assets: &BTreeMap<String, u64>) -> Vec<Asset> {
+    assets
+        .iter()
+        .map(|(key, amount)| {
+            let (policy, name) = key.split_once('.').unwrap_or((key, ""));
+            (policy.to_string(), name.to_string(), *amount)
+        })
+        .collect()
+}
+
+fn policy_id(policy: &str) -> miette::Result<Hash<28>> {
+    let bytes: [u8; 28] = hex::decode(policy)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| miette!("policy id `{policy}` is not 28 bytes of hex"))?;
+
+    Ok(Hash::new(bytes))
+}
+
+/// Output value for `coin` lovelace plus `assets`, grouped by policy.
+pub fn value(coin: u64, assets: &[Asset]) -> miette::Result<Value> {
+    let mut policies: Vec<(Hash<28>, Vec<(Bytes, PositiveCoin)>)> = vec![];
+
+    for (policy, name, amount) in assets {
+        let policy = policy_id(policy)?;
+
+        let name = hex::decode(name).map_err(|_| miette!("asset name `{name}` is not hex"))?;
+
+        if name.len() > 32 {
+            bail!(
+                "asset name `{}` is longer than 32 bytes",
+                hex::encode(&name)
+            );
+        }
+
+        let Ok(amount) = PositiveCoin::try_from(*amount) else {
+            bail!("asset `{policy}.{}` has a zero amount", hex::encode(&name));
+        };
+
+        match policies.iter_mut().find(|(p, _)| *p == policy) {
+            Some((_, names)) => names.push((name.into(), amount)),
+            None => policies.push((policy, vec![(name.into(), amount)])),
+        }
+    }
+
+    if policies.is_empty() {
+        return Ok(Value::Coin(coin));
+    }
+
+    let multiasset = policies
+        .into_iter()
+        .map(|(policy, names)| {
+            (
+                policy,
+                NonEmptyKeyValuePairs::from_vec(names).expect("each policy has an asset"),
+            )
+        })
+        .collect();
+
+    Ok(Value::Multiasset(
+        coin,
+        NonEmptyKeyValuePairs::from_vec(multiasset).expect("checked non-empty"),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: &str = "0000000000000000000000000000000000000000000000000000000a";
+
+    #[test]
+    fn groups_assets_by_policy() {
+        let table = BTreeMap::from([
+            (format!("{POLICY}.6e6674"), 1),
+            (format!("{POLICY}.746f6b656e"), 500),
+        ]);
+
+        let Value::Multiasset(coin, multiasset) = value(2_000_000, &parse_table(&table)).unwrap()
+        else {
+            panic!("expected a multiasset value");
+        };
+
+        assert_eq!(coin, 2_000_000);
+        assert_eq!(multiasset.len(), 1);
+        assert_eq!(multiasset[0].1.len(), 2);
+    }
+
+    #[test]
+    fn rejects_bad_policy_and_zero_amount() {
+        let short = BTreeMap::from([("abcd.00".to_string(), 1)]);
+        let err = value(1, &parse_table(&short)).unwrap_err();
+        assert!(err.to_string().contains("not 28 bytes of hex"));
+
+        let zero = BTreeMap::from([(format!("{POLICY}.00"), 0)]);
+        let err = value(1, &parse_table(&zero)).unwrap_err();
+        assert!(err.to_string().contains("zero amount"));
+    }
+}
@@ -0,0 +1,112 @@
+//! Chain timing and funding for the devnet, set in devnet.toml as
+//!
+//! ```toml
+//! [chain]
+//! slot_length_ms = 200
+//! epoch_length_slots = 500
+//! initial_funds_per_actor = 100000000000
+//! ```
+//!
+//! Timing lands in the rendered Shelley genesis and in dolos' block
+//! production interval. Dolos produces blocks on whole seconds, so the
+//! interval is the slot length rounded up to one. Funds seed one extra UTxO
+//! per profile identity, next to the explicit `[[utxos]]`.
+
+use std::time::Duration;
+
+use miette::{Context as _, IntoDiagnostic as _, bail};
+use serde::{Deserialize, Serialize};
+
+pub const MIN_SLOT_LENGTH_MS: u64 = 100;
+
+/// `slotLength` of the bundled Shelley genesis.
+pub const DEFAULT_SLOT_LENGTH_MS: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Chain {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot_length_ms: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch_length_slots: Option<u64>,
+
+    /// Lovelace seeded to every identity of the profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_funds_per_actor: Option<u64>,
+}
+
+impl Chain {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Wall-clock length of one slot.
+    pub fn slot_length(&self) -> Duration {
+        Duration::from_millis(self.slot_length_ms.unwrap_or(DEFAULT_SLOT_LENGTH_MS))
+    }
+
+    pub fn validate(&self) -> miette::Result<()> {
+        if let Some(ms) = self.slot_length_ms
+            && ms < MIN_SLOT_LENGTH_MS
+        {
+            bail!(
+                help = format!("use a slot length of at least {MIN_SLOT_LENGTH_MS}ms"),
+                "devnet.toml: chain.slot_length_ms = {ms} is too short"
+            );
+        }
+
+        if self.epoch_length_slots == Some(0) {
+            bail!("devnet.toml: chain.epoch_length_slots must be at least 1");
+        }
+
+        if self.initial_funds_per_actor == Some(0) {
+            bail!(
+                help = "drop the key to seed no extra funds",
+                "devnet.toml: chain.initial_funds_per_actor must be positive"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Set dolos' `[upstream] block_production_interval` (seconds) in the
+    /// dolos.toml template.
+    pub fn apply_root(&self, template: &mut toml::Table) {
+        let Some(ms) = self.slot_length_ms else {
+            return;
+        };
+
+        if let Some(upstream) = template
+            .get_mut("upstream")
+            .and_then(toml::Value::as_table_mut)
+        {
+            let secs = ms.div_ceil(1000).max(1);
+            upstream.insert(
+                "block_production_interval".to_string(),
+                toml::Value::Integer(secs as i64),
+            );
+        }
+    }
+
+    /// Render the Shelley genesis template with this slot and epoch length.
+    pub fn apply_shelley(&self, genesis: &str) -> miette::Result<String> {
+        if self.slot_length_ms.is_none() && self.epoch_length_slots.is_none() {
+            return Ok(genesis.to_string());
+        }
+
+        let mut value: serde_json::Value = serde_json::from_str(genesis)
+            .into_diagnostic()
+            .context("parsing shelley genesis template")?;
+
+        if let Some(ms) = self.slot_length_ms {
+            value["slotLength"] = serde_json::json!(ms as f64 / 1000.0);
+        }
+
+        if let Some(slots) = self.epoch_length_slots {
+            value["epochLength"] = serde_json::json!(slots);
+        }
+
+        serde_json::to_string_pretty(&value).into_diagnostic()
+    }
+}
@@ -0,0 +1,183 @@
+//! Datums for seeded UTxOs. An inline datum is written either as hex CBOR
+//! or in the detailed JSON schema used by cardano-cli
+//! (`{"constructor": 0, "fields": [{"int": 42}, {"bytes": "beef"}]}`),
+//! inline in devnet.toml or as a JSON string.
+
+use miette::{bail, miette, IntoDiagnostic as _};
+use pallas::codec::minicbor::{data::Tag, Encoder};
+use pallas::codec::utils::KeepRaw;
+use pallas::ledger::primitives::conway::PlutusData;
+
+/// Longest byte string chunk Plutus accepts; longer ones are split.
+const BYTES_CHUNK: usize = 64;
+
+/// CBOR bytes for a `datum_inline` value, checked to decode as Plutus data.
+pub fn inline_datum_cbor(value: &serde_json::Value) -> miette::Result<Vec<u8>> {
+    let cbor = match value {
+        serde_json::Value::String(text) => match hex::decode(text) {
+            Ok(cbor) => cbor,
+            Err(_) => {
+                let json: serde_json::Value = serde_json::from_str(text)
+                    .map_err(|_| miette!("datum_inline is neither hex CBOR nor JSON"))?;
+                encode_json(&json)?
+            }
+        },
+        json => encode_json(json)?,
+    };
+
+    pallas::codec::minicbor::decode::<KeepRaw<PlutusData>>(&cbor)
+        .map_err(|e| miette!("datum_inline is not valid Plutus data: {e}"))?;
+
+    Ok(cbor)
+}
+
+/// Encode detailed-schema JSON as Plutus data CBOR.
+pub fn encode_json(json: &serde_json::Value) -> miette::Result<Vec<u8>> {
+    let mut encoder = Encoder::new(Vec::new());
+    write_json(&mut encoder, json)?;
+    Ok(encoder.into_writer())
+}
+
+/// CBOR tag for constructor `index`, per the Plutus data encoding.
+fn constructor_tag(index: u64) -> Option<u64> {
+    match index {
+        0..=6 => Some(121 + index),
+        7..=127 => Some(1280 + index - 7),
+        _ => None,
+    }
+}
+
+fn write_list(
+    encoder: &mut Encoder<Vec<u8>>,
+    items: &[serde_json::Value],
+) -> miette::Result<()> {
+    // Same shape cardano-cli emits: indefinite arrays unless empty.
+    if items.is_empty() {
+        encoder.array(0).into_diagnostic()?;
+        return Ok(());
+    }
+
+    encoder.begin_array().into_diagnostic()?;
+    for item in items {
+        write_json(encoder, item)?;
+    }
+    encoder.end().into_diagnostic()?;
+
+    Ok(())
+}
+
+fn write_json(encoder: &mut Encoder<Vec<u8>>, json: &serde_json::Value) -> miette::Result<()> {
+    let Some(object) = json.as_object() else {
+        bail!("datum JSON must be an object, got `{json}`");
+    };
+
+    if let Some(index) = object.get("constructor") {
+        let index = index
+            .as_u64()
+            .ok_or_else(|| miette!("`constructor` must be a non-negative integer"))?;
+
+        let fields = object
+            .get("fields")
+            .and_then(|f| f.as_array())
+            .ok_or_else(|| miette!("constructor {index} needs a `fields` array"))?;
+
+        match constructor_tag(index) {
+            Some(tag) => {
+                encoder.tag(Tag::new(tag)).into_diagnostic()?;
+            }
+            None => {
+                encoder.tag(Tag::new(102)).into_diagnostic()?;
+                encoder.array(2).into_diagnostic()?;
+                encoder.u64(index).into_diagnostic()?;
+            }
+        }
+
+        return write_list(encoder, fields);
+    }
+
+    if let Some(int) = object.get("int") {
+        match (int.as_u64(), int.as_i64()) {
+            (Some(n), _) => encoder.u64(n).into_diagnostic()?,
+            (None, Some(n)) => encoder.i64(n).into_diagnostic()?,
+            _ => bail!("`int` must fit in 64 bits, got `{int}`"),
+        };
+        return Ok(());
+    }
+
+    if let Some(bytes) = object.get("bytes") {
+        let bytes = bytes
+            .as_str()
+            .and_then(|b| hex::decode(b).ok())
+            .ok_or_else(|| miette!("`bytes` must be a hex string"))?;
+
+        if bytes.len() <= BYTES_CHUNK {
+            encoder.bytes(&bytes).into_diagnostic()?;
+        } else {
+            encoder.begin_bytes().into_diagnostic()?;
+            for chunk in bytes.chunks(BYTES_CHUNK) {
+                encoder.bytes(chunk).into_diagnostic()?;
+            }
+            encoder.end().into_diagnostic()?;
+        }
+        return Ok(());
+    }
+
+    if let Some(list) = object.get("list") {
+        let list = list
+            .as_array()
+            .ok_or_else(|| miette!("`list` must be an array"))?;
+        return write_list(encoder, list);
+    }
+
+    if let Some(map) = object.get("map") {
+        let entries = map
+            .as_array()
+            .ok_or_else(|| miette!("`map` must be an array of {{\"k\", \"v\"}} objects"))?;
+
+        encoder.map(entries.len() as u64).into_diagnostic()?;
+        for entry in entries {
+            let (Some(k), Some(v)) = (entry.get("k"), entry.get("v")) else {
+                bail!("map entries need `k` and `v`");
+            };
+            write_json(encoder, k)?;
+            write_json(encoder, v)?;
+        }
+        return Ok(());
+    }
+
+    bail!("unknown datum JSON `{json}`; expected constructor, int, bytes, list or map")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_matches_cardano_cli_encoding() {
+        let json = serde_json::json!({
+            "constructor": 0,
+            "fields": [{ "int": 42 }, { "bytes": "beef" }]
+        });
+
+        assert_eq!(hex::encode(encode_json(&json).unwrap()), "d8799f182a42beefff");
+    }
+
+    #[test]
+    fn hex_and_json_strings_are_both_accepted() {
+        let from_hex = inline_datum_cbor(&serde_json::json!("d8799f182a42beefff")).unwrap();
+        let from_text = inline_datum_cbor(&serde_json::json!(
+            r#"{"constructor": 0, "fields": [{"int": 42}, {"bytes": "beef"}]}"#
+        ))
+        .unwrap();
+
+        assert_eq!(from_hex, from_text);
+        assert!(inline_datum_cbor(&serde_json::json!("zz")).is_err());
+    }
+
+    #[test]
+    fn large_constructor_index_uses_general_form() {
+        let json = serde_json::json!({ "constructor": 200, "fields": [] });
+
+        assert_eq!(hex::encode(encode_json(&json).unwrap()), "d8668218c880");
+    }
+}
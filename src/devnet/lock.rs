@@ -0,0 +1,236 @@
+//! One dolos per devnet home. Two daemons sharing a home overwrite each
+//! other's config and corrupt the WAL, so every start takes `daemon.lock`
+//! in the home before touching it.
+//!
+//! The lock is an OS file lock held while the home is prepared and, for a
+//! foreground devnet, for as long as dolos runs. The file also records the
+//! holder's PID and start time: a background devnet outlives the `trix`
+//! that started it, so the lock is [handed over](HomeLock::hand_over) to
+//! its supervisor by recording the supervisor's PID. A record whose process
+//! is gone is stale and is reclaimed by the next start.
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read as _, Seek as _, SeekFrom, Write as _};
+use std::path::{Path, PathBuf};
+
+use miette::{Context as _, IntoDiagnostic as _};
+
+use crate::spawn::shutdown;
+
+const LOCK_FILE: &str = "daemon.lock";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holder {
+    pub pid: u32,
+    /// RFC 3339 timestamp.
+    pub started_at: String,
+}
+
+impl Holder {
+    fn parse(record: &str) -> Option<Self> {
+        let mut lines = record.lines();
+        let pid = lines.next()?.trim().parse().ok()?;
+        let started_at = lines.next().unwrap_or_default().trim().to_string();
+
+        Some(Self { pid, started_at })
+    }
+
+    fn render(&self) -> String {
+        format!("{}\n{}\n", self.pid, self.started_at)
+    }
+}
+
+pub fn lock_file(home: &Path) -> PathBuf {
+    home.join(LOCK_FILE)
+}
+
+/// The devnet recorded in `home`'s lock, whether or not it still runs.
+pub fn holder(home: &Path) -> Option<Holder> {
+    let record = std::fs::read_to_string(lock_file(home)).ok()?;
+    Holder::parse(&record)
+}
+
+/// The lock on a devnet home, released when dropped.
+pub struct HomeLock {
+    file: File,
+    clear_on_drop: bool,
+}
+
+fn record(file: &mut File, pid: u32) -> std::io::Result<()> {
+    let holder = Holder {
+        pid,
+        started_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    file.set_len(0)?;
+    file.seek(SeekFrom::Start(0))?;
+    file.write_all(holder.render().as_bytes())?;
+    file.sync_all()
+}
+
+fn busy(home: &Path, holder: Option<Holder>) -> miette::Report {
+    let running = match holder {
+        Some(Holder { pid, started_at }) if !started_at.is_empty() => {
+            format!("pid {pid}, started {started_at}")
+        }
+        Some(Holder { pid, .. }) => format!("pid {pid}"),
+        None => "still starting".to_string(),
+    };
+
+    miette::miette!(
+        help =
+            "stop it with `trix devnet stop` (or Ctrl-C in its terminal) before starting another",
+        "a devnet is already running in {} ({running})",
+        home.display()
+    )
+}
+
+impl HomeLock {
+    /// Take the lock on `home`, creating the home if needed. Fails naming
+    /// the running devnet when another process holds it.
+    pub fn acquire(home: &Path) -> miette::Result<Self> {
+        std::fs::create_dir_all(home)
+            .into_diagnostic()
+            .with_context(|| format!("creating {}", home.display()))?;
+
+        let path = lock_file(home);
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .into_diagnostic()
+            .with_context(|| format!("opening {}", path.display()))?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => return Err(busy(home, holder(home))),
+            Err(TryLockError::Error(err)) => {
+                return Err(err)
+                    .into_diagnostic()
+                    .with_context(|| format!("locking {}", path.display()));
+            }
+        }
+
+        // Nobody holds the file lock, but a background devnet keeps the home
+        // through its recorded supervisor.
+        let mut existing = String::new();
+        file.read_to_string(&mut existing).into_diagnostic()?;
+
+        if let Some(holder) = Holder::parse(&existing)
+            && holder.pid != std::process::id()
+            && shutdown::alive(holder.pid)
+        {
+            return Err(busy(home, Some(holder)));
+        }
+
+        record(&mut file, std::process::id())
+            .into_diagnostic()
+            .with_context(|| format!("writing {}", path.display()))?;
+
+        Ok(Self {
+            file,
+            clear_on_drop: true,
+        })
+    }
+
+    /// Record `pid`, the supervisor of a background devnet, as the holder
+    /// and let go of the file lock. The home stays taken for as long as
+    /// `pid` is alive.
+    pub fn hand_over(mut self, pid: u32) -> miette::Result<()> {
+        record(&mut self.file, pid)
+            .into_diagnostic()
+            .context("recording the devnet supervisor")?;
+
+        self.clear_on_drop = false;
+
+        Ok(())
+    }
+}
+
+impl Drop for HomeLock {
+    fn drop(&mut self) {
+        if self.clear_on_drop {
+            let _ = self.file.set_len(0);
+        }
+    }
+}
+
+/// Clear the record of a supervisor that is exiting, if it is the holder.
+pub fn release(home: &Path, pid: u32) {
+    if holder(home).is_some_and(|holder| holder.pid == pid) {
+        let _ = std::fs::write(lock_file(home), "");
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn dummy_process() -> std::process::Child {
+        std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap()
+    }
+
+    #[test]
+    fn second_start_sees_the_held_lock() {
+        let home = tempfile::tempdir().unwrap();
+
+        let lock = HomeLock::acquire(home.path()).unwrap();
+
+        let err = HomeLock::acquire(home.path()).err().unwrap().to_string();
+        assert!(err.contains("already running"), "{err}");
+        assert!(
+            err.contains(&format!("pid {}", std::process::id())),
+            "{err}"
+        );
+
+        drop(lock);
+        assert!(HomeLock::acquire(home.path()).is_ok());
+    }
+
+    #[test]
+    fn handed_over_lock_holds_while_the_supervisor_lives() {
+        let home = tempfile::tempdir().unwrap();
+        let mut supervisor = dummy_process();
+
+        HomeLock::acquire(home.path())
+            .unwrap()
+            .hand_over(supervisor.id())
+            .unwrap();
+
+        let err = HomeLock::acquire(home.path()).err().unwrap().to_string();
+        assert!(err.contains(&format!("pid {}", supervisor.id())), "{err}");
+
+        supervisor.kill().unwrap();
+        supervisor.wait().unwrap();
+
+        // The record now names a dead process and is reclaimed.
+        let _lock = HomeLock::acquire(home.path()).unwrap();
+        assert_eq!(holder(home.path()).unwrap().pid, std::process::id());
+    }
+
+    #[test]
+    fn release_only_clears_own_record() {
+        let home = tempfile::tempdir().unwrap();
+        let mut supervisor = dummy_process();
+
+        HomeLock::acquire(home.path())
+            .unwrap()
+            .hand_over(supervisor.id())
+            .unwrap();
+
+        release(home.path(), supervisor.id() + 1);
+        assert_eq!(holder(home.path()).unwrap().pid, supervisor.id());
+
+        release(home.path(), supervisor.id());
+        assert_eq!(holder(home.path()), None);
+
+        supervisor.kill().unwrap();
+        supervisor.wait().unwrap();
+    }
+}
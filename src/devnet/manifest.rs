@@ -0,0 +1,231 @@
+//! A machine-readable description of the devnet, for test harnesses that
+//! drive it from outside: endpoints, network magic and, per identity, its
+//! address, key hash and the lovelace devnet.toml seeds it with.
+//!
+//! The manifest is written to `manifest.json` in the devnet home whenever
+//! the devnet starts, and printed on demand by `trix devnet manifest`.
+//! Consumers should check `schema_version`; it is bumped whenever a field
+//! is renamed or removed.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use miette::IntoDiagnostic as _;
+use serde::Serialize;
+
+use crate::config::{IdentityConfig, KnownNetwork, NetworkConfig, ProfileConfig};
+use crate::wallet::Derivation;
+
+use super::{AddressSpec, Config, Ports, UtxoSpec};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub const FILE_NAME: &str = "manifest.json";
+
+#[derive(Debug, Serialize)]
+pub struct Endpoints {
+    pub trp: String,
+    pub u5c: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Identity {
+    pub name: String,
+    pub address: String,
+    /// Hex hash of the address' payment key, `None` for script addresses.
+    pub public_key_hash: Option<String>,
+    /// Lovelace seeded to the address by devnet.toml.
+    pub initial_lovelace: u64,
+    /// Only for random-key identities, and only when asked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mnemonic: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Manifest {
+    pub schema_version: u32,
+    pub protocol: String,
+    pub network_magic: Option<u64>,
+    pub endpoints: Endpoints,
+    pub identities: Vec<Identity>,
+}
+
+/// The magic of the chain the dolos.toml template declares.
+fn network_magic() -> Option<u64> {
+    let template: toml::Table = toml::from_str(crate::spawn::dolos::DOLOS_TEMPLATE).ok()?;
+
+    template
+        .get("chain")?
+        .get("magic")?
+        .as_integer()?
+        .try_into()
+        .ok()
+}
+
+/// Lovelace of the devnet.toml UTxOs at identity `name`'s `address`, plus
+/// the per-actor funds.
+pub(crate) fn initial_lovelace(devnet: &Config, name: &str, address: &str) -> u64 {
+    let explicit: u64 = devnet
+        .utxos
+        .iter()
+        .filter_map(|utxo| match utxo {
+            UtxoSpec::Explicit(spec) => Some(spec),
+            UtxoSpec::NativeBytes(_) => None,
+        })
+        .filter(|spec| match &spec.address {
+            AddressSpec::NamedWallet(wallet) => wallet == name,
+            AddressSpec::Address(other) => other == address,
+        })
+        .map(|spec| spec.value)
+        .sum();
+
+    explicit + devnet.chain.initial_funds_per_actor.unwrap_or(0)
+}
+
+impl Manifest {
+    /// The manifest of a devnet seeded from `devnet` and listening on
+    /// `ports`, for identities with addresses `aliases`.
+    pub fn build(
+        protocol: &str,
+        devnet: &Config,
+        ports: &Ports,
+        aliases: &HashMap<String, String>,
+    ) -> Self {
+        let mut network = NetworkConfig::from(KnownNetwork::CardanoLocal);
+        ports.apply(&mut network);
+
+        let mut identities: Vec<_> = aliases
+            .iter()
+            .map(|(name, address)| Identity {
+                name: name.clone(),
+                address: address.clone(),
+                public_key_hash: crate::wallet::payment_key_hash(address)
+                    .map(|hash| hash.to_string()),
+                initial_lovelace: initial_lovelace(devnet, name, address),
+                mnemonic: None,
+            })
+            .collect();
+
+        identities.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Self {
+            schema_version: SCHEMA_VERSION,
+            protocol: protocol.to_string(),
+            network_magic: network_magic(),
+            endpoints: Endpoints {
+                trp: network.trp.url,
+                u5c: network.u5c.url,
+            },
+            identities,
+        }
+    }
+
+    /// Add the deterministic mnemonic of every random-key identity. Keys
+    /// imported by the user are left out.
+    pub fn include_mnemonics(
+        &mut self,
+        profile: &ProfileConfig,
+        derivation: &Derivation,
+    ) -> miette::Result<()> {
+        for identity in &mut self.identities {
+            if let Some(IdentityConfig::RandomKey(_)) = profile.identities.get(&identity.name) {
+                identity.mnemonic = Some(derivation.mnemonic(&identity.name)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> miette::Result<String> {
+        serde_json::to_string_pretty(self).into_diagnostic()
+    }
+
+    /// Write the manifest to `path`, atomically so a reader never sees a
+    /// half-written one.
+    pub fn save(&self, path: &Path) -> miette::Result<()> {
+        crate::fsutil::write_atomic(path, self.to_json()? + "\n")
+    }
+}
+
+/// Where the devnet started for `home` keeps its manifest.
+pub fn path(home: &Path) -> PathBuf {
+    home.join(FILE_NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "addr_test1vqqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgasfzjt";
+    const BOB: &str = "addr_test1vqpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqsw74k48";
+
+    fn aliases() -> HashMap<String, String> {
+        HashMap::from([
+            ("bob".to_string(), BOB.to_string()),
+            ("alice".to_string(), ALICE.to_string()),
+        ])
+    }
+
+    fn devnet() -> Config {
+        toml::from_str(&format!(
+            "[[utxos]]\naddress = \"@alice\"\nvalue = 5000000\n\n\
+             [[utxos]]\naddress = \"{BOB}\"\nvalue = 2000000\n\n\
+             [[utxos]]\naddress = \"@alice\"\nvalue = 1000000\n\n\
+             [ports]\ntrp = 18164\n\n\
+             [chain]\ninitial_funds_per_actor = 100\n"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn manifest_schema() {
+        let devnet = devnet();
+        let manifest = Manifest::build("demo", &devnet, &devnet.ports, &aliases());
+
+        insta::assert_snapshot!(manifest.to_json().unwrap(), @r#"
+        {
+          "schema_version": 1,
+          "protocol": "demo",
+          "network_magic": 2,
+          "endpoints": {
+            "trp": "http://localhost:18164",
+            "u5c": "http://localhost:5164/u5c"
+          },
+          "identities": [
+            {
+              "name": "alice",
+              "address": "addr_test1vqqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgasfzjt",
+              "public_key_hash": "01010101010101010101010101010101010101010101010101010101",
+              "initial_lovelace": 6000100
+            },
+            {
+              "name": "bob",
+              "address": "addr_test1vqpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqszqgpqyqsw74k48",
+              "public_key_hash": "02020202020202020202020202020202020202020202020202020202",
+              "initial_lovelace": 2000100
+            }
+          ]
+        }
+        "#);
+    }
+
+    #[test]
+    fn mnemonics_only_for_random_keys() {
+        let profile: ProfileConfig = toml::from_str(
+            "network = \"cardano-local\"\n\n\
+             [identities.alice]\ntype = \"RandomKey\"\nrandom_key = true\n\n\
+             [identities.bob]\ntype = \"ExplicitKey\"\nkey_path = \"bob.skey\"\n",
+        )
+        .unwrap();
+
+        let derivation = Derivation::Namespaced("acme/demo".to_string());
+
+        let mut manifest = Manifest::build("demo", &devnet(), &Ports::default(), &aliases());
+        manifest.include_mnemonics(&profile, &derivation).unwrap();
+
+        let alice = manifest.identities[0].mnemonic.as_deref().unwrap();
+        assert_eq!(alice.split_whitespace().count(), 24);
+        assert_eq!(alice, derivation.mnemonic("alice").unwrap());
+        assert_eq!(manifest.identities[1].mnemonic, None);
+    }
+}
@@ -1,12 +1,12 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt::Display,
     path::{Path, PathBuf},
-    process::Child,
+    process::{Child, Command, Stdio},
     str::FromStr,
 };
 
-use miette::{Diagnostic, IntoDiagnostic as _};
+use miette::{Context as _, Diagnostic, IntoDiagnostic as _};
 
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
@@ -14,6 +14,17 @@ use thiserror::Error;
 
 use crate::wallet::WalletProxy;
 
+pub mod assets;
+pub mod chain;
+mod datum;
+pub mod lock;
+pub mod manifest;
+pub mod ports;
+mod script;
+
+pub use chain::Chain;
+pub use ports::Ports;
+
 #[derive(Debug, Error, Diagnostic)]
 #[error("devnet error")]
 pub enum Error {
@@ -74,6 +85,28 @@ pub struct ExplicitUtxoSpec {
     #[serde_as(as = "DisplayFromStr")]
     pub address: AddressSpec,
     pub value: u64,
+
+    /// Native assets keyed by `<policy id>.<asset name hex>`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub assets: BTreeMap<String, u64>,
+
+    /// Inline datum: hex CBOR, or Plutus data in cardano-cli's detailed JSON
+    /// schema (as a table or a JSON string).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datum_inline: Option<serde_json::Value>,
+
+    /// Hex hash of a datum held off-chain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datum_hash: Option<String>,
+
+    /// File with a Plutus script to publish as this output's reference
+    /// script, relative to devnet.toml.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script_ref: Option<PathBuf>,
+
+    /// `plutus-v2` or `plutus-v3`; needed when `script_ref` is bare hex.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script_language: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -93,13 +126,32 @@ pub enum UtxoSpec {
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct Config {
     pub utxos: Vec<UtxoSpec>,
+
+    #[serde(default, skip_serializing_if = "Ports::is_default")]
+    pub ports: Ports,
+
+    #[serde(default, skip_serializing_if = "Chain::is_default")]
+    pub chain: Chain,
 }
 
 impl Config {
     pub fn load(path: impl AsRef<Path>) -> miette::Result<Self> {
         let data = std::fs::read_to_string(&path).map_err(Error::CantOpenConfig)?;
 
-        let config = toml::from_str::<Self>(&data).map_err(Error::InvalidConfig)?;
+        let mut config = toml::from_str::<Self>(&data).map_err(Error::InvalidConfig)?;
+
+        config.chain.validate()?;
+
+        let base = path.as_ref().parent().unwrap_or(Path::new("."));
+
+        for utxo in &mut config.utxos {
+            if let UtxoSpec::Explicit(spec) = utxo
+                && let Some(script) = &mut spec.script_ref
+                && script.is_relative()
+            {
+                *script = base.join(&script);
+            }
+        }
 
         Ok(config)
     }
@@ -113,26 +165,67 @@ fn map_address(
     pallas::ledger::addresses::Address::from_bech32(&resolved).into_diagnostic()
 }
 
-fn dolos_utxo_from_explicit_spec(
+fn map_datum_hash(hash: &str) -> miette::Result<pallas::crypto::hash::Hash<32>> {
+    let bytes = hex::decode(hash)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| miette::miette!("datum_hash `{hash}` is not 32 bytes of hex"))?;
+
+    Ok(pallas::crypto::hash::Hash::new(bytes))
+}
+
+/// `ordinal` is the spec's position in devnet.toml. It is mixed into the
+/// ref hash so identical specs still seed distinct UTxOs, while the same
+/// file always produces the same refs.
+pub(crate) fn dolos_utxo_from_explicit_spec(
     spec: &ExplicitUtxoSpec,
+    ordinal: usize,
     aliases: &HashMap<String, String>,
 ) -> miette::Result<dolos_core::config::CustomUtxo> {
+    let datum_cbor = match (&spec.datum_inline, &spec.datum_hash) {
+        (Some(_), Some(_)) => miette::bail!(
+            help = "keep `datum_inline` to seed the datum itself, or `datum_hash` to reference it",
+            "utxo for {} declares both `datum_inline` and `datum_hash`",
+            spec.address
+        ),
+        (Some(inline), None) => Some(datum::inline_datum_cbor(inline)?),
+        _ => None,
+    };
+
+    let datum_option = match (&datum_cbor, &spec.datum_hash) {
+        (Some(cbor), _) => Some(pallas::ledger::primitives::conway::DatumOption::Data(
+            pallas::codec::utils::CborWrap(pallas::codec::minicbor::decode(cbor).into_diagnostic()?),
+        )),
+        (None, Some(hash)) => Some(pallas::ledger::primitives::conway::DatumOption::Hash(
+            map_datum_hash(hash)?,
+        )),
+        (None, None) => None,
+    };
+
+    let script_ref = spec
+        .script_ref
+        .as_deref()
+        .map(|path| script::load(path, spec.script_language.as_deref()))
+        .transpose()?;
+
     let utxo = pallas::ledger::primitives::conway::TransactionOutput::PostAlonzo(
         pallas::codec::utils::KeepRaw::from(
             pallas::ledger::primitives::conway::PostAlonzoTransactionOutput {
                 address: map_address(&spec.address, aliases)?.to_vec().into(),
-                value: pallas::ledger::primitives::conway::Value::Coin(spec.value),
-                // TODO: support this data from explicit spec
-                datum_option: None,
-                script_ref: None,
+                value: assets::value(spec.value, &assets::parse_table(&spec.assets))
+                    .with_context(|| format!("assets of utxo for {}", spec.address))?,
+                datum_option: datum_option.map(Into::into),
+                script_ref: script_ref.map(pallas::codec::utils::CborWrap),
             },
         ),
     );
 
     let cbor = pallas::codec::minicbor::to_vec(&utxo).into_diagnostic()?;
 
-    // TODO: use pallas::crypto::hash::Hasher to create a unique hash from the cbor bytes
-    let hash = pallas::crypto::hash::Hasher::<256>::hash(&cbor);
+    let mut hasher = pallas::crypto::hash::Hasher::<256>::new();
+    hasher.input(&(ordinal as u64).to_be_bytes());
+    hasher.input(&cbor);
+    let hash = hasher.finalize();
 
     Ok(dolos_core::config::CustomUtxo {
         ref_: dolos_core::TxoRef(hash, 0),
@@ -143,10 +236,11 @@ fn dolos_utxo_from_explicit_spec(
 
 fn dolos_utxo_from_spec(
     utxo: &UtxoSpec,
+    ordinal: usize,
     aliases: &HashMap<String, String>,
 ) -> miette::Result<dolos_core::config::CustomUtxo> {
     match utxo {
-        UtxoSpec::Explicit(x) => dolos_utxo_from_explicit_spec(x, aliases),
+        UtxoSpec::Explicit(x) => dolos_utxo_from_explicit_spec(x, ordinal, aliases),
         UtxoSpec::NativeBytes(x) => Ok(dolos_core::config::CustomUtxo {
             ref_: x.r#ref.parse().map_err(|e: String| miette::miette!(e))?,
             cbor: hex::decode(&x.raw_bytes).into_diagnostic()?,
@@ -155,50 +249,180 @@ fn dolos_utxo_from_spec(
     }
 }
 
+/// `[chain] initial_funds_per_actor` as one spec per identity, in name
+/// order so the seeded refs are stable.
+fn actor_funding_specs(config: &Config, aliases: &HashMap<String, String>) -> Vec<UtxoSpec> {
+    let Some(value) = config.chain.initial_funds_per_actor else {
+        return vec![];
+    };
+
+    let mut names: Vec<_> = aliases.keys().collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| {
+            UtxoSpec::Explicit(ExplicitUtxoSpec {
+                address: AddressSpec::NamedWallet(name.clone()),
+                value,
+                assets: BTreeMap::new(),
+                datum_inline: None,
+                datum_hash: None,
+                script_ref: None,
+                script_language: None,
+            })
+        })
+        .collect()
+}
+
 pub fn build_dolos_utxos(
     config: &Config,
     aliases: &HashMap<String, String>,
 ) -> miette::Result<Vec<dolos_core::config::CustomUtxo>> {
+    let funding = actor_funding_specs(config, aliases);
+
     config
         .utxos
         .iter()
-        .map(|spec| dolos_utxo_from_spec(spec, aliases))
+        .chain(&funding)
+        .enumerate()
+        .map(|(ordinal, spec)| dolos_utxo_from_spec(spec, ordinal, aliases))
         .collect()
 }
 
-fn setup_home(devnet: &Config, ctx: &Context) -> miette::Result<PathBuf> {
-    let dolos_dir = crate::dirs::target_dir("dolos")?;
+/// Dolos home for the project's devnet. Keyed by derivation mode: the
+/// seeded UTxOs belong to wallet addresses that differ between legacy and
+/// namespaced identities.
+pub fn home_dir(derivation_tag: &str) -> miette::Result<PathBuf> {
+    Ok(crate::dirs::target_dir("dolos")?.join(derivation_tag))
+}
+
+/// Lock the home and write its dolos config. The lock is taken first, so
+/// a devnet already running there keeps its config.
+fn setup_home(devnet: &Config, ctx: &Context) -> miette::Result<(PathBuf, lock::HomeLock)> {
+    let dolos_dir = home_dir(&ctx.derivation_tag)?;
+
+    let lock = lock::HomeLock::acquire(&dolos_dir)?;
 
     let initial_utxos = build_dolos_utxos(devnet, &ctx.aliases)?;
 
-    let _ = crate::spawn::dolos::initialize_config(&dolos_dir, initial_utxos)?;
+    let _ = crate::spawn::dolos::initialize_config(
+        &dolos_dir,
+        initial_utxos,
+        &devnet.ports,
+        &devnet.chain,
+    )?;
+
+    Ok((dolos_dir, lock))
+}
+
+const PID_FILE: &str = "daemon.pid";
+
+/// Where a background devnet records its PID, next to the dolos state.
+pub fn pid_file(home: &Path) -> PathBuf {
+    home.join(PID_FILE)
+}
+
+/// PID of the background devnet started for `home`, if one was recorded.
+/// The process may have exited since.
+pub fn read_pid(home: &Path) -> Option<u32> {
+    std::fs::read_to_string(pid_file(home))
+        .ok()
+        .and_then(|content| content.trim().parse().ok())
+}
+
+fn write_pid(home: &Path, pid: u32) -> miette::Result<()> {
+    let path = pid_file(home);
 
-    Ok(dolos_dir)
+    std::fs::write(&path, format!("{pid}\n"))
+        .into_diagnostic()
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+pub fn clear_pid(home: &Path) {
+    let _ = std::fs::remove_file(pid_file(home));
 }
 
 pub struct DevnetDaemon {
     pub home: PathBuf,
-    pub daemon: Child,
+    pub daemon: crate::spawn::dolos::Daemon,
+    _lock: lock::HomeLock,
 }
 
 pub struct Context {
     pub aliases: HashMap<String, String>,
+    pub derivation_tag: String,
 }
 
 impl Context {
     pub fn from_wallet(wallet: &WalletProxy) -> Self {
         Self {
             aliases: wallet.addresses.clone(),
+            derivation_tag: wallet.derivation.tag(),
         }
     }
 }
 
+/// Start dolos as a child of this process. `silent` captures its output
+/// into the devnet's rotating log instead of the terminal.
 pub fn start_daemon(devnet: &Config, ctx: &Context, silent: bool) -> miette::Result<DevnetDaemon> {
-    let home = setup_home(devnet, ctx)?;
+    let (home, lock) = setup_home(devnet, ctx)?;
 
     let daemon = crate::spawn::dolos::daemon(&home, silent)?;
 
-    Ok(DevnetDaemon { home, daemon })
+    Ok(DevnetDaemon {
+        home,
+        daemon,
+        _lock: lock,
+    })
+}
+
+/// Start dolos detached from this process. The log pump threads have to
+/// outlive `trix`, so dolos runs under a hidden `trix devnet supervise`
+/// process that owns them; that process exits when dolos does. Its PID is
+/// recorded in the home so `trix devnet stop` can find it later, and the
+/// home's lock is handed over to it.
+pub fn start_supervised(devnet: &Config, ctx: &Context) -> miette::Result<(PathBuf, Child)> {
+    let (home, lock) = setup_home(devnet, ctx)?;
+
+    let child = supervise_home(&home)?;
+
+    lock.hand_over(child.id())?;
+
+    Ok((home, child))
+}
+
+/// Start the supervisor for a home that is already prepared, e.g. one
+/// restored from a snapshot.
+pub fn supervise_home(home: &Path) -> miette::Result<Child> {
+    let exe = std::env::current_exe()
+        .into_diagnostic()
+        .context("locating the trix executable")?;
+
+    let mut cmd = Command::new(exe);
+
+    cmd.args(["devnet", "supervise", "--home"]);
+    cmd.arg(home);
+    cmd.current_dir(crate::dirs::protocol_root()?);
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    // Own process group, so a Ctrl-C in the launching shell doesn't reach it.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt as _;
+        cmd.process_group(0);
+    }
+
+    let child = cmd
+        .spawn()
+        .into_diagnostic()
+        .context("failed to spawn devnet supervisor")?;
+
+    write_pid(home, child.id())?;
+
+    Ok(child)
 }
 
 #[cfg(test)]
@@ -213,4 +437,227 @@ mod tests {
         let address = AddressSpec::from_str("addr1abcdef").unwrap();
         assert_eq!(address, AddressSpec::Address("addr1abcdef".to_string()));
     }
+
+    fn spec(datum_inline: Option<&str>, datum_hash: Option<&str>) -> ExplicitUtxoSpec {
+        let mut bytes = vec![0x60];
+        bytes.extend([7u8; 28]);
+        let address = pallas::ledger::addresses::Address::from_bytes(&bytes)
+            .unwrap()
+            .to_bech32()
+            .unwrap();
+
+        ExplicitUtxoSpec {
+            address: AddressSpec::Address(address),
+            value: 5_000_000,
+            assets: BTreeMap::new(),
+            datum_inline: datum_inline.map(|d| serde_json::Value::String(d.to_string())),
+            datum_hash: datum_hash.map(str::to_string),
+            script_ref: None,
+            script_language: None,
+        }
+    }
+
+    #[test]
+    fn explicit_spec_carries_inline_datum() {
+        let utxo =
+            dolos_utxo_from_explicit_spec(&spec(Some("d8799f182aff"), None), 0, &HashMap::new())
+                .unwrap();
+
+        let output = pallas::ledger::traverse::MultiEraOutput::decode(
+            pallas::ledger::traverse::Era::Conway,
+            &utxo.cbor,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            output.datum(),
+            Some(pallas::ledger::primitives::conway::DatumOption::Data(_))
+        ));
+    }
+
+    #[test]
+    fn explicit_spec_rejects_both_datum_kinds() {
+        let hash = "923918e403bf43c34b4ef6b48eb2ee04babed17320d8d1b9ff9ad086e86f44ec";
+
+        let err =
+            dolos_utxo_from_explicit_spec(&spec(Some("01"), Some(hash)), 0, &HashMap::new())
+                .unwrap_err();
+
+        assert!(err.to_string().contains("both `datum_inline` and `datum_hash`"));
+    }
+
+    #[test]
+    fn chain_section_validates_and_funds_actors() {
+        let config: Config = toml::from_str(
+            "utxos = []\n\n[chain]\nslot_length_ms = 50\ninitial_funds_per_actor = 7\n",
+        )
+        .unwrap();
+        assert!(config.chain.validate().is_err());
+
+        let aliases = HashMap::from([
+            ("bob".to_string(), "addr_test1b".to_string()),
+            ("alice".to_string(), "addr_test1a".to_string()),
+        ]);
+
+        let specs = actor_funding_specs(&config, &aliases);
+        let targets: Vec<_> = specs
+            .iter()
+            .map(|spec| match spec {
+                UtxoSpec::Explicit(spec) => (spec.address.to_string(), spec.value),
+                UtxoSpec::NativeBytes(_) => unreachable!(),
+            })
+            .collect();
+
+        assert_eq!(
+            targets,
+            [("@alice".to_string(), 7), ("@bob".to_string(), 7)]
+        );
+    }
+
+    #[test]
+    fn datum_inline_accepts_toml_table() {
+        let config: Config = toml::from_str(
+            r#"
+            [[utxos]]
+            address = "@alice"
+            value = 1
+            datum_inline = { constructor = 0, fields = [{ int = 42 }] }
+            "#,
+        )
+        .unwrap();
+
+        let UtxoSpec::Explicit(spec) = &config.utxos[0] else {
+            panic!("expected an explicit spec");
+        };
+        assert!(spec.datum_inline.as_ref().unwrap().is_object());
+    }
+
+    #[test]
+    fn explicit_spec_carries_assets() {
+        let mut spec = spec(None, None);
+        spec.assets.insert(
+            "0000000000000000000000000000000000000000000000000000000a.6e6674".into(),
+            1,
+        );
+
+        let utxo = dolos_utxo_from_explicit_spec(&spec, 0, &HashMap::new()).unwrap();
+
+        let output = pallas::ledger::traverse::MultiEraOutput::decode(
+            pallas::ledger::traverse::Era::Conway,
+            &utxo.cbor,
+        )
+        .unwrap();
+
+        assert_eq!(output.value().coin(), 5_000_000);
+        assert_eq!(output.value().assets().len(), 1);
+    }
+
+    #[test]
+    fn explicit_spec_seeds_plutus_v2_and_v3_script_refs() {
+        use pallas::codec::minicbor;
+        use pallas::ledger::primitives::conway::{ScriptRef, TransactionOutput};
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("always.hex");
+        std::fs::write(&script, "4d01000033222220051200120011").unwrap();
+
+        for language in ["plutus-v2", "plutus-v3"] {
+            let spec = ExplicitUtxoSpec {
+                script_ref: Some(script.clone()),
+                script_language: Some(language.to_string()),
+                ..spec(None, None)
+            };
+
+            let utxo = dolos_utxo_from_explicit_spec(&spec, 0, &HashMap::new()).unwrap();
+
+            let output: TransactionOutput = minicbor::decode(&utxo.cbor).unwrap();
+            let TransactionOutput::PostAlonzo(output) = output else {
+                panic!("{language}: seeded output should be post-Alonzo");
+            };
+            let seeded = &output
+                .script_ref
+                .as_ref()
+                .unwrap_or_else(|| panic!("{language}: output should carry the script"))
+                .0;
+
+            match (language, seeded) {
+                ("plutus-v2", ScriptRef::PlutusV2Script(_)) => {}
+                ("plutus-v3", ScriptRef::PlutusV3Script(_)) => {}
+                _ => panic!("{language}: seeded {seeded:?}"),
+            }
+
+            let loaded = script::load(&script, Some(language)).unwrap();
+            assert_eq!(
+                minicbor::to_vec(seeded).unwrap(),
+                minicbor::to_vec(&loaded).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn script_ref_resolves_next_to_devnet_toml() {
+        let dir = tempfile::tempdir().unwrap();
+
+        std::fs::create_dir(dir.path().join("scripts")).unwrap();
+        std::fs::write(
+            dir.path().join("scripts/always.plutus"),
+            r#"{"type": "PlutusScriptV2", "cborHex": "4e4d01000033222220051200120011"}"#,
+        )
+        .unwrap();
+
+        let address = spec(None, None).address;
+        std::fs::write(
+            dir.path().join("devnet.toml"),
+            format!("[[utxos]]\naddress = \"{address}\"\nvalue = 1\nscript_ref = \"scripts/always.plutus\"\n"),
+        )
+        .unwrap();
+
+        let config = Config::load(dir.path().join("devnet.toml")).unwrap();
+        let utxo = dolos_utxo_from_spec(&config.utxos[0], 0, &HashMap::new()).unwrap();
+
+        let output = pallas::ledger::traverse::MultiEraOutput::decode(
+            pallas::ledger::traverse::Era::Conway,
+            &utxo.cbor,
+        )
+        .unwrap();
+
+        assert!(output.script_ref().is_some());
+    }
+
+    #[test]
+    fn identical_specs_get_distinct_refs() {
+        let config = Config {
+            utxos: vec![
+                UtxoSpec::Explicit(spec(None, None)),
+                UtxoSpec::Explicit(spec(None, None)),
+                UtxoSpec::Explicit(spec(None, None)),
+            ],
+            ports: Default::default(),
+            chain: Default::default(),
+        };
+
+        let first = build_dolos_utxos(&config, &HashMap::new()).unwrap();
+        let refs: std::collections::HashSet<_> =
+            first.iter().map(|u| (u.ref_.0, u.ref_.1)).collect();
+        assert_eq!(refs.len(), 3);
+
+        let again = build_dolos_utxos(&config, &HashMap::new()).unwrap();
+        assert_eq!(
+            first.iter().map(|u| u.ref_.0).collect::<Vec<_>>(),
+            again.iter().map(|u| u.ref_.0).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn pid_file_roundtrip() {
+        let home = tempfile::tempdir().unwrap();
+
+        assert_eq!(read_pid(home.path()), None);
+
+        write_pid(home.path(), 4242).unwrap();
+        assert_eq!(read_pid(home.path()), Some(4242));
+
+        clear_pid(home.path());
+        assert_eq!(read_pid(home.path()), None);
+    }
 }
@@ -0,0 +1,139 @@
+//! Listen ports for the devnet's dolos services, set in devnet.toml as
+//!
+//! ```toml
+//! [ports]
+//! trp = 18164
+//! grpc = 15164   # u5c; `u5c = ...` works too
+//! minibf = 13164
+//! ```
+//!
+//! Unset ports keep the defaults of the dolos.toml template. The same ports
+//! are used for the `cardano-local` network, so cshell and the other
+//! clients reach the devnet wherever it listens.
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::NetworkConfig;
+
+pub const DEFAULT_TRP: u16 = 8164;
+pub const DEFAULT_GRPC: u16 = 5164;
+pub const DEFAULT_MINIBF: u16 = 3164;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Ports {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trp: Option<u16>,
+
+    /// UTxO RPC, served over gRPC.
+    #[serde(default, alias = "u5c", skip_serializing_if = "Option::is_none")]
+    pub grpc: Option<u16>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minibf: Option<u16>,
+}
+
+/// Just the `[ports]` table, so reading it doesn't depend on the UTxO
+/// specs being valid.
+#[derive(Deserialize)]
+struct PortsOnly {
+    #[serde(default)]
+    ports: Ports,
+}
+
+impl Ports {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn trp(&self) -> u16 {
+        self.trp.unwrap_or(DEFAULT_TRP)
+    }
+
+    pub fn grpc(&self) -> u16 {
+        self.grpc.unwrap_or(DEFAULT_GRPC)
+    }
+
+    pub fn minibf(&self) -> u16 {
+        self.minibf.unwrap_or(DEFAULT_MINIBF)
+    }
+
+    /// `(dolos.toml [serve.*] section, port)` for every service.
+    pub fn listen(&self) -> [(&'static str, u16); 3] {
+        [
+            ("trp", self.trp()),
+            ("grpc", self.grpc()),
+            ("minibf", self.minibf()),
+        ]
+    }
+
+    /// Ports from the project's devnet.toml; defaults outside a project or
+    /// when it has no devnet.toml.
+    pub fn for_project() -> miette::Result<Self> {
+        let Ok(root) = crate::dirs::protocol_root() else {
+            return Ok(Self::default());
+        };
+
+        let Ok(content) = std::fs::read_to_string(root.join("devnet.toml")) else {
+            return Ok(Self::default());
+        };
+
+        let parsed: PortsOnly = toml::from_str(&content).map_err(super::Error::InvalidConfig)?;
+
+        Ok(parsed.ports)
+    }
+
+    /// Point `network`'s TRP and u5c URLs at these ports.
+    pub fn apply(&self, network: &mut NetworkConfig) {
+        if let Some(url) = with_port(&network.trp.url, self.trp()) {
+            network.trp.url = url;
+        }
+
+        if let Some(url) = with_port(&network.u5c.url, self.grpc()) {
+            network.u5c.url = url;
+        }
+    }
+}
+
+fn with_port(url: &str, port: u16) -> Option<String> {
+    let mut url = url::Url::parse(url).ok()?;
+    url.set_port(Some(port)).ok()?;
+
+    // `Url` adds a trailing slash to a bare origin; keep the configured form.
+    let rendered = url.to_string();
+    match rendered.strip_suffix('/') {
+        Some(bare) if url.path() == "/" => Some(bare.to_string()),
+        _ => Some(rendered),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::KnownNetwork;
+
+    #[test]
+    fn apply_moves_local_endpoints() {
+        let ports = Ports {
+            trp: Some(18164),
+            grpc: Some(15164),
+            minibf: None,
+        };
+
+        let mut network = NetworkConfig::from(KnownNetwork::CardanoLocal);
+        ports.apply(&mut network);
+
+        assert_eq!(network.trp.url, "http://localhost:18164");
+        assert_eq!(network.u5c.url, "http://localhost:15164/u5c");
+        assert_eq!(ports.minibf(), DEFAULT_MINIBF);
+    }
+
+    #[test]
+    fn u5c_is_an_alias_for_grpc() {
+        let parsed: PortsOnly = toml::from_str("[ports]\nu5c = 15164\n").unwrap();
+        assert_eq!(parsed.ports.grpc(), 15164);
+        assert_eq!(parsed.ports.trp(), DEFAULT_TRP);
+
+        assert!(toml::from_str::<PortsOnly>("[ports]\nhttp = 1\n").is_err());
+    }
+}
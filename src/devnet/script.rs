@@ -0,0 +1,150 @@
+//! Reference scripts for seeded UTxOs. The script file is either a
+//! cardano-cli text envelope (`{"type": "PlutusScriptV3", "cborHex": ..}`)
+//! or bare hex, in which case `script_language` names the Plutus version.
+
+use std::path::Path;
+
+use miette::{Context as _, IntoDiagnostic as _, bail, miette};
+use pallas::codec::minicbor;
+use pallas::ledger::primitives::conway::{PlutusScript, ScriptRef};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Language {
+    PlutusV2,
+    PlutusV3,
+}
+
+impl Language {
+    fn parse(value: &str) -> miette::Result<Self> {
+        match value.to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+            "plutusv2" | "v2" | "plutusscriptv2" => Ok(Self::PlutusV2),
+            "plutusv3" | "v3" | "plutusscriptv3" => Ok(Self::PlutusV3),
+            _ => bail!(
+                help = "use `plutus-v2` or `plutus-v3`",
+                "unsupported script language `{value}`"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(rename = "cborHex")]
+    cbor_hex: String,
+}
+
+/// Script bytes as they go into a script ref. cardano-cli's `cborHex` wraps
+/// the script in one more CBOR byte string than compilers like Aiken emit;
+/// that extra layer is peeled off when present.
+fn script_bytes(hex_text: &str) -> miette::Result<Vec<u8>> {
+    let cbor = hex::decode(hex_text.trim()).map_err(|_| miette!("script is not valid hex"))?;
+
+    let inner: &[u8] =
+        minicbor::decode(&cbor).map_err(|_| miette!("script is not a CBOR byte string"))?;
+
+    match minicbor::decode::<&[u8]>(inner) {
+        Ok(innermost) if minicbor::to_vec(innermost).is_ok_and(|v| v == inner) => {
+            Ok(inner.to_vec())
+        }
+        _ => Ok(cbor),
+    }
+}
+
+/// Load the script at `path` as a script ref. `language_name` is required for
+/// bare hex files and, for envelopes, has to agree with the envelope type.
+pub fn load(path: &Path, language_name: Option<&str>) -> miette::Result<ScriptRef<'static>> {
+    let text = std::fs::read_to_string(path)
+        .into_diagnostic()
+        .with_context(|| format!("reading script_ref {}", path.display()))?;
+
+    let declared = language_name.map(Language::parse).transpose()?;
+
+    let (language, hex_text) = match serde_json::from_str::<Envelope>(&text) {
+        Ok(envelope) => {
+            let language = Language::parse(&envelope.kind)
+                .with_context(|| format!("envelope type in {}", path.display()))?;
+
+            if let (Some(declared), Some(named)) = (declared, language_name)
+                && declared != language
+            {
+                bail!(
+                    "script_language `{named}` does not match envelope type `{}` of {}",
+                    envelope.kind,
+                    path.display()
+                );
+            }
+
+            (language, envelope.cbor_hex)
+        }
+        Err(_) => {
+            let Some(language) = declared else {
+                bail!(
+                    help = "add `script_language = \"plutus-v3\"` (or `plutus-v2`) to the utxo",
+                    "{} is bare hex, so the script language can't be inferred",
+                    path.display()
+                );
+            };
+
+            (language, text)
+        }
+    };
+
+    let bytes = script_bytes(&hex_text).with_context(|| format!("in {}", path.display()))?;
+
+    Ok(match language {
+        Language::PlutusV2 => ScriptRef::PlutusV2Script(PlutusScript::<2>(bytes.into())),
+        Language::PlutusV3 => ScriptRef::PlutusV3Script(PlutusScript::<3>(bytes.into())),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Always-succeeds validator as cardano-cli writes it.
+    const ENVELOPE_CBOR_HEX: &str = "4e4d01000033222220051200120011";
+
+    #[test]
+    fn envelope_and_bare_hex_give_same_script() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let envelope = dir.path().join("always.plutus");
+        std::fs::write(
+            &envelope,
+            format!(r#"{{"type": "PlutusScriptV2", "description": "", "cborHex": "{ENVELOPE_CBOR_HEX}"}}"#),
+        )
+        .unwrap();
+
+        // Aiken's `compiledCode`: one CBOR byte string layer less.
+        let bare = dir.path().join("always.hex");
+        std::fs::write(&bare, "4d01000033222220051200120011\n").unwrap();
+
+        let from_envelope = load(&envelope, None).unwrap();
+        let from_bare = load(&bare, Some("plutus-v2")).unwrap();
+
+        assert_eq!(
+            minicbor::to_vec(&from_envelope).unwrap(),
+            minicbor::to_vec(&from_bare).unwrap()
+        );
+        assert!(matches!(from_envelope, ScriptRef::PlutusV2Script(_)));
+    }
+
+    #[test]
+    fn rejects_missing_file_bad_hex_and_unknown_language() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert!(load(&dir.path().join("missing.plutus"), Some("v3")).is_err());
+
+        let bad = dir.path().join("bad.hex");
+        std::fs::write(&bad, "not hex").unwrap();
+        assert!(load(&bad, Some("v3")).is_err());
+        assert!(load(&bad, None).is_err());
+
+        let good = dir.path().join("good.hex");
+        std::fs::write(&good, "4d01000033222220051200120011").unwrap();
+        assert!(load(&good, Some("plutus-v1")).is_err());
+    }
+}
@@ -19,6 +19,12 @@ pub fn protocol_root() -> miette::Result<PathBuf> {
     }
 }
 
+/// User-level cache shared by every project, e.g. downloaded codegen
+/// templates. Safe to delete.
+pub fn cache_dir() -> miette::Result<PathBuf> {
+    crate::home::dir(crate::home::Location::Cache)
+}
+
 pub fn toolchain_owned_dir() -> miette::Result<PathBuf> {
     let root = protocol_root()?;
 
@@ -0,0 +1,212 @@
+//! The profile's env file as a live source for `env:` references. Long
+//! running commands started with `--watch-env` load the file once and then
+//! [`watch`] it: on every change it is read again, the values it holds
+//! replace the previous ones, and one line names the variables that
+//! changed. [`var`] serves those values when the process environment
+//! doesn't set the variable, so secrets resolved after a reload, e.g. TRP
+//! and u5c headers written into cshell's config, pick up the new ones.
+//!
+//! Child processes already running keep the environment and config they
+//! started with; callers restart them when they reload.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{RecvTimeoutError, channel};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Duration;
+
+use miette::{Context as _, IntoDiagnostic as _};
+use notify::{Event, EventKind, RecursiveMode, Watcher as _};
+
+use crate::commands::profile::load_env_vars;
+
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Values read from an env file, shared by a [`watch`] and its readers.
+#[derive(Clone, Default)]
+pub struct Values(Arc<RwLock<BTreeMap<String, String>>>);
+
+impl Values {
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.0.read().ok()?.get(name).cloned()
+    }
+
+    fn replace(&self, values: BTreeMap<String, String>) {
+        if let Ok(mut current) = self.0.write() {
+            *current = values;
+        }
+    }
+}
+
+/// The values `env:` references resolve from; what `--watch-env` loads.
+pub fn loaded() -> &'static Values {
+    static LOADED: OnceLock<Values> = OnceLock::new();
+    LOADED.get_or_init(Values::default)
+}
+
+fn lookup(name: &str, file: &Values) -> Option<String> {
+    std::env::var(name).ok().or_else(|| file.get(name))
+}
+
+/// Value of `name` in the process environment or, failing that, in the
+/// loaded env file.
+pub fn var(name: &str) -> Option<String> {
+    lookup(name, loaded())
+}
+
+/// Read `path` into `values`. A missing file counts as empty, so a watch
+/// can start before the file is created.
+fn load(path: &Path, values: &Values) -> miette::Result<BTreeMap<String, String>> {
+    let read = match path.is_file() {
+        true => load_env_vars(path)?,
+        false => BTreeMap::new(),
+    };
+
+    values.replace(read.clone());
+
+    Ok(read)
+}
+
+/// Names of the variables added, removed or given a new value.
+fn changed(old: &BTreeMap<String, String>, new: &BTreeMap<String, String>) -> Vec<String> {
+    let mut names: Vec<_> = old
+        .keys()
+        .chain(new.keys())
+        .filter(|name| old.get(*name) != new.get(*name))
+        .cloned()
+        .collect();
+
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn touches(path: &Path, event: &Event) -> bool {
+    !matches!(event.kind, EventKind::Access(_))
+        && event
+            .paths
+            .iter()
+            .any(|changed| changed.file_name() == path.file_name())
+}
+
+/// Keeps the env file watched until dropped.
+pub struct Watch {
+    _watcher: notify::RecommendedWatcher,
+}
+
+/// Load `path` into `into`, then reload it whenever it changes, until the
+/// returned [`Watch`] is dropped. After each reload that changed a value,
+/// `on_reload` runs with the names of the variables that did.
+pub fn watch(
+    path: &Path,
+    into: Values,
+    on_reload: impl Fn(&[String]) + Send + 'static,
+) -> miette::Result<Watch> {
+    let path = std::path::absolute(path).into_diagnostic()?;
+    let dir = path.parent().map(PathBuf::from).unwrap_or_default();
+
+    let mut values = load(&path, &into)?;
+
+    let (tx, events) = channel();
+
+    // Editors often replace the file rather than write to it, so the
+    // directory is watched and events filtered by file name.
+    let mut watcher = notify::recommended_watcher(tx)
+        .into_diagnostic()
+        .context("starting env file watcher")?;
+
+    watcher
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .into_diagnostic()
+        .with_context(|| format!("watching {}", path.display()))?;
+
+    std::thread::spawn(move || {
+        while let Ok(event) = events.recv() {
+            if !event.is_ok_and(|event| touches(&path, &event)) {
+                continue;
+            }
+
+            loop {
+                match events.recv_timeout(DEBOUNCE) {
+                    Ok(_) => continue,
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+
+            let reloaded = match load(&path, &into) {
+                Ok(reloaded) => reloaded,
+                Err(err) => {
+                    eprintln!("env file {} not reloaded: {err}", path.display());
+                    continue;
+                }
+            };
+
+            let names = changed(&values, &reloaded);
+            values = reloaded;
+
+            if names.is_empty() {
+                continue;
+            }
+
+            eprintln!("reloaded {}: {} changed", path.display(), names.join(", "));
+
+            on_reload(&names);
+        }
+    });
+
+    Ok(Watch { _watcher: watcher })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn changed_names_added_removed_and_updated_vars() {
+        let old = vars(&[("KEEP", "1"), ("ROTATED", "old"), ("GONE", "x")]);
+        let new = vars(&[("KEEP", "1"), ("ROTATED", "new"), ("ADDED", "y")]);
+
+        assert_eq!(changed(&old, &new), ["ADDED", "GONE", "ROTATED"]);
+        assert!(changed(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn reloads_file_changed_mid_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".env.preview");
+        std::fs::write(&path, "TRP_KEY=first\n").unwrap();
+
+        let values = Values::default();
+
+        let (tx, reloads) = channel();
+        let _watch = watch(&path, values.clone(), move |names| {
+            tx.send(names.to_vec()).unwrap()
+        })
+        .unwrap();
+
+        assert_eq!(values.get("TRP_KEY").as_deref(), Some("first"));
+
+        std::fs::write(&path, "TRP_KEY=second\n").unwrap();
+
+        let names = reloads.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(names, ["TRP_KEY"]);
+        assert_eq!(values.get("TRP_KEY").as_deref(), Some("second"));
+    }
+
+    #[test]
+    fn process_env_wins_over_the_file() {
+        let file = Values::default();
+        file.replace(vars(&[("PATH", "from-file"), ("TRIX_ENVFILE_ONLY", "x")]));
+
+        assert_eq!(lookup("PATH", &file), std::env::var("PATH").ok());
+        assert_eq!(lookup("TRIX_ENVFILE_ONLY", &file).as_deref(), Some("x"));
+    }
+}
@@ -0,0 +1,190 @@
+//! Crash-safe writes for the state files trix keeps between runs.
+//!
+//! [`write_atomic`] writes next to the target and renames over it, so an
+//! interrupted write leaves either the old file or the new one, never a
+//! truncated mix. [`read_json`] is the matching reader: a file that still
+//! fails to parse (written by an older trix, or edited by hand) is moved
+//! aside with a timestamp suffix instead of failing the command.
+//!
+//! [`files_under`] is the directory walk shared by the commands that match
+//! files against globs.
+
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use miette::{Context as _, IntoDiagnostic as _};
+use serde::de::DeserializeOwned;
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Replace `path` with `contents`: write `path.tmp`, fsync it, then rename
+/// it over `path`.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> miette::Result<()> {
+    let tmp = sibling(path, ".tmp");
+
+    let result = (|| {
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, path)
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+
+    result
+        .into_diagnostic()
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+/// Move a corrupt `path` aside as `path.corrupt-<timestamp>` and return
+/// where it went.
+fn quarantine(path: &Path) -> miette::Result<PathBuf> {
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S");
+    let aside = sibling(path, &format!(".corrupt-{stamp}"));
+
+    std::fs::rename(path, &aside)
+        .into_diagnostic()
+        .with_context(|| format!("moving aside {}", path.display()))?;
+
+    Ok(aside)
+}
+
+/// Parse the JSON at `path`. `None` when the file doesn't exist, or when it
+/// doesn't parse: then it is preserved under a `.corrupt-*` name and a
+/// warning says where, so the caller can start over from empty state.
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> miette::Result<Option<T>> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(e)
+                .into_diagnostic()
+                .with_context(|| format!("reading {}", path.display()));
+        }
+    };
+
+    match serde_json::from_slice(&bytes) {
+        Ok(value) => Ok(Some(value)),
+        Err(err) => {
+            let aside = quarantine(path)?;
+            eprintln!(
+                "warning: {} was unreadable ({err}); preserved it as {} and started fresh",
+                path.display(),
+                aside.display()
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Every file under `path`, at any depth, in path order. A file is its own
+/// only entry; a path that doesn't exist has none.
+pub fn files_under(path: &Path) -> miette::Result<Vec<PathBuf>> {
+    fn walk(dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                walk(&path, out)?;
+            } else if path.is_file() {
+                out.push(path);
+            }
+        }
+
+        Ok(())
+    }
+
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = vec![];
+
+    if path.is_dir() {
+        walk(path, &mut files)
+            .into_diagnostic()
+            .with_context(|| format!("reading {}", path.display()))?;
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_atomic_replaces_and_leaves_no_temp_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+
+        write_atomic(&path, b"{\"v\": 1}").unwrap();
+        write_atomic(&path, b"{\"v\": 2}").unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"{\"v\": 2}");
+        assert!(!sibling(&path, ".tmp").exists());
+    }
+
+    #[test]
+    fn interrupted_write_keeps_previous_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        write_atomic(&path, b"{\"v\": 1}").unwrap();
+
+        // A crash mid-write leaves only a partial temp file behind; the
+        // target itself was never opened.
+        std::fs::write(sibling(&path, ".tmp"), b"{\"v\": ").unwrap();
+
+        let value: serde_json::Value = read_json(&path).unwrap().unwrap();
+        assert_eq!(value["v"], 1);
+
+        write_atomic(&path, b"{\"v\": 3}").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"{\"v\": 3}");
+    }
+
+    #[test]
+    fn files_under_walks_nested_dirs_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("b/c")).unwrap();
+        std::fs::write(dir.path().join("b/c/x.tx3"), "").unwrap();
+        std::fs::write(dir.path().join("a.tx3"), "").unwrap();
+
+        let files = files_under(dir.path()).unwrap();
+        assert_eq!(
+            files,
+            vec![dir.path().join("a.tx3"), dir.path().join("b/c/x.tx3")]
+        );
+
+        assert_eq!(files_under(&files[0]).unwrap(), vec![files[0].clone()]);
+        assert!(files_under(&dir.path().join("missing")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn truncated_json_is_preserved_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        std::fs::write(&path, b"{\"files\": {\"a\": ").unwrap();
+
+        let value: Option<serde_json::Value> = read_json(&path).unwrap();
+        assert!(value.is_none());
+        assert!(!path.exists());
+
+        let preserved: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| name.starts_with("state.json.corrupt-"))
+            .collect();
+        assert_eq!(preserved.len(), 1);
+
+        let missing: Option<serde_json::Value> = read_json(&dir.path().join("none.json")).unwrap();
+        assert!(missing.is_none());
+    }
+}
@@ -0,0 +1,314 @@
+//! Guarded writes for files trix generates (codegen output, `trix init`
+//! templates).
+//!
+//! Every file written is recorded with its content hash in a manifest. On
+//! the next run a file whose content still matches its recorded hash is
+//! trix's to replace; one that differs was edited by hand and is only
+//! replaced according to the [`OverwritePolicy`]. A file that exists but
+//! was never recorded belongs to the user and needs `--force`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use cryptoxide::{digest::Digest as _, sha2::Sha256};
+use miette::{Context as _, IntoDiagnostic as _};
+use serde::{Deserialize, Serialize};
+
+/// Manifest file name inside a codegen output dir.
+pub const MANIFEST_FILE: &str = ".trix-generated.json";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OverwritePolicy {
+    /// Replace hand-edited files
+    Always,
+    /// Keep hand-edited files
+    Never,
+    /// Ask for each hand-edited file; keeps them when not in a terminal
+    #[default]
+    Ask,
+}
+
+pub fn hash(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.input(bytes);
+    hasher.result_str()
+}
+
+/// Content hash of every generated file, keyed by path relative to the
+/// guarded root.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    files: BTreeMap<PathBuf, String>,
+}
+
+/// What happened to one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Did not exist before.
+    Written,
+    /// Replaced.
+    Updated,
+    /// Already had the generated content.
+    Unchanged,
+    /// Kept as it was: hand-edited or user-owned.
+    Kept,
+}
+
+/// How an existing file relates to what trix last generated there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    New,
+    Identical,
+    /// Still what trix generated last time.
+    Generated,
+    /// Generated once, edited since.
+    Edited,
+    /// Never generated by trix.
+    UserOwned,
+}
+
+fn classify(current: Option<&[u8]>, recorded: Option<&str>, new: &[u8]) -> State {
+    let Some(current) = current else {
+        return State::New;
+    };
+
+    if current == new {
+        return State::Identical;
+    }
+
+    match recorded {
+        Some(recorded) if recorded == hash(current) => State::Generated,
+        Some(_) => State::Edited,
+        None => State::UserOwned,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Choice {
+    Overwrite,
+    Skip,
+    Diff,
+}
+
+impl std::fmt::Display for Choice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Choice::Overwrite => write!(f, "overwrite"),
+            Choice::Skip => write!(f, "skip"),
+            Choice::Diff => write!(f, "show diff"),
+        }
+    }
+}
+
+/// Writes files under `root`, consulting and updating the manifest at
+/// `manifest_path`. Call [`Guard::finish`] to persist the manifest.
+pub struct Guard {
+    root: PathBuf,
+    manifest_path: PathBuf,
+    manifest: Manifest,
+    policy: OverwritePolicy,
+    force: bool,
+}
+
+impl Guard {
+    pub fn open(
+        root: impl Into<PathBuf>,
+        manifest_path: impl Into<PathBuf>,
+        policy: OverwritePolicy,
+        force: bool,
+    ) -> miette::Result<Self> {
+        let manifest_path = manifest_path.into();
+
+        // A lost manifest only means every existing file is treated as
+        // user-owned until it is regenerated with --force.
+        let manifest = crate::fsutil::read_json(&manifest_path)?.unwrap_or_default();
+
+        Ok(Self {
+            root: root.into(),
+            manifest_path,
+            manifest,
+            policy,
+            force,
+        })
+    }
+
+    fn confirm_edited(&self, relative: &Path, current: &[u8], new: &[u8]) -> miette::Result<bool> {
+        match self.policy {
+            OverwritePolicy::Always => return Ok(true),
+            OverwritePolicy::Never => return Ok(false),
+            OverwritePolicy::Ask if !crate::term::prompt::is_interactive() => {
+                eprintln!(
+                    "warning: kept hand-edited {} (pass --overwrite-policy always to replace it)",
+                    relative.display()
+                );
+                return Ok(false);
+            }
+            OverwritePolicy::Ask => {}
+        }
+
+        loop {
+            let choice = crate::term::prompt::select(
+                &format!("{} was edited since trix generated it:", relative.display()),
+                vec![Choice::Overwrite, Choice::Skip, Choice::Diff],
+            )?;
+
+            match choice {
+                Choice::Overwrite => return Ok(true),
+                Choice::Skip => return Ok(false),
+                Choice::Diff => {
+                    let current = String::from_utf8_lossy(current);
+                    let new = String::from_utf8_lossy(new);
+                    let diff = similar::TextDiff::from_lines(current.as_ref(), new.as_ref());
+                    eprintln!("{}", diff.unified_diff().header("current", "generated"));
+                }
+            }
+        }
+    }
+
+    /// Write `contents` to `root/relative`, unless the file is hand-edited or
+    /// user-owned and the policy says to keep it.
+    pub fn write(&mut self, relative: &Path, contents: &[u8]) -> miette::Result<Outcome> {
+        let path = self.root.join(relative);
+        let current = std::fs::read(&path).ok();
+        let recorded = self.manifest.files.get(relative).map(String::as_str);
+
+        let state = classify(current.as_deref(), recorded, contents);
+
+        let outcome = match state {
+            State::Identical => Outcome::Unchanged,
+            State::New => Outcome::Written,
+            State::Generated => Outcome::Updated,
+            State::Edited => {
+                let current = current.as_deref().unwrap_or_default();
+                if self.confirm_edited(relative, current, contents)? {
+                    Outcome::Updated
+                } else {
+                    Outcome::Kept
+                }
+            }
+            State::UserOwned if self.force => Outcome::Updated,
+            State::UserOwned => {
+                eprintln!(
+                    "warning: kept {}, which trix did not generate (pass --force to replace it)",
+                    relative.display()
+                );
+                Outcome::Kept
+            }
+        };
+
+        if matches!(outcome, Outcome::Written | Outcome::Updated) {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).into_diagnostic()?;
+            }
+
+            std::fs::write(&path, contents)
+                .into_diagnostic()
+                .with_context(|| format!("writing {}", path.display()))?;
+        }
+
+        // A kept edit stays unrecorded as generated content, so the next run
+        // still sees it as edited.
+        if outcome != Outcome::Kept {
+            self.manifest
+                .files
+                .insert(relative.to_path_buf(), hash(contents));
+        }
+
+        Ok(outcome)
+    }
+
+    pub fn finish(self) -> miette::Result<()> {
+        if let Some(parent) = self.manifest_path.parent() {
+            std::fs::create_dir_all(parent).into_diagnostic()?;
+        }
+
+        let json = serde_json::to_vec_pretty(&self.manifest).into_diagnostic()?;
+
+        crate::fsutil::write_atomic(&self.manifest_path, json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard(root: &Path, policy: OverwritePolicy, force: bool) -> Guard {
+        Guard::open(root, root.join(MANIFEST_FILE), policy, force).unwrap()
+    }
+
+    #[test]
+    fn classify_covers_every_case() {
+        let generated = hash(b"v1");
+
+        assert_eq!(classify(None, None, b"v2"), State::New);
+        assert_eq!(classify(Some(b"v2"), None, b"v2"), State::Identical);
+        assert_eq!(
+            classify(Some(b"v1"), Some(&generated), b"v2"),
+            State::Generated
+        );
+        assert_eq!(
+            classify(Some(b"mine"), Some(&generated), b"v2"),
+            State::Edited
+        );
+        assert_eq!(classify(Some(b"mine"), None, b"v2"), State::UserOwned);
+    }
+
+    #[test]
+    fn generated_unchanged_file_is_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = Path::new("pkg/index.ts");
+
+        let mut first = guard(dir.path(), OverwritePolicy::Never, false);
+        assert_eq!(first.write(file, b"v1").unwrap(), Outcome::Written);
+        first.finish().unwrap();
+
+        let mut second = guard(dir.path(), OverwritePolicy::Never, false);
+        assert_eq!(second.write(file, b"v2").unwrap(), Outcome::Updated);
+        assert_eq!(std::fs::read(dir.path().join(file)).unwrap(), b"v2");
+    }
+
+    #[test]
+    fn user_edited_file_follows_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = Path::new("package.json");
+
+        let mut first = guard(dir.path(), OverwritePolicy::Never, false);
+        first.write(file, b"generated").unwrap();
+        first.finish().unwrap();
+
+        std::fs::write(dir.path().join(file), b"hand edited").unwrap();
+
+        let mut never = guard(dir.path(), OverwritePolicy::Never, false);
+        assert_eq!(never.write(file, b"regenerated").unwrap(), Outcome::Kept);
+        never.finish().unwrap();
+        assert_eq!(
+            std::fs::read(dir.path().join(file)).unwrap(),
+            b"hand edited"
+        );
+
+        let mut always = guard(dir.path(), OverwritePolicy::Always, false);
+        assert_eq!(
+            always.write(file, b"regenerated").unwrap(),
+            Outcome::Updated
+        );
+        assert_eq!(
+            std::fs::read(dir.path().join(file)).unwrap(),
+            b"regenerated"
+        );
+    }
+
+    #[test]
+    fn unrecorded_file_needs_force() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = Path::new("README.md");
+        std::fs::write(dir.path().join(file), b"mine").unwrap();
+
+        let mut plain = guard(dir.path(), OverwritePolicy::Always, false);
+        assert_eq!(plain.write(file, b"generated").unwrap(), Outcome::Kept);
+        assert_eq!(std::fs::read(dir.path().join(file)).unwrap(), b"mine");
+
+        let mut forced = guard(dir.path(), OverwritePolicy::Never, true);
+        assert_eq!(forced.write(file, b"generated").unwrap(), Outcome::Updated);
+        assert_eq!(std::fs::read(dir.path().join(file)).unwrap(), b"generated");
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use miette::{Context, IntoDiagnostic};
 use serde::{Deserialize, Serialize};
@@ -6,6 +6,20 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Config {
     pub telemetry: TelemetryConfig,
+
+    #[serde(default)]
+    pub cshell: CshellConfig,
+
+    #[serde(default)]
+    pub dolos: DolosConfig,
+
+    #[serde(default)]
+    pub http: HttpConfig,
+
+    /// Shortcuts expanded before argument parsing, e.g.
+    /// `tb = ["test", "tests/basic.toml"]`. See [`crate::alias`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, Vec<String>>,
 }
 
 fn default_otlp_endpoint() -> String {
@@ -16,9 +30,99 @@ fn default_timeout_ms() -> u64 {
     3_000 // 3 seconds
 }
 
+fn default_sample_rate() -> f64 {
+    1.0
+}
+
+/// A kind of data telemetry reports, toggled on its own with
+/// `trix telemetry enable|disable <category>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TelemetryCategory {
+    Usage,
+    Performance,
+    Errors,
+}
+
+impl TelemetryCategory {
+    pub const ALL: [TelemetryCategory; 3] = [
+        TelemetryCategory::Usage,
+        TelemetryCategory::Performance,
+        TelemetryCategory::Errors,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TelemetryCategory::Usage => "usage",
+            TelemetryCategory::Performance => "performance",
+            TelemetryCategory::Errors => "errors",
+        }
+    }
+
+    /// What the category sends, in one line.
+    pub fn covers(&self) -> &'static str {
+        match self {
+            TelemetryCategory::Usage => "an anonymous count of each command run",
+            TelemetryCategory::Performance => "how long each counted command took",
+            TelemetryCategory::Errors => {
+                "the error code a counted command failed with, never the message"
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct TelemetryCategories {
+    #[serde(default)]
+    pub usage: bool,
+    #[serde(default)]
+    pub performance: bool,
+    #[serde(default)]
+    pub errors: bool,
+}
+
+impl TelemetryCategories {
+    /// What a config from before categories existed reports.
+    pub const ALL: Self = Self {
+        usage: true,
+        performance: true,
+        errors: true,
+    };
+
+    /// What a new install reports.
+    pub const USAGE_ONLY: Self = Self {
+        usage: true,
+        performance: false,
+        errors: false,
+    };
+
+    pub fn get(&self, category: TelemetryCategory) -> bool {
+        match category {
+            TelemetryCategory::Usage => self.usage,
+            TelemetryCategory::Performance => self.performance,
+            TelemetryCategory::Errors => self.errors,
+        }
+    }
+
+    pub fn set(&mut self, category: TelemetryCategory, on: bool) {
+        match category {
+            TelemetryCategory::Usage => self.usage = on,
+            TelemetryCategory::Performance => self.performance = on,
+            TelemetryCategory::Errors => self.errors = on,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TelemetryConfig {
     pub enabled: bool,
+    /// What is reported while `enabled`. Configs written before categories
+    /// existed have none and report every category.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub categories: Option<TelemetryCategories>,
+    /// Fraction of invocations that report, from 0.0 to 1.0. Lower it on
+    /// CI machines that run trix many times a minute.
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate: f64,
     #[serde(default = "default_timeout_ms")]
     pub timeout_ms: u64,
     #[serde(default = "default_otlp_endpoint")]
@@ -27,23 +131,97 @@ pub struct TelemetryConfig {
     pub otlp_headers: HashMap<String, String>,
 }
 
+impl TelemetryConfig {
+    pub fn categories(&self) -> TelemetryCategories {
+        self.categories.unwrap_or(TelemetryCategories::ALL)
+    }
+
+    /// Whether `category` is reported.
+    pub fn allows(&self, category: TelemetryCategory) -> bool {
+        self.enabled && self.categories().get(category)
+    }
+}
+
 impl Default for TelemetryConfig {
     fn default() -> Self {
         Self {
             enabled: true,
+            categories: Some(TelemetryCategories::USAGE_ONLY),
             otlp_endpoint: default_otlp_endpoint(),
             otlp_headers: HashMap::new(),
             timeout_ms: default_timeout_ms(),
+            sample_rate: default_sample_rate(),
         }
     }
 }
 
+fn default_cshell_timeout_secs() -> u64 {
+    crate::spawn::cshell::DEFAULT_TIMEOUT_SECS
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CshellConfig {
+    /// Seconds a non-interactive cshell call may run before it is killed.
+    /// Overridden per invocation by `--cshell-timeout`.
+    #[serde(default = "default_cshell_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for CshellConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: default_cshell_timeout_secs(),
+        }
+    }
+}
+
+fn default_dolos_log_max_bytes() -> u64 {
+    crate::spawn::dolos::logs::DEFAULT_MAX_BYTES
+}
+
+fn default_dolos_log_keep() -> usize {
+    crate::spawn::dolos::logs::DEFAULT_KEEP
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DolosConfig {
+    /// Size a captured devnet log file may reach before it's rotated.
+    #[serde(default = "default_dolos_log_max_bytes")]
+    pub log_max_bytes: u64,
+    /// Number of devnet log files kept, the current one included.
+    #[serde(default = "default_dolos_log_keep")]
+    pub log_keep: usize,
+}
+
+impl Default for DolosConfig {
+    fn default() -> Self {
+        Self {
+            log_max_bytes: default_dolos_log_max_bytes(),
+            log_keep: default_dolos_log_keep(),
+        }
+    }
+}
+
+/// Outbound HTTP for requests trix sends itself (template downloads,
+/// GitHub lookups, telemetry, registry pulls and publishes). See
+/// [`crate::net::build_http_client`].
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct HttpConfig {
+    /// Proxy for every request, e.g. `http://proxy.corp:3128`. Without it,
+    /// `HTTPS_PROXY` and `HTTP_PROXY` apply.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_url: Option<String>,
+
+    /// Comma-separated hosts reached without the proxy, e.g.
+    /// `localhost,.corp`. Without it, `NO_PROXY` applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+}
+
 pub fn ensure_global_config() -> miette::Result<Config> {
-    let mut trix_path = crate::home::tx3_dir()?;
-    trix_path.push("trix/config.toml");
+    let trix_path = crate::home::config_file()?;
 
     if !trix_path.exists() {
-        std::fs::create_dir_all(trix_path.parent().unwrap()).into_diagnostic()?;
         save_config(&Config::default())?;
         print_telemetry_info();
     }
@@ -58,8 +236,7 @@ pub fn print_telemetry_info() {
 }
 
 pub fn read_config() -> miette::Result<Config> {
-    let mut trix_path = crate::home::tx3_dir()?;
-    trix_path.push("trix/config.toml");
+    let trix_path = crate::home::config_file()?;
 
     let trix_config = std::fs::read_to_string(&trix_path).into_diagnostic()?;
     let config = toml::from_str::<Config>(&trix_config)
@@ -72,9 +249,26 @@ pub fn read_config() -> miette::Result<Config> {
     Ok(config)
 }
 
+/// Aliases from the global config, read leniently: a missing or broken
+/// file means no aliases here and is reported by [`ensure_global_config`]
+/// once arguments are parsed.
+pub fn read_aliases() -> BTreeMap<String, Vec<String>> {
+    match crate::home::config_file() {
+        Ok(path) if path.exists() => read_config().map(|c| c.aliases).unwrap_or_default(),
+        _ => BTreeMap::new(),
+    }
+}
+
+/// `[http]` from the global config, read leniently like [`read_aliases`].
+pub fn read_http() -> HttpConfig {
+    match crate::home::config_file() {
+        Ok(path) if path.exists() => read_config().map(|c| c.http).unwrap_or_default(),
+        _ => HttpConfig::default(),
+    }
+}
+
 pub fn save_config(config: &Config) -> miette::Result<()> {
-    let mut trix_path = crate::home::tx3_dir()?;
-    trix_path.push("trix/config.toml");
+    let trix_path = crate::home::config_file()?;
 
     let toml_str = toml::to_string_pretty(&config).into_diagnostic()?;
 
@@ -84,3 +278,32 @@ pub fn save_config(config: &Config) -> miette::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY: &str = "[telemetry]\nenabled = true\n";
+
+    #[test]
+    fn legacy_enabled_flag_reports_every_category() {
+        let config: Config = toml::from_str(LEGACY).unwrap();
+
+        for category in TelemetryCategory::ALL {
+            assert!(config.telemetry.allows(category), "{}", category.name());
+        }
+
+        let config: Config = toml::from_str(&LEGACY.replace("true", "false")).unwrap();
+        assert!(!config.telemetry.allows(TelemetryCategory::Usage));
+    }
+
+    #[test]
+    fn new_install_reports_usage_only() {
+        let saved = toml::to_string_pretty(&Config::default()).unwrap();
+        let config: Config = toml::from_str(&saved).unwrap();
+
+        assert!(config.telemetry.allows(TelemetryCategory::Usage));
+        assert!(!config.telemetry.allows(TelemetryCategory::Performance));
+        assert!(!config.telemetry.allows(TelemetryCategory::Errors));
+    }
+}
@@ -1,13 +1,242 @@
-/// Utility functions for the home directory
-use std::path::PathBuf;
+//! Utility functions for the home directory
+//!
+//! Every piece of user-level state trix keeps outside a project resolves
+//! through [`resolve`]. Precedence, per [`Location`]:
+//!
+//! 1. `TRIX_HOME`, which relocates all trix state under one directory;
+//! 2. on Linux, `XDG_CONFIG_HOME` / `XDG_DATA_HOME` / `XDG_CACHE_HOME` when
+//!    set, each suffixed with `trix`;
+//! 3. the historical `~/.tx3` layout.
+//!
+//! The toolchain binaries under `~/.tx3/default/bin` belong to `tx3up` and
+//! are not relocated; use `TX3_<TOOL>_PATH` to point at a tool elsewhere.
+use std::path::{Path, PathBuf};
 
 use cryptoxide::{digest::Digest as _, sha2::Sha256};
 use miette::{Context as _, IntoDiagnostic as _};
 
+/// Kinds of user-level state, each resolved independently so XDG users can
+/// keep config, data and cache apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    /// Global `config.toml`.
+    Config,
+    /// Persistent state trix owns, e.g. the telemetry fingerprint.
+    Data,
+    /// Disposable state: tmp dirs and devnet homes.
+    Cache,
+}
+
+impl Location {
+    pub const ALL: [Location; 3] = [Location::Config, Location::Data, Location::Cache];
+
+    fn xdg_var(&self) -> &'static str {
+        match self {
+            Location::Config => "XDG_CONFIG_HOME",
+            Location::Data => "XDG_DATA_HOME",
+            Location::Cache => "XDG_CACHE_HOME",
+        }
+    }
+
+    fn trix_home_subdir(&self) -> Option<&'static str> {
+        match self {
+            Location::Config | Location::Data => None,
+            Location::Cache => Some("cache"),
+        }
+    }
+
+    /// Where this location lived before it became configurable, relative
+    /// to `~/.tx3`.
+    fn legacy_subdir(&self) -> &'static str {
+        match self {
+            Location::Config | Location::Data => "trix",
+            Location::Cache => "tmp",
+        }
+    }
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::Config => write!(f, "config"),
+            Location::Data => write!(f, "data"),
+            Location::Cache => write!(f, "cache"),
+        }
+    }
+}
+
+/// What decided a resolved path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    TrixHome,
+    Xdg(&'static str),
+    Legacy,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::TrixHome => write!(f, "TRIX_HOME"),
+            Source::Xdg(var) => write!(f, "{var}"),
+            Source::Legacy => write!(f, "default"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Resolved {
+    pub path: PathBuf,
+    pub source: Source,
+}
+
+fn non_empty_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+fn user_home() -> miette::Result<PathBuf> {
+    dirs::home_dir().ok_or_else(|| miette::miette!("failed to get home directory"))
+}
+
+fn resolve_with(
+    location: Location,
+    var: impl Fn(&str) -> Option<String>,
+    home: &Path,
+    honor_xdg: bool,
+) -> Resolved {
+    if let Some(root) = var("TRIX_HOME") {
+        let mut path = PathBuf::from(root);
+        if let Some(sub) = location.trix_home_subdir() {
+            path.push(sub);
+        }
+        return Resolved {
+            path,
+            source: Source::TrixHome,
+        };
+    }
+
+    if let Some(base) = var(location.xdg_var()).filter(|_| honor_xdg) {
+        return Resolved {
+            path: PathBuf::from(base).join("trix"),
+            source: Source::Xdg(location.xdg_var()),
+        };
+    }
+
+    Resolved {
+        path: home.join(".tx3").join(location.legacy_subdir()),
+        source: Source::Legacy,
+    }
+}
+
+/// Resolve `location` without touching the filesystem.
+pub fn resolve(location: Location) -> miette::Result<Resolved> {
+    let home = user_home()?;
+
+    Ok(resolve_with(
+        location,
+        non_empty_var,
+        &home,
+        cfg!(target_os = "linux"),
+    ))
+}
+
+/// Resolve `location` and make sure the directory exists.
+pub fn dir(location: Location) -> miette::Result<PathBuf> {
+    let path = resolve(location)?.path;
+
+    if !path.exists() {
+        std::fs::create_dir_all(&path)
+            .into_diagnostic()
+            .context(format!("failed to create trix {location} directory"))?;
+    }
+
+    Ok(path)
+}
+
+/// Path of the global `config.toml`.
+pub fn config_file() -> miette::Result<PathBuf> {
+    Ok(dir(Location::Config)?.join("config.toml"))
+}
+
+/// Files that used to live in `~/.tx3/trix`, by the location that owns
+/// them now. Cache contents are disposable and are not migrated.
+const LEGACY_FILES: &[(Location, &str)] = &[
+    (Location::Config, "config.toml"),
+    (Location::Data, "fingerprint"),
+];
+
+fn move_file(from: &Path, to: &Path) -> miette::Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent).into_diagnostic()?;
+    }
+
+    // rename fails across filesystems (e.g. into a mounted XDG dir).
+    if std::fs::rename(from, to).is_err() {
+        std::fs::copy(from, to).into_diagnostic()?;
+        std::fs::remove_file(from).into_diagnostic()?;
+    }
+
+    Ok(())
+}
+
+/// Detect state left at the legacy `~/.tx3/trix` location after the user
+/// has relocated it with `TRIX_HOME` / XDG variables, and offer to move it.
+/// Non-interactive sessions only get a note; nothing moves without consent.
+pub fn migrate_legacy_state() -> miette::Result<()> {
+    let Ok(home) = user_home() else {
+        return Ok(());
+    };
+
+    let mut pending = vec![];
+
+    for (location, file) in LEGACY_FILES {
+        let resolved = resolve(*location)?;
+        if resolved.source == Source::Legacy {
+            continue;
+        }
+
+        let legacy = home.join(".tx3").join(location.legacy_subdir()).join(file);
+        let target = resolved.path.join(file);
+
+        if legacy.is_file() && !target.exists() {
+            pending.push((legacy, target));
+        }
+    }
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let listing = pending
+        .iter()
+        .map(|(from, to)| format!("  {} → {}", from.display(), to.display()))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if !crate::term::prompt::is_interactive() {
+        eprintln!(
+            "note: trix state found at its old location; run trix interactively to move it, or move it by hand:\n{listing}\n"
+        );
+        return Ok(());
+    }
+
+    let accepted = crate::term::prompt::confirm(
+        &format!("trix state found at its old location:\n{listing}\nMove it?"),
+        true,
+    )?;
+
+    if accepted {
+        for (from, to) in pending {
+            move_file(&from, &to)
+                .context(format!("moving {} to {}", from.display(), to.display()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Root of the `tx3up`-managed toolchain.
 pub fn tx3_dir() -> miette::Result<PathBuf> {
-    let home = dirs::home_dir()
-        .ok_or_else(|| miette::miette!("failed to get home directory"))?
-        .join(".tx3");
+    let home = user_home()?.join(".tx3");
 
     if !home.exists() {
         std::fs::create_dir_all(&home)
@@ -33,7 +262,8 @@ pub fn bin_dir() -> miette::Result<PathBuf> {
     Ok(bin)
 }
 
-pub fn default_tool_path(name: &str) -> miette::Result<PathBuf> {
+/// Where `tx3up` installs `name`, whether or not it's there.
+pub fn default_tool_location(name: &str) -> miette::Result<PathBuf> {
     let bin = bin_dir()?;
 
     let mut file = bin.join(name);
@@ -42,6 +272,12 @@ pub fn default_tool_path(name: &str) -> miette::Result<PathBuf> {
         file.set_extension("exe");
     }
 
+    Ok(file)
+}
+
+pub fn default_tool_path(name: &str) -> miette::Result<PathBuf> {
+    let file = default_tool_location(name)?;
+
     if !file.is_file() {
         miette::bail!(
             help = "please run tx3up or make sure your tx3 toolchain is correctly installed",
@@ -70,31 +306,14 @@ pub fn tool_path(name: &str) -> miette::Result<PathBuf> {
     }
 }
 
-#[allow(dead_code)]
 pub fn tmp_dir() -> miette::Result<PathBuf> {
-    let home = tx3_dir()?;
-
-    let tmp = home.join("tmp");
-
-    if !tmp.exists() {
-        std::fs::create_dir_all(&tmp)
-            .into_diagnostic()
-            .context("failed to create tx3 tmp directory")?;
-    }
-
-    Ok(tmp)
+    dir(Location::Cache)
 }
 
 #[allow(dead_code)]
 pub fn consistent_tmp_dir(prefix: &str, hashable: &[u8]) -> miette::Result<PathBuf> {
     let tmp = tmp_dir()?;
 
-    if !tmp.exists() {
-        std::fs::create_dir_all(&tmp)
-            .into_diagnostic()
-            .context("failed to create tx3 tmp directory")?;
-    }
-
     let mut hasher = Sha256::new();
 
     hasher.input(hashable);
@@ -105,3 +324,54 @@ pub fn consistent_tmp_dir(prefix: &str, hashable: &[u8]) -> miette::Result<PathB
 
     Ok(path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| v.to_string())
+        }
+    }
+
+    #[test]
+    fn defaults_to_legacy_layout() {
+        let home = Path::new("/home/u");
+
+        let config = resolve_with(Location::Config, env(&[]), home, true);
+        assert_eq!(config.path, PathBuf::from("/home/u/.tx3/trix"));
+        assert_eq!(config.source, Source::Legacy);
+
+        let cache = resolve_with(Location::Cache, env(&[]), home, true);
+        assert_eq!(cache.path, PathBuf::from("/home/u/.tx3/tmp"));
+    }
+
+    #[test]
+    fn xdg_vars_apply_only_where_honored() {
+        let home = Path::new("/home/u");
+        let vars = [("XDG_CACHE_HOME", "/var/cache/u")];
+
+        let linux = resolve_with(Location::Cache, env(&vars), home, true);
+        assert_eq!(linux.path, PathBuf::from("/var/cache/u/trix"));
+        assert_eq!(linux.source, Source::Xdg("XDG_CACHE_HOME"));
+
+        let other = resolve_with(Location::Cache, env(&vars), home, false);
+        assert_eq!(other.source, Source::Legacy);
+    }
+
+    #[test]
+    fn trix_home_wins_over_xdg() {
+        let home = Path::new("/home/u");
+        let vars = [("TRIX_HOME", "/trix"), ("XDG_CONFIG_HOME", "/cfg")];
+
+        let config = resolve_with(Location::Config, env(&vars), home, true);
+        assert_eq!(config.path, PathBuf::from("/trix"));
+        assert_eq!(config.source, Source::TrixHome);
+
+        let cache = resolve_with(Location::Cache, env(&vars), home, true);
+        assert_eq!(cache.path, PathBuf::from("/trix/cache"));
+    }
+}
@@ -242,7 +242,7 @@ fn check_trust(
 fn pull_ref(config: &RootConfig, reference: &ProtocolRef) -> Result<oci::PulledArtifact> {
     let registry_url = config.registry_url();
     let oci_reference = oci::reference_for(&registry_url, reference)?;
-    let client = oci::client_for(&registry_url);
+    let client = oci::client_for(&registry_url)?;
     futures::executor::block_on(oci::pull(&client, &oci_reference))
 }
 
@@ -304,7 +304,7 @@ fn write_cache(
         verified_at: None,
     };
     let manifest_bytes = serde_json::to_vec_pretty(&manifest).into_diagnostic()?;
-    std::fs::write(&paths.manifest, manifest_bytes).into_diagnostic()?;
+    crate::fsutil::write_atomic(&paths.manifest, manifest_bytes)?;
     Ok(())
 }
 
@@ -51,17 +51,24 @@ pub struct ImageMetadata {
     pub commit_sha: Option<String>,
 }
 
-pub fn client_for(registry_url: &str) -> oci_client::Client {
+/// Registry client for `registry_url`, routed through the same proxy as
+/// trix's own HTTP requests.
+pub fn client_for(registry_url: &str) -> Result<oci_client::Client> {
     let registry_protocol = registry_url.split("://").next().unwrap_or("https");
+    let proxy = crate::net::checked_proxy_settings()?;
+
     let client_config = oci_client::client::ClientConfig {
         protocol: if registry_protocol == "http" {
             oci_client::client::ClientProtocol::Http
         } else {
             oci_client::client::ClientProtocol::Https
         },
+        http_proxy: proxy.http,
+        https_proxy: proxy.https,
+        no_proxy: proxy.no_proxy,
         ..Default::default()
     };
-    oci_client::Client::new(client_config)
+    Ok(oci_client::Client::new(client_config))
 }
 
 fn registry_host(registry_url: &str) -> &str {
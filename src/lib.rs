@@ -4,6 +4,7 @@
 //! including configuration management, command execution, and blockchain
 //! integration for the Tx3 language.
 
+pub mod alias;
 pub mod builder;
 pub mod cli;
 pub mod commands;
@@ -11,10 +12,17 @@ pub mod config;
 pub mod interfaces;
 pub mod devnet;
 pub mod dirs;
+pub mod envfile;
+pub mod fsutil;
+pub mod generated;
 pub mod global;
 pub mod home;
+pub mod net;
 pub mod refs;
+pub mod secrets;
 pub mod spawn;
+pub mod submissions;
 pub mod telemetry;
+pub mod term;
 pub mod updates;
 pub mod wallet;
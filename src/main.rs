@@ -1,4 +1,7 @@
-use std::path::PathBuf;
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
 use clap::Parser;
 
@@ -15,13 +18,25 @@ use miette::{IntoDiagnostic as _, Result};
 /// on-disk path so callers (e.g. `trix codegen`) can save back to the same
 /// file regardless of cwd.
 pub fn load_config() -> Result<Option<(RootConfig, PathBuf)>> {
+    let Some(candidate) = find_config()? else {
+        return Ok(None);
+    };
+
+    // A file from an older trix gets a pointer to `trix migrate` rather
+    // than whatever serde makes of it.
+    trix::config::migrate::ensure_current(&candidate)?;
+
+    let config = RootConfig::load(&candidate)?;
+    Ok(Some((config, candidate)))
+}
+
+fn find_config() -> Result<Option<PathBuf>> {
     let mut cwd = std::env::current_dir().into_diagnostic()?;
 
     loop {
         let candidate = cwd.join("trix.toml");
         if candidate.exists() {
-            let config = RootConfig::load(&candidate)?;
-            return Ok(Some((config, candidate)));
+            return Ok(Some(candidate));
         }
         match cwd.parent() {
             Some(parent) => cwd = parent.to_path_buf(),
@@ -32,8 +47,11 @@ pub fn load_config() -> Result<Option<(RootConfig, PathBuf)>> {
 
 fn run_global_command(cli: Cli) -> Result<()> {
     match cli.command {
-        Commands::Init(args) => cmds::init::run(args, None),
+        Commands::Init(args) => cmds::init::run(args, None, None),
         Commands::Telemetry(args) => cmds::telemetry::run(args),
+        Commands::Doctor(args) => cmds::doctor::run(args),
+        Commands::Alias(args) => cmds::alias::run(args),
+        Commands::Secret(args) => cmds::secret::run(args),
         _ => Err(miette::miette!("No trix.toml found in current directory")),
     }
 }
@@ -43,28 +61,48 @@ async fn run_scoped_command(cli: Cli, config: RootConfig, config_path: PathBuf)
     // spawn a tool, so version gating (spawn::compat) enforces them.
     trix::spawn::compat::register_project_requirements(&config)?;
 
+    // Validation reports a broken profile rather than failing on it up front.
+    if let Commands::ConfigValidate(args) = cli.command {
+        return cmds::config::validate::run(args, &config, &config_path);
+    }
+
+    // Either version may lack the profile selected with `--profile`.
+    if let Commands::ConfigDiff(args) = cli.command {
+        return cmds::config::diff::run(args, &config, &config_path);
+    }
+
     let profile = config.resolve_profile(&cli.profile)?;
 
-    let metric = telemetry::track_command_execution(&cli);
+    let metric: Option<telemetry::CommandMetric> = (&cli).into();
+    let started = Instant::now();
 
     let result = match cli.command {
-        Commands::Init(args) => cmds::init::run(args, Some(&config)),
+        Commands::Init(args) => cmds::init::run(args, Some(&config), Some(&config_path)),
         Commands::Invoke(args) => cmds::invoke::run(args, &config, &profile),
+        Commands::Shell(args) => cmds::shell::run(args, &config, &profile),
         Commands::Devnet(args) => cmds::devnet::run(args, &config, &profile),
         Commands::Explore(args) => cmds::explore::run(args, &config, &profile),
         Commands::Codegen(args) => cmds::codegen::run(args, &config, &config_path, &profile).await,
         Commands::Check(args) => cmds::check::run(args, &config, &profile),
+        Commands::ConfigValidate(_) => unreachable!("handled before profile resolution"),
+        Commands::ConfigDiff(_) => unreachable!("handled before profile resolution"),
+        Commands::Migrate(_) => unreachable!("handled before the config is loaded"),
         Commands::Inspect(args) => cmds::inspect::run(args, &config),
+        Commands::Submissions(args) => cmds::submissions::run(args, &config, &profile),
         Commands::Test(args) => cmds::test::run(args, &config, &profile),
         Commands::Build(args) => cmds::build::run(args, &config, &profile),
         Commands::Identities(args) => cmds::identities::run(args, &config, &profile),
+        Commands::Wallet(args) => cmds::wallet::run(args, &config, &profile),
         Commands::Profile(args) => cmds::profile::run(args, &config, &profile),
         Commands::Publish(args) => cmds::publish::run(args, &config).await,
         Commands::Use(args) => cmds::use_cmd::run(args, &config, &config_path, &profile),
         Commands::Telemetry(args) => cmds::telemetry::run(args),
+        Commands::Doctor(args) => cmds::doctor::run(args),
+        Commands::Alias(args) => cmds::alias::run(args),
+        Commands::Secret(args) => cmds::secret::run(args),
     };
 
-    if let Some(handle) = metric {
+    if let Some(handle) = telemetry::track_command_execution(metric, started.elapsed(), &result) {
         handle.await.unwrap();
     }
 
@@ -73,7 +111,19 @@ async fn run_scoped_command(cli: Cli, config: RootConfig, config_path: PathBuf)
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let args = trix::alias::expand(
+        std::env::args_os().collect(),
+        &global::read_aliases(),
+        &trix::alias::builtin_names(),
+    )?;
+
+    let cli = Cli::parse_from(args);
+
+    trix::term::console::set_color_choice(cli.color);
+    let colors = trix::term::console::colors_enabled();
+    let _ = miette::set_hook(Box::new(move |_| {
+        Box::new(miette::MietteHandlerOpts::new().color(colors).build())
+    }));
 
     if cli.verbose {
         tracing_subscriber::fmt::fmt()
@@ -81,13 +131,52 @@ async fn main() -> Result<()> {
             .init();
     }
 
+    // Healthchecks skip the startup work below to stay fast and silent.
+    if let Commands::Devnet(args) = &cli.command
+        && let Some(probe) = args.probe()
+    {
+        cmds::devnet::probe::run(probe);
+    }
+
+    // Kill any tool child still running if trix is interrupted mid-call.
+    trix::spawn::shutdown::install();
+
     // Check for updates silently
     let _ = updates::check_for_updates();
 
+    // Listing the built-in codegen plugins needs no project.
+    if let Commands::Codegen(args) = &cli.command
+        && let Some(list) = args.list_plugins()
+    {
+        return cmds::codegen::list_plugins(list);
+    }
+
+    // Migrating works on a trix.toml the current schema can't load yet.
+    if let Commands::Migrate(args) = cli.command {
+        return match find_config()? {
+            Some(path) => cmds::migrate::run(args, &path),
+            None => Err(miette::miette!("No trix.toml found in current directory")),
+        };
+    }
+
     let loaded = load_config()?;
 
+    // Offer to move state left behind by a TRIX_HOME / XDG relocation
+    // before the global config is read from its new place.
+    trix::home::migrate_legacy_state()?;
+
     let global_config = global::ensure_global_config()?;
 
+    let cshell_timeout = cli
+        .cshell_timeout
+        .unwrap_or(global_config.cshell.timeout_secs);
+    trix::spawn::cshell::set_timeout(Duration::from_secs(cshell_timeout));
+
+    trix::spawn::dolos::logs::set_config(trix::spawn::dolos::logs::LogConfig {
+        max_bytes: global_config.dolos.log_max_bytes,
+        keep: global_config.dolos.log_keep,
+    });
+
     if global_config.telemetry.enabled {
         telemetry::initialize_telemetry(&global_config.telemetry)?;
     }
@@ -102,8 +191,19 @@ async fn main() -> Result<()> {
         loaded
     };
 
-    match loaded {
+    let net_stats = cli.net_stats;
+    if net_stats {
+        trix::net::enable_stats();
+    }
+
+    let result = match loaded {
         Some((config, path)) => run_scoped_command(cli, config, path).await,
         None => run_global_command(cli),
+    };
+
+    if net_stats {
+        trix::net::print_stats();
     }
+
+    result
 }
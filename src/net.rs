@@ -0,0 +1,472 @@
+//! Shared HTTP layer. Requests trix sends itself go through an
+//! [`HttpClient`] from [`build_http_client`], which routes them through the
+//! proxy configured under `[http]` in the global config. The OCI registry
+//! client gets the same proxy from [`checked_proxy_settings`]. With `--net-stats`
+//! every request the client sends is timed and tallied per host, and the
+//! totals are printed when the command ends. Without the flag nothing is
+//! recorded and sending is a plain `RequestBuilder::send`.
+//!
+//! UTxO RPC queries trix makes itself use a client from [`u5c_client`].
+//!
+//! TRP and u5c traffic of child tools (cshell, tx3c) happens in their own
+//! processes and is not included.
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use miette::{IntoDiagnostic as _, miette};
+use reqwest::header::{HeaderMap, HeaderName};
+use reqwest::{Client, NoProxy, Proxy, RequestBuilder, Response};
+use serde::Serialize;
+use utxorpc::{Cardano, ClientBuilder, QueryClient};
+
+use crate::config::U5cConfig;
+use crate::global::HttpConfig;
+
+static STATS: OnceLock<Recorder> = OnceLock::new();
+
+const PROXY_HELP: &str =
+    "set `[http] proxy_url` in the global config, or fix HTTP_PROXY / HTTPS_PROXY";
+
+/// Proxies to route through, per scheme, and the hosts that bypass them.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ProxySettings {
+    pub http: Option<String>,
+    pub https: Option<String>,
+    pub no_proxy: Option<String>,
+}
+
+impl ProxySettings {
+    /// `[http]` from the global config wins; otherwise the usual proxy env
+    /// vars, upper or lower case, apply. Empty values count as unset.
+    fn resolve(config: &HttpConfig, env: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name: &str| {
+            [name.to_uppercase(), name.to_lowercase()]
+                .iter()
+                .find_map(|name| env(name).filter(|value| !value.is_empty()))
+        };
+
+        let some = |value: &Option<String>| value.clone().filter(|value| !value.is_empty());
+
+        Self {
+            http: some(&config.proxy_url).or_else(|| var("HTTP_PROXY")),
+            https: some(&config.proxy_url).or_else(|| var("HTTPS_PROXY")),
+            no_proxy: some(&config.no_proxy).or_else(|| var("NO_PROXY")),
+        }
+    }
+
+    fn client(&self) -> miette::Result<Client> {
+        let mut builder = Client::builder();
+
+        let bypass = self.no_proxy.as_deref().and_then(NoProxy::from_string);
+
+        let no_proxy = |proxy: reqwest::Result<Proxy>, url: &str| {
+            proxy
+                .map(|proxy| proxy.no_proxy(bypass.clone()))
+                .map_err(|err| miette!(help = PROXY_HELP, "invalid proxy URL `{url}`: {err}"))
+        };
+
+        // With nothing configured, reqwest's own system proxy lookup stays
+        // in place.
+        if let Some(url) = &self.http {
+            builder = builder.proxy(no_proxy(Proxy::http(url), url)?);
+        }
+
+        if let Some(url) = &self.https {
+            builder = builder.proxy(no_proxy(Proxy::https(url), url)?);
+        }
+
+        builder.build().into_diagnostic()
+    }
+}
+
+/// A client whose requests are recorded when `--net-stats` is on.
+#[derive(Debug, Clone, Default)]
+pub struct HttpClient(Client);
+
+/// A request of an [`HttpClient`], built like a `RequestBuilder`.
+pub struct HttpRequest(RequestBuilder);
+
+impl HttpClient {
+    pub fn get(&self, url: &str) -> HttpRequest {
+        HttpRequest(self.0.get(url))
+    }
+
+    pub fn post(&self, url: &str) -> HttpRequest {
+        HttpRequest(self.0.post(url))
+    }
+}
+
+impl HttpRequest {
+    pub fn header(self, key: HeaderName, value: &str) -> Self {
+        Self(self.0.header(key, value))
+    }
+
+    pub fn headers(self, headers: HeaderMap) -> Self {
+        Self(self.0.headers(headers))
+    }
+
+    pub fn json<T: Serialize + ?Sized>(self, json: &T) -> Self {
+        Self(self.0.json(json))
+    }
+
+    /// Send the request, recording it when `--net-stats` is on.
+    pub async fn send(self) -> reqwest::Result<Response> {
+        match STATS.get() {
+            None => self.0.send().await,
+            Some(recorder) => timed(recorder, self.0).await,
+        }
+    }
+}
+
+/// A client for requests trix sends itself, routed through the proxy from
+/// `[http]` in the global config, or else from `HTTP_PROXY`, `HTTPS_PROXY`
+/// and `NO_PROXY`.
+pub fn build_http_client() -> miette::Result<HttpClient> {
+    proxy_settings().client().map(HttpClient)
+}
+
+/// The proxy settings [`build_http_client`] uses, for clients built by
+/// other crates, like the OCI registry client. Fails like
+/// [`build_http_client`] when a proxy URL doesn't parse.
+pub fn checked_proxy_settings() -> miette::Result<ProxySettings> {
+    let settings = proxy_settings();
+    settings.client()?;
+
+    Ok(settings)
+}
+
+fn proxy_settings() -> ProxySettings {
+    ProxySettings::resolve(&crate::global::read_http(), |var| std::env::var(var).ok())
+}
+
+/// A UTxO RPC query client for `u5c`, sending its headers with the
+/// secrets they reference resolved.
+pub async fn u5c_client(u5c: &U5cConfig) -> miette::Result<QueryClient<Cardano>> {
+    let mut builder = ClientBuilder::new().uri(&u5c.url).into_diagnostic()?;
+
+    for (key, value) in crate::secrets::resolve_headers(&u5c.headers)?.iter() {
+        builder = builder.metadata(key, value).into_diagnostic()?;
+    }
+
+    Ok(builder.build::<QueryClient<Cardano>>().await)
+}
+
+/// Start recording request stats for the rest of the process.
+pub fn enable_stats() {
+    let _ = STATS.set(Recorder::default());
+}
+
+/// Print the per-host table to stderr, if stats are on and anything was
+/// sent.
+pub fn print_stats() {
+    let Some(recorder) = STATS.get() else {
+        return;
+    };
+
+    let summary = recorder.summary();
+
+    if summary.is_empty() {
+        eprintln!("net-stats: no HTTP requests");
+        return;
+    }
+
+    eprintln!("{}", render(&summary));
+}
+
+#[derive(Debug, Default)]
+struct Samples {
+    latencies: Vec<Duration>,
+    /// Count per HTTP status; transport failures have no status.
+    statuses: BTreeMap<u16, usize>,
+    errors: usize,
+}
+
+#[derive(Debug, Default)]
+struct Recorder {
+    hosts: Mutex<BTreeMap<String, Samples>>,
+}
+
+impl Recorder {
+    fn record(&self, host: String, status: Option<u16>, elapsed: Duration) {
+        let mut hosts = self.hosts.lock().unwrap();
+        let samples = hosts.entry(host).or_default();
+
+        samples.latencies.push(elapsed);
+
+        match status {
+            Some(status) => *samples.statuses.entry(status).or_default() += 1,
+            None => samples.errors += 1,
+        }
+
+        if status.is_some_and(|s| s >= 400) {
+            samples.errors += 1;
+        }
+    }
+
+    fn summary(&self) -> Vec<HostSummary> {
+        let hosts = self.hosts.lock().unwrap();
+
+        hosts
+            .iter()
+            .map(|(host, samples)| {
+                let mut sorted = samples.latencies.clone();
+                sorted.sort();
+
+                HostSummary {
+                    host: host.clone(),
+                    requests: sorted.len(),
+                    errors: samples.errors,
+                    statuses: samples.statuses.clone(),
+                    p50: percentile(&sorted, 50),
+                    p95: percentile(&sorted, 95),
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HostSummary {
+    host: String,
+    requests: usize,
+    errors: usize,
+    statuses: BTreeMap<u16, usize>,
+    p50: Duration,
+    p95: Duration,
+}
+
+/// Nearest-rank percentile of `sorted`.
+fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn host_of(request: &reqwest::Request) -> String {
+    let url = request.url();
+    let host = url.host_str().unwrap_or("unknown");
+
+    match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    }
+}
+
+async fn timed(recorder: &Recorder, request: RequestBuilder) -> reqwest::Result<Response> {
+    let (client, request) = request.build_split();
+    let request = request?;
+    let host = host_of(&request);
+
+    let started = Instant::now();
+    let result = client.execute(request).await;
+    let elapsed = started.elapsed();
+
+    let status = result.as_ref().ok().map(|r| r.status().as_u16());
+    recorder.record(host, status, elapsed);
+
+    result
+}
+
+fn render(summary: &[HostSummary]) -> String {
+    let rows: Vec<[String; 6]> = summary
+        .iter()
+        .map(|s| {
+            let statuses = s
+                .statuses
+                .iter()
+                .map(|(status, count)| format!("{status}x{count}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            [
+                s.host.clone(),
+                s.requests.to_string(),
+                s.errors.to_string(),
+                s.p50.as_millis().to_string(),
+                s.p95.as_millis().to_string(),
+                statuses,
+            ]
+        })
+        .collect();
+
+    let header = ["host", "requests", "errors", "p50 ms", "p95 ms", "statuses"].map(String::from);
+
+    let mut widths = header.clone().map(|h| h.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    std::iter::once(&header)
+        .chain(&rows)
+        .map(|row| {
+            row.iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    fn http_config(proxy_url: Option<&str>, no_proxy: Option<&str>) -> HttpConfig {
+        HttpConfig {
+            proxy_url: proxy_url.map(String::from),
+            no_proxy: no_proxy.map(String::from),
+        }
+    }
+
+    #[test]
+    fn proxy_config_wins_over_env() {
+        let env = |name: &str| match name {
+            "https_proxy" => Some("http://env-proxy:3128".to_string()),
+            "HTTP_PROXY" => Some(String::new()),
+            "NO_PROXY" => Some("localhost".to_string()),
+            _ => None,
+        };
+
+        let from_env = ProxySettings::resolve(&HttpConfig::default(), env);
+        assert_eq!(
+            from_env,
+            ProxySettings {
+                http: None,
+                https: Some("http://env-proxy:3128".to_string()),
+                no_proxy: Some("localhost".to_string()),
+            }
+        );
+
+        let config = http_config(Some("http://corp:8080"), Some(".corp"));
+        let from_config = ProxySettings::resolve(&config, env);
+        assert_eq!(
+            from_config,
+            ProxySettings {
+                http: Some("http://corp:8080".to_string()),
+                https: Some("http://corp:8080".to_string()),
+                no_proxy: Some(".corp".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn client_sends_through_proxy() {
+        let mut server = mockito::Server::new();
+
+        // A proxy receives the request for the origin host.
+        let proxied = server
+            .mock("GET", "/ping")
+            .match_header("host", "trix.invalid")
+            .with_body("pong")
+            .expect(1)
+            .create();
+
+        let settings = ProxySettings::resolve(&http_config(Some(&server.url()), None), |_| None);
+        let client = settings.client().unwrap();
+
+        let body = runtime().block_on(async {
+            let response = client.get("http://trix.invalid/ping").send().await.unwrap();
+            response.text().await.unwrap()
+        });
+
+        assert_eq!(body, "pong");
+        proxied.assert();
+    }
+
+    #[test]
+    fn invalid_proxy_url_is_reported() {
+        let settings = ProxySettings::resolve(&http_config(Some("not a url"), None), |_| None);
+
+        let err = settings.client().unwrap_err();
+        assert!(err.to_string().contains("invalid proxy URL"), "{err}");
+    }
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let sorted: Vec<_> = (1..=20).map(Duration::from_millis).collect();
+
+        assert_eq!(percentile(&sorted, 50), Duration::from_millis(10));
+        assert_eq!(percentile(&sorted, 95), Duration::from_millis(19));
+        assert_eq!(percentile(&[], 95), Duration::ZERO);
+    }
+
+    #[test]
+    fn records_latency_and_errors_per_host() {
+        let mut server = mockito::Server::new();
+
+        let slow = server
+            .mock("GET", "/slow")
+            .with_body_from_request(|_| {
+                std::thread::sleep(Duration::from_millis(80));
+                b"ok".to_vec()
+            })
+            .expect(2)
+            .create();
+
+        let failing = server
+            .mock("GET", "/fail")
+            .with_status(503)
+            .expect(1)
+            .create();
+
+        let recorder = Recorder::default();
+        let client = reqwest::Client::new();
+
+        runtime().block_on(async {
+            for path in ["/slow", "/slow", "/fail"] {
+                let url = format!("{}{path}", server.url());
+                timed(&recorder, client.get(url)).await.unwrap();
+            }
+        });
+
+        slow.assert();
+        failing.assert();
+
+        let summary = recorder.summary();
+        assert_eq!(summary.len(), 1);
+
+        let host = &summary[0];
+        assert_eq!(host.requests, 3);
+        assert_eq!(host.errors, 1);
+        assert_eq!(host.statuses, BTreeMap::from([(200, 2), (503, 1)]));
+        assert!(host.p50 >= Duration::from_millis(80), "p50 {:?}", host.p50);
+        assert!(host.p95 >= Duration::from_millis(80), "p95 {:?}", host.p95);
+
+        let table = render(&summary);
+        assert!(table.starts_with("host"));
+        assert!(table.contains(&host.host));
+    }
+
+    #[test]
+    fn transport_failure_counts_as_error() {
+        let recorder = Recorder::default();
+        let client = reqwest::Client::new();
+
+        // Nothing listens on port 9 (discard) in the test environment.
+        let result = runtime().block_on(timed(&recorder, client.get("http://127.0.0.1:9/")));
+        assert!(result.is_err());
+
+        let summary = recorder.summary();
+        assert_eq!(summary[0].host, "127.0.0.1:9");
+        assert_eq!(summary[0].errors, 1);
+        assert!(summary[0].statuses.is_empty());
+    }
+}
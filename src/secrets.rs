@@ -0,0 +1,181 @@
+//! Indirect values for secrets trix sends over the wire, such as TRP and
+//! u5c headers. A value is used as written unless it starts with a scheme:
+//!
+//! - `env:VAR` reads `VAR` from the process environment, or from the
+//!   profile's env file when a command watches it (see [`crate::envfile`]);
+//! - `keyring:service/account` reads the OS credential store (macOS
+//!   Keychain, Windows Credential Manager or the Secret Service). Only
+//!   available when trix is built with the `keyring` feature.
+//!
+//! Resolution happens right before a value is used, so trix.toml and
+//! `trix profile show` only ever see the reference.
+
+use std::collections::HashMap;
+
+use miette::{Context as _, bail};
+
+const ENV_PREFIX: &str = "env:";
+const KEYRING_PREFIX: &str = "keyring:";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretRef<'a> {
+    Literal(&'a str),
+    Env(&'a str),
+    Keyring { service: &'a str, account: &'a str },
+}
+
+/// Split `service/account`. The account is everything after the first
+/// `/`, so accounts may themselves contain slashes.
+pub fn parse_keyring_key(key: &str) -> miette::Result<(&str, &str)> {
+    match key.split_once('/') {
+        Some((service, account)) if !service.is_empty() && !account.is_empty() => {
+            Ok((service, account))
+        }
+        _ => bail!(
+            help = "write it as `service/account`, e.g. `trix/preview-trp`",
+            "invalid keyring key `{key}`"
+        ),
+    }
+}
+
+impl<'a> SecretRef<'a> {
+    pub fn parse(value: &'a str) -> miette::Result<Self> {
+        if let Some(var) = value.strip_prefix(ENV_PREFIX) {
+            return Ok(SecretRef::Env(var));
+        }
+
+        if let Some(key) = value.strip_prefix(KEYRING_PREFIX) {
+            let (service, account) = parse_keyring_key(key)?;
+            return Ok(SecretRef::Keyring { service, account });
+        }
+
+        Ok(SecretRef::Literal(value))
+    }
+
+    pub fn resolve(&self) -> miette::Result<String> {
+        match self {
+            SecretRef::Literal(value) => Ok(value.to_string()),
+            SecretRef::Env(var) => match crate::envfile::var(var) {
+                Some(value) => Ok(value),
+                None => bail!(
+                    help = "export the variable, or add it to the profile's env file",
+                    "secret not found: env backend has no variable `{var}`"
+                ),
+            },
+            SecretRef::Keyring { service, account } => keyring::get(service, account),
+        }
+    }
+}
+
+/// Resolve one configured value.
+pub fn resolve(value: &str) -> miette::Result<String> {
+    SecretRef::parse(value)?.resolve()
+}
+
+/// Resolve every value of a header map, naming the header on failure.
+pub fn resolve_headers(headers: &HashMap<String, String>) -> miette::Result<HashMap<String, String>> {
+    headers
+        .iter()
+        .map(|(key, value)| {
+            let resolved = resolve(value).with_context(|| format!("resolving header `{key}`"))?;
+            Ok((key.clone(), resolved))
+        })
+        .collect()
+}
+
+#[cfg(feature = "keyring")]
+pub mod keyring {
+    use miette::{IntoDiagnostic as _, bail, miette};
+
+    #[cfg(target_os = "macos")]
+    pub const BACKEND: &str = "macOS Keychain";
+    #[cfg(target_os = "windows")]
+    pub const BACKEND: &str = "Windows Credential Manager";
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    pub const BACKEND: &str = "Secret Service";
+
+    fn entry(service: &str, account: &str) -> miette::Result<::keyring::Entry> {
+        ::keyring::Entry::new(service, account)
+            .map_err(|e| miette!("{BACKEND}: can't open `{service}/{account}`: {e}"))
+    }
+
+    pub fn get(service: &str, account: &str) -> miette::Result<String> {
+        match entry(service, account)?.get_password() {
+            Ok(secret) => Ok(secret),
+            Err(::keyring::Error::NoEntry) => bail!(
+                help = format!("store it with `trix secret set {service}/{account}`"),
+                "secret not found: {BACKEND} has no entry for `{service}/{account}`"
+            ),
+            Err(e) => bail!("{BACKEND}: can't read `{service}/{account}`: {e}"),
+        }
+    }
+
+    pub fn set(service: &str, account: &str, secret: &str) -> miette::Result<()> {
+        entry(service, account)?
+            .set_password(secret)
+            .into_diagnostic()
+    }
+
+    pub fn delete(service: &str, account: &str) -> miette::Result<()> {
+        match entry(service, account)?.delete_credential() {
+            Ok(()) => Ok(()),
+            Err(::keyring::Error::NoEntry) => {
+                bail!("{BACKEND} has no entry for `{service}/{account}`")
+            }
+            Err(e) => bail!("{BACKEND}: can't delete `{service}/{account}`: {e}"),
+        }
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+pub mod keyring {
+    use miette::bail;
+
+    pub const BACKEND: &str = "keyring (not built in)";
+
+    fn unavailable<T>(service: &str, account: &str) -> miette::Result<T> {
+        bail!(
+            help = "rebuild trix with `--features keyring`, or use an `env:` reference",
+            "can't reach `{service}/{account}`: this trix was built without the `keyring` feature"
+        )
+    }
+
+    pub fn get(service: &str, account: &str) -> miette::Result<String> {
+        unavailable(service, account)
+    }
+
+    pub fn set(service: &str, account: &str, _secret: &str) -> miette::Result<()> {
+        unavailable(service, account)
+    }
+
+    pub fn delete(service: &str, account: &str) -> miette::Result<()> {
+        unavailable(service, account)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_references() {
+        assert_eq!(SecretRef::parse("abc").unwrap(), SecretRef::Literal("abc"));
+        assert_eq!(SecretRef::parse("env:KEY").unwrap(), SecretRef::Env("KEY"));
+        assert_eq!(
+            SecretRef::parse("keyring:trix/preview/trp").unwrap(),
+            SecretRef::Keyring {
+                service: "trix",
+                account: "preview/trp"
+            }
+        );
+        assert!(SecretRef::parse("keyring:no-account").is_err());
+    }
+
+    #[test]
+    fn env_failure_names_backend_and_key() {
+        let err = resolve("env:TRIX_SECRET_THAT_IS_NOT_SET").unwrap_err();
+
+        assert!(err.to_string().contains("env backend"));
+        assert!(err.to_string().contains("TRIX_SECRET_THAT_IS_NOT_SET"));
+    }
+}
@@ -39,6 +39,26 @@ const COMPAT_MATRIX: &[Compat] = &[
     },
 ];
 
+/// A capability `trix` relies on only from `min` on, above its tool's floor
+/// in [`COMPAT_MATRIX`]. Only the commands that use it are gated, so an
+/// older tool still serves everything else.
+struct Feature {
+    tool: &'static str,
+    name: &'static str,
+    min: &'static str,
+}
+
+const FEATURES: &[Feature] = &[
+    // `tx3c build --emit ast-json`, behind `trix inspect ast`, is outside
+    // tx3c's documented CLI: the AST is the parser's own serialization and
+    // trix reads it only from the release it is known to match.
+    Feature {
+        tool: "tx3c",
+        name: "ast-json",
+        min: "0.23.0",
+    },
+];
+
 fn entry(tool: &str) -> Option<&'static Compat> {
     COMPAT_MATRIX.iter().find(|c| c.tool == tool)
 }
@@ -128,6 +148,11 @@ fn check(
     matrix: Option<&Compat>,
     project_min: Option<&semver::Version>,
 ) -> Result<(), String> {
+    evaluate(tool, &probe(tool)?, matrix, project_min)
+}
+
+/// The version `<tool> --version` reports.
+fn probe(tool: &str) -> Result<semver::Version, String> {
     let path = crate::home::tool_path(tool).map_err(|e| e.to_string())?;
 
     let output = Command::new(&path)
@@ -142,10 +167,42 @@ fn check(
     // clap-based tools print `<name> <semver>`.
     let stdout = String::from_utf8_lossy(&output.stdout);
     let raw = stdout.split_whitespace().last().unwrap_or("").trim();
-    let found = semver::Version::parse(raw)
-        .map_err(|e| format!("cannot parse {tool} version from {stdout:?}: {e}"))?;
+    semver::Version::parse(raw)
+        .map_err(|e| format!("cannot parse {tool} version from {stdout:?}: {e}"))
+}
+
+/// Confirm `tool` is recent enough for `feature`, one of [`FEATURES`].
+/// Call it before the subprocess that uses the feature; the same
+/// `TX3_SKIP_COMPAT_CHECK` escape hatch as [`ensure_supported`] applies.
+pub fn ensure_feature(tool: &str, feature: &str) -> miette::Result<()> {
+    if std::env::var_os("TX3_SKIP_COMPAT_CHECK").is_some_and(|v| !v.is_empty()) {
+        return Ok(());
+    }
 
-    evaluate(tool, &found, matrix, project_min)
+    let Some(feature) = FEATURES
+        .iter()
+        .find(|f| f.tool == tool && f.name == feature)
+    else {
+        return Ok(());
+    };
+
+    probe(tool)
+        .and_then(|found| evaluate_feature(feature, &found))
+        .map_err(|m| miette::miette!("incompatible tx3 toolchain: {m}"))
+}
+
+fn evaluate_feature(feature: &Feature, found: &semver::Version) -> Result<(), String> {
+    let min = semver::Version::parse(feature.min).expect("valid feature const");
+
+    if *found < min {
+        return Err(format!(
+            "`{}` needs {} >= {min}, but yours is {found}. \
+             Run `tx3up` to update your tx3 toolchain.",
+            feature.name, feature.tool
+        ));
+    }
+
+    Ok(())
 }
 
 /// Decide whether `found` satisfies the project floor and trix's support
@@ -233,6 +290,15 @@ mod tests {
         assert!(err.contains("newer than this trix supports"), "got: {err}");
     }
 
+    #[test]
+    fn feature_needs_its_own_minimum() {
+        let feature = &FEATURES[0];
+
+        let err = evaluate_feature(feature, &v("0.22.0")).unwrap_err();
+        assert!(err.contains("needs tx3c >= 0.23.0"), "got: {err}");
+        assert!(evaluate_feature(feature, &v("0.23.0")).is_ok());
+    }
+
     const BASE_TOML: &str = "\
 [protocol]
 name = \"x\"
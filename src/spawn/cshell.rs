@@ -1,7 +1,11 @@
 use std::{
     collections::HashMap,
+    io::{Read, Write},
     path::Path,
-    process::{Child, Command, Stdio},
+    process::{Child, Command, Output, Stdio},
+    sync::OnceLock,
+    thread::JoinHandle,
+    time::{Duration, Instant},
 };
 
 use askama::Template;
@@ -11,6 +15,126 @@ use serde::{de, Deserialize, Deserializer, Serialize};
 use utxorpc::spec::query::{any_utxo_data::ParsedState, AnyUtxoData};
 
 use crate::config::{TrpConfig, U5cConfig};
+use crate::spawn::shutdown;
+
+/// Ceiling for a single non-interactive cshell call when neither
+/// `--cshell-timeout` nor the global `[cshell] timeout_secs` is set.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Flags whose value must never be echoed back in a diagnostic.
+const SECRET_FLAGS: &[&str] = &["--mnemonic"];
+
+/// Effective timeout for non-interactive cshell calls. Set once at command
+/// startup (see `main.rs`), read on every call.
+static TIMEOUT: OnceLock<Duration> = OnceLock::new();
+
+/// Record the timeout applied to non-interactive cshell calls. First writer
+/// wins; a process only ever resolves it once.
+pub fn set_timeout(timeout: Duration) {
+    let _ = TIMEOUT.set(timeout);
+}
+
+fn timeout() -> Duration {
+    TIMEOUT
+        .get()
+        .copied()
+        .unwrap_or(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+}
+
+/// Render `cmd` as a single line for diagnostics, masking the value of any
+/// flag in [`SECRET_FLAGS`].
+fn redacted_command_line(cmd: &Command) -> String {
+    let mut parts = vec![cmd.get_program().to_string_lossy().to_string()];
+    let mut mask_next = false;
+
+    for arg in cmd.get_args() {
+        let arg = arg.to_string_lossy();
+
+        if mask_next {
+            parts.push("***".to_string());
+        } else {
+            parts.push(arg.to_string());
+        }
+
+        mask_next = SECRET_FLAGS.contains(&arg.as_ref());
+    }
+
+    parts.join(" ")
+}
+
+fn drain<R: Read + Send + 'static>(mut pipe: R) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
+fn collect(handle: Option<JoinHandle<Vec<u8>>>) -> Vec<u8> {
+    handle.and_then(|h| h.join().ok()).unwrap_or_default()
+}
+
+/// Run a non-interactive cshell invocation to completion. Stdout is always
+/// captured; stderr is captured only if the caller piped it. A call that
+/// outlives [`timeout`] is killed and reported with its (redacted) command
+/// line and whatever output it produced before expiring.
+fn output_with_timeout(cmd: &mut Command, what: &str) -> miette::Result<Output> {
+    let timeout = timeout();
+    let command_line = redacted_command_line(cmd);
+
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .spawn()
+        .into_diagnostic()
+        .with_context(|| format!("spawning CShell {what}"))?;
+
+    let _tracked = shutdown::track(&child);
+
+    let stdout = child.stdout.take().map(drain);
+    let stderr = child.stderr.take().map(drain);
+
+    let deadline = Instant::now() + timeout;
+
+    let status = loop {
+        let polled = child
+            .try_wait()
+            .into_diagnostic()
+            .with_context(|| format!("running CShell {what}"))?;
+
+        if let Some(status) = polled {
+            break Some(status);
+        }
+
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+    };
+
+    let stdout = collect(stdout);
+    let stderr = collect(stderr);
+
+    let Some(status) = status else {
+        bail!(
+            help = "raise the limit with `--cshell-timeout <SECS>` or `[cshell] timeout_secs` in the global trix config, or check that the network endpoints are reachable",
+            "CShell {what} timed out after {}s\ncommand: {command_line}\nstdout: {}\nstderr: {}",
+            timeout.as_secs(),
+            String::from_utf8_lossy(&stdout).trim(),
+            String::from_utf8_lossy(&stderr).trim(),
+        );
+    };
+
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
 
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
@@ -41,8 +165,9 @@ pub struct OutputBalance {
     pub coin: u64,
 }
 
-// Flat view of a wallet UTxO, holding just what the `expect` checks read:
-// lovelace, native assets, and the datum hash. Built from cshell's utxorpc
+// Flat view of a wallet UTxO, holding just what the `expect` checks and
+// `trix wallet utxos` read: its ref, lovelace, native assets, and the datum
+// hash and payload. Built from cshell's utxorpc
 // `AnyUtxoData` output via `flatten_utxo` — bytes are already decoded here, so
 // callers `hex::encode`/`from_utf8` them directly.
 #[derive(Debug, Clone, PartialEq)]
@@ -54,6 +179,9 @@ pub struct Asset {
 #[derive(Debug, Clone, PartialEq)]
 pub struct Datum {
     pub hash: Vec<u8>,
+    /// The inline datum as JSON (see `plutus_data_to_json`); `None` for a
+    /// datum only referenced by hash.
+    pub payload: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -64,6 +192,8 @@ pub struct UtxoAsset {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct UTxO {
+    /// `hash#index` of the output, when cshell reported it.
+    pub txo_ref: Option<String>,
     pub coin: String, // lovelace, kept as a string to sidestep overflow
     pub assets: Vec<UtxoAsset>,
     pub datum: Option<Datum>,
@@ -93,11 +223,113 @@ fn bigint_to_string(b: &utxorpc::spec::cardano::BigInt) -> String {
     }
 }
 
+/// Render Plutus data in the cardano-cli "detailed schema":
+/// `{ "constructor": 0, "fields": [...] }`, `{ "int": 42 }`,
+/// `{ "bytes": "<hex>" }`, `{ "list": [...] }` and
+/// `{ "map": [{ "k": ..., "v": ... }] }`. Integers too big for an `i64`
+/// are kept as decimal strings.
+pub fn plutus_data_to_json(data: &utxorpc::spec::cardano::PlutusData) -> serde_json::Value {
+    use serde_json::json;
+    use utxorpc::spec::cardano::{big_int::BigInt, plutus_data::PlutusData};
+
+    let Some(data) = data.plutus_data.as_ref() else {
+        return serde_json::Value::Null;
+    };
+
+    match data {
+        PlutusData::Constr(constr) => {
+            // Constructors 0-6 are tags 121-127, 7-127 are tags 1280-1400 and
+            // anything else is tag 102 with an explicit index.
+            let constructor = match constr.tag {
+                121..=127 => u64::from(constr.tag - 121),
+                1280..=1400 => u64::from(constr.tag - 1280 + 7),
+                _ => constr.any_constructor,
+            };
+
+            json!({
+                "constructor": constructor,
+                "fields": constr.fields.iter().map(plutus_data_to_json).collect::<Vec<_>>(),
+            })
+        }
+        PlutusData::Map(map) => json!({
+            "map": map
+                .pairs
+                .iter()
+                .map(|pair| json!({
+                    "k": pair.key.as_ref().map(plutus_data_to_json).unwrap_or_default(),
+                    "v": pair.value.as_ref().map(plutus_data_to_json).unwrap_or_default(),
+                }))
+                .collect::<Vec<_>>(),
+        }),
+        PlutusData::BigInt(big) => match big.big_int.as_ref() {
+            Some(BigInt::Int(i)) => json!({ "int": i }),
+            Some(BigInt::BigUInt(bytes)) => json!({ "int": big_uint_to_string(bytes) }),
+            Some(BigInt::BigNInt(bytes)) => {
+                // CBOR negative bignums encode -1 - n.
+                json!({ "int": format!("-{}", big_uint_to_string_plus_one(bytes)) })
+            }
+            None => serde_json::Value::Null,
+        },
+        PlutusData::BoundedBytes(bytes) => json!({ "bytes": hex::encode(bytes) }),
+        PlutusData::Array(array) => json!({
+            "list": array.items.iter().map(plutus_data_to_json).collect::<Vec<_>>(),
+        }),
+    }
+}
+
+/// Decimal rendering of a big-endian unsigned integer of any length.
+fn big_uint_to_string(bytes: &[u8]) -> String {
+    let mut digits = vec![0u8];
+
+    for byte in bytes {
+        let mut carry = u32::from(*byte);
+
+        for digit in digits.iter_mut() {
+            let value = u32::from(*digit) * 256 + carry;
+            *digit = (value % 10) as u8;
+            carry = value / 10;
+        }
+
+        while carry > 0 {
+            digits.push((carry % 10) as u8);
+            carry /= 10;
+        }
+    }
+
+    while digits.len() > 1 && digits.last() == Some(&0) {
+        digits.pop();
+    }
+
+    digits.iter().rev().map(|d| char::from(b'0' + d)).collect()
+}
+
+fn big_uint_to_string_plus_one(bytes: &[u8]) -> String {
+    let mut bytes = bytes.to_vec();
+
+    let carried = bytes.iter_mut().rev().all(|byte| {
+        let (next, overflow) = byte.overflowing_add(1);
+        *byte = next;
+        overflow
+    });
+
+    if carried {
+        bytes.insert(0, 1);
+    }
+
+    big_uint_to_string(&bytes)
+}
+
 fn flatten_utxo(any: AnyUtxoData) -> UTxO {
     use utxorpc::spec::cardano::asset::Quantity;
 
+    let txo_ref = any
+        .txo_ref
+        .as_ref()
+        .map(|r| format!("{}#{}", hex::encode(&r.hash), r.index));
+
     let Some(ParsedState::Cardano(output)) = any.parsed_state else {
         return UTxO {
+            txo_ref,
             coin: "0".to_string(),
             assets: Vec::new(),
             datum: None,
@@ -131,14 +363,13 @@ fn flatten_utxo(any: AnyUtxoData) -> UTxO {
 
     // A no-datum output still carries an (empty) `datum` message; treat an empty
     // hash as "no datum" so `datum_equals` checks behave as before.
-    let datum = output
-        .datum
-        .filter(|d| !d.hash.is_empty())
-        .map(|d| Datum {
-            hash: d.hash.to_vec(),
-        });
+    let datum = output.datum.filter(|d| !d.hash.is_empty()).map(|d| Datum {
+        hash: d.hash.to_vec(),
+        payload: d.payload.as_ref().map(plutus_data_to_json),
+    });
 
     UTxO {
+        txo_ref,
         coin,
         assets,
         datum,
@@ -189,18 +420,9 @@ pub fn wallet_info(home: &Path, wallet_name: &str) -> miette::Result<WalletInfoO
         wallet_name,
         "--output-format",
         "json",
-    ])
-    .stdout(Stdio::piped());
-
-    let child = cmd
-        .spawn()
-        .into_diagnostic()
-        .context("spawning CShell wallet info")?;
+    ]);
 
-    let output = child
-        .wait_with_output()
-        .into_diagnostic()
-        .context("running CShell wallet info")?;
+    let output = output_with_timeout(&mut cmd, "wallet info")?;
 
     if !output.status.success() {
         bail!("CShell failed to get wallet info");
@@ -224,18 +446,9 @@ pub fn wallet_create(home: &Path, name: &str, mnemonic: &str) -> miette::Result<
         "--unsafe",
         "--output-format",
         "json",
-    ])
-    .stdout(Stdio::piped());
-
-    let child = cmd
-        .spawn()
-        .into_diagnostic()
-        .context("spawning CShell wallet create")?;
+    ]);
 
-    let output = child
-        .wait_with_output()
-        .into_diagnostic()
-        .context("running CShell wallet create")?;
+    let output = output_with_timeout(&mut cmd, "wallet create")?;
 
     if !output.status.success() {
         bail!("CShell failed to create wallet");
@@ -248,12 +461,9 @@ pub fn wallet_create(home: &Path, name: &str, mnemonic: &str) -> miette::Result<
 pub fn wallet_list(home: &Path) -> miette::Result<Vec<OutputWallet>> {
     let mut cmd = new_generic_command(home)?;
 
-    let output = cmd
-        .args(["wallet", "list", "--output-format", "json"])
-        .stdout(Stdio::piped())
-        .output()
-        .into_diagnostic()
-        .context("running CShell wallet list")?;
+    cmd.args(["wallet", "list", "--output-format", "json"]);
+
+    let output = output_with_timeout(&mut cmd, "wallet list")?;
 
     if !output.status.success() {
         bail!("CShell failed to list wallets");
@@ -326,7 +536,7 @@ pub fn tx_invoke_interactive(
     r#unsafe: bool,
     skip_submit: bool,
     provider: Option<&str>,
-) -> miette::Result<()> {
+) -> miette::Result<Option<serde_json::Value>> {
     let mut cmd = tx_invoke_cmd(
         home,
         tii_file,
@@ -339,19 +549,64 @@ pub fn tx_invoke_interactive(
         provider,
     )?;
 
-    let output = cmd
-        .stdout(Stdio::inherit())
+    // Interactive: the user drives the prompts, so no timeout applies. The
+    // child is still tracked so an interrupted trix doesn't orphan it.
+    let mut child = cmd
+        .stdout(Stdio::piped())
         .stdin(Stdio::inherit())
         .stderr(Stdio::inherit())
-        .output()
+        .spawn()
+        .into_diagnostic()
+        .context("spawning CShell transaction")?;
+
+    let _tracked = shutdown::track(&child);
+
+    // Prompts render on stderr; stdout carries the JSON result. Echo it as
+    // it arrives and keep a copy so the caller can pick the tx hash out.
+    let mut captured = Vec::new();
+
+    if let Some(mut stdout) = child.stdout.take() {
+        let mut echo = std::io::stdout();
+        let mut chunk = [0u8; 4096];
+
+        loop {
+            let read = stdout.read(&mut chunk).into_diagnostic()?;
+            if read == 0 {
+                break;
+            }
+
+            captured.extend_from_slice(&chunk[..read]);
+            echo.write_all(&chunk[..read]).into_diagnostic()?;
+            echo.flush().into_diagnostic()?;
+        }
+    }
+
+    let status = child
+        .wait()
         .into_diagnostic()
         .context("running CShell transaction")?;
 
-    if !output.status.success() {
+    if !status.success() {
         bail!("CShell failed to execute transaction");
     }
 
-    Ok(())
+    Ok(serde_json::from_slice(&captured).ok())
+}
+
+/// A `tx invoke` that cshell rejected. `stderr` carries the reason, e.g.
+/// the error body of the TRP resolve call.
+#[derive(Debug, thiserror::Error, miette::Diagnostic)]
+#[error("CShell failed to execute transaction: {}", stderr.trim())]
+pub struct InvokeFailed {
+    pub stderr: String,
+}
+
+/// Transaction hash reported by a cshell `tx invoke` JSON result, if any.
+pub fn tx_hash(output: &serde_json::Value) -> Option<&str> {
+    output
+        .get("hash")
+        .or_else(|| output.get("tx_hash"))
+        .and_then(|h| h.as_str())
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -378,30 +633,89 @@ pub fn tx_invoke_json(
         provider,
     )?;
 
-    let output = cmd
-        .stdout(Stdio::piped())
-        .stderr(Stdio::inherit())
-        .output()
-        .into_diagnostic()
-        .context("running CShell transaction")?;
+    cmd.stderr(Stdio::piped());
+
+    let output = output_with_timeout(&mut cmd, "transaction")?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
 
     if !output.status.success() {
-        bail!("CShell failed to execute transaction");
+        return Err(InvokeFailed { stderr }.into());
+    }
+
+    // Captured so a failure can be explained; on success pass it through.
+    eprint!("{stderr}");
+
+    serde_json::from_slice(&output.stdout).into_diagnostic()
+}
+
+/// Sign an already-resolved transaction (hex CBOR) with `signers`' keys
+/// and return the signed CBOR. No resolution happens; inputs stay exactly
+/// as they were.
+pub fn tx_sign(home: &Path, cbor: &str, signers: Vec<&str>) -> miette::Result<String> {
+    let mut cmd = new_generic_command(home)?;
+
+    cmd.args(["tx", "sign", "--cbor", cbor, "--output-format", "json"]);
+
+    for signer in signers {
+        cmd.args(["--signers", signer]);
+    }
+
+    cmd.stderr(Stdio::piped());
+
+    let output = output_with_timeout(&mut cmd, "tx sign")?;
+
+    if !output.status.success() {
+        bail!(
+            "CShell failed to sign transaction: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).into_diagnostic()?;
+
+    value
+        .get("cbor")
+        .and_then(|c| c.as_str())
+        .map(String::from)
+        .ok_or_else(|| miette::miette!("cshell tx sign did not report the signed cbor"))
+}
+
+/// Submit a signed transaction (hex CBOR) through `provider`.
+pub fn tx_submit(home: &Path, cbor: &str, provider: &str) -> miette::Result<serde_json::Value> {
+    let mut cmd = new_generic_command(home)?;
+
+    cmd.args([
+        "tx",
+        "submit",
+        "--cbor",
+        cbor,
+        "--provider",
+        provider,
+        "--output-format",
+        "json",
+    ]);
+
+    cmd.stderr(Stdio::piped());
+
+    let output = output_with_timeout(&mut cmd, "tx submit")?;
+
+    if !output.status.success() {
+        bail!(
+            "CShell failed to submit transaction: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
     }
 
     serde_json::from_slice(&output.stdout).into_diagnostic()
 }
 
-#[allow(dead_code)]
 pub fn wallet_balance(home: &Path, wallet_name: &str) -> miette::Result<OutputBalance> {
     let mut cmd = new_generic_command(home)?;
 
-    let output = cmd
-        .args(["wallet", "balance", wallet_name, "--output-format", "json"])
-        .stdout(Stdio::piped())
-        .output()
-        .into_diagnostic()
-        .context("running CShell wallet balance")?;
+    cmd.args(["wallet", "balance", wallet_name, "--output-format", "json"]);
+
+    let output = output_with_timeout(&mut cmd, "wallet balance")?;
 
     if !output.status.success() {
         bail!("CShell failed to get wallet balance");
@@ -427,12 +741,9 @@ pub fn wallet_utxos(home: &Path, wallet_name: &str, provider: &str) -> miette::R
         "json",
     ]);
 
-    let output = cmd
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .into_diagnostic()
-        .context("running CShell wallet utxos")?;
+    cmd.stderr(Stdio::piped());
+
+    let output = output_with_timeout(&mut cmd, "wallet utxos")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -465,18 +776,35 @@ pub fn explorer(home: &Path, provider: &str) -> miette::Result<Child> {
     Ok(child)
 }
 
+/// Run cshell with arbitrary user-supplied `args` against the store at
+/// `home`, attached to the terminal. No timeout: the user drives it.
+pub fn passthrough(home: &Path, args: &[&str]) -> miette::Result<std::process::ExitStatus> {
+    let mut cmd = new_generic_command(home)?;
+
+    cmd.args(args);
+
+    let mut child = cmd
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .into_diagnostic()
+        .context("spawning CShell")?;
+
+    let _tracked = shutdown::track(&child);
+
+    child.wait().into_diagnostic().context("waiting for CShell")
+}
+
 /// Test connection to a provider
 pub fn provider_test(home: &Path, provider: &str) -> miette::Result<()> {
     let mut cmd = new_generic_command(home)?;
 
     cmd.args(["provider", "test", "--name", provider]);
 
-    let output = cmd
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .into_diagnostic()
-        .context("running CShell provider test")?;
+    cmd.stderr(Stdio::piped());
+
+    let output = output_with_timeout(&mut cmd, "provider test")?;
 
     if !output.status.success() {
         bail!("CShell provider test failed");
@@ -484,3 +812,121 @@ pub fn provider_test(home: &Path, provider: &str) -> miette::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_line_masks_mnemonic() {
+        let mut cmd = Command::new("cshell");
+        cmd.args(["wallet", "restore", "--name", "alice", "--mnemonic", "abandon abandon"]);
+
+        let line = redacted_command_line(&cmd);
+
+        assert_eq!(line, "cshell wallet restore --name alice --mnemonic ***");
+    }
+
+    #[test]
+    fn command_line_keeps_plain_args() {
+        let mut cmd = Command::new("cshell");
+        cmd.args(["wallet", "info", "--name", "bob"]);
+
+        assert_eq!(redacted_command_line(&cmd), "cshell wallet info --name bob");
+    }
+
+    #[test]
+    fn big_uints_render_in_decimal() {
+        assert_eq!(big_uint_to_string(&[]), "0");
+        assert_eq!(big_uint_to_string(&[0x01, 0x00]), "256");
+        assert_eq!(
+            big_uint_to_string(&[0x01, 0, 0, 0, 0, 0, 0, 0, 0]),
+            "18446744073709551616"
+        );
+        assert_eq!(big_uint_to_string_plus_one(&[0xff]), "256");
+    }
+
+    mod plutus {
+        use utxorpc::spec::cardano::{
+            self, BigInt, Constr, PlutusDataArray, PlutusDataMap, PlutusDataPair, big_int,
+            plutus_data::PlutusData,
+        };
+
+        pub fn data(inner: PlutusData) -> cardano::PlutusData {
+            cardano::PlutusData {
+                plutus_data: Some(inner),
+                ..Default::default()
+            }
+        }
+
+        pub fn int(i: i64) -> cardano::PlutusData {
+            data(PlutusData::BigInt(BigInt {
+                big_int: Some(big_int::BigInt::Int(i)),
+            }))
+        }
+
+        pub fn constr(tag: u32, any_constructor: u64) -> cardano::PlutusData {
+            data(PlutusData::Constr(Constr {
+                tag,
+                any_constructor,
+                fields: vec![int(1)],
+            }))
+        }
+
+        pub fn general() -> cardano::PlutusData {
+            let big = |big_int| {
+                data(PlutusData::BigInt(BigInt {
+                    big_int: Some(big_int),
+                }))
+            };
+
+            data(PlutusData::Array(PlutusDataArray {
+                items: vec![
+                    data(PlutusData::BoundedBytes(vec![0xcau8, 0xfe].into())),
+                    data(PlutusData::Map(PlutusDataMap {
+                        pairs: vec![PlutusDataPair {
+                            key: Some(int(1)),
+                            value: Some(int(-2)),
+                        }],
+                    })),
+                    big(big_int::BigInt::BigUInt(
+                        vec![0x01u8, 0, 0, 0, 0, 0, 0, 0, 0].into(),
+                    )),
+                    big(big_int::BigInt::BigNInt(vec![0xffu8].into())),
+                ],
+            }))
+        }
+    }
+
+    #[test]
+    fn constructor_tags_map_to_indexes() {
+        let constructor =
+            |tag, any| plutus_data_to_json(&plutus::constr(tag, any))["constructor"].clone();
+
+        assert_eq!(constructor(121, 0), 0);
+        assert_eq!(constructor(127, 0), 6);
+        assert_eq!(constructor(1280, 0), 7);
+        assert_eq!(constructor(1400, 0), 127);
+        assert_eq!(constructor(102, 300), 300);
+
+        assert_eq!(
+            plutus_data_to_json(&plutus::constr(121, 0)),
+            serde_json::json!({ "constructor": 0, "fields": [{ "int": 1 }] })
+        );
+    }
+
+    #[test]
+    fn plutus_data_renders_the_detailed_schema() {
+        assert_eq!(
+            plutus_data_to_json(&plutus::general()),
+            serde_json::json!({
+                "list": [
+                    { "bytes": "cafe" },
+                    { "map": [{ "k": { "int": 1 }, "v": { "int": -2 } }] },
+                    { "int": "18446744073709551616" },
+                    { "int": "-256" },
+                ]
+            })
+        );
+    }
+}
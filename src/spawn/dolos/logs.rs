@@ -0,0 +1,325 @@
+//! Size-bounded, rotating capture of dolos output.
+//!
+//! The daemon's stdout/stderr are piped into trix and copied line by line
+//! into `<home>/logs/dolos.log` by one thread per stream. Both threads share
+//! a single [`RotatingLog`] behind a mutex, so rotation never races a write
+//! and never splits a line. When the current file would exceed
+//! [`LogConfig::max_bytes`] it becomes `dolos.log.1`, older files shift up,
+//! and anything past [`LogConfig::keep`] files in total is deleted.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::JoinHandle;
+
+use miette::{Context as _, IntoDiagnostic as _};
+
+pub const DEFAULT_MAX_BYTES: u64 = 20 * 1024 * 1024;
+pub const DEFAULT_KEEP: usize = 5;
+
+const LOG_FILE: &str = "dolos.log";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogConfig {
+    /// Size a log file may reach before it's rotated.
+    pub max_bytes: u64,
+    /// Total number of files kept, the current one included.
+    pub keep: usize,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_BYTES,
+            keep: DEFAULT_KEEP,
+        }
+    }
+}
+
+static CONFIG: OnceLock<LogConfig> = OnceLock::new();
+
+/// Set the log limits for this process. Called once at startup from the
+/// global `[dolos]` config; later calls are ignored.
+pub fn set_config(config: LogConfig) {
+    let _ = CONFIG.set(config);
+}
+
+fn config() -> LogConfig {
+    CONFIG.get().copied().unwrap_or_default()
+}
+
+/// Where rotated log bytes go. [`FileStore`] is the real one; tests use an
+/// in-memory fake.
+pub trait LogStore {
+    /// Bytes in the current file.
+    fn current_len(&self) -> u64;
+
+    fn append(&mut self, buf: &[u8]) -> io::Result<()>;
+
+    /// Retire the current file and start an empty one, keeping at most
+    /// `keep` files in total.
+    fn rotate(&mut self, keep: usize) -> io::Result<()>;
+}
+
+pub struct RotatingLog<S> {
+    store: S,
+    config: LogConfig,
+}
+
+impl<S: LogStore> RotatingLog<S> {
+    pub fn new(store: S, config: LogConfig) -> Self {
+        Self { store, config }
+    }
+
+    /// Append one chunk (a line, for the pump threads). Rotates first if
+    /// the chunk would push a non-empty file past the limit; a single chunk
+    /// larger than the limit still lands whole in a fresh file.
+    pub fn write_chunk(&mut self, buf: &[u8]) -> io::Result<()> {
+        let current = self.store.current_len();
+
+        if current > 0 && current + buf.len() as u64 > self.config.max_bytes {
+            self.store.rotate(self.config.keep)?;
+        }
+
+        self.store.append(buf)
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+}
+
+/// `dolos.log` plus its numbered predecessors in one directory.
+pub struct FileStore {
+    dir: PathBuf,
+    file: File,
+    len: u64,
+}
+
+fn rotated_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("{LOG_FILE}.{index}"))
+}
+
+impl FileStore {
+    pub fn open(dir: &Path) -> io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+
+        let path = dir.join(LOG_FILE);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let len = file.metadata()?.len();
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            file,
+            len,
+        })
+    }
+}
+
+impl LogStore for FileStore {
+    fn current_len(&self) -> u64 {
+        self.len
+    }
+
+    fn append(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.file.write_all(buf)?;
+        self.len += buf.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self, keep: usize) -> io::Result<()> {
+        self.file.flush()?;
+
+        let oldest = keep.saturating_sub(1);
+
+        if oldest == 0 {
+            self.file.set_len(0)?;
+            self.len = 0;
+            return Ok(());
+        }
+
+        let _ = std::fs::remove_file(rotated_path(&self.dir, oldest));
+
+        for index in (1..oldest).rev() {
+            let from = rotated_path(&self.dir, index);
+            if from.exists() {
+                std::fs::rename(&from, rotated_path(&self.dir, index + 1))?;
+            }
+        }
+
+        std::fs::rename(self.dir.join(LOG_FILE), rotated_path(&self.dir, 1))?;
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(LOG_FILE))?;
+        self.len = 0;
+
+        Ok(())
+    }
+}
+
+/// Log directory inside a dolos home.
+pub fn log_dir(home: &Path) -> PathBuf {
+    home.join("logs")
+}
+
+pub type SharedLog = Arc<Mutex<RotatingLog<FileStore>>>;
+
+/// Open the rotating log for the dolos home at `home`.
+pub fn open(home: &Path) -> miette::Result<SharedLog> {
+    let store = FileStore::open(&log_dir(home))
+        .into_diagnostic()
+        .context("opening dolos log")?;
+
+    Ok(Arc::new(Mutex::new(RotatingLog::new(store, config()))))
+}
+
+/// Copy `source` into `log` line by line until the child closes it.
+pub fn pump(source: impl Read + Send + 'static, log: SharedLog) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(source);
+        let mut line = Vec::new();
+
+        loop {
+            line.clear();
+
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let mut log = log.lock().unwrap();
+                    if log.write_chunk(&line).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Every log file under `home`, oldest first.
+pub fn log_files(home: &Path) -> Vec<PathBuf> {
+    let dir = log_dir(home);
+
+    let mut rotated: Vec<(usize, PathBuf)> = std::fs::read_dir(&dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let index = name.strip_prefix(&format!("{LOG_FILE}."))?.parse().ok()?;
+            Some((index, entry.path()))
+        })
+        .collect();
+
+    rotated.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut files: Vec<PathBuf> = rotated.into_iter().map(|(_, path)| path).collect();
+
+    let current = dir.join(LOG_FILE);
+    if current.is_file() {
+        files.push(current);
+    }
+
+    files
+}
+
+/// Write the whole captured log, across rotations, to `out`.
+pub fn copy_all(home: &Path, out: &mut impl Write) -> miette::Result<()> {
+    for path in log_files(home) {
+        let mut file = File::open(&path)
+            .into_diagnostic()
+            .context(format!("opening {}", path.display()))?;
+
+        io::copy(&mut file, out).into_diagnostic()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Files newest-first: `files[0]` is the current one.
+    #[derive(Default)]
+    struct FakeStore {
+        files: Vec<Vec<u8>>,
+    }
+
+    impl LogStore for FakeStore {
+        fn current_len(&self) -> u64 {
+            self.files.first().map(|f| f.len() as u64).unwrap_or(0)
+        }
+
+        fn append(&mut self, buf: &[u8]) -> io::Result<()> {
+            if self.files.is_empty() {
+                self.files.push(vec![]);
+            }
+            self.files[0].extend_from_slice(buf);
+            Ok(())
+        }
+
+        fn rotate(&mut self, keep: usize) -> io::Result<()> {
+            self.files.insert(0, vec![]);
+            self.files.truncate(keep);
+            Ok(())
+        }
+    }
+
+    fn log(max_bytes: u64, keep: usize) -> RotatingLog<FakeStore> {
+        RotatingLog::new(FakeStore::default(), LogConfig { max_bytes, keep })
+    }
+
+    #[test]
+    fn rotates_before_exceeding_limit() {
+        let mut log = log(10, 5);
+
+        log.write_chunk(b"12345\n").unwrap();
+        log.write_chunk(b"1234\n").unwrap();
+        log.write_chunk(b"abc\n").unwrap();
+
+        assert_eq!(log.store().files, vec![b"abc\n".to_vec(), b"12345\n1234\n".to_vec()]);
+    }
+
+    #[test]
+    fn keeps_at_most_configured_files() {
+        let mut log = log(4, 3);
+
+        for line in [b"aaa\n", b"bbb\n", b"ccc\n", b"ddd\n"] {
+            log.write_chunk(line).unwrap();
+        }
+
+        assert_eq!(
+            log.store().files,
+            vec![b"ddd\n".to_vec(), b"ccc\n".to_vec(), b"bbb\n".to_vec()]
+        );
+    }
+
+    #[test]
+    fn oversized_chunk_is_not_split() {
+        let mut log = log(4, 2);
+
+        log.write_chunk(b"a\n").unwrap();
+        log.write_chunk(b"0123456789\n").unwrap();
+
+        assert_eq!(log.store().files[0], b"0123456789\n".to_vec());
+    }
+
+    #[test]
+    fn file_store_reads_back_in_order() {
+        let home = tempfile::tempdir().unwrap();
+        let store = FileStore::open(&log_dir(home.path())).unwrap();
+        let mut log = RotatingLog::new(store, LogConfig { max_bytes: 4, keep: 3 });
+
+        for line in [b"one\n", b"two\n", b"six\n", b"ten\n"] {
+            log.write_chunk(line).unwrap();
+        }
+
+        let mut out = vec![];
+        copy_all(home.path(), &mut out).unwrap();
+
+        assert_eq!(out, b"two\nsix\nten\n".to_vec());
+    }
+}
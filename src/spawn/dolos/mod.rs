@@ -0,0 +1,182 @@
+use miette::{Context as _, IntoDiagnostic as _};
+use std::{
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+};
+
+pub mod logs;
+
+pub const DOLOS_TEMPLATE: &str = include_str!("../../../templates/configs/dolos/dolos.toml");
+pub const ALONZO_TEMPLATE: &str = include_str!("../../../templates/configs/dolos/alonzo.json");
+pub const BYRON_TEMPLATE: &str = include_str!("../../../templates/configs/dolos/byron.json");
+pub const CONWAY_TEMPLATE: &str = include_str!("../../../templates/configs/dolos/conway.json");
+pub const SHELLEY_TEMPLATE: &str = include_str!("../../../templates/configs/dolos/shelley.json");
+
+fn build_root_config(
+    custom_utxos: Vec<dolos_core::config::CustomUtxo>,
+    ports: &crate::devnet::Ports,
+    chain: &crate::devnet::Chain,
+) -> miette::Result<dolos_core::config::RootConfig> {
+    let mut template: toml::Table = toml::from_str(DOLOS_TEMPLATE)
+        .into_diagnostic()
+        .context("parsing dolos.toml template")?;
+
+    // Rebind each `[serve.*]` listener before the template is typed, so the
+    // override doesn't depend on dolos' serve config shape.
+    for (service, port) in ports.listen() {
+        if let Some(section) = template
+            .get_mut("serve")
+            .and_then(|serve| serve.get_mut(service))
+            .and_then(toml::Value::as_table_mut)
+        {
+            section.insert(
+                "listen_address".to_string(),
+                toml::Value::String(format!("[::]:{port}")),
+            );
+        }
+    }
+
+    chain.apply_root(&mut template);
+
+    let mut config: dolos_core::config::RootConfig = template
+        .try_into()
+        .into_diagnostic()
+        .context("parsing dolos.toml template")?;
+
+    // Override chain config with custom UTXOs
+    config.chain = dolos_core::config::ChainConfig::Cardano(dolos_core::config::CardanoConfig {
+        custom_utxos,
+        ..Default::default()
+    });
+
+    Ok(config)
+}
+
+fn save_config(home: &Path, name: &str, content: &str) -> miette::Result<PathBuf> {
+    let config = home.join(name);
+
+    std::fs::write(&config, content)
+        .into_diagnostic()
+        .context("saving config file")?;
+
+    Ok(config)
+}
+
+pub fn initialize_config(
+    home: &Path,
+    custom_utxos: Vec<dolos_core::config::CustomUtxo>,
+    ports: &crate::devnet::Ports,
+    chain: &crate::devnet::Chain,
+) -> miette::Result<PathBuf> {
+    std::fs::create_dir_all(home).into_diagnostic()?;
+
+    let shelley = chain.apply_shelley(SHELLEY_TEMPLATE)?;
+
+    save_config(home, "byron.json", BYRON_TEMPLATE)?;
+    save_config(home, "shelley.json", &shelley)?;
+    save_config(home, "alonzo.json", ALONZO_TEMPLATE)?;
+    save_config(home, "conway.json", CONWAY_TEMPLATE)?;
+
+    let root_content = build_root_config(custom_utxos, ports, chain)?;
+    let root_content = toml::to_string_pretty(&root_content).into_diagnostic()?;
+
+    let root_path = save_config(home, "dolos.toml", &root_content)?;
+
+    Ok(root_path)
+}
+
+/// A running dolos daemon. When its output is captured, the pump threads
+/// finish on their own once the child exits and closes its pipes.
+pub struct Daemon {
+    pub child: Child,
+    pumps: Vec<std::thread::JoinHandle<()>>,
+}
+
+impl Daemon {
+    /// Wait for dolos to exit and for its captured output to be flushed.
+    pub fn wait(&mut self) -> std::io::Result<std::process::ExitStatus> {
+        let status = self.child.wait()?;
+
+        for pump in self.pumps.drain(..) {
+            let _ = pump.join();
+        }
+
+        Ok(status)
+    }
+
+    pub fn kill(&mut self) -> std::io::Result<()> {
+        self.child.kill()?;
+        self.wait().map(|_| ())
+    }
+}
+
+/// Spawn dolos for `home`. `captured` sends its output to the rotating log
+/// under `<home>/logs` (see [`logs`]) instead of the terminal.
+pub fn daemon(home: &Path, captured: bool) -> miette::Result<Daemon> {
+    crate::spawn::ensure_supported("dolos")?;
+
+    let tool_path = crate::home::tool_path("dolos")?;
+
+    let config_path = home.join("dolos.toml");
+
+    let mut cmd = Command::new(tool_path.to_str().unwrap_or_default());
+
+    cmd.args(["-c", config_path.to_str().unwrap(), "daemon"]);
+    cmd.current_dir(home);
+
+    if captured {
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    } else {
+        cmd.stdout(Stdio::inherit()).stderr(Stdio::inherit());
+    }
+
+    let mut child = cmd
+        .spawn()
+        .into_diagnostic()
+        .context("failed to spawn dolos devnet")?;
+
+    let mut pumps = vec![];
+
+    if captured {
+        let log = logs::open(home)?;
+
+        if let Some(stdout) = child.stdout.take() {
+            pumps.push(logs::pump(stdout, log.clone()));
+        }
+
+        if let Some(stderr) = child.stderr.take() {
+            pumps.push(logs::pump(stderr, log));
+        }
+    }
+
+    Ok(Daemon { child, pumps })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::devnet::{Chain, Ports};
+
+    #[test]
+    fn chain_params_reach_rendered_config() {
+        let home = tempfile::tempdir().unwrap();
+        let chain = Chain {
+            slot_length_ms: Some(200),
+            epoch_length_slots: Some(500),
+            initial_funds_per_actor: None,
+        };
+
+        let root = initialize_config(home.path(), vec![], &Ports::default(), &chain).unwrap();
+
+        let dolos: toml::Value = toml::from_str(&std::fs::read_to_string(root).unwrap()).unwrap();
+        assert_eq!(
+            dolos["upstream"]["block_production_interval"].as_integer(),
+            Some(1)
+        );
+
+        let shelley = std::fs::read_to_string(home.path().join("shelley.json")).unwrap();
+        let shelley: serde_json::Value = serde_json::from_str(&shelley).unwrap();
+        assert_eq!(shelley["slotLength"], 0.2);
+        assert_eq!(shelley["epochLength"], 500);
+    }
+}
@@ -4,10 +4,12 @@
 //! `cshell`, `dolos`) as subprocesses. Version compatibility for every
 //! integration lives in [`compat`]; each spawn path calls
 //! [`ensure_supported`] at its command chokepoint before invoking the tool.
+//! Children that outlive a call are reaped on interrupt via [`shutdown`].
 
 pub mod compat;
 pub mod cshell;
 pub mod dolos;
+pub mod shutdown;
 pub mod tx3c;
 
 pub use compat::ensure_supported;
@@ -0,0 +1,155 @@
+//! Graceful-shutdown handling for the subprocesses `trix` spawns.
+//!
+//! Every child that `trix` waits on is registered here for as long as it
+//! runs. When `trix` itself is interrupted (Ctrl-C / SIGINT), the handler
+//! installed by [`install`] kills whatever is still registered before
+//! exiting, so an interrupted command never leaves an orphaned tool behind.
+//! A long-running loop can [`catch_interrupt`] instead, to wind down and
+//! report before it exits; a second Ctrl-C still exits immediately.
+
+use std::collections::HashSet;
+use std::process::Child;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use tracing::debug;
+
+/// Exit code used when `trix` is interrupted, matching the shell convention
+/// of `128 + SIGINT`.
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+static CATCH: AtomicBool = AtomicBool::new(false);
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+fn registry() -> &'static Mutex<HashSet<u32>> {
+    static REGISTRY: OnceLock<Mutex<HashSet<u32>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Registration handle for a running child. The child stays registered
+/// until the handle is dropped, which callers do once they've reaped it.
+pub struct Tracked(u32);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        registry().lock().unwrap().remove(&self.0);
+    }
+}
+
+/// Register `child` to be killed if `trix` is interrupted while it runs.
+pub fn track(child: &Child) -> Tracked {
+    let pid = child.id();
+    registry().lock().unwrap().insert(pid);
+    Tracked(pid)
+}
+
+#[cfg(unix)]
+fn kill_pid(pid: u32) {
+    unsafe {
+        libc::kill(pid as i32, libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_pid(pid: u32) {
+    let _ = std::process::Command::new("taskkill")
+        .args(["/F", "/PID", &pid.to_string()])
+        .output();
+}
+
+/// Whether the process `pid` is still alive.
+#[cfg(unix)]
+pub fn alive(pid: u32) -> bool {
+    unsafe { libc::kill(pid as i32, 0) == 0 }
+}
+
+#[cfg(not(unix))]
+pub fn alive(pid: u32) -> bool {
+    group_alive(pid)
+}
+
+/// Whether any process in the group led by `pid` is still alive. Background
+/// devnets run in their own group, so this covers dolos as well as the
+/// supervisor that spawned it.
+#[cfg(unix)]
+pub fn group_alive(pid: u32) -> bool {
+    unsafe { libc::kill(-(pid as i32), 0) == 0 }
+}
+
+#[cfg(not(unix))]
+pub fn group_alive(pid: u32) -> bool {
+    std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {pid}"), "/NH"])
+        .output()
+        .is_ok_and(|out| String::from_utf8_lossy(&out.stdout).contains(&pid.to_string()))
+}
+
+/// Ask every process in the group led by `pid` to exit.
+#[cfg(unix)]
+pub fn terminate_group(pid: u32) {
+    unsafe {
+        libc::kill(-(pid as i32), libc::SIGTERM);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn terminate_group(pid: u32) {
+    let _ = std::process::Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid.to_string()])
+        .output();
+}
+
+/// Ask `pid` to stop as if Ctrl-C was pressed, so a `trix` child gets to
+/// tear down the tools it spawned.
+#[cfg(unix)]
+pub fn interrupt(pid: u32) {
+    unsafe {
+        libc::kill(pid as i32, libc::SIGINT);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn interrupt(pid: u32) {
+    let _ = std::process::Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid.to_string()])
+        .output();
+}
+
+/// Kill every child still registered. Called by the interrupt handler; safe
+/// to call at any time.
+pub fn kill_all() {
+    let pids: Vec<u32> = registry().lock().unwrap().drain().collect();
+
+    for pid in pids {
+        debug!(pid, "killing child process on shutdown");
+        kill_pid(pid);
+    }
+}
+
+/// Have the first Ctrl-C only kill the registered children and raise
+/// [`interrupted`], for the caller to poll, rather than exit.
+pub fn catch_interrupt() {
+    CATCH.store(true, Ordering::SeqCst);
+}
+
+/// Whether a Ctrl-C was caught after [`catch_interrupt`].
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Install the interrupt handler. Must be called from within the tokio
+/// runtime, once, before the first tool spawn.
+pub fn install() {
+    tokio::spawn(async {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            kill_all();
+
+            if CATCH.load(Ordering::SeqCst) && !interrupted() {
+                INTERRUPTED.store(true, Ordering::SeqCst);
+                continue;
+            }
+
+            std::process::exit(INTERRUPTED_EXIT_CODE);
+        }
+    });
+}
@@ -81,6 +81,9 @@ pub fn codegen(tii_path: &Path, templates: &Path, output: &Path) -> miette::Resu
     cmd.args(["--template", templates.to_str().unwrap()]);
     cmd.args(["--output", output.to_str().unwrap()]);
 
+    // Keep trix's own stdout free for machine-readable summaries.
+    cmd.stdout(std::io::stderr());
+
     let output = cmd
         .status()
         .into_diagnostic()
@@ -153,6 +156,18 @@ pub fn tir_from_source(
     capture_json(cmd, "tir-json")
 }
 
+/// Parse `source` without analyzing or lowering it and return its AST as
+/// JSON, as `tx3c` serializes the parser's output. The flag is not part of
+/// tx3c's documented surface, so it is gated on its own minimum.
+pub fn ast_from_source(source: &Path) -> miette::Result<serde_json::Value> {
+    let mut cmd = tx3c()?;
+    crate::spawn::compat::ensure_feature("tx3c", "ast-json")?;
+
+    cmd.args(["build", source.to_str().unwrap()]);
+    cmd.args(["--emit", "ast-json"]);
+    capture_json(cmd, "ast-json")
+}
+
 /// Decode `tx_name`'s TIR out of a published `.tii`. Same JSON shape as
 /// [`tir_from_source`], so callers can't tell source from artifact.
 pub fn decode_tir(
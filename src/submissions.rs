@@ -0,0 +1,130 @@
+//! The project's submissions log: one JSON line per transaction trix
+//! submitted, in `.tx3/submissions/log.jsonl`, which `trix submissions`
+//! lists with explorer links. `trix test` submits to a devnet that is gone
+//! once the test ends, so its transactions aren't logged.
+
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use miette::{Context as _, IntoDiagnostic as _};
+use serde::{Deserialize, Serialize};
+
+use crate::config::NetworkConfig;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Submission {
+    pub submitted_at: String,
+    pub profile: String,
+    pub network: String,
+    /// `None` for envelopes replayed from before templates were recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    pub hash: String,
+}
+
+pub fn log_path() -> miette::Result<PathBuf> {
+    Ok(crate::dirs::target_dir("submissions")?.join("log.jsonl"))
+}
+
+fn append(path: &Path, submission: &Submission) -> miette::Result<()> {
+    let mut line = serde_json::to_string(submission).into_diagnostic()?;
+    line.push('\n');
+
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .into_diagnostic()
+        .with_context(|| format!("appending to {}", path.display()))
+}
+
+/// Every submission logged at `path`, oldest first. Lines that don't parse,
+/// e.g. one cut short by a crash, are skipped.
+pub fn read(path: &Path) -> miette::Result<Vec<Submission>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => {
+            return Err(e)
+                .into_diagnostic()
+                .with_context(|| format!("reading {}", path.display()));
+        }
+    };
+
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Log the transaction cshell reported in `output`, when it reported a
+/// hash. The transaction is already on its way, so a log that can't be
+/// written only warns.
+pub fn record(
+    output: &serde_json::Value,
+    profile: &str,
+    network: &NetworkConfig,
+    template: Option<&str>,
+) {
+    let Some(hash) = crate::spawn::cshell::tx_hash(output) else {
+        return;
+    };
+
+    let submission = Submission {
+        submitted_at: chrono::Utc::now().to_rfc3339(),
+        profile: profile.to_string(),
+        network: network.name.clone(),
+        template: template.map(String::from),
+        hash: hash.to_string(),
+    };
+
+    if let Err(err) = log_path().and_then(|path| append(&path, &submission)) {
+        eprintln!("warning: submission {hash} not logged: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(hash: &str, template: Option<&str>) -> Submission {
+        Submission {
+            submitted_at: "2025-01-01T00:00:00+00:00".to_string(),
+            profile: "preview".to_string(),
+            network: "cardano-preview".to_string(),
+            template: template.map(String::from),
+            hash: hash.to_string(),
+        }
+    }
+
+    #[test]
+    fn appended_submissions_read_back_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.jsonl");
+
+        assert!(read(&path).unwrap().is_empty());
+
+        let first = submission("aa", Some("transfer"));
+        let second = submission("bb", None);
+        append(&path, &first).unwrap();
+        append(&path, &second).unwrap();
+
+        assert_eq!(read(&path).unwrap(), vec![first, second]);
+    }
+
+    #[test]
+    fn truncated_line_is_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.jsonl");
+
+        let logged = submission("aa", Some("transfer"));
+        append(&path, &logged).unwrap();
+
+        let mut content = std::fs::read_to_string(&path).unwrap();
+        content.push_str("{\"submitted_at\": \"2025");
+        std::fs::write(&path, content).unwrap();
+
+        assert_eq!(read(&path).unwrap(), vec![logged]);
+    }
+}
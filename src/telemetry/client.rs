@@ -1,22 +1,43 @@
-use reqwest::{
-    Client,
-    header::{HeaderMap, HeaderName, HeaderValue},
-};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::{collections::HashMap, time::Duration};
 use tracing::{debug, warn};
 
-use crate::{global::TelemetryConfig, telemetry::fingerprint};
+use crate::{
+    global::{TelemetryCategories, TelemetryCategory, TelemetryConfig},
+    net::HttpClient,
+    telemetry::fingerprint,
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommandMetric {
     pub command_name: String,
+    /// When the command ran, so a spooled metric keeps its original time.
+    pub timestamp_ns: u64,
+    /// How long the command took; only with the `performance` category.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Diagnostic code of the error the command failed with; only with the
+    /// `errors` category.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+}
+
+pub(crate) fn now_ns() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
 }
 
 impl CommandMetric {
     pub fn new(command_name: &str) -> Self {
         Self {
             command_name: command_name.to_string(),
+            timestamp_ns: now_ns(),
+            duration_ms: None,
+            error_code: None,
         }
     }
 }
@@ -42,26 +63,41 @@ fn parse_headers(headers: HashMap<String, String>) -> HeaderMap {
 
 #[derive(Clone)]
 pub struct OtlpClient {
-    client: Client,
+    client: HttpClient,
     endpoint: String,
     headers: HeaderMap,
     timeout: Duration,
     user: String,
+    sample_rate: f64,
+    categories: TelemetryCategories,
 }
 
 impl OtlpClient {
     pub fn setup(config: &TelemetryConfig) -> Self {
         Self {
-            client: Client::new(),
+            // Telemetry never fails a command; a broken proxy setting is
+            // reported by the commands that download.
+            client: crate::net::build_http_client().unwrap_or_default(),
             endpoint: config.otlp_endpoint.clone(),
             headers: parse_headers(config.otlp_headers.clone()),
             timeout: Duration::from_millis(config.timeout_ms),
             user: fingerprint::get_user_fingerprint(),
+            sample_rate: config.sample_rate,
+            categories: config.categories(),
         }
     }
 
-    pub async fn send_metric(&self, metric: CommandMetric) -> Result<(), ()> {
-        let payload = self.encode_metric(metric);
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    pub fn allows(&self, category: TelemetryCategory) -> bool {
+        self.categories.get(category)
+    }
+
+    /// Send all `metrics` in a single request.
+    pub async fn send_metrics(&self, metrics: &[CommandMetric]) -> Result<(), ()> {
+        let payload = self.encode_metrics(metrics);
 
         let endpoint = format!("{}/v1/metrics", self.endpoint);
 
@@ -85,13 +121,38 @@ impl OtlpClient {
         }
     }
 
-    fn encode_metric(&self, metric: CommandMetric) -> serde_json::Value {
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos() as u64;
-
-        // Manual OTLP JSON encoding for single metric
+    fn encode_metrics(&self, metrics: &[CommandMetric]) -> serde_json::Value {
+        let data_points: Vec<_> = metrics
+            .iter()
+            .map(|metric| {
+                let mut attributes = vec![json!({
+                    "key": "command_name",
+                    "value": {"stringValue": metric.command_name}
+                })];
+
+                if let Some(duration_ms) = metric.duration_ms {
+                    attributes.push(json!({
+                        "key": "duration_ms",
+                        "value": {"intValue": duration_ms.to_string()}
+                    }));
+                }
+
+                if let Some(code) = &metric.error_code {
+                    attributes.push(json!({
+                        "key": "error_code",
+                        "value": {"stringValue": code}
+                    }));
+                }
+
+                json!({
+                    "attributes": attributes,
+                    "timeUnixNano": format!("{}", metric.timestamp_ns),
+                    "asInt": "1"
+                })
+            })
+            .collect();
+
+        // Manual OTLP JSON encoding, one data point per invocation
         json!({
             "resourceMetrics": [{
                 "resource": {
@@ -111,14 +172,7 @@ impl OtlpClient {
                     "metrics": [{
                         "name": "command_invocation",
                         "sum": {
-                            "dataPoints": [{
-                                "attributes": [{
-                                    "key": "command_name",
-                                    "value": {"stringValue": metric.command_name}
-                                }],
-                                "timeUnixNano": format!("{}", timestamp),
-                                "asInt": "1"
-                            }],
+                            "dataPoints": data_points,
                             "aggregationTemporality": 1,
                             "isMonotonic": true
                         }
@@ -70,20 +70,19 @@ fn generate_stable_random() -> u64 {
     hasher.finish()
 }
 
-fn load_stored_fingerprint() -> Option<String> {
-    // Load from ~/.tx3/trix/fingerprint
-    let mut path = crate::home::tx3_dir().ok()?;
-    path.push("trix/fingerprint");
+/// The fingerprint stored by an earlier report, if any. Unlike
+/// [`get_user_fingerprint`], never creates one.
+pub fn load_stored_fingerprint() -> Option<String> {
+    // Load from the trix data dir (~/.tx3/trix/fingerprint by default)
+    let mut path = crate::home::dir(crate::home::Location::Data).ok()?;
+    path.push("fingerprint");
     std::fs::read_to_string(path).ok()
 }
 
 fn store_fingerprint(fingerprint: &str) {
-    // Store in ~/.tx3/trix/fingerprint
-    if let Ok(mut path) = crate::home::tx3_dir() {
-        path.push("trix");
-        if std::fs::create_dir_all(&path).is_ok() {
-            path.push("fingerprint");
-            let _ = std::fs::write(path, fingerprint);
-        }
+    // Store in the trix data dir (~/.tx3/trix/fingerprint by default)
+    if let Ok(mut path) = crate::home::dir(crate::home::Location::Data) {
+        path.push("fingerprint");
+        let _ = std::fs::write(path, fingerprint);
     }
 }
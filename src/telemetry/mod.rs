@@ -1,15 +1,48 @@
+use std::time::Duration;
+
+use cryptoxide::{digest::Digest as _, sha2::Sha256};
 use tokio::{sync::OnceCell, task::JoinHandle};
 use tracing::debug;
 
-use crate::{cli::{Cli, Commands}, global::TelemetryConfig};
+use crate::{
+    cli::{Cli, Commands},
+    global::{TelemetryCategory, TelemetryConfig},
+};
 
 mod client;
 mod fingerprint;
+pub mod spool;
 
 pub use client::{CommandMetric, OtlpClient};
+pub use fingerprint::load_stored_fingerprint;
 
 static TELEMETRY_CLIENT: OnceCell<OtlpClient> = OnceCell::const_new();
 
+/// Whether an invocation identified by `key` reports, given `rate`. The
+/// key is hashed so the decision is a pure function of it.
+fn sampled(key: &str, rate: f64) -> bool {
+    if rate >= 1.0 {
+        return true;
+    }
+
+    if rate <= 0.0 {
+        return false;
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.input(key.as_bytes());
+    let mut digest = [0u8; 32];
+    hasher.result(&mut digest);
+
+    let bucket = u64::from_be_bytes(digest[..8].try_into().unwrap());
+
+    (bucket as f64 / u64::MAX as f64) < rate
+}
+
+fn invocation_key(metric: &CommandMetric) -> String {
+    format!("{}:{}:{}", std::process::id(), metric.timestamp_ns, metric.command_name)
+}
+
 pub fn initialize_telemetry(config: &TelemetryConfig) -> miette::Result<()> {
     if !config.enabled {
         debug!("telemetry is disabled, skipping telemetry initialization");
@@ -40,32 +73,98 @@ impl From<&Cli> for Option<CommandMetric> {
             Commands::Inspect(_) => Some(CommandMetric::new("inspect")),
             Commands::Test(_) => Some(CommandMetric::new("test")),
             Commands::Identities(_) => Some(CommandMetric::new("identities")),
+            Commands::Wallet(_) => Some(CommandMetric::new("wallet")),
             Commands::Publish(_) => Some(CommandMetric::new("publish")),
             Commands::Use(_) => Some(CommandMetric::new("use")),
+            Commands::Shell(_) => Some(CommandMetric::new("shell")),
             _ => None,
         }
     }
 }
 
-pub fn track_command_execution(call: &Cli) -> Option<JoinHandle<()>> {
+/// Diagnostic code of a command's error, or `unclassified`. Never the
+/// message, which can hold paths and addresses.
+fn error_code(err: &miette::Report) -> String {
+    err.code()
+        .map(|code| code.to_string())
+        .unwrap_or_else(|| "unclassified".to_string())
+}
+
+/// Report a finished command. `metric` is what [`From<&Cli>`] made of its
+/// invocation; duration and error are added as the enabled categories
+/// allow.
+pub fn track_command_execution(
+    metric: Option<CommandMetric>,
+    duration: Duration,
+    result: &miette::Result<()>,
+) -> Option<JoinHandle<()>> {
     let Some(client) = TELEMETRY_CLIENT.get() else {
         debug!("skipping since not initialized");
         return None;
     };
 
-    let metric: Option<CommandMetric> = call.into();
+    // Performance and errors ride on the usage data point.
+    if !client.allows(TelemetryCategory::Usage) {
+        debug!("skipping since usage telemetry is disabled");
+        return None;
+    }
 
-    let Some(metric) = metric else {
+    let Some(mut metric) = metric else {
         debug!("skipping since command is not relevant for telemetry");
         return None;
     };
 
+    if client.allows(TelemetryCategory::Performance) {
+        metric.duration_ms = Some(duration.as_millis() as u64);
+    }
+
+    if client.allows(TelemetryCategory::Errors) {
+        metric.error_code = result.as_ref().err().map(error_code);
+    }
+
+    let sampled = sampled(&invocation_key(&metric), client.sample_rate());
+
+    let spool = spool::spool_path();
+    let mut batch = spool
+        .as_deref()
+        .map(|path| spool::take_batch(path, client::now_ns()))
+        .unwrap_or_default();
+
+    if sampled {
+        batch.push(metric);
+    }
+
+    if batch.is_empty() {
+        debug!("skipping since invocation is not sampled");
+        return None;
+    }
+
     debug!("submitting command telemetry");
 
     let handle = tokio::spawn(async move {
-        let _ = client.send_metric(metric).await; // Silent failure
+        // Silent failure: unsent metrics are spooled for a later run.
+        if client.send_metrics(&batch).await.is_err()
+            && let Some(path) = spool
+        {
+            spool::append(&path, batch);
+        }
         debug!("telemetry sent");
     });
 
     Some(handle)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::sampled;
+
+    #[test]
+    fn sampling_is_deterministic_and_bounded() {
+        assert!(sampled("any", 1.0));
+        assert!(!sampled("any", 0.0));
+        assert_eq!(sampled("key-1", 0.5), sampled("key-1", 0.5));
+
+        let hits = (0..1000).filter(|i| sampled(&format!("run-{i}"), 0.25)).count();
+        assert!((150..350).contains(&hits), "{hits} of 1000 sampled");
+    }
+}
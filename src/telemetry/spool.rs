@@ -0,0 +1,139 @@
+//! Local spool for metrics that couldn't reach the collector.
+//!
+//! A failed send appends its metrics to a file in the cache dir; the next
+//! invocation takes a bounded batch back out and sends it together with its
+//! own metric. Entries past [`MAX_AGE`] are dropped on read and the file
+//! never holds more than [`MAX_ENTRIES`]. Every operation is best effort:
+//! a broken spool only ever means lost metrics.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::CommandMetric;
+
+const SPOOL_FILE: &str = "telemetry-spool.ndjson";
+
+/// Entries kept on disk; the oldest go first.
+pub const MAX_ENTRIES: usize = 500;
+
+/// Entries older than this are dropped instead of sent.
+pub const MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Entries resent per invocation, so one run never carries a huge payload.
+pub const FLUSH_BATCH: usize = 50;
+
+pub fn spool_path() -> Option<PathBuf> {
+    crate::home::dir(crate::home::Location::Cache)
+        .ok()
+        .map(|dir| dir.join(SPOOL_FILE))
+}
+
+fn read(path: &Path) -> Vec<CommandMetric> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return vec![];
+    };
+
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn write(path: &Path, metrics: &[CommandMetric]) {
+    if metrics.is_empty() {
+        let _ = std::fs::remove_file(path);
+        return;
+    }
+
+    let content: String = metrics
+        .iter()
+        .filter_map(|m| serde_json::to_string(m).ok())
+        .map(|line| line + "\n")
+        .collect();
+
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+
+    let _ = crate::fsutil::write_atomic(path, content);
+}
+
+fn is_fresh(metric: &CommandMetric, now_ns: u64) -> bool {
+    now_ns.saturating_sub(metric.timestamp_ns) <= MAX_AGE.as_nanos() as u64
+}
+
+/// Remove and return up to [`FLUSH_BATCH`] of the oldest fresh entries.
+/// Whatever isn't taken stays spooled for a later run.
+pub fn take_batch(path: &Path, now_ns: u64) -> Vec<CommandMetric> {
+    let mut fresh: Vec<_> = read(path)
+        .into_iter()
+        .filter(|m| is_fresh(m, now_ns))
+        .collect();
+
+    let rest = fresh.split_off(fresh.len().min(FLUSH_BATCH));
+    write(path, &rest);
+
+    fresh
+}
+
+/// Spool `metrics` after a failed send, trimming to [`MAX_ENTRIES`].
+pub fn append(path: &Path, metrics: Vec<CommandMetric>) {
+    let mut all = read(path);
+    all.extend(metrics);
+
+    let excess = all.len().saturating_sub(MAX_ENTRIES);
+    all.drain(..excess);
+
+    write(path, &all);
+}
+
+/// Forget everything spooled; used when telemetry is turned off.
+pub fn clear() {
+    if let Some(path) = spool_path() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metric(name: &str, timestamp_ns: u64) -> CommandMetric {
+        CommandMetric {
+            command_name: name.to_string(),
+            timestamp_ns,
+            duration_ms: None,
+            error_code: None,
+        }
+    }
+
+    #[test]
+    fn take_batch_drops_expired_and_keeps_remainder() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SPOOL_FILE);
+        let now = MAX_AGE.as_nanos() as u64 * 2;
+
+        let mut metrics = vec![metric("old", 0)];
+        metrics.extend((0..FLUSH_BATCH + 3).map(|_| metric("build", now - 1)));
+        append(&path, metrics);
+
+        let batch = take_batch(&path, now);
+        assert_eq!(batch.len(), FLUSH_BATCH);
+        assert!(batch.iter().all(|m| m.command_name == "build"));
+
+        assert_eq!(take_batch(&path, now).len(), 3);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn append_keeps_newest_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SPOOL_FILE);
+
+        append(&path, (0..MAX_ENTRIES as u64 + 10).map(|i| metric("test", i)).collect());
+
+        let kept = read(&path);
+        assert_eq!(kept.len(), MAX_ENTRIES);
+        assert_eq!(kept[0].timestamp_ns, 10);
+    }
+}
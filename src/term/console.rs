@@ -0,0 +1,182 @@
+//! Process-wide output capabilities: whether to emit color and how wide
+//! the terminal is. Decided once at startup from `--color`, `NO_COLOR`,
+//! `CLICOLOR_FORCE` and whether stdout is a terminal, so every command
+//! renders the same way in CI logs.
+
+use std::io::IsTerminal as _;
+use std::sync::OnceLock;
+
+use termimad::MadSkin;
+
+/// Width used when stdout isn't a terminal and `COLUMNS` is unset.
+const FALLBACK_WIDTH: usize = 120;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal, unless NO_COLOR is set
+    #[default]
+    Auto,
+    /// Always color, even when redirected
+    Always,
+    /// Never color
+    Never,
+}
+
+/// Environment inputs to the color decision, split out so the decision
+/// itself is a pure function.
+#[derive(Debug, Default, Clone)]
+pub struct ColorEnv {
+    pub is_tty: bool,
+    pub no_color: Option<String>,
+    pub clicolor_force: Option<String>,
+    pub term: Option<String>,
+}
+
+impl ColorEnv {
+    fn current() -> Self {
+        Self {
+            is_tty: std::io::stdout().is_terminal(),
+            no_color: std::env::var("NO_COLOR").ok(),
+            clicolor_force: std::env::var("CLICOLOR_FORCE").ok(),
+            term: std::env::var("TERM").ok(),
+        }
+    }
+}
+
+fn is_set(value: &Option<String>) -> bool {
+    value.as_deref().is_some_and(|v| !v.is_empty() && v != "0")
+}
+
+impl ColorChoice {
+    /// `CLICOLOR_FORCE` wins over everything in auto mode, then
+    /// `NO_COLOR`, then a dumb or redirected terminal.
+    pub fn resolve(self, env: &ColorEnv) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto if is_set(&env.clicolor_force) => true,
+            ColorChoice::Auto if env.no_color.as_deref().is_some_and(|v| !v.is_empty()) => false,
+            ColorChoice::Auto if env.term.as_deref() == Some("dumb") => false,
+            ColorChoice::Auto => env.is_tty,
+        }
+    }
+}
+
+static COLOR: OnceLock<bool> = OnceLock::new();
+
+/// Fix the color decision for this process. Called once from main; later
+/// calls are ignored.
+pub fn set_color_choice(choice: ColorChoice) {
+    let _ = COLOR.set(choice.resolve(&ColorEnv::current()));
+}
+
+pub fn colors_enabled() -> bool {
+    *COLOR.get_or_init(|| ColorChoice::Auto.resolve(&ColorEnv::current()))
+}
+
+/// Columns available for output: the terminal's width, else `COLUMNS`,
+/// else [`FALLBACK_WIDTH`].
+pub fn width() -> usize {
+    if std::io::stdout().is_terminal() {
+        let (columns, _) = termimad::terminal_size();
+        if columns > 0 {
+            return columns as usize;
+        }
+    }
+
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse().ok())
+        .filter(|c| *c > 0)
+        .unwrap_or(FALLBACK_WIDTH)
+}
+
+/// Shorten `text` to at most `max` characters by replacing its middle
+/// with `…`, so both the scheme/prefix and the unique tail of URLs and
+/// addresses stay visible.
+pub fn truncate_middle(text: &str, max: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+
+    if chars.len() <= max {
+        return text.to_string();
+    }
+
+    if max == 0 {
+        return String::new();
+    }
+
+    let keep = max - 1;
+    let head = keep.div_ceil(2);
+    let tail = keep / 2;
+
+    let mut out: String = chars[..head].iter().collect();
+    out.push('…');
+    out.extend(&chars[chars.len() - tail..]);
+    out
+}
+
+/// Budget for a single value on a line that also carries `overhead`
+/// columns of labels and markup. Never below a readable minimum.
+pub fn value_width(overhead: usize) -> usize {
+    width().saturating_sub(overhead).max(24)
+}
+
+pub fn skin() -> MadSkin {
+    if colors_enabled() {
+        MadSkin::default()
+    } else {
+        MadSkin::no_style()
+    }
+}
+
+/// Render markdown with the process-wide skin at the detected width.
+pub fn print_markdown(markdown: &str) {
+    println!("{}", skin().text(markdown, Some(width())));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(is_tty: bool, no_color: Option<&str>, force: Option<&str>) -> ColorEnv {
+        ColorEnv {
+            is_tty,
+            no_color: no_color.map(String::from),
+            clicolor_force: force.map(String::from),
+            term: None,
+        }
+    }
+
+    #[test]
+    fn color_decision_matrix() {
+        let cases = [
+            (ColorChoice::Auto, env(true, None, None), true),
+            (ColorChoice::Auto, env(false, None, None), false),
+            (ColorChoice::Auto, env(true, Some("1"), None), false),
+            (ColorChoice::Auto, env(true, Some(""), None), true),
+            (ColorChoice::Auto, env(false, None, Some("1")), true),
+            (ColorChoice::Auto, env(false, None, Some("0")), false),
+            (ColorChoice::Auto, env(true, Some("1"), Some("1")), true),
+            (ColorChoice::Always, env(false, Some("1"), None), true),
+            (ColorChoice::Never, env(true, None, Some("1")), false),
+        ];
+
+        for (choice, env, expected) in cases {
+            assert_eq!(choice.resolve(&env), expected, "{choice:?} with {env:?}");
+        }
+
+        let dumb = ColorEnv {
+            term: Some("dumb".into()),
+            ..env(true, None, None)
+        };
+        assert!(!ColorChoice::Auto.resolve(&dumb));
+    }
+
+    #[test]
+    fn truncate_middle_keeps_both_ends() {
+        assert_eq!(truncate_middle("short", 10), "short");
+        assert_eq!(truncate_middle("addr_test1qabcdefxyz", 9), "addr…fxyz");
+        assert_eq!(truncate_middle("abcdef", 5).chars().count(), 5);
+        assert_eq!(truncate_middle("abcdef", 1), "…");
+    }
+}
@@ -0,0 +1,37 @@
+//! Terminal capability helpers for human-facing output.
+
+use std::io::IsTerminal as _;
+
+pub mod console;
+pub mod prompt;
+
+/// `--output` choice for commands whose report CI may want to parse.
+/// `human` renders markdown to the terminal; `json` prints one JSON
+/// document to stdout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+#[clap(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    #[default]
+    Human,
+    Json,
+}
+
+/// Whether stdout is a terminal that can render OSC 8 hyperlinks. Dumb
+/// terminals and redirected output get plain text.
+pub fn supports_hyperlinks() -> bool {
+    if !std::io::stdout().is_terminal() {
+        return false;
+    }
+
+    !matches!(std::env::var("TERM").as_deref(), Ok("dumb"))
+}
+
+/// Render `text` as a clickable link to `url` when the terminal supports
+/// it, otherwise as the bare `url`.
+pub fn hyperlink(url: &str, text: &str) -> String {
+    if supports_hyperlinks() {
+        format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+    } else {
+        url.to_string()
+    }
+}
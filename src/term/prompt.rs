@@ -0,0 +1,75 @@
+//! Shared terminal interaction for interactive features.
+//!
+//! Every prompt trix shows goes through here so TTY detection and the
+//! "this needs a terminal" error are consistent across commands.
+
+use std::io::{IsTerminal as _, Read as _};
+
+use miette::{bail, IntoDiagnostic as _};
+
+/// Whether both stdin and stdout are attached to a terminal.
+pub fn is_interactive() -> bool {
+    std::io::stdin().is_terminal() && std::io::stdout().is_terminal()
+}
+
+/// Reject `feature` (e.g. a `--interactive` flag) outside a terminal.
+pub fn ensure_interactive(feature: &str) -> miette::Result<()> {
+    if !is_interactive() {
+        bail!(
+            help = "run it from a terminal, or drop the flag in CI and scripts",
+            "{feature} needs an interactive terminal"
+        );
+    }
+
+    Ok(())
+}
+
+/// Pick one of `options`.
+pub fn select<T: std::fmt::Display>(message: &str, options: Vec<T>) -> miette::Result<T> {
+    inquire::Select::new(message, options)
+        .prompt()
+        .into_diagnostic()
+}
+
+/// Yes/no question.
+pub fn confirm(message: &str, default: bool) -> miette::Result<bool> {
+    inquire::Confirm::new(message)
+        .with_default(default)
+        .prompt()
+        .into_diagnostic()
+}
+
+/// Free-form line of input.
+pub fn text(message: &str) -> miette::Result<String> {
+    inquire::Text::new(message).prompt().into_diagnostic()
+}
+
+/// Hidden line of input, for secrets.
+pub fn password(message: &str) -> miette::Result<String> {
+    inquire::Password::new(message)
+        .without_confirmation()
+        .prompt()
+        .into_diagnostic()
+}
+
+/// A secret from piped stdin when there is one, so scripts never put it in
+/// argv, otherwise from a hidden prompt showing `message`. `what` names the
+/// secret when stdin holds nothing.
+pub fn secret(message: &str, what: &str) -> miette::Result<String> {
+    if std::io::stdin().is_terminal() {
+        return password(message);
+    }
+
+    let mut secret = String::new();
+    std::io::stdin()
+        .read_to_string(&mut secret)
+        .into_diagnostic()?;
+
+    let secret = secret.trim_end_matches(['\r', '\n']).to_string();
+
+    if secret.is_empty() {
+        bail!("no {what} on stdin");
+    }
+
+    Ok(secret)
+}
@@ -1,15 +1,28 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
 use askama::Template as _;
 use bip39::Mnemonic;
-use cryptoxide::{digest::Digest, sha2::Sha256};
+use cryptoxide::{
+    digest::Digest,
+    hmac::Hmac,
+    pbkdf2::pbkdf2,
+    sha2::{Sha256, Sha512},
+};
+use ed25519_bip32::{DerivationScheme, XPRV_SIZE, XPrv};
 use miette::{bail, Context, IntoDiagnostic as _, Result};
+use pallas::codec::minicbor;
+use pallas::codec::utils::Bytes;
+use pallas::crypto::hash::{Hash, Hasher};
+use pallas::crypto::key::ed25519::SecretKey;
+use pallas::ledger::addresses::{
+    Address, Network, ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart,
+};
 
 use crate::{
-    config::{IdentityConfig, NetworkConfig, ProfileConfig, RootConfig},
+    config::{IdentityConfig, MnemonicIdentityConfig, NetworkConfig, ProfileConfig, RootConfig},
     spawn::cshell::{CshellTomlTemplate, Provider, WalletInfoOutput},
 };
 
@@ -23,10 +36,325 @@ fn generate_deterministic_mnemonic(input: &str) -> miette::Result<Mnemonic> {
     Mnemonic::from_entropy(&entropy).into_diagnostic()
 }
 
-fn setup_wallet_key(home: &Path, ident: &str) -> miette::Result<String> {
-    let mnemonic = generate_deterministic_mnemonic(ident)?.to_string();
+/// How random-key identities derive their keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Derivation {
+    /// Seeded by the identity name alone: `alice` has the same keys in
+    /// every project. Kept behind `legacy_wallet_derivation = true`.
+    Legacy,
+    /// Seeded by `<namespace>/<name>`, unique per project.
+    Namespaced(String),
+}
+
+impl Derivation {
+    /// The derivation `config` asks for. The namespace is `wallet_seed`
+    /// when set, otherwise the protocol's `scope/name`.
+    pub fn for_project(config: &RootConfig) -> Self {
+        if config.legacy_wallet_derivation {
+            return Derivation::Legacy;
+        }
+
+        let namespace = config.wallet_seed.clone().unwrap_or_else(|| {
+            let scope = config
+                .protocol
+                .scope
+                .as_deref()
+                .unwrap_or(crate::dirs::LOCAL_SCOPE);
+            format!("{scope}/{}", config.protocol.name)
+        });
+
+        Derivation::Namespaced(namespace)
+    }
+
+    fn seed(&self, ident: &str) -> String {
+        match self {
+            Derivation::Legacy => ident.to_string(),
+            Derivation::Namespaced(namespace) => format!("{namespace}/{ident}"),
+        }
+    }
+
+    /// The mnemonic random-key identity `ident` is restored from.
+    pub(crate) fn mnemonic(&self, ident: &str) -> miette::Result<String> {
+        Ok(generate_deterministic_mnemonic(&self.seed(ident))?.to_string())
+    }
+
+    /// Short, path-safe tag distinguishing derivation modes, so state keyed
+    /// by wallet addresses (e.g. the devnet home) never mixes the two.
+    pub fn tag(&self) -> String {
+        match self {
+            Derivation::Legacy => "legacy".to_string(),
+            Derivation::Namespaced(namespace) => {
+                let mut hasher = Sha256::new();
+                hasher.input(namespace.as_bytes());
+                hasher.result_str()[..16].to_string()
+            }
+        }
+    }
+}
+
+/// Restore `ident` into the cshell store at `home` and return its testnet
+/// address.
+pub(crate) fn setup_wallet_key(
+    home: &Path,
+    ident: &str,
+    derivation: &Derivation,
+) -> miette::Result<String> {
+    let mnemonic = derivation.mnemonic(ident)?;
+
+    restore_wallet(home, ident, &mnemonic)
+}
+
+const IMPORTED_MNEMONIC_WORDS: usize = 24;
+
+/// Parse an imported BIP-39 phrase. Only 24-word phrases are accepted, the
+/// length cshell restores.
+pub(crate) fn parse_mnemonic(phrase: &str) -> miette::Result<Mnemonic> {
+    let mnemonic = Mnemonic::parse(phrase.trim())
+        .into_diagnostic()
+        .context("invalid BIP-39 mnemonic")?;
+
+    if mnemonic.word_count() != IMPORTED_MNEMONIC_WORDS {
+        bail!(
+            "mnemonic has {} words, expected {IMPORTED_MNEMONIC_WORDS}",
+            mnemonic.word_count()
+        );
+    }
+
+    Ok(mnemonic)
+}
+
+const HARDENED: u32 = 0x8000_0000;
+
+/// CIP-1852 path of the first payment key of `account`,
+/// `m/1852'/1815'/account'/0/0`.
+fn payment_key_path(account: u32) -> [u32; 5] {
+    [HARDENED | 1852, HARDENED | 1815, HARDENED | account, 0, 0]
+}
+
+/// An ed25519 payment signing key.
+pub(crate) enum SigningKey {
+    /// A plain key, as `cardano-cli address key-gen` writes them.
+    Plain([u8; SecretKey::SIZE]),
+    /// A BIP32 key, as derived from a mnemonic.
+    Extended(XPrv),
+}
+
+impl SigningKey {
+    /// The first payment key of `account` in the wallet `mnemonic`
+    /// restores, the one its address pays to.
+    pub(crate) fn from_mnemonic(mnemonic: &Mnemonic, account: u32) -> Self {
+        let mut seed = [0u8; XPRV_SIZE];
+        let mut mac = Hmac::new(Sha512::new(), &[]);
+        pbkdf2(&mut mac, &mnemonic.to_entropy(), 4096, &mut seed);
+
+        let key = payment_key_path(account)
+            .iter()
+            .fold(XPrv::normalize_bytes_force3rd(seed), |key, index| {
+                key.derive(DerivationScheme::V2, *index)
+            });
+
+        SigningKey::Extended(key)
+    }
+
+    pub(crate) fn public_key(&self) -> [u8; 32] {
+        match self {
+            SigningKey::Plain(bytes) => {
+                let public = SecretKey::from(*bytes).public_key();
+                public.as_ref().try_into().expect("ed25519 public key")
+            }
+            SigningKey::Extended(key) => key.public().public_key(),
+        }
+    }
+
+    pub(crate) fn key_hash(&self) -> Hash<28> {
+        Hasher::<224>::hash(&self.public_key())
+    }
+
+    /// The key as a cardano-cli text envelope.
+    pub(crate) fn to_envelope(&self) -> miette::Result<String> {
+        let (kind, bytes) = match self {
+            SigningKey::Plain(bytes) => ("PaymentSigningKeyShelley_ed25519", bytes.to_vec()),
+            // cardano-cli's layout: extended key, public key, chain code.
+            SigningKey::Extended(key) => (
+                "PaymentExtendedSigningKeyShelley_ed25519_bip32",
+                [
+                    key.extended_secret_key().as_slice(),
+                    self.public_key().as_slice(),
+                    key.chain_code().as_slice(),
+                ]
+                .concat(),
+            ),
+        };
+
+        let cbor = minicbor::to_vec(Bytes::from(bytes)).into_diagnostic()?;
+
+        let envelope = KeyEnvelope {
+            kind: kind.to_string(),
+            description: "Payment Signing Key".to_string(),
+            cbor_hex: hex::encode(cbor),
+        };
+
+        serde_json::to_string_pretty(&envelope).into_diagnostic()
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct KeyEnvelope {
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    description: String,
+    #[serde(rename = "cborHex")]
+    cbor_hex: String,
+}
+
+/// Read an ed25519 signing key, either a cardano-cli text envelope
+/// (`PaymentSigningKeyShelley_ed25519` or its `Extended` BIP32 variant) or
+/// bare CBOR hex.
+pub(crate) fn read_signing_key(path: &Path) -> miette::Result<SigningKey> {
+    let text = std::fs::read_to_string(path)
+        .into_diagnostic()
+        .with_context(|| format!("reading key {}", path.display()))?;
+
+    let cbor_hex = match serde_json::from_str::<KeyEnvelope>(&text) {
+        Ok(envelope) => envelope.cbor_hex,
+        Err(_) => text.trim().to_string(),
+    };
+
+    let cbor = hex::decode(&cbor_hex)
+        .map_err(|_| miette::miette!("{} is not CBOR hex", path.display()))?;
+
+    let bytes: &[u8] = minicbor::decode(&cbor)
+        .map_err(|_| miette::miette!("{} is not a CBOR byte string", path.display()))?;
+
+    if let Ok(bytes) = <[u8; SecretKey::SIZE]>::try_from(bytes) {
+        return Ok(SigningKey::Plain(bytes));
+    }
+
+    if bytes.len() == EXTENDED_KEY_SIZE {
+        let mut xprv = [0u8; XPRV_SIZE];
+        xprv[..64].copy_from_slice(&bytes[..64]);
+        xprv[64..].copy_from_slice(&bytes[96..]);
+
+        return XPrv::from_bytes_verified(xprv)
+            .map(SigningKey::Extended)
+            .map_err(|err| {
+                miette::miette!("{} is not a valid extended key: {err}", path.display())
+            });
+    }
+
+    bail!(
+        "{} holds {} key bytes, expected an ed25519 signing key ({} or {})",
+        path.display(),
+        bytes.len(),
+        SecretKey::SIZE,
+        EXTENDED_KEY_SIZE
+    );
+}
+
+/// Bytes in a cardano-cli extended signing key envelope.
+const EXTENDED_KEY_SIZE: usize = 128;
+
+/// The signing key of identity `name`: derived from its mnemonic, or read
+/// from its key file.
+pub(crate) fn identity_signing_key(
+    profile: &ProfileConfig,
+    derivation: &Derivation,
+    name: &str,
+) -> miette::Result<SigningKey> {
+    let Some(identity) = profile.identities.get(name) else {
+        bail!("profile `{}` has no identity named `{name}`", profile.name);
+    };
+
+    match identity {
+        IdentityConfig::RandomKey(_) => Ok(SigningKey::from_mnemonic(
+            &generate_deterministic_mnemonic(&derivation.seed(name))?,
+            0,
+        )),
+        IdentityConfig::Mnemonic(ident) => Ok(SigningKey::from_mnemonic(
+            &identity_mnemonic(profile, ident)?,
+            ident.account_index.unwrap_or(0),
+        )),
+        IdentityConfig::ExplicitKey(ident) => read_signing_key(&ident.key_path),
+    }
+}
+
+/// The phrase of mnemonic identity `ident`: its `mnemonic` reference, or
+/// the `mnemonic_env` variable of `profile`'s env file or the process
+/// environment.
+pub(crate) fn identity_mnemonic(
+    profile: &ProfileConfig,
+    ident: &MnemonicIdentityConfig,
+) -> miette::Result<Mnemonic> {
+    let name = &ident.name;
+
+    match (&ident.mnemonic, &ident.mnemonic_env) {
+        (Some(reference), None) => {
+            let phrase = crate::secrets::resolve(reference)
+                .with_context(|| format!("mnemonic of identity `{name}`"))?;
+            parse_mnemonic(&phrase).with_context(|| format!("mnemonic of identity `{name}`"))
+        }
+        (None, Some(var)) => {
+            let env_file = profile.env_file_path();
+
+            let from_file = match env_file.is_file() {
+                true => crate::commands::profile::load_env_vars(&env_file)?.remove(var),
+                false => None,
+            };
+
+            let Some(phrase) = from_file.or_else(|| std::env::var(var).ok()) else {
+                bail!(
+                    help = format!(
+                        "add `{var}=<24 words>` to {}, or export it",
+                        env_file.display()
+                    ),
+                    "identity `{name}` reads its mnemonic from `{var}`, which is not set in {} or the environment",
+                    env_file.display()
+                );
+            };
+
+            parse_mnemonic(&phrase)
+                .with_context(|| format!("`{var}`, mnemonic of identity `{name}`"))
+        }
+        (Some(_), Some(_)) => bail!(
+            help = "keep one of the two",
+            "identity `{name}` sets both `mnemonic` and `mnemonic_env`"
+        ),
+        (None, None) => bail!(
+            help = "set `mnemonic_env` to the variable holding the phrase",
+            "identity `{name}` has no `mnemonic` or `mnemonic_env`"
+        ),
+    }
+}
+
+/// Hash of the key `address` pays to, `None` for script and Byron
+/// addresses.
+pub(crate) fn payment_key_hash(address: &str) -> Option<Hash<28>> {
+    match Address::from_bech32(address).ok()? {
+        Address::Shelley(address) => match address.payment() {
+            ShelleyPaymentPart::Key(hash) => Some(*hash),
+            ShelleyPaymentPart::Script(_) => None,
+        },
+        _ => None,
+    }
+}
+
+/// Enterprise testnet address of `key`, the address an explicit key
+/// identity is funded at.
+pub(crate) fn key_address(key: &SigningKey) -> miette::Result<String> {
+    let address = ShelleyAddress::new(
+        Network::Testnet,
+        ShelleyPaymentPart::Key(key.key_hash()),
+        ShelleyDelegationPart::Null,
+    );
+
+    Address::Shelley(address).to_bech32().into_diagnostic()
+}
 
-    let output = crate::spawn::cshell::wallet_create(home, ident, &mnemonic)?;
+/// Restore a wallet from `mnemonic` into the cshell store at `home` and
+/// return its testnet address.
+fn restore_wallet(home: &Path, ident: &str, mnemonic: &str) -> miette::Result<String> {
+    let output = crate::spawn::cshell::wallet_create(home, ident, mnemonic)?;
 
     let address = output
         .get("addresses")
@@ -43,9 +371,26 @@ pub(crate) fn provider_name(trix_profile: &str) -> String {
     format!("trix-{}", trix_profile)
 }
 
+/// Print the explorer link for a submitted transaction, when cshell
+/// reported a hash and the network has an explorer template.
+pub(crate) fn print_tx_link(output: &serde_json::Value, network: &NetworkConfig) {
+    let Some(hash) = crate::spawn::cshell::tx_hash(output) else {
+        return;
+    };
+
+    match network.explorer_tx_url(hash) {
+        Some(url) => println!("Tx: {hash} ({})", crate::term::hyperlink(&url, &url)),
+        None => println!("Tx: {hash}"),
+    }
+}
+
 pub struct WalletProxy {
     pub target_dir: PathBuf,
     pub addresses: HashMap<String, String>,
+    pub derivation: Derivation,
+    /// Identities with an address but no cshell wallet, see [`in_cshell`].
+    /// They can't sign.
+    pub address_only: HashSet<String>,
 }
 
 impl WalletProxy {
@@ -60,6 +405,8 @@ impl WalletProxy {
 
         let mut child = crate::spawn::cshell::explorer(&self.target_dir, &provider)?;
 
+        let _tracked = crate::spawn::shutdown::track(&child);
+
         let status = child
             .wait()
             .into_diagnostic()
@@ -75,25 +422,37 @@ impl WalletProxy {
     pub fn invoke_interactive(
         &self,
         tii_file: &Path,
+        tx_template: Option<&str>,
         args: &serde_json::Value,
         profile: &str,
         skip_submit: bool,
-    ) -> miette::Result<()> {
+    ) -> miette::Result<Option<serde_json::Value>> {
         let provider = provider_name(profile);
 
         crate::spawn::cshell::tx_invoke_interactive(
             &self.target_dir,
             tii_file,
             Some(profile),
-            None,
+            tx_template,
             args,
             vec![],
             true,
             skip_submit,
             Some(&provider),
-        )?;
+        )
+    }
 
-        Ok(())
+    /// Fail on a signer cshell holds no wallet for, before cshell is asked
+    /// to sign with it and reports the wallet as missing.
+    pub fn ensure_signers(&self, signers: &[&str]) -> miette::Result<()> {
+        let Some(signer) = signers.iter().find(|s| self.address_only.contains(**s)) else {
+            return Ok(());
+        };
+
+        bail!(
+            help = "cshell holds random keys and the first account of a mnemonic; sign with one of those",
+            "identity `{signer}` has an address but no cshell wallet, so it can't sign"
+        );
     }
 
     pub fn invoke_json(
@@ -103,7 +462,10 @@ impl WalletProxy {
         args: &serde_json::Value,
         signers: Vec<&str>,
         profile: &str,
+        skip_submit: bool,
     ) -> miette::Result<serde_json::Value> {
+        self.ensure_signers(&signers)?;
+
         let provider = provider_name(profile);
 
         let output = crate::spawn::cshell::tx_invoke_json(
@@ -114,19 +476,42 @@ impl WalletProxy {
             Some(tx_template),
             signers,
             true,
-            false,
+            skip_submit,
             Some(&provider),
         )?;
 
         Ok(output)
     }
+
+    pub fn sign_and_submit(
+        &self,
+        cbor: &str,
+        signers: Vec<&str>,
+        profile: &str,
+    ) -> miette::Result<serde_json::Value> {
+        self.ensure_signers(&signers)?;
+
+        let provider = provider_name(profile);
+
+        let signed = crate::spawn::cshell::tx_sign(&self.target_dir, cbor, signers)?;
+
+        crate::spawn::cshell::tx_submit(&self.target_dir, &signed, &provider)
+    }
 }
 
+/// The cshell provider for a profile. Header values are resolved here, so
+/// `env:` and `keyring:` references reach cshell as the actual secrets.
 fn define_provider(profile_name: &str, network: &NetworkConfig) -> Result<Provider> {
+    let mut u5c = network.u5c.clone();
+    u5c.headers = crate::secrets::resolve_headers(&u5c.headers).context("u5c headers")?;
+
+    let mut trp = network.trp.clone();
+    trp.headers = crate::secrets::resolve_headers(&trp.headers).context("trp headers")?;
+
     Ok(Provider {
         name: provider_name(profile_name),
-        u5c: network.u5c.clone(),
-        trp: network.trp.clone(),
+        u5c,
+        trp,
         is_testnet: network.is_testnet,
     })
 }
@@ -136,8 +521,50 @@ pub fn setup(protocol: &RootConfig, profile: &ProfileConfig) -> miette::Result<W
 
     let network = protocol.resolve_profile_network(&profile.name)?;
 
+    setup_in(protocol, profile, target_dir, &network)
+}
+
+/// Like [`setup`], but in a cshell home of its own named `name`, with the
+/// local network moved to `ports`. For a devnet running next to the
+/// project's, e.g. one of several `trix test --jobs` files.
+pub fn setup_isolated(
+    protocol: &RootConfig,
+    profile: &ProfileConfig,
+    name: &str,
+    ports: &crate::devnet::Ports,
+) -> miette::Result<WalletProxy> {
+    let target_dir = crate::dirs::target_dir("cshell")?.join(name);
+
+    std::fs::create_dir_all(&target_dir)
+        .into_diagnostic()
+        .with_context(|| format!("creating {}", target_dir.display()))?;
+
+    let mut network = protocol.resolve_profile_network(&profile.name)?;
+    ports.apply(&mut network);
+
+    setup_in(protocol, profile, target_dir, &network)
+}
+
+/// Whether cshell holds a wallet for `ident`, so it can sign and report
+/// balances for it. Raw keys and mnemonic accounts past the first only get
+/// an address.
+pub(crate) fn in_cshell(ident: &IdentityConfig) -> bool {
+    match ident {
+        IdentityConfig::RandomKey(_) => true,
+        IdentityConfig::Mnemonic(ident) => ident.account_index.unwrap_or(0) == 0,
+        IdentityConfig::ExplicitKey(_) => false,
+    }
+}
+
+/// Write the cshell config of `target_dir` with the provider of
+/// `profile_name`, resolving its header secrets again.
+pub(crate) fn write_cshell_config(
+    target_dir: &Path,
+    profile_name: &str,
+    network: &NetworkConfig,
+) -> miette::Result<()> {
     let toml = CshellTomlTemplate {
-        provider: define_provider(profile.name.as_str(), &network)?,
+        provider: define_provider(profile_name, network)?,
     };
 
     let toml = toml.render().into_diagnostic()?;
@@ -146,21 +573,241 @@ pub fn setup(protocol: &RootConfig, profile: &ProfileConfig) -> miette::Result<W
 
     std::fs::write(&toml_path, toml)
         .into_diagnostic()
-        .context("writing cshell config")?;
+        .context("writing cshell config")
+}
+
+fn setup_in(
+    protocol: &RootConfig,
+    profile: &ProfileConfig,
+    target_dir: PathBuf,
+    network: &NetworkConfig,
+) -> miette::Result<WalletProxy> {
+    write_cshell_config(&target_dir, profile.name.as_str(), network)?;
+
+    let derivation = Derivation::for_project(protocol);
 
     let mut addresses = HashMap::new();
+    let mut address_only = HashSet::new();
 
     for (name, ident) in profile.identities.iter() {
-        if let IdentityConfig::RandomKey(ident) = ident {
-            let address = setup_wallet_key(&target_dir, &ident.name)?;
-            addresses.insert(name.clone(), address);
-        } else {
-            bail!("only random key identities are supported");
+        if !in_cshell(ident) {
+            address_only.insert(name.clone());
         }
+
+        let address = match ident {
+            IdentityConfig::RandomKey(ident) => {
+                setup_wallet_key(&target_dir, &ident.name, &derivation)?
+            }
+            IdentityConfig::Mnemonic(ident) => {
+                let mnemonic = identity_mnemonic(profile, ident)?;
+
+                match ident.account_index.unwrap_or(0) {
+                    0 => restore_wallet(&target_dir, &ident.name, &mnemonic.to_string())?,
+                    // cshell only restores the first account; later ones get
+                    // an address but, like raw keys, can't sign through it.
+                    account => key_address(&SigningKey::from_mnemonic(&mnemonic, account))?,
+                }
+            }
+            // cshell restores wallets from mnemonics only, so a raw key gets
+            // an address (for devnet funding and `@name` references) but
+            // can't sign through cshell.
+            IdentityConfig::ExplicitKey(ident) => key_address(&read_signing_key(&ident.key_path)?)?,
+        };
+
+        addresses.insert(name.clone(), address);
     }
 
     Ok(WalletProxy {
         target_dir,
         addresses,
+        derivation,
+        address_only,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(extra: &str) -> RootConfig {
+        let toml = format!(
+            "{extra}\n[protocol]\nname = \"demo\"\nscope = \"acme\"\nversion = \"0.0.0\"\nmain = \"main.tx3\"\n[ledger]\nfamily = \"cardano\"\n"
+        );
+        toml::from_str(&toml).unwrap()
+    }
+
+    #[test]
+    fn identities_are_namespaced_by_protocol() {
+        let derivation = Derivation::for_project(&config(""));
+        assert_eq!(derivation, Derivation::Namespaced("acme/demo".to_string()));
+        assert_eq!(derivation.seed("alice"), "acme/demo/alice");
+    }
+
+    #[test]
+    fn wallet_seed_overrides_namespace() {
+        let derivation = Derivation::for_project(&config("wallet_seed = \"shared\""));
+        assert_eq!(derivation.seed("alice"), "shared/alice");
+    }
+
+    #[test]
+    fn legacy_derivation_uses_bare_name() {
+        let derivation = Derivation::for_project(&config("legacy_wallet_derivation = true"));
+        assert_eq!(derivation.seed("alice"), "alice");
+        assert_eq!(derivation.tag(), "legacy");
+    }
+
+    const ZERO_MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art";
+
+    #[test]
+    fn imported_mnemonic_needs_24_valid_words() {
+        assert!(parse_mnemonic(ZERO_MNEMONIC).is_ok());
+
+        let twelve = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let err = parse_mnemonic(twelve).unwrap_err();
+        assert!(err.to_string().contains("12 words"));
+
+        let bad_checksum = ZERO_MNEMONIC.replace("art", "abandon");
+        assert!(parse_mnemonic(&bad_checksum).is_err());
+    }
+
+    #[test]
+    fn signing_key_envelope_and_bare_hex_agree() {
+        let dir = tempfile::tempdir().unwrap();
+        let cbor_hex = format!("5820{}", "11".repeat(32));
+
+        let envelope = dir.path().join("payment.skey");
+        std::fs::write(
+            &envelope,
+            format!(
+                r#"{{"type": "PaymentSigningKeyShelley_ed25519", "description": "", "cborHex": "{cbor_hex}"}}"#
+            ),
+        )
+        .unwrap();
+
+        let bare = dir.path().join("payment.hex");
+        std::fs::write(&bare, format!("{cbor_hex}\n")).unwrap();
+
+        let from_envelope = key_address(&read_signing_key(&envelope).unwrap()).unwrap();
+        let from_bare = key_address(&read_signing_key(&bare).unwrap()).unwrap();
+
+        assert_eq!(from_envelope, from_bare);
+        assert!(from_envelope.starts_with("addr_test1"));
+
+        let short = dir.path().join("short.hex");
+        std::fs::write(&short, "4411223344").unwrap();
+        assert!(read_signing_key(&short).is_err());
+    }
+
+    #[test]
+    fn exported_keys_read_back_with_the_same_address() {
+        let dir = tempfile::tempdir().unwrap();
+        let mnemonic = parse_mnemonic(ZERO_MNEMONIC).unwrap();
+
+        for key in [
+            SigningKey::from_mnemonic(&mnemonic, 0),
+            SigningKey::Plain([0x11; 32]),
+        ] {
+            let path = dir.path().join("payment.skey");
+            std::fs::write(&path, key.to_envelope().unwrap()).unwrap();
+
+            let read = read_signing_key(&path).unwrap();
+            assert_eq!(key_address(&read).unwrap(), key_address(&key).unwrap());
+        }
+    }
+
+    #[test]
+    fn mnemonic_keys_export_as_bip32_envelopes() {
+        let key = SigningKey::from_mnemonic(&parse_mnemonic(ZERO_MNEMONIC).unwrap(), 0);
+
+        let envelope: serde_json::Value =
+            serde_json::from_str(&key.to_envelope().unwrap()).unwrap();
+        let cbor_hex = envelope["cborHex"].as_str().unwrap();
+
+        assert_eq!(
+            envelope["type"],
+            "PaymentExtendedSigningKeyShelley_ed25519_bip32"
+        );
+        assert!(cbor_hex.starts_with("5880"));
+        assert_eq!(cbor_hex.len(), 2 * (2 + EXTENDED_KEY_SIZE));
+    }
+
+    fn env_profile(env_file: &Path) -> ProfileConfig {
+        toml::from_str(&format!(
+            "network = \"cardano-local\"\nenv_file = {:?}\n\n\
+             [identities.team]\ntype = \"Mnemonic\"\nmnemonic_env = \"TRIX_TEST_TEAM_MNEMONIC\"\naccount_index = 1\n",
+            env_file.display().to_string()
+        ))
+        .unwrap()
+    }
+
+    fn team(profile: &ProfileConfig) -> &MnemonicIdentityConfig {
+        match profile.identities.get("team") {
+            Some(IdentityConfig::Mnemonic(ident)) => ident,
+            _ => panic!("team is not a mnemonic identity"),
+        }
+    }
+
+    #[test]
+    fn mnemonic_env_identities_parse() {
+        let profile = env_profile(Path::new(".env.test"));
+        let team = team(&profile);
+
+        assert_eq!(team.name, "team");
+        assert_eq!(team.mnemonic, None);
+        assert_eq!(
+            team.mnemonic_env.as_deref(),
+            Some("TRIX_TEST_TEAM_MNEMONIC")
+        );
+        assert_eq!(team.account_index, Some(1));
+    }
+
+    #[test]
+    fn mnemonic_env_is_read_from_the_profile_env_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let env_file = dir.path().join(".env.test");
+        let profile = env_profile(&env_file);
+
+        let err = identity_mnemonic(&profile, team(&profile))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`TRIX_TEST_TEAM_MNEMONIC`"), "{err}");
+
+        std::fs::write(
+            &env_file,
+            format!("TRIX_TEST_TEAM_MNEMONIC=\"{ZERO_MNEMONIC}\"\n"),
+        )
+        .unwrap();
+
+        let mnemonic = identity_mnemonic(&profile, team(&profile)).unwrap();
+        assert_eq!(mnemonic.to_string(), ZERO_MNEMONIC);
+    }
+
+    #[test]
+    fn account_index_selects_another_payment_key() {
+        let mnemonic = parse_mnemonic(ZERO_MNEMONIC).unwrap();
+
+        let first = SigningKey::from_mnemonic(&mnemonic, 0).key_hash();
+        let second = SigningKey::from_mnemonic(&mnemonic, 1).key_hash();
+
+        assert_ne!(first, second);
+        assert_eq!(SigningKey::from_mnemonic(&mnemonic, 1).key_hash(), second);
+    }
+
+    #[test]
+    fn address_only_identities_cant_sign() {
+        let wallet = WalletProxy {
+            target_dir: Default::default(),
+            addresses: Default::default(),
+            derivation: Derivation::Legacy,
+            address_only: HashSet::from(["treasury".to_string()]),
+        };
+
+        assert!(wallet.ensure_signers(&["alice"]).is_ok());
+
+        let err = wallet
+            .ensure_signers(&["alice", "treasury"])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`treasury`"), "{err}");
+    }
+}
@@ -0,0 +1,53 @@
+// Escrow held by a trusted arbiter: the buyer deposits the price with the
+// arbiter, who either releases it to the seller or refunds the buyer.
+
+party Buyer;
+
+party Seller;
+
+party Arbiter;
+
+tx deposit(
+    quantity: Int
+) {
+    input source {
+        from: Buyer,
+        min_amount: Ada(quantity) + fees,
+    }
+
+    output {
+        to: Arbiter,
+        amount: Ada(quantity),
+    }
+
+    output {
+        to: Buyer,
+        amount: source - Ada(quantity) - fees,
+    }
+}
+
+tx release(
+    deposit: UtxoRef
+) {
+    input escrowed {
+        ref: deposit,
+    }
+
+    output {
+        to: Seller,
+        amount: escrowed - fees,
+    }
+}
+
+tx refund(
+    deposit: UtxoRef
+) {
+    input escrowed {
+        ref: deposit,
+    }
+
+    output {
+        to: Buyer,
+        amount: escrowed - fees,
+    }
+}
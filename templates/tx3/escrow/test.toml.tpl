@@ -0,0 +1,30 @@
+file="./main.tx3"
+
+[[wallets]]
+name = "alice"
+balance = 100000000000
+
+[[wallets]]
+name = "bob"
+balance = 100000000000
+
+[[wallets]]
+name = "charlie"
+balance = 100000000000
+
+[[transactions]]
+description = "alice deposits 10 ada with charlie"
+template = "deposit"
+signers = ["alice"]
+args = { quantity = 10000000, buyer = "@alice", seller = "@bob", arbiter = "@charlie" }
+
+[[transactions]]
+description = "charlie releases the deposit to bob"
+template = "release"
+signers = ["charlie"]
+args = { deposit = "$tx[0].outputs[0].ref", buyer = "@alice", seller = "@bob", arbiter = "@charlie" }
+
+[[expect_balance]]
+wallet = "@bob"
+min = 9000000
+delta_from_initial = true
@@ -0,0 +1,48 @@
+// NFT minting: the issuer mints a single token under a minting policy and
+// keeps it, then can send it on like any other asset.
+
+party Issuer;
+
+party Holder;
+
+// TODO: replace with the hash of your minting policy.
+policy Collection = 0x00000000000000000000000000000000000000000000000000000000;
+
+// TODO: attach the minting policy script (e.g. from a reference input).
+tx mint_nft(
+    token_name: Bytes
+) {
+    input source {
+        from: Issuer,
+        min_amount: fees,
+    }
+
+    mint {
+        amount: AnyAsset(Collection, token_name, 1),
+        redeemer: (),
+    }
+
+    output {
+        to: Issuer,
+        amount: source + AnyAsset(Collection, token_name, 1) - fees,
+    }
+}
+
+tx send_nft(
+    token_name: Bytes
+) {
+    input source {
+        from: Issuer,
+        min_amount: Ada(2000000) + AnyAsset(Collection, token_name, 1) + fees,
+    }
+
+    output {
+        to: Holder,
+        amount: Ada(2000000) + AnyAsset(Collection, token_name, 1),
+    }
+
+    output {
+        to: Issuer,
+        amount: source - Ada(2000000) - AnyAsset(Collection, token_name, 1) - fees,
+    }
+}
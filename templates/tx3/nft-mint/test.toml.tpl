@@ -0,0 +1,29 @@
+file="./main.tx3"
+
+# Minting needs the policy script, so there is nothing to run yet. Once
+# main.tx3 points at your minting policy, uncomment the steps below.
+
+[[wallets]]
+name = "alice"
+balance = 100000000000
+
+[[wallets]]
+name = "bob"
+balance = 100000000000
+
+# [[transactions]]
+# description = "alice mints an nft"
+# template = "mint_nft"
+# signers = ["alice"]
+# args = { token_name = "6d792d6e6674", issuer = "@alice", holder = "@bob" }
+#
+# [[transactions]]
+# description = "alice sends the nft to bob"
+# template = "send_nft"
+# signers = ["alice"]
+# args = { token_name = "6d792d6e6674", issuer = "@alice", holder = "@bob" }
+#
+# [[expect_balance]]
+# wallet = "@bob"
+# min = 2000000
+# delta_from_initial = true
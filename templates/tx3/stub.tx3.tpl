@@ -0,0 +1,2 @@
+// Tx3 protocol for this project. Declare parties, types and transactions
+// here; see https://docs.txpipe.io/tx3 for the language reference.
@@ -0,0 +1,57 @@
+// Vesting: the owner locks funds at a validator that lets the beneficiary
+// claim them once `unlock_after` (a POSIX time in milliseconds) has passed.
+
+party Owner;
+
+party Beneficiary;
+
+// TODO: replace with the hash of your vesting validator.
+policy Vesting = 0x00000000000000000000000000000000000000000000000000000000;
+
+type VestingDatum {
+    unlock_after: Int,
+}
+
+tx lock(
+    quantity: Int,
+    unlock_after: Int
+) {
+    input source {
+        from: Owner,
+        min_amount: Ada(quantity) + fees,
+    }
+
+    output {
+        to: Vesting,
+        amount: Ada(quantity),
+        datum: VestingDatum {
+            unlock_after: unlock_after,
+        },
+    }
+
+    output {
+        to: Owner,
+        amount: source - Ada(quantity) - fees,
+    }
+}
+
+// TODO: attach the validator script (e.g. from a reference input) and the
+// validity range it checks against `unlock_after`.
+tx claim(
+    locked_utxo: UtxoRef
+) {
+    input gas {
+        from: Beneficiary,
+        min_amount: fees,
+    }
+
+    input locked {
+        ref: locked_utxo,
+        redeemer: (),
+    }
+
+    output {
+        to: Beneficiary,
+        amount: gas + locked - fees,
+    }
+}
@@ -0,0 +1,19 @@
+file="./main.tx3"
+
+# `claim` needs the vesting validator; add it here once main.tx3 points at
+# your script.
+
+[[wallets]]
+name = "alice"
+balance = 100000000000
+
+[[transactions]]
+description = "alice locks 10 ada for bob"
+template = "lock"
+signers = ["alice"]
+args = { quantity = 10000000, unlock_after = 1767225600000, owner = "@alice", beneficiary = "@bob" }
+
+[[expect_balance]]
+wallet = "@alice"
+max = -10000000
+delta_from_initial = true
@@ -1,4 +1,4 @@
-//! Interface-aware codegen (`src/commands/codegen.rs`), which delegates the
+//! Interface-aware codegen (`src/commands/codegen/`), which delegates the
 //! whole pipeline to the `tx3c` binary. These tests require a real `tx3c`
 //! (like `happy_path::codegen_generates_bindings_from_fixture`).
 
@@ -79,3 +79,36 @@ fn codegen_without_interface_still_uses_subdir() {
         "unified layout applies even with zero deps"
     );
 }
+
+/// A failing `[[codegen]]` job doesn't stop the others; the JSON summary
+/// records both and the exit code reflects the failure.
+#[test]
+fn codegen_json_summary_reports_failed_job() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    append_codegen_block(&ctx);
+
+    let mut trix_toml = ctx.read_file("trix.toml");
+    trix_toml.push_str(&format!(
+        "\n[[codegen]]\njob_id = \"broken\"\noutput_dir = \"gen-broken\"\nplugin = {{ repo = \"{}\", path = \"missing\" }}\n",
+        codegen_template_dir()
+    ));
+    ctx.write_file("trix.toml", &trix_toml);
+
+    let project_name = ctx.load_trix_config().protocol.name;
+
+    let result = ctx.run_trix(&["codegen", "--output", "json"]);
+    assert!(!result.success(), "a failed job should fail the run");
+
+    let summary: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("stdout should be the JSON summary");
+    let jobs = summary["jobs"].as_array().expect("jobs array");
+    assert_eq!(jobs.len(), 2);
+    assert_eq!(jobs[0]["status"], "ok");
+    assert_eq!(jobs[0]["written"], 1);
+    assert_eq!(jobs[1]["job_id"], "broken");
+    assert_eq!(jobs[1]["status"], "failed");
+
+    ctx.assert_file_exists(format!("gen/{project_name}/bindings.txt"));
+}
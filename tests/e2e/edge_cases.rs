@@ -40,3 +40,612 @@ fn init_preserves_existing_test_file() {
     ctx.assert_file_contains("tests/basic.toml", "# Custom test file");
     ctx.assert_file_contains("tests/basic.toml", "name = \"custom\"");
 }
+
+#[test]
+fn init_rerun_respects_hand_edits_and_replaces_untouched_templates() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    ctx.write_file(".gitignore", "# edited by hand\n");
+
+    let kept = ctx.run_trix(&["init", "--yes", "--overwrite-policy", "never"]);
+    assert_success(&kept);
+    ctx.assert_file_contains(".gitignore", "# edited by hand");
+
+    let replaced = ctx.run_trix(&["init", "--yes", "--overwrite-policy", "always"]);
+    assert_success(&replaced);
+    assert!(
+        !ctx.read_file(".gitignore").contains("# edited by hand"),
+        "always should replace the hand-edited template"
+    );
+}
+
+#[test]
+fn codegen_keeps_hand_edited_and_user_owned_files() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let fixture = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/e2e/fixtures/codegen-template/bindings.txt.hbs");
+    let template = std::fs::read_to_string(fixture).expect("fixture should be readable");
+    ctx.write_file("templates/my-lang/bindings.txt.hbs", &template);
+
+    let mut trix_toml = ctx.read_file("trix.toml");
+    trix_toml.push_str(
+        "\n[[codegen]]\noutput_dir = \"gen\"\nplugin = { local_path = \"./templates/my-lang\" }\n",
+    );
+    ctx.write_file("trix.toml", &trix_toml);
+
+    let project_name = ctx.load_trix_config().protocol.name;
+    let bindings = format!("gen/{project_name}/bindings.txt");
+
+    // Brand-new output: written and recorded.
+    assert_success(&ctx.run_trix(&["codegen"]));
+    ctx.assert_file_contains(&bindings, "Transactions:");
+
+    // Hand edit survives `never` and is reported as kept.
+    ctx.write_file(&bindings, "my scripts");
+    let kept = ctx.run_trix(&["codegen", "--overwrite-policy", "never"]);
+    assert_success(&kept);
+    assert_output_contains(&kept, "kept");
+    ctx.assert_file_contains(&bindings, "my scripts");
+
+    // `always` regenerates it.
+    assert_success(&ctx.run_trix(&["codegen", "--overwrite-policy", "always"]));
+    ctx.assert_file_contains(&bindings, "Transactions:");
+
+    // A file trix never generated needs --force, whatever the policy.
+    std::fs::remove_file(ctx.file_path("gen/.trix-generated.json")).unwrap();
+    ctx.write_file(&bindings, "user owned");
+    assert_success(&ctx.run_trix(&["codegen", "--overwrite-policy", "always"]));
+    ctx.assert_file_contains(&bindings, "user owned");
+
+    assert_success(&ctx.run_trix(&["codegen", "--force"]));
+    ctx.assert_file_contains(&bindings, "Transactions:");
+}
+
+#[cfg(unix)]
+#[test]
+fn cshell_call_is_killed_after_timeout() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    // `exec` so the killed child is the sleeper itself — no grandchild keeps
+    // the stdout pipe open past the timeout.
+    ctx.write_file("fake-bin/cshell", "#!/bin/sh\nexec sleep 30\n");
+    let fake = ctx.file_path("fake-bin/cshell");
+    std::fs::set_permissions(&fake, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let started = std::time::Instant::now();
+    let result = ctx.run_trix_with_env(
+        &["identities", "alice", "--cshell-timeout", "1"],
+        &[("TX3_CSHELL_PATH", fake.to_str().unwrap())],
+    );
+
+    assert!(!result.success(), "expected the slow cshell call to fail");
+    assert!(
+        result.stderr.contains("timed out after 1s"),
+        "missing timeout diagnostic in stderr:\n{}",
+        result.stderr
+    );
+    assert!(
+        result.stderr.contains("--mnemonic ***"),
+        "mnemonic should be redacted in stderr:\n{}",
+        result.stderr
+    );
+    assert!(started.elapsed() < std::time::Duration::from_secs(20));
+}
+
+#[test]
+fn test_interactive_is_rejected_without_a_terminal() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let result = ctx.run_trix(&["test", "--interactive", "tests/basic.toml"]);
+
+    assert!(!result.success(), "--interactive should fail outside a TTY");
+    assert!(
+        result.stderr.contains("needs an interactive terminal"),
+        "missing TTY diagnostic in stderr:\n{}",
+        result.stderr
+    );
+}
+
+#[test]
+fn shell_is_rejected_without_a_terminal() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let result = ctx.run_trix(&["shell"]);
+
+    assert!(!result.success(), "shell should fail outside a TTY");
+    assert!(
+        result
+            .stderr
+            .contains("trix shell needs an interactive terminal"),
+        "missing TTY diagnostic in stderr:\n{}",
+        result.stderr
+    );
+    assert!(
+        !ctx.file_path(".tx3/shell").exists(),
+        "shell should give up before setting anything up"
+    );
+}
+
+#[test]
+fn invoke_unknown_preset_suggests_close_match() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let mut config = ctx.read_file("trix.toml");
+    config.push_str("\n[invoke.presets.small-bid]\ntemplate = \"place_bid\"\nargs = { quantity = 1 }\n");
+    ctx.write_file("trix.toml", &config);
+
+    let result = ctx.run_trix(&["invoke", "--preset", "smal-bid"]);
+
+    assert!(!result.success(), "unknown preset should fail");
+    assert!(
+        result.stderr.contains("small-bid"),
+        "expected a suggestion, got: {}",
+        result.stderr
+    );
+}
+
+#[test]
+fn devnet_stop_without_background_devnet_warns() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let result = ctx.run_trix(&["devnet", "stop"]);
+
+    assert_success(&result);
+    assert!(
+        result.stderr.contains("no background devnet is running"),
+        "missing diagnostic in stderr:\n{}",
+        result.stderr
+    );
+}
+
+/// Appends `extra` to a freshly-initialized trix.toml and runs
+/// `trix config-validate`, returning its stderr.
+fn validate_with(ctx: &TestContext, extra: &str) -> String {
+    let mut config = ctx.read_file("trix.toml");
+    config.push_str(extra);
+    ctx.write_file("trix.toml", &config);
+
+    let result = ctx.run_trix(&["config-validate"]);
+    assert!(!result.success(), "validation should fail for:\n{extra}");
+
+    result.stderr
+}
+
+#[test]
+fn config_validate_passes_on_fresh_project() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let result = ctx.run_trix(&["config-validate"]);
+
+    assert_success(&result);
+    assert_output_contains(&result, "is valid");
+}
+
+#[test]
+fn config_validate_warns_about_literal_api_keys() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let result = ctx.run_trix(&["config-validate"]);
+    assert!(
+        !result.stderr.contains("looks like a secret"),
+        "stderr:\n{}",
+        result.stderr
+    );
+
+    let mut config = ctx.read_file("trix.toml");
+    config.push_str(
+        r#"
+[networks.staging]
+is_testnet = true
+trp = { url = "https://trp.example.com", headers = { "dmtr-api-key" = "dmtr_utxorpc1x7k2m9q4w8e5r3t6y1u0" } }
+u5c = { url = "https://u5c.example.com" }
+"#,
+    );
+    ctx.write_file("trix.toml", &config);
+
+    let result = ctx.run_trix(&["config-validate"]);
+    assert_success(&result);
+    assert!(
+        result
+            .stderr
+            .contains("networks.staging.trp.headers.dmtr-api-key"),
+        "stderr:\n{}",
+        result.stderr
+    );
+
+    let result = ctx.run_trix(&["config-validate", "--secrets-scan=off"]);
+    assert_success(&result);
+    assert!(!result.stderr.contains("looks like a secret"));
+}
+
+#[test]
+fn config_validate_reports_missing_main() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+    std::fs::remove_file(ctx.file_path("main.tx3")).unwrap();
+
+    let result = ctx.run_trix(&["config-validate"]);
+
+    assert!(!result.success(), "missing main should fail");
+    assert!(
+        result.stderr.contains("protocol.main"),
+        "stderr:\n{}",
+        result.stderr
+    );
+}
+
+#[test]
+fn config_validate_reports_unknown_network_and_missing_parent() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let stderr = validate_with(
+        &ctx,
+        "\n[profiles.staging]\nnetwork = \"nowhere\"\n\n[profiles.qa]\nextends = \"ghost\"\n",
+    );
+
+    assert!(
+        stderr.contains("network `nowhere` is not defined"),
+        "stderr:\n{stderr}"
+    );
+    assert!(stderr.contains("extends `ghost`"), "stderr:\n{stderr}");
+    assert!(stderr.contains("2 problem(s)"), "stderr:\n{stderr}");
+}
+
+#[test]
+fn config_validate_reports_malformed_endpoint_urls() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let stderr = validate_with(
+        &ctx,
+        r#"
+[networks.staging]
+is_testnet = true
+trp = { url = "not a url", headers = {} }
+u5c = { url = "ftp://staging.example.com" }
+"#,
+    );
+
+    assert!(
+        stderr.contains("networks.staging.trp.url"),
+        "stderr:\n{stderr}"
+    );
+    assert!(
+        stderr.contains("unsupported scheme `ftp`"),
+        "stderr:\n{stderr}"
+    );
+}
+
+#[test]
+fn config_validate_reports_missing_env_file_and_key() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let stderr = validate_with(
+        &ctx,
+        r#"
+[profiles.preview]
+network = "cardano-preview"
+env_file = ".env.missing"
+
+[profiles.preview.identities.ops]
+type = "ExplicitKey"
+key_path = "keys/ops.skey"
+"#,
+    );
+
+    assert!(
+        stderr.contains("profiles.preview.env_file"),
+        "stderr:\n{stderr}"
+    );
+    assert!(
+        stderr.contains("profiles.preview.identities.ops.key_path"),
+        "stderr:\n{stderr}"
+    );
+}
+
+#[test]
+fn config_validate_reports_missing_codegen_templates() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let stderr = validate_with(
+        &ctx,
+        r#"
+[[codegen]]
+job_id = "sdk"
+plugin = { local_path = "./templates/missing" }
+verify_ignore = ["node_modules/[**"]
+"#,
+    );
+
+    assert!(stderr.contains("codegen[0].plugin"), "stderr:\n{stderr}");
+    assert!(stderr.contains("invalid glob"), "stderr:\n{stderr}");
+}
+
+#[test]
+fn devnet_status_without_devnet_reports_not_running() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let result = ctx.run_trix(&["devnet", "status"]);
+    assert_success(&result);
+    assert_output_contains(&result, "no devnet running");
+
+    let result = ctx.run_trix(&["devnet", "status", "--output", "json"]);
+    assert_success(&result);
+
+    let status: serde_json::Value = serde_json::from_str(&result.stdout).unwrap();
+    assert_eq!(status["running"], false);
+}
+
+#[test]
+fn devnet_reset_without_state_and_unconfirmed_all() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let result = ctx.run_trix(&["devnet", "reset"]);
+    assert_success(&result);
+    assert_output_contains(&result, "no devnet state to reset");
+
+    let stale = ctx.path().join(".tx3/dolos/stale");
+    std::fs::create_dir_all(&stale).unwrap();
+
+    // Not a terminal, so --all won't delete without --yes.
+    let result = ctx.run_trix(&["devnet", "reset", "--all"]);
+    assert!(!result.success(), "--all without --yes should refuse");
+    assert!(
+        result.stderr.contains("without confirmation"),
+        "stderr: {}",
+        result.stderr
+    );
+    assert!(stale.exists());
+}
+
+#[test]
+fn wallet_import_rejects_short_mnemonic() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let twelve = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    let result = ctx.run_trix_with_stdin(&["wallet", "import", "treasury"], twelve);
+
+    assert!(!result.success(), "a 12-word phrase should be rejected");
+    assert!(
+        result.stderr.contains("expected 24"),
+        "stderr:\n{}",
+        result.stderr
+    );
+    assert!(!ctx.read_file("trix.toml").contains("treasury"));
+}
+
+#[test]
+fn wallet_import_refuses_a_phrase_on_the_command_line() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art";
+    let result = ctx.run_trix(&["wallet", "import", "treasury", "--mnemonic", phrase]);
+
+    assert!(!result.success(), "a literal --mnemonic should be rejected");
+    assert!(
+        result.stderr.contains("`env:` or `keyring:` reference"),
+        "stderr:\n{}",
+        result.stderr
+    );
+    assert!(!ctx.read_file("trix.toml").contains("treasury"));
+}
+
+#[test]
+fn wallet_export_refuses_to_overwrite_without_force() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+    ctx.write_file("alice.skey", "keep me");
+
+    let result = ctx.run_trix(&["wallet", "export", "alice", "--out", "alice.skey"]);
+
+    assert!(!result.success(), "export should not replace the file");
+    assert!(
+        result.stderr.contains("already exists"),
+        "stderr:\n{}",
+        result.stderr
+    );
+    assert_eq!(ctx.read_file("alice.skey"), "keep me");
+
+    let forced = ctx.run_trix(&[
+        "wallet",
+        "export",
+        "alice",
+        "--out",
+        "alice.skey",
+        "--force",
+    ]);
+    assert_success(&forced);
+    assert!(ctx.read_file("alice.skey").contains("cborHex"));
+}
+
+#[test]
+fn devnet_simulate_refuses_public_network() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let result = ctx.run_trix(&["devnet", "simulate", "--profile", "preview"]);
+
+    assert!(!result.success(), "simulate must not target preview");
+    assert!(
+        result.stderr.contains("only targets a local devnet"),
+        "stderr:\n{}",
+        result.stderr
+    );
+}
+
+#[test]
+fn wallet_fund_refuses_public_network() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let result = ctx.run_trix(&[
+        "wallet",
+        "fund",
+        "--to",
+        "@bob",
+        "--amount",
+        "1000000",
+        "--profile",
+        "preview",
+    ]);
+
+    assert!(!result.success(), "funding must refuse a public network");
+    assert!(
+        result
+            .stderr
+            .contains("only targets the `cardano-local` network"),
+        "stderr:\n{}",
+        result.stderr
+    );
+}
+
+#[test]
+fn devnet_manifest_refuses_secrets_off_testnet() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let result = ctx.run_trix(&[
+        "devnet",
+        "manifest",
+        "--include-secrets",
+        "--profile",
+        "mainnet",
+    ]);
+
+    assert!(!result.success(), "mainnet mnemonics must not be printed");
+    assert!(
+        result.stderr.contains("not on a testnet"),
+        "stderr:\n{}",
+        result.stderr
+    );
+}
+
+#[test]
+fn mnemonic_env_identity_names_the_missing_variable() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let mut trix_toml = ctx.read_file("trix.toml");
+    trix_toml.push_str(
+        "\n[profiles.local]\nnetwork = \"cardano-local\"\n\n\
+         [profiles.local.identities.team]\ntype = \"Mnemonic\"\nmnemonic_env = \"TRIX_E2E_UNSET_MNEMONIC\"\n",
+    );
+    ctx.write_file("trix.toml", &trix_toml);
+
+    let result = ctx.run_trix(&["identities", "team", "address-testnet"]);
+
+    assert!(!result.success(), "a missing mnemonic variable must fail");
+    assert!(
+        result.stderr.contains("TRIX_E2E_UNSET_MNEMONIC"),
+        "stderr:\n{}",
+        result.stderr
+    );
+}
+
+#[test]
+fn build_check_budgets_rejects_unknown_templates() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let mut trix_toml = ctx.read_file("trix.toml");
+    trix_toml.push_str("\n[budgets.mint]\nmax_fee = 500000\n");
+    ctx.write_file("trix.toml", &trix_toml);
+
+    let result = ctx.run_trix(&["build", "--check-budgets"]);
+
+    assert!(
+        !result.success(),
+        "a budget for a missing template must fail"
+    );
+    assert!(
+        result
+            .stderr
+            .contains("`[budgets.mint]` names no transaction template"),
+        "stderr:\n{}",
+        result.stderr
+    );
+}
+
+#[test]
+fn publish_dry_run_fails_without_main_file() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let config = ctx.read_file("trix.toml").replacen(
+        "[protocol]\n",
+        "[protocol]\nscope = \"acme\"\nrepository = \"https://github.com/acme/widget\"\n",
+        1,
+    );
+    ctx.write_file("trix.toml", &config);
+    std::fs::remove_file(ctx.file_path("main.tx3")).unwrap();
+
+    let result = ctx.run_trix(&["publish", "--dry-run"]);
+
+    assert!(
+        !result.success(),
+        "a missing main file must fail validation"
+    );
+    assert!(
+        result
+            .stderr
+            .contains("`[protocol].main` file 'main.tx3' not found"),
+        "stderr:\n{}",
+        result.stderr
+    );
+}
+
+#[test]
+fn legacy_trix_toml_points_at_migrate() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let config = ctx
+        .read_file("trix.toml")
+        .replace("config_schema_version = 1\n", "config_schema_version = 0\n");
+    ctx.write_file("trix.toml", &config);
+
+    let result = ctx.run_trix(&["build"]);
+
+    assert!(!result.success(), "an un-migrated project must not build");
+    assert!(
+        result.stderr.contains("pending migrations") && result.stderr.contains("trix migrate"),
+        "stderr:\n{}",
+        result.stderr
+    );
+}
+
+#[test]
+fn init_unknown_template_lists_the_built_in_ones() {
+    let ctx = TestContext::new();
+
+    let result = ctx.run_trix(&["init", "--yes", "--template", "auction"]);
+
+    assert!(!result.success(), "an unknown template must be rejected");
+    assert!(
+        result.stderr.contains("escrow")
+            && result.stderr.contains("vesting")
+            && result.stderr.contains("nft-mint"),
+        "stderr:\n{}",
+        result.stderr
+    );
+    assert!(!ctx.file_path("trix.toml").exists());
+}
@@ -96,6 +96,135 @@ fn check_validates_valid_project() {
     assert_output_contains(&result, "check passed, no errors found");
 }
 
+#[test]
+fn check_and_build_multi_file_protocol() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    // Move the party declarations out of main.tx3 into a second file.
+    let main = ctx.read_file("main.tx3");
+    let body = main
+        .replace("party Sender;", "")
+        .replace("party Receiver;", "");
+    ctx.write_file("main.tx3", &body);
+    ctx.write_file("lib/parties.tx3", "party Sender;\n\nparty Receiver;\n");
+
+    let result = ctx.run_trix(&["check"]);
+    assert!(!result.success(), "main.tx3 alone should not check");
+
+    let config = ctx
+        .read_file("trix.toml")
+        .replacen("[protocol]\n", "[protocol]\nsources = [\"lib/*.tx3\"]\n", 1);
+    ctx.write_file("trix.toml", &config);
+
+    let result = ctx.run_trix(&["check"]);
+    assert_success(&result);
+    assert_output_contains(&result, "check passed, no errors found");
+
+    assert_success(&ctx.run_trix(&["build"]));
+}
+
+#[test]
+fn publish_dry_run_lists_layers_without_uploading() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let config = ctx.read_file("trix.toml").replacen(
+        "[protocol]\n",
+        "[protocol]\nscope = \"acme\"\nrepository = \"https://github.com/acme/widget\"\n",
+        1,
+    );
+    ctx.write_file("trix.toml", &config);
+
+    let result = ctx.run_trix(&["publish", "--dry-run"]);
+
+    assert_success(&result);
+    assert_output_contains(&result, "Would publish acme/");
+    assert_output_contains(&result, "Repository: https://github.com/acme/widget");
+    assert_output_contains(&result, "Dry run, nothing was uploaded.");
+
+    assert_success(&ctx.run_trix(&["check", "--publish"]));
+}
+
+#[test]
+fn init_tx_scaffolds_transactions_from_blueprint() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let blueprint =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("examples/aiken/onchain/plutus.json");
+
+    let result = ctx.run_trix(&[
+        "init",
+        "tx",
+        "--from-blueprint",
+        blueprint.to_str().unwrap(),
+        "--validator",
+        "hello_world",
+        "--out",
+        "lib/hello.tx3",
+    ]);
+
+    assert_success(&result);
+    assert_output_contains(&result, "Added `lock_hello_world` and `spend_hello_world`");
+
+    let source = ctx.read_file("lib/hello.tx3");
+    assert!(source.contains("tx lock_hello_world("));
+    assert!(source.contains("tx spend_hello_world("));
+    assert!(ctx.read_file("trix.toml").contains("lib/hello.tx3"));
+
+    assert_success(&ctx.run_trix(&["check"]));
+}
+
+#[test]
+fn inspect_ast_shows_parsed_declarations() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    // Without --file, the protocol `init` generated is parsed.
+    let generated = ctx.run_trix(&["inspect", "ast"]);
+    assert_success(&generated);
+
+    for name in ["Sender", "Receiver", "transfer", "quantity"] {
+        assert_output_contains(&generated, &format!("\"{name}\""));
+    }
+
+    ctx.write_file(
+        "minimal.tx3",
+        r#"party Payer;
+
+tx pay(amount: Int) {
+    input source {
+        from: Payer,
+        min_amount: Ada(amount),
+    }
+}
+"#,
+    );
+
+    let pretty = ctx.run_trix(&["inspect", "ast", "--file", "minimal.tx3"]);
+    assert_success(&pretty);
+
+    for name in ["Payer", "pay", "amount", "source"] {
+        assert_output_contains(&pretty, &format!("\"{name}\""));
+    }
+
+    let json = ctx.run_trix(&[
+        "inspect",
+        "ast",
+        "--file",
+        "minimal.tx3",
+        "--format",
+        "json",
+    ]);
+    assert_success(&json);
+    assert_eq!(json.stdout.trim().lines().count(), 1, "{}", json.stdout);
+
+    let ast: serde_json::Value =
+        serde_json::from_str(json.stdout.trim()).expect("ast should be JSON");
+    assert!(ast.to_string().contains("\"Payer\""), "{ast}");
+}
+
 #[test]
 fn devnet_starts_and_cshell_connects() {
     let ctx = TestContext::new();
@@ -139,54 +268,1426 @@ fn devnet_starts_and_cshell_connects() {
         test_result.err()
     );
 
-    // Cleanup: kill dolos process
-    let _ = std::process::Command::new("pkill")
-        .args(["-f", "dolos"])
-        .output();
+    let stop = ctx.run_trix(&["devnet", "stop"]);
+    assert_success(&stop);
+    assert_output_contains(&stop, "stopped devnet");
 }
 
 #[test]
-fn codegen_generates_bindings_from_fixture() {
+fn devnet_seeds_reference_script_utxo() {
     let ctx = TestContext::new();
 
     let init_result = ctx.run_trix(&["init", "--yes"]);
     assert_success(&init_result);
 
-    let tx3c_path = ctx
-        .tx3c_path()
-        .expect("tx3c should be available in PATH or TX3_TX3C_PATH");
-    assert!(tx3c_path.is_file(), "tx3c path should exist");
+    ctx.write_file(
+        "scripts/always.plutus",
+        r#"{"type": "PlutusScriptV2", "description": "", "cborHex": "4e4d01000033222220051200120011"}"#,
+    );
 
-    let fixture_dir =
-        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/e2e/fixtures/codegen-template");
-    let fixture_dir = fixture_dir
-        .to_str()
-        .expect("fixture path should be valid UTF-8");
+    let mut devnet_toml = ctx.read_file("devnet.toml");
+    devnet_toml.push_str(
+        "\n[[utxos]]\naddress = \"@alice\"\nvalue = 7654321\nscript_ref = \"scripts/always.plutus\"\n",
+    );
+    ctx.write_file("devnet.toml", &devnet_toml);
 
-    let mut trix_toml = ctx.read_file("trix.toml");
-    trix_toml.push_str(&format!(
-        "\n[[codegen]]\noutput_dir = \"gen\"\nplugin = {{ repo = \"{}\", path = \".\" }}\n",
-        fixture_dir
-    ));
-    ctx.write_file("trix.toml", &trix_toml);
+    let ports = DevnetPorts::slot(1);
+    ctx.set_devnet_ports(ports);
 
-    let project_name = ctx.load_trix_config().protocol.name;
+    let result = ctx.run_trix(&["devnet", "--background"]);
+    assert_success(&result);
+    assert!(
+        wait_for_port(ports.grpc, 30),
+        "devnet gRPC port should open"
+    );
 
-    let result = ctx.run_trix(&["codegen"]);
+    let original_dir = std::env::current_dir().expect("should get current dir");
+    std::env::set_current_dir(ctx.path()).expect("should change to temp dir");
+
+    let config = ctx.load_trix_config();
+    let profile = config
+        .resolve_profile("local")
+        .expect("should resolve local profile");
+    let wallet = trix::wallet::setup(&config, &profile).expect("should setup cshell environment");
+
+    std::env::set_current_dir(original_dir).expect("should restore original dir");
+
+    let utxos = trix::spawn::cshell::wallet_utxos(&wallet.target_dir, "alice", "trix-local")
+        .expect("cshell should list alice's utxos");
+
+    assert!(
+        utxos.iter().any(|utxo| utxo.coin == "7654321"),
+        "seeded reference script utxo should be listed: {utxos:?}"
+    );
+
+    let stop = ctx.run_trix(&["devnet", "stop"]);
+    assert_success(&stop);
+}
+
+#[test]
+fn wallet_balance_and_utxos_query_the_local_devnet() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let mut devnet_toml = ctx.read_file("devnet.toml");
+    devnet_toml.push_str("\n[[utxos]]\naddress = \"@alice\"\nvalue = 7654321\n");
+    ctx.write_file("devnet.toml", &devnet_toml);
+
+    let ports = DevnetPorts::slot(2);
+    ctx.set_devnet_ports(ports);
+
+    assert_success(&ctx.run_trix(&["devnet", "--background"]));
+    assert!(
+        wait_for_port(ports.grpc, 30),
+        "devnet gRPC port should open"
+    );
+
+    let utxos = ctx.run_trix(&["wallet", "utxos", "@alice"]);
+    assert_success(&utxos);
+    assert_output_contains(&utxos, "LOVELACE");
+    assert_output_contains(&utxos, "7654321");
+
+    let utxos = ctx.run_trix(&["wallet", "utxos", "alice", "--output", "json"]);
+    assert_success(&utxos);
+    let json: serde_json::Value = serde_json::from_str(&utxos.stdout).unwrap();
+    assert!(
+        json.as_array()
+            .unwrap()
+            .iter()
+            .any(|utxo| utxo["lovelace"] == "7654321" && utxo["ref"].is_string()),
+        "{json}"
+    );
+
+    let balance = ctx.run_trix(&["wallet", "balance", "alice", "--output", "json"]);
+    assert_success(&balance);
+    let json: serde_json::Value = serde_json::from_str(&balance.stdout).unwrap();
+    assert_eq!(json["wallet"], "alice");
+    assert!(json["lovelace"].as_u64().unwrap() >= 7654321, "{json}");
+
+    let unknown = ctx.run_trix(&["wallet", "balance", "carol"]);
+    assert!(!unknown.success());
+    assert!(
+        unknown.stderr.contains("no identity named `carol`")
+            && unknown.stderr.contains("alice"),
+        "{}",
+        unknown.stderr
+    );
+
+    assert_success(&ctx.run_trix(&["devnet", "stop"]));
+}
+
+#[test]
+fn devnet_fund_adds_a_wallet_utxo() {
+    let ctx = TestContext::new();
+
+    let init_result = ctx.run_trix(&["init", "--yes"]);
+    assert_success(&init_result);
+
+    let not_running = ctx.run_trix(&["devnet", "fund", "alice", "123456789"]);
+    assert!(!not_running.success(), "fund should fail without a devnet");
+    assert!(
+        not_running
+            .stderr
+            .contains("no background devnet is running"),
+        "stderr: {}",
+        not_running.stderr
+    );
+
+    let ports = DevnetPorts::slot(3);
+    ctx.set_devnet_ports(ports);
+
+    let result = ctx.run_trix(&["devnet", "--background"]);
     assert_success(&result);
+    assert!(
+        wait_for_port(ports.grpc, 30),
+        "devnet gRPC port should open"
+    );
 
-    // Output nests under the project's own subdir (the deliberate layout of
-    // the tx3c-delegating codegen, see `codegen_deps`); nothing is written
-    // flat at `gen/bindings.txt`.
-    let bindings = format!("gen/{project_name}/bindings.txt");
-    ctx.assert_file_exists(&bindings);
-    ctx.assert_file_contains(&bindings, "Protocol:");
-    ctx.assert_file_contains(&bindings, "Transactions:");
-    ctx.assert_file_contains(&bindings, "transfer");
-    ctx.assert_file_contains(&bindings, "Profiles:");
-    ctx.assert_file_contains(&bindings, "local");
+    let unconfirmed = ctx.run_trix(&["devnet", "fund", "@alice", "123456789"]);
+    assert!(!unconfirmed.success(), "restarting should need --yes");
     assert!(
-        !ctx.file_path("gen/bindings.txt").exists(),
-        "unified layout: nothing should be written flat at gen/bindings.txt"
+        unconfirmed.stderr.contains("pass --yes"),
+        "stderr: {}",
+        unconfirmed.stderr
+    );
+
+    let fund = ctx.run_trix(&["devnet", "fund", "@alice", "123456789", "--yes"]);
+    assert_success(&fund);
+    assert_output_contains(&fund, "funded @alice with 123456789 lovelace");
+    assert!(
+        wait_for_port(ports.grpc, 30),
+        "restarted devnet gRPC port should open"
+    );
+
+    let original_dir = std::env::current_dir().expect("should get current dir");
+    std::env::set_current_dir(ctx.path()).expect("should change to temp dir");
+
+    let config = ctx.load_trix_config();
+    let profile = config
+        .resolve_profile("local")
+        .expect("should resolve local profile");
+    let wallet = trix::wallet::setup(&config, &profile).expect("should setup cshell environment");
+
+    std::env::set_current_dir(original_dir).expect("should restore original dir");
+
+    let utxos = trix::spawn::cshell::wallet_utxos(&wallet.target_dir, "alice", "trix-local")
+        .expect("cshell should list alice's utxos");
+
+    assert!(
+        utxos.iter().any(|utxo| utxo.coin == "123456789"),
+        "funded utxo should be listed: {utxos:?}"
+    );
+
+    let stop = ctx.run_trix(&["devnet", "stop"]);
+    assert_success(&stop);
+}
+
+#[test]
+fn wallet_fund_sends_lovelace_to_a_fresh_wallet() {
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art";
+
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+    assert_success(&ctx.run_trix_with_stdin(&["wallet", "import", "carol"], MNEMONIC));
+
+    let ports = DevnetPorts::slot(4);
+    ctx.set_devnet_ports(ports);
+
+    assert_success(&ctx.run_trix(&["devnet", "--background"]));
+    assert!(
+        wait_for_port(ports.grpc, 30),
+        "devnet gRPC port should open"
+    );
+
+    let fund = ctx.run_trix(&["wallet", "fund", "--to", "@carol", "--amount", "4000000"]);
+    assert_success(&fund);
+    assert_output_contains(&fund, "from @alice");
+    assert_output_contains(&fund, "tx: ");
+
+    let balance = ctx.run_trix(&["wallet", "balance", "carol", "--output", "json"]);
+    assert_success(&balance);
+    let json: serde_json::Value = serde_json::from_str(&balance.stdout).unwrap();
+    assert_eq!(json["lovelace"].as_u64(), Some(4000000), "{json}");
+
+    assert_success(&ctx.run_trix(&["devnet", "stop"]));
+}
+
+#[test]
+fn build_check_budgets_reports_templates_over_budget() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let ports = DevnetPorts::slot(5);
+    ctx.set_devnet_ports(ports);
+
+    assert_success(&ctx.run_trix(&["devnet", "--background"]));
+    assert!(
+        wait_for_port(ports.grpc, 30),
+        "devnet gRPC port should open"
+    );
+
+    let trix_toml = ctx.read_file("trix.toml");
+
+    ctx.write_file(
+        "trix.toml",
+        &format!("{trix_toml}\n[budgets.transfer]\nmax_fee = 10000000\n"),
+    );
+    let within = ctx.run_trix(&["build", "--check-budgets"]);
+    assert_success(&within);
+    assert_output_contains(&within, "transfer: within budget (fee ");
+
+    ctx.write_file(
+        "trix.toml",
+        &format!("{trix_toml}\n[budgets.transfer]\nmax_fee = 1\n"),
+    );
+    let over = ctx.run_trix(&["build", "--check-budgets"]);
+
+    assert_success(&ctx.run_trix(&["devnet", "stop"]));
+
+    assert!(!over.success(), "a fee over budget must fail the build");
+    assert!(
+        over.stderr.contains("`transfer` fee: measured ") && over.stderr.contains("allowed 1"),
+        "stderr:\n{}",
+        over.stderr
+    );
+}
+
+#[test]
+fn devnet_simulate_submits_transfers_at_the_target_rate() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let ports = DevnetPorts::slot(6);
+    ctx.set_devnet_ports(ports);
+
+    assert_success(&ctx.run_trix(&["devnet", "--background"]));
+    assert!(wait_for_port(ports.trp, 30), "TRP port should open");
+
+    let result = ctx.run_trix(&[
+        "devnet",
+        "simulate",
+        "--tps",
+        "0.5",
+        "--duration",
+        "8s",
+        "--report-every",
+        "2",
+        "--wallets",
+        "alice,bob,charlie",
+        "--seed",
+        "42",
+    ]);
+
+    assert_success(&ctx.run_trix(&["devnet", "stop"]));
+    assert_success(&result);
+
+    assert_output_contains(&result, "simulating `transfer` at 0.5 tps");
+    assert_output_contains(&result, "== Summary ==");
+    assert!(
+        !result.stdout.contains("submitted: 0 "),
+        "at least one transfer should go through:\n{}",
+        result.stdout
+    );
+}
+
+#[test]
+fn devnet_snapshot_restore_preserves_utxos() {
+    let ctx = TestContext::new();
+
+    let init_result = ctx.run_trix(&["init", "--yes"]);
+    assert_success(&init_result);
+
+    let ports = DevnetPorts::slot(7);
+    ctx.set_devnet_ports(ports);
+
+    let result = ctx.run_trix(&["devnet", "--background"]);
+    assert_success(&result);
+    assert!(
+        wait_for_port(ports.grpc, 30),
+        "devnet gRPC port should open"
+    );
+
+    let before = ctx.run_trix(&["devnet", "dump-utxos", "--out", "before.ndjson"]);
+    assert_success(&before);
+
+    let snapshot = ctx.run_trix(&["devnet", "snapshot", "seeded"]);
+    assert_success(&snapshot);
+    assert_output_contains(&snapshot, "saved snapshot `seeded`");
+    ctx.assert_file_exists(".tx3/snapshots/seeded.tar.gz");
+
+    let stop = ctx.run_trix(&["devnet", "stop"]);
+    assert_success(&stop);
+
+    let restore = ctx.run_trix(&["devnet", "restore", "seeded", "--background"]);
+    assert_success(&restore);
+    assert_output_contains(&restore, "restored snapshot `seeded`");
+    assert!(
+        wait_for_port(ports.grpc, 30),
+        "restored devnet gRPC port should open"
+    );
+
+    let after = ctx.run_trix(&["devnet", "dump-utxos", "--out", "after.ndjson"]);
+    assert_success(&after);
+
+    assert_eq!(
+        ctx.read_file("before.ndjson"),
+        ctx.read_file("after.ndjson")
+    );
+
+    let stop = ctx.run_trix(&["devnet", "stop"]);
+    assert_success(&stop);
+}
+
+#[test]
+fn devnet_status_reports_background_devnet() {
+    let ctx = TestContext::new();
+
+    let init_result = ctx.run_trix(&["init", "--yes"]);
+    assert_success(&init_result);
+
+    let ports = DevnetPorts::slot(8);
+    ctx.set_devnet_ports(ports);
+
+    let result = ctx.run_trix(&["devnet", "--background"]);
+    assert_success(&result);
+    assert!(wait_for_port(ports.trp, 30), "devnet TRP port should open");
+
+    let status = ctx.run_trix(&["devnet", "status", "--output", "json"]);
+    assert_success(&status);
+
+    let status: serde_json::Value = serde_json::from_str(&status.stdout).unwrap();
+    assert_eq!(status["running"], true);
+    let pid = status["pid"]
+        .as_u64()
+        .expect("running status should carry a pid");
+    assert!(is_process_running(pid as u32), "pid {pid} should be alive");
+
+    let trp = status["ports"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|p| p["service"] == "TRP")
+        .expect("TRP port should be listed");
+    assert_eq!(trp["port"], ports.trp);
+    assert_eq!(trp["listening"], true);
+
+    let stop = ctx.run_trix(&["devnet", "stop"]);
+    assert_success(&stop);
+    assert!(
+        !is_process_running(pid as u32),
+        "pid {pid} should be gone after stop"
+    );
+
+    let status = ctx.run_trix(&["devnet", "status", "--output", "json"]);
+    assert_success(&status);
+    let status: serde_json::Value = serde_json::from_str(&status.stdout).unwrap();
+    assert_eq!(status["running"], false);
+}
+
+#[test]
+fn second_devnet_for_the_same_home_is_refused() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let ports = DevnetPorts::slot(9);
+    ctx.set_devnet_ports(ports);
+
+    assert_success(&ctx.run_trix(&["devnet", "--background"]));
+    assert!(wait_for_port(ports.trp, 30), "devnet TRP port should open");
+
+    let status = ctx.run_trix(&["devnet", "status", "--output", "json"]);
+    let status: serde_json::Value = serde_json::from_str(&status.stdout).unwrap();
+    let pid = status["pid"]
+        .as_u64()
+        .expect("running status should carry a pid");
+
+    let second = ctx.run_trix(&["devnet", "--background"]);
+    assert!(!second.success(), "a second devnet should not start");
+    assert!(
+        second.stderr.contains("already running") && second.stderr.contains(&format!("pid {pid}")),
+        "stderr:\n{}",
+        second.stderr
+    );
+
+    assert_success(&ctx.run_trix(&["devnet", "stop"]));
+
+    // The stopped devnet's lock doesn't keep the next one out.
+    assert_success(&ctx.run_trix(&["devnet", "--background"]));
+    assert_success(&ctx.run_trix(&["devnet", "stop"]));
+}
+
+#[test]
+fn devnets_with_distinct_ports_run_side_by_side() {
+    let projects = [(18164, 15164, 13164, 1111111), (28164, 25164, 23164, 2222222)].map(
+        |(trp, grpc, minibf, coin)| {
+            let ctx = TestContext::new();
+            assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+            let mut devnet = ctx.read_file("devnet.toml");
+            devnet.push_str(&format!(
+                "\n[[utxos]]\naddress = \"@alice\"\nvalue = {coin}\n\n[ports]\ntrp = {trp}\ngrpc = {grpc}\nminibf = {minibf}\n"
+            ));
+            ctx.write_file("devnet.toml", &devnet);
+
+            assert_success(&ctx.run_trix(&["devnet", "--background"]));
+
+            (ctx, trp, grpc, coin)
+        },
     );
+
+    for (ctx, trp, grpc, coin) in &projects {
+        assert!(wait_for_port(*trp, 30), "TRP port {trp} should open");
+        assert!(wait_for_port(*grpc, 30), "u5c port {grpc} should open");
+
+        // cshell's provider follows devnet.toml, so each project sees the
+        // UTxO seeded into its own devnet.
+        let original_dir = std::env::current_dir().expect("should get current dir");
+        std::env::set_current_dir(ctx.path()).expect("should change to temp dir");
+
+        let config = ctx.load_trix_config();
+        let profile = config
+            .resolve_profile("local")
+            .expect("should resolve local profile");
+        let wallet =
+            trix::wallet::setup(&config, &profile).expect("should setup cshell environment");
+
+        std::env::set_current_dir(original_dir).expect("should restore original dir");
+
+        let utxos = trix::spawn::cshell::wallet_utxos(&wallet.target_dir, "alice", "trix-local")
+            .expect("cshell should list alice's utxos");
+
+        assert!(
+            utxos.iter().any(|utxo| utxo.coin == coin.to_string()),
+            "project on TRP port {trp} should see its own seeded utxo: {utxos:?}"
+        );
+    }
+
+    for (ctx, ..) in &projects {
+        assert_success(&ctx.run_trix(&["devnet", "stop"]));
+    }
+}
+
+#[test]
+fn invoke_json_output_reports_tx_hash() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let ports = DevnetPorts::slot(10);
+    ctx.set_devnet_ports(ports);
+
+    assert_success(&ctx.run_trix(&["devnet", "--background"]));
+    assert!(wait_for_port(ports.trp, 30), "TRP port should open");
+
+    let result = ctx.run_trix(&[
+        "invoke",
+        "--template",
+        "transfer",
+        "--args-json",
+        r#"{"quantity": 1000000, "sender": "@alice", "receiver": "@bob"}"#,
+        "--signer",
+        "alice",
+        "--output",
+        "json",
+    ]);
+
+    assert_success(&ctx.run_trix(&["devnet", "stop"]));
+    assert_success(&result);
+
+    let view: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("invoke should print JSON");
+
+    let hash = view["tx_hash"].as_str().expect("tx_hash should be set");
+    assert_eq!(hash.len(), 64, "tx hash: {hash}");
+    assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_eq!(view["submitted"], true);
+    assert_eq!(view["args"]["quantity"], 1000000);
+}
+
+#[test]
+fn invoke_dry_run_reports_cbor_without_submitting() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let ports = DevnetPorts::slot(11);
+    ctx.set_devnet_ports(ports);
+
+    assert_success(&ctx.run_trix(&["devnet", "--background"]));
+    assert!(wait_for_port(ports.trp, 30), "TRP port should open");
+
+    let result = ctx.run_trix(&[
+        "invoke",
+        "--template",
+        "transfer",
+        "--args-json",
+        r#"{"quantity": 1000000, "sender": "@alice", "receiver": "@bob"}"#,
+        "--dry-run",
+        "--output",
+        "json",
+    ]);
+
+    assert_success(&ctx.run_trix(&["devnet", "stop"]));
+    assert_success(&result);
+
+    let view: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("invoke should print JSON");
+
+    assert_eq!(view["submitted"], false);
+    let cbor = view["tx_cbor"].as_str().expect("tx_cbor should be set");
+    assert!(!cbor.is_empty() && cbor.chars().all(|c| c.is_ascii_hexdigit()));
+}
+
+#[test]
+fn invoke_rejects_address_for_another_network() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let mainnet = "addr1vyrswpc8qurswpc8qurswpc8qurswpc8qurswpc8qurswpcfxqeyc";
+    let args = format!(r#"{{"quantity": 1000000, "sender": "@alice", "receiver": "{mainnet}"}}"#);
+
+    let result = ctx.run_trix(&[
+        "invoke",
+        "--template",
+        "transfer",
+        "--args-json",
+        &args,
+        "--skip-submit",
+        "--output",
+        "json",
+    ]);
+
+    assert!(!result.success(), "a mainnet address on local should fail");
+    assert!(
+        result.stderr.contains("`receiver` is a mainnet address"),
+        "{}",
+        result.stderr
+    );
+    assert!(
+        result.stderr.contains("--allow-network-mismatch"),
+        "{}",
+        result.stderr
+    );
+}
+
+#[test]
+fn invoke_script_submits_steps_in_order() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    ctx.write_file(
+        "demo.toml",
+        r#"
+[[transactions]]
+description = "Pay bob"
+template = "transfer"
+signers = ["alice"]
+args = { quantity = 1000000, sender = "@alice", receiver = "@bob" }
+
+[[transactions]]
+description = "Pay alice back"
+template = "transfer"
+signers = ["bob"]
+args = { quantity = 500000, sender = "@bob", receiver = "@alice" }
+
+[[transactions]]
+description = "Pay nobody"
+template = "transfer"
+signers = ["alice"]
+args = { quantity = 1, sender = "@alice", receiver = "@nobody" }
+"#,
+    );
+
+    let ports = DevnetPorts::slot(12);
+    ctx.set_devnet_ports(ports);
+
+    assert_success(&ctx.run_trix(&["devnet", "--background"]));
+    assert!(wait_for_port(ports.trp, 30), "TRP port should open");
+
+    let result = ctx.run_trix(&["invoke", "--script", "demo.toml"]);
+
+    assert_success(&ctx.run_trix(&["devnet", "stop"]));
+
+    assert!(!result.success(), "the third step should fail");
+    assert!(
+        result.stdout.contains("1. [ok] Pay bob"),
+        "{}",
+        result.stdout
+    );
+    assert!(
+        result.stdout.contains("2. [ok] Pay alice back"),
+        "{}",
+        result.stdout
+    );
+    assert!(
+        result.stdout.contains("3. [FAILED] Pay nobody"),
+        "{}",
+        result.stdout
+    );
+    assert!(
+        result.stderr.contains("stopped at step 3"),
+        "{}",
+        result.stderr
+    );
+}
+
+#[test]
+fn invoke_without_terminal_lists_missing_args() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let result = ctx.run_trix(&["invoke", "--template", "transfer", "--skip-submit"]);
+
+    assert!(!result.success(), "invoke should fail instead of prompting");
+    assert!(
+        result.stderr.contains("missing args for `transfer`"),
+        "{}",
+        result.stderr
+    );
+    assert!(result.stderr.contains("quantity"), "{}", result.stderr);
+}
+
+#[test]
+fn wallet_import_mnemonic_gives_deterministic_address() {
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art";
+
+    let addresses = [0, 1].map(|_| {
+        let ctx = TestContext::new();
+        assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+        let import = ctx.run_trix_with_stdin(&["wallet", "import", "treasury"], MNEMONIC);
+        assert_success(&import);
+        assert_output_contains(&import, "imported `treasury`");
+
+        // The identity round-trips through trix.toml next to the built-ins.
+        let config = ctx.load_trix_config();
+        let local = &config.profiles["local"];
+        assert!(matches!(
+            local.identities["treasury"],
+            trix::config::IdentityConfig::Mnemonic(_)
+        ));
+        assert!(local.identities.contains_key("alice"));
+
+        let address = ctx.run_trix(&["identities", "treasury", "address-testnet"]);
+        assert_success(&address);
+        address.stdout.trim().to_string()
+    });
+
+    assert!(addresses[0].starts_with("addr_test1"), "{addresses:?}");
+    assert_eq!(addresses[0], addresses[1]);
+}
+
+#[test]
+fn mnemonic_env_identity_resolves_the_same_address() {
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art";
+
+    let addresses = [0, 1].map(|_| {
+        let ctx = TestContext::new();
+        assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+        let mut trix_toml = ctx.read_file("trix.toml");
+        trix_toml.push_str(
+            "\n[profiles.local]\nnetwork = \"cardano-local\"\n\n\
+             [profiles.local.identities.team]\ntype = \"Mnemonic\"\nmnemonic_env = \"TEAM_MNEMONIC\"\n",
+        );
+        ctx.write_file("trix.toml", &trix_toml);
+        ctx.write_file(".env.local", &format!("TEAM_MNEMONIC=\"{MNEMONIC}\"\n"));
+
+        let address = ctx.run_trix(&["identities", "team", "address-testnet"]);
+        assert_success(&address);
+        address.stdout.trim().to_string()
+    });
+
+    assert!(addresses[0].starts_with("addr_test1"), "{addresses:?}");
+    assert_eq!(addresses[0], addresses[1]);
+}
+
+#[test]
+fn wallet_export_key_imports_with_the_same_payment_key() {
+    use pallas::ledger::addresses::Address;
+
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let export = ctx.run_trix(&["wallet", "export", "@alice", "--out", "alice.skey"]);
+    assert_success(&export);
+
+    let envelope: serde_json::Value = serde_json::from_str(&ctx.read_file("alice.skey")).unwrap();
+    assert_eq!(
+        envelope["type"],
+        "PaymentExtendedSigningKeyShelley_ed25519_bip32"
+    );
+
+    let import = ctx.run_trix(&["wallet", "import", "alice-cli", "--key", "alice.skey"]);
+    assert_success(&import);
+
+    let imported = import
+        .stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("address: "))
+        .expect("import should print the address");
+
+    let alice = ctx.run_trix(&["identities", "alice", "address-testnet"]);
+    assert_success(&alice);
+
+    // The imported key is funded at an enterprise address; it pays to the
+    // same key as alice's own address.
+    let payment_part = |address: &str| match Address::from_bech32(address.trim()).unwrap() {
+        Address::Shelley(address) => address.payment().to_vec(),
+        other => panic!("{other:?} is not a Shelley address"),
+    };
+
+    assert_eq!(payment_part(&alice.stdout), payment_part(imported));
+}
+
+#[test]
+fn codegen_generates_bindings_from_fixture() {
+    let ctx = TestContext::new();
+
+    let init_result = ctx.run_trix(&["init", "--yes"]);
+    assert_success(&init_result);
+
+    let tx3c_path = ctx
+        .tx3c_path()
+        .expect("tx3c should be available in PATH or TX3_TX3C_PATH");
+    assert!(tx3c_path.is_file(), "tx3c path should exist");
+
+    let fixture_dir =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/e2e/fixtures/codegen-template");
+    let fixture_dir = fixture_dir
+        .to_str()
+        .expect("fixture path should be valid UTF-8");
+
+    let mut trix_toml = ctx.read_file("trix.toml");
+    trix_toml.push_str(&format!(
+        "\n[[codegen]]\noutput_dir = \"gen\"\nplugin = {{ repo = \"{}\", path = \".\" }}\n",
+        fixture_dir
+    ));
+    ctx.write_file("trix.toml", &trix_toml);
+
+    let project_name = ctx.load_trix_config().protocol.name;
+
+    let result = ctx.run_trix(&["codegen"]);
+    assert_success(&result);
+
+    // Output nests under the project's own subdir (the deliberate layout of
+    // the tx3c-delegating codegen, see `codegen_deps`); nothing is written
+    // flat at `gen/bindings.txt`.
+    let bindings = format!("gen/{project_name}/bindings.txt");
+    ctx.assert_file_exists(&bindings);
+    ctx.assert_file_contains(&bindings, "Protocol:");
+    ctx.assert_file_contains(&bindings, "Transactions:");
+    ctx.assert_file_contains(&bindings, "transfer");
+    ctx.assert_file_contains(&bindings, "Profiles:");
+    ctx.assert_file_contains(&bindings, "local");
+    assert!(
+        !ctx.file_path("gen/bindings.txt").exists(),
+        "unified layout: nothing should be written flat at gen/bindings.txt"
+    );
+}
+
+#[test]
+fn codegen_vendored_templates_render_identically() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let fixture_dir =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/e2e/fixtures/codegen-template");
+    let fixture_dir = fixture_dir
+        .to_str()
+        .expect("fixture path should be valid UTF-8");
+
+    let mut trix_toml = ctx.read_file("trix.toml");
+    trix_toml.push_str(&format!(
+        "\n[[codegen]]\njob_id = \"fixture\"\noutput_dir = \"gen\"\nplugin = {{ repo = \"{}\", path = \".\" }}\n",
+        fixture_dir
+    ));
+    ctx.write_file("trix.toml", &trix_toml);
+
+    let project_name = ctx.load_trix_config().protocol.name;
+    let bindings = format!("gen/{project_name}/bindings.txt");
+
+    assert_success(&ctx.run_trix(&["codegen"]));
+    let direct = std::fs::read(ctx.file_path(&bindings)).expect("direct bindings");
+
+    let vendor = ctx.run_trix(&["codegen", "vendor", "--job", "fixture"]);
+    assert_success(&vendor);
+    ctx.assert_file_exists("vendor/trix-templates/fixture/templates/bindings.txt.hbs");
+    ctx.assert_file_exists("vendor/trix-templates/fixture/provenance.toml");
+    ctx.assert_file_contains("trix.toml", "vendored = true");
+
+    std::fs::remove_dir_all(ctx.file_path("gen")).expect("should remove gen");
+
+    assert_success(&ctx.run_trix(&["codegen"]));
+    let vendored = std::fs::read(ctx.file_path(&bindings)).expect("vendored bindings");
+
+    assert_eq!(direct, vendored);
+}
+
+#[test]
+fn codegen_renders_templates_from_local_path() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/e2e/fixtures/codegen-template/bindings.txt.hbs");
+    let template = std::fs::read_to_string(fixture).expect("fixture should be readable");
+    ctx.write_file("templates/my-lang/bindings.txt.hbs", &template);
+
+    let mut trix_toml = ctx.read_file("trix.toml");
+    trix_toml.push_str(
+        "\n[[codegen]]\noutput_dir = \"gen\"\nplugin = { local_path = \"./templates/my-lang\" }\n",
+    );
+    ctx.write_file("trix.toml", &trix_toml);
+
+    let project_name = ctx.load_trix_config().protocol.name;
+
+    let result = ctx.run_trix(&["codegen"]);
+    assert_success(&result);
+
+    let bindings = format!("gen/{project_name}/bindings.txt");
+    ctx.assert_file_contains(&bindings, "Transactions:");
+}
+
+#[test]
+fn codegen_copies_static_files_from_an_out_of_tree_template() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let template_dir = tempfile::tempdir().unwrap();
+
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/e2e/fixtures/codegen-template/bindings.txt.hbs");
+    std::fs::copy(fixture, template_dir.path().join("bindings.txt.hbs")).unwrap();
+    std::fs::write(template_dir.path().join("LICENSE"), "static license text\n").unwrap();
+
+    let mut trix_toml = ctx.read_file("trix.toml");
+    trix_toml.push_str(&format!(
+        "\n[[codegen]]\noutput_dir = \"gen\"\nplugin = {{ local_path = {:?} }}\n",
+        template_dir.path().display().to_string()
+    ));
+    ctx.write_file("trix.toml", &trix_toml);
+
+    let project_name = ctx.load_trix_config().protocol.name;
+
+    let result = ctx.run_trix(&["codegen"]);
+    assert_success(&result);
+
+    ctx.assert_file_contains(format!("gen/{project_name}/bindings.txt"), "Transactions:");
+    ctx.assert_file_contains(format!("gen/{project_name}/LICENSE"), "static license text");
+}
+
+#[test]
+fn codegen_verify_detects_stale_and_orphaned_files() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/e2e/fixtures/codegen-template/bindings.txt.hbs");
+    let template = std::fs::read_to_string(fixture).expect("fixture should be readable");
+    ctx.write_file("templates/my-lang/bindings.txt.hbs", &template);
+
+    let mut trix_toml = ctx.read_file("trix.toml");
+    trix_toml.push_str(
+        "\n[[codegen]]\noutput_dir = \"gen\"\nplugin = { local_path = \"./templates/my-lang\" }\nverify_ignore = [\"**/node_modules/**\"]\n",
+    );
+    ctx.write_file("trix.toml", &trix_toml);
+
+    assert_success(&ctx.run_trix(&["codegen"]));
+
+    ctx.write_file("gen/node_modules/dep/index.js", "ignored");
+    let clean = ctx.run_trix(&["codegen", "verify"]);
+    assert_success(&clean);
+    assert_output_contains(&clean, "up to date");
+
+    let project_name = ctx.load_trix_config().protocol.name;
+    ctx.write_file(&format!("gen/{project_name}/bindings.txt"), "hand edited");
+    ctx.write_file("gen/leftover.txt", "old");
+
+    let drifted = ctx.run_trix(&["bindgen", "--verify"]);
+    assert!(!drifted.success(), "verify should fail on drift");
+    assert_output_contains(&drifted, "stale");
+    assert_output_contains(&drifted, "leftover.txt");
+    assert!(
+        drifted.stderr.contains("trix codegen"),
+        "remediation should name the regenerate command: {}",
+        drifted.stderr
+    );
+
+    // Verify never writes: the hand edit is still there.
+    ctx.assert_file_contains(&format!("gen/{project_name}/bindings.txt"), "hand edited");
+}
+
+#[test]
+fn test_init_generates_parseable_test_file() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let result = ctx.run_trix(&["test", "init", "--name", "generated"]);
+    assert_success(&result);
+    ctx.assert_file_exists("tests/generated.toml");
+
+    let parsed = TestConfig::load(ctx.file_path("tests/generated.toml"))
+        .expect("generated test file should parse");
+    assert!(
+        parsed
+            .transactions
+            .iter()
+            .any(|tx| tx.template == "transfer"),
+        "generated file should contain a block for the transfer template"
+    );
+    assert!(!parsed.wallets.is_empty(), "one wallet per declared party");
+
+    let dry = ctx.run_trix(&["test", "--dry-parse", "tests/generated.toml"]);
+    assert_success(&dry);
+}
+
+/// A `trix test` file paying bob 2 ADA from alice, whose balances start at
+/// the 100k ADA `trix init` funds each identity with.
+fn balance_test_file(bob_min: i64, bob_max: i64) -> String {
+    format!(
+        r#"
+[[wallets]]
+name = "alice"
+balance = 100000000000
+
+[[wallets]]
+name = "bob"
+balance = 100000000000
+
+[[transactions]]
+description = "alice pays bob"
+template = "transfer"
+signers = ["alice"]
+args = {{ quantity = 2000000, sender = "@alice", receiver = "@bob" }}
+
+[[expect_balance]]
+wallet = "@alice"
+min = -2500000
+max = -2000001
+delta_from_initial = true
+
+[[expect_balance]]
+wallet = "@bob"
+min = {bob_min}
+max = {bob_max}
+delta_from_initial = true
+"#
+    )
+}
+
+#[test]
+fn test_expect_balance_within_tolerance_passes() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    ctx.write_file("tests/balance.toml", &balance_test_file(2000000, 2000000));
+
+    let result = ctx.run_trix(&["test", "tests/balance.toml"]);
+
+    assert_success(&result);
+    assert_output_contains(&result, "Test Passed");
+}
+
+#[test]
+fn test_expect_balance_out_of_range_reports_expected_and_actual() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    ctx.write_file("tests/balance.toml", &balance_test_file(5000000, 6000000));
+
+    let result = ctx.run_trix(&["test", "tests/balance.toml"]);
+
+    assert!(!result.success(), "bob only received 2 ADA");
+    assert!(
+        result
+            .stderr
+            .contains("wallet `@bob` change from initial balance 100000000000 out of range"),
+        "{}",
+        result.stderr
+    );
+    assert!(
+        result
+            .stderr
+            .contains("Expected: between 5000000 and 6000000 lovelace"),
+        "{}",
+        result.stderr
+    );
+    assert!(
+        result.stderr.contains("Found: 2000000 lovelace"),
+        "{}",
+        result.stderr
+    );
+}
+
+/// Minimal well-formedness check for a JUnit report: a single root
+/// element and every tag closed in order. Returns the `name` of each
+/// `<testcase>`.
+fn junit_testcase_names(xml: &str) -> Vec<String> {
+    let body = xml
+        .strip_prefix("<?xml version=\"1.0\" encoding=\"UTF-8\"?>")
+        .expect("XML declaration");
+
+    let mut open: Vec<&str> = vec![];
+    let mut roots = 0;
+    let mut names = vec![];
+
+    for tag in body.split('<').skip(1) {
+        let tag = tag.split('>').next().expect("unterminated tag");
+        let name = tag
+            .trim_start_matches('/')
+            .split([' ', '/'])
+            .next()
+            .unwrap();
+
+        if name == "testcase" && !tag.starts_with('/') {
+            let attr = tag.split("name=\"").nth(1).expect("testcase name");
+            names.push(attr[..attr.find('"').unwrap()].to_string());
+        }
+
+        if tag.starts_with('/') {
+            assert_eq!(open.pop(), Some(name), "mismatched </{name}>");
+        } else if !tag.ends_with('/') {
+            roots += usize::from(open.is_empty());
+            open.push(name);
+        }
+    }
+
+    assert!(open.is_empty(), "unclosed tags: {open:?}");
+    assert_eq!(roots, 1, "expected a single root element");
+
+    names
+}
+
+#[test]
+fn test_report_junit_written_for_failing_run() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    ctx.write_file("tests/balance.toml", &balance_test_file(5000000, 6000000));
+
+    let result = ctx.run_trix(&[
+        "test",
+        "tests/balance.toml",
+        "--report",
+        "junit",
+        "--report-out",
+        "report.xml",
+    ]);
+
+    assert!(!result.success(), "bob only received 2 ADA");
+
+    let xml = ctx.read_file("report.xml");
+
+    assert_eq!(
+        junit_testcase_names(&xml),
+        [
+            "alice pays bob",
+            "expect balance of @alice",
+            "expect balance of @bob"
+        ]
+    );
+    assert!(
+        xml.contains(r#"<testsuite name="tests/balance.toml""#),
+        "{xml}"
+    );
+    assert!(xml.contains(r#"failures="1""#), "{xml}");
+    assert!(
+        xml.contains("<failure message=\"Test Failed: wallet `@bob` change from initial balance"),
+        "{xml}"
+    );
+
+    let result = ctx.run_trix(&[
+        "test",
+        "tests/balance.toml",
+        "--report",
+        "json",
+        "--report-out",
+        "report.json",
+    ]);
+
+    assert!(!result.success());
+
+    let json: serde_json::Value = serde_json::from_str(&ctx.read_file("report.json")).unwrap();
+    let cases = &json["suites"][0]["cases"];
+
+    assert_eq!(cases[0]["kind"], "transaction");
+    assert_eq!(cases[0]["status"], "passed");
+    assert_eq!(cases[2]["status"], "failed");
+    assert!(
+        cases[2]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Found: 2000000 lovelace")
+    );
+}
+
+const RECLAIM_TX: &str = r#"
+tx reclaim(
+    locked_utxo: UtxoRef
+) {
+    input locked {
+        ref: locked_utxo,
+    }
+
+    output {
+        to: Sender,
+        amount: locked - fees,
+    }
+}
+"#;
+
+#[test]
+fn test_spends_output_of_earlier_transaction() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let main = ctx.read_file("main.tx3") + RECLAIM_TX;
+    ctx.write_file("main.tx3", &main);
+
+    ctx.write_file(
+        "tests/reclaim.toml",
+        r#"
+[[wallets]]
+name = "alice"
+balance = 100000000000
+
+[[wallets]]
+name = "bob"
+balance = 100000000000
+
+[[transactions]]
+description = "alice locks 5 ADA at bob"
+template = "transfer"
+signers = ["alice"]
+args = { quantity = 5000000, sender = "@alice", receiver = "@bob" }
+
+[[transactions]]
+description = "bob hands the locked UTxO back"
+template = "reclaim"
+signers = ["bob"]
+args = { locked_utxo = "$tx[0].outputs[0].ref", sender = "@alice" }
+
+[[expect_balance]]
+wallet = "@bob"
+min = 0
+max = 0
+delta_from_initial = true
+"#,
+    );
+
+    let result = ctx.run_trix(&["test", "tests/reclaim.toml"]);
+
+    assert_success(&result);
+    assert_output_contains(&result, "bob hands the locked UTxO back");
+}
+
+#[test]
+fn test_without_path_runs_every_discovered_file() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let scenario = |quantity: u64| {
+        format!(
+            r#"
+[[transactions]]
+description = "alice pays bob {quantity}"
+template = "transfer"
+signers = ["alice"]
+args = {{ quantity = {quantity}, sender = "@alice", receiver = "@bob" }}
+
+[[expect]]
+from = "@bob"
+"#
+        )
+    };
+
+    ctx.write_file("tests/pay.toml", &scenario(1000000));
+    ctx.write_file("tests/nested/pay_more.toml", &scenario(3000000));
+
+    let result = ctx.run_trix(&["test", "--only", "pay"]);
+
+    assert_success(&result);
+    assert_output_contains(&result, "=== ");
+    assert_output_contains(&result, "== Test summary ==");
+    assert_output_contains(&result, "2 passed, 0 failed, 0 skipped");
+
+    for file in ["pay.toml", "pay_more.toml"] {
+        assert!(
+            result
+                .stdout
+                .lines()
+                .any(|line| line.contains("passed") && line.contains(file)),
+            "{file} should be reported:\n{}",
+            result.stdout
+        );
+    }
+
+    assert!(
+        !result.stdout.contains("basic.toml"),
+        "--only should leave out the scaffolded test:\n{}",
+        result.stdout
+    );
+}
+
+#[test]
+fn test_jobs_runs_files_side_by_side() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let scenario = |quantity: u64| {
+        format!(
+            r#"
+[[transactions]]
+description = "alice pays bob {quantity}"
+template = "transfer"
+signers = ["alice"]
+args = {{ quantity = {quantity}, sender = "@alice", receiver = "@bob" }}
+
+[[expect]]
+from = "@bob"
+"#
+        )
+    };
+
+    ctx.write_file("tests/jobs/one.toml", &scenario(1000000));
+    ctx.write_file("tests/jobs/two.toml", &scenario(2000000));
+
+    let result = ctx.run_trix(&["test", "--jobs", "2", "--only", "jobs/"]);
+
+    assert_success(&result);
+    assert_output_contains(&result, "[tests/jobs/one.toml] ");
+    assert_output_contains(&result, "[tests/jobs/two.toml] ");
+    assert_output_contains(&result, "== Test summary ==");
+    assert_output_contains(&result, "2 passed, 0 failed, 0 skipped");
+}
+
+#[test]
+fn test_fail_fast_stops_after_first_failing_file() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    ctx.write_file(
+        "tests/a_broke.toml",
+        r#"
+[[wallets]]
+name = "alice"
+balance = 100000000000
+
+[[transactions]]
+description = "alice pays bob"
+template = "transfer"
+signers = ["alice"]
+args = { quantity = 1000000, sender = "@alice", receiver = "@bob" }
+
+[[expect_balance]]
+wallet = "@alice"
+min = 100000000000
+"#,
+    );
+
+    let result = ctx.run_trix(&["test", "--fail-fast"]);
+
+    assert!(!result.success(), "a failing file should fail the run");
+    assert_output_contains(&result, "not run");
+    assert_output_contains(&result, "0 passed, 1 failed, 0 skipped, 1 not run");
+}
+
+/// `trix init --template` writes a project that checks as it is, with a
+/// test whose wallets are ones the devnet funds.
+fn assert_init_template(template: &str, transaction: &str) -> TestContext {
+    let ctx = TestContext::new();
+
+    let result = ctx.run_trix(&["init", "--yes", "--template", template]);
+    assert_success(&result);
+
+    ctx.assert_file_exists("trix.toml");
+    ctx.assert_file_exists("devnet.toml");
+    ctx.assert_file_contains("main.tx3", &format!("tx {transaction}("));
+    ctx.assert_file_exists("tests/basic.toml");
+
+    assert_eq!(ctx.load_devnet_config().utxos.len(), 3);
+
+    assert_success(&ctx.run_trix(&["check"]));
+
+    ctx
+}
+
+#[test]
+fn init_template_escrow_creates_project_files() {
+    let ctx = assert_init_template("escrow", "deposit");
+
+    let result = ctx.run_trix(&["test"]);
+
+    assert_success(&result);
+    assert_output_contains(&result, "charlie releases the deposit to bob");
+}
+
+#[test]
+fn init_template_vesting_creates_project_files() {
+    let ctx = assert_init_template("vesting", "lock");
+
+    ctx.assert_file_contains("tests/basic.toml", "template = \"lock\"");
+    assert!(!ctx.load_test_config().transactions.is_empty());
+}
+
+#[test]
+fn init_template_nft_mint_creates_project_files() {
+    let ctx = assert_init_template("nft-mint", "mint_nft");
+
+    // Nothing can mint until the project has a policy.
+    assert!(ctx.load_test_config().transactions.is_empty());
+}
+
+#[test]
+fn init_bare_writes_only_trix_toml() {
+    let ctx = TestContext::new();
+
+    let result = ctx.run_trix(&["init", "--bare", "--yes", "--main", "contracts/main.tx3"]);
+
+    assert_success(&result);
+    ctx.assert_file_exists("trix.toml");
+    ctx.assert_file_exists("contracts/main.tx3");
+    assert!(
+        !ctx.file_path("tests").exists(),
+        "bare init must not create tests/"
+    );
+    assert!(
+        !ctx.file_path(".gitignore").exists(),
+        "bare init must not create .gitignore"
+    );
+    assert!(
+        !ctx.file_path("main.tx3").exists(),
+        "bare init must not create main.tx3"
+    );
+
+    let config = ctx.load_trix_config();
+    assert_eq!(
+        config.protocol.main,
+        std::path::PathBuf::from("contracts/main.tx3")
+    );
+}
+
+#[test]
+fn devnet_manifest_describes_endpoints_and_identities() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let result = ctx.run_trix(&[
+        "devnet",
+        "manifest",
+        "--include-secrets",
+        "--out",
+        "devnet-manifest.json",
+    ]);
+    assert_success(&result);
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&ctx.read_file("devnet-manifest.json")).unwrap();
+
+    assert_eq!(manifest["schema_version"], 1);
+    assert_eq!(manifest["network_magic"], 2);
+    assert_eq!(manifest["endpoints"]["trp"], "http://localhost:8164");
+
+    let identities = manifest["identities"].as_array().unwrap();
+    assert!(!identities.is_empty(), "{manifest}");
+
+    for identity in identities {
+        let address = identity["address"].as_str().unwrap();
+        let mnemonic = identity["mnemonic"].as_str().unwrap();
+
+        assert!(address.starts_with("addr_test"), "{identity}");
+        assert_eq!(identity["public_key_hash"].as_str().unwrap().len(), 56);
+        assert_eq!(mnemonic.split_whitespace().count(), 24);
+    }
+
+    let without_secrets = ctx.run_trix(&["devnet", "manifest"]);
+    assert_success(&without_secrets);
+    assert!(!without_secrets.stdout.contains("mnemonic"));
+}
+
+#[test]
+fn migrate_moves_legacy_profile_networks() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let config = ctx
+        .read_file("trix.toml")
+        .replace("config_schema_version = 1\n", "")
+        + "\n[profiles.staging]\nchain = \"CardanoPreview\"\n\n[profiles.staging.u5c]\nurl = \"http://localhost:50051\"\n";
+    ctx.write_file("trix.toml", &config);
+
+    let result = ctx.run_trix(&["migrate", "--dry-run"]);
+    assert_success(&result);
+    assert_output_contains(&result, "pending migrations");
+    ctx.assert_file_contains("trix.toml", "chain = \"CardanoPreview\"");
+
+    let result = ctx.run_trix(&["migrate", "--yes"]);
+    assert_success(&result);
+    assert_output_contains(&result, "migrated to config schema 1");
+
+    let config = ctx.load_trix_config();
+    assert_eq!(config.config_schema_version, Some(1));
+    assert_eq!(config.profiles["staging"].network, "staging");
+    assert!(config.networks.contains_key("staging"));
+
+    assert_success(&ctx.run_trix(&["profile", "show", "staging"]));
 }
@@ -65,6 +65,26 @@ impl TestContext {
         }
     }
 
+    /// Run trix command with `stdin` piped to it
+    pub fn run_trix_with_stdin(&self, args: &[&str], stdin: &str) -> CommandResult {
+        let mut cmd = Command::cargo_bin("trix").expect("Failed to find trix binary");
+        cmd.args(args);
+        cmd.current_dir(self.path());
+        cmd.write_stdin(stdin);
+
+        for (key, value) in self.tool_envs() {
+            cmd.env(key, value);
+        }
+
+        let output = cmd.output().expect("Failed to execute trix command");
+
+        CommandResult {
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            status: output.status,
+        }
+    }
+
     pub fn tx3c_path(&self) -> Option<PathBuf> {
         resolve_tool_path("tx3c")
     }
@@ -109,6 +129,17 @@ impl TestContext {
             .unwrap_or_else(|_| panic!("Failed to write file: {}", full_path.display()));
     }
 
+    /// Move the project's devnet onto `ports`, so its background devnet
+    /// doesn't reach, or get reached by, another test's.
+    pub fn set_devnet_ports(&self, ports: DevnetPorts) {
+        let mut devnet = self.read_file("devnet.toml");
+        devnet.push_str(&format!(
+            "\n[ports]\ntrp = {}\ngrpc = {}\nminibf = {}\n",
+            ports.trp, ports.grpc, ports.minibf
+        ));
+        self.write_file("devnet.toml", &devnet);
+    }
+
     /// Assert file exists
     pub fn assert_file_exists(&self, path: impl AsRef<Path>) {
         let full_path = self.file_path(&path);
@@ -242,6 +273,30 @@ pub fn assert_output_contains(result: &CommandResult, pattern: &str) {
     );
 }
 
+/// Listen ports for one test's background devnet.
+///
+/// Tests run in parallel, and devnets on the default ports would take each
+/// other's place, so every test that starts one takes a slot of its own.
+#[derive(Debug, Clone, Copy)]
+pub struct DevnetPorts {
+    pub trp: u16,
+    pub grpc: u16,
+    pub minibf: u16,
+}
+
+impl DevnetPorts {
+    /// Ports of `slot`, from 40010 up. No two tests share a slot.
+    pub const fn slot(slot: u16) -> Self {
+        let base = 40_000 + slot * 10;
+
+        Self {
+            trp: base,
+            grpc: base + 1,
+            minibf: base + 2,
+        }
+    }
+}
+
 /// Wait for a port to be open with timeout
 pub fn wait_for_port(port: u16, timeout_secs: u64) -> bool {
     use std::net::TcpStream;
@@ -8,3 +8,252 @@ fn init_runs_without_error() {
     assert_success(&result);
     ctx.assert_file_exists("trix.toml");
 }
+
+#[test]
+fn test_dry_parse_accepts_init_template() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let result = ctx.run_trix(&["test", "--dry-parse", "tests/basic.toml"]);
+
+    assert_success(&result);
+    assert_output_contains(&result, "test file is valid");
+}
+
+#[test]
+fn doctor_reports_trix_home_and_writes_config_there() {
+    let ctx = TestContext::new();
+    let trix_home = ctx.file_path("trix-home");
+    let trix_home = trix_home.to_str().expect("temp path should be valid UTF-8");
+
+    let result = ctx.run_trix_with_env(&["doctor"], &[("TRIX_HOME", trix_home)]);
+
+    assert_success(&result);
+    assert_output_contains(&result, "TRIX_HOME");
+    ctx.assert_file_exists("trix-home/config.toml");
+}
+
+#[test]
+fn telemetry_categories_toggle_independently() {
+    let ctx = TestContext::new();
+    let trix_home = ctx.file_path("trix-home");
+    let env = [("TRIX_HOME", trix_home.to_str().unwrap())];
+
+    let result = ctx.run_trix_with_env(&["telemetry", "status"], &env);
+    assert_success(&result);
+    assert_output_contains(&result, "usage        on");
+    assert_output_contains(&result, "performance  off");
+
+    let result = ctx.run_trix_with_env(&["telemetry", "enable", "errors"], &env);
+    assert_success(&result);
+    assert_output_contains(&result, "errors       on");
+
+    let result = ctx.run_trix_with_env(&["telemetry", "disable", "usage"], &env);
+    assert_success(&result);
+    assert_output_contains(&result, "usage        off");
+    assert_output_contains(&result, "errors       on");
+
+    let result = ctx.run_trix_with_env(&["telemetry", "status", "--output", "json"], &env);
+    assert_success(&result);
+    assert_output_contains(&result, "\"otlp_endpoint\"");
+}
+
+#[test]
+fn alias_expands_with_trailing_args() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let trix_home = ctx.file_path("trix-home");
+    let env = [("TRIX_HOME", trix_home.to_str().unwrap())];
+
+    let result = ctx.run_trix_with_env(&["alias", "set", "tp", "test", "--dry-parse"], &env);
+    assert_success(&result);
+
+    let result = ctx.run_trix_with_env(&["tp", "tests/basic.toml"], &env);
+    assert_success(&result);
+    assert_output_contains(&result, "test file is valid");
+
+    let result = ctx.run_trix_with_env(&["alias", "set", "check", "build"], &env);
+    assert!(!result.success(), "aliasing a built-in should fail");
+
+    let result = ctx.run_trix_with_env(&["--no-alias", "tp"], &env);
+    assert!(!result.success(), "--no-alias should leave `tp` unknown");
+
+    assert_success(&ctx.run_trix_with_env(&["alias", "remove", "tp"], &env));
+    let result = ctx.run_trix_with_env(&["alias", "list"], &env);
+    assert_output_contains(&result, "no aliases defined");
+}
+
+#[test]
+fn test_dry_run_lists_filtered_transactions() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let test = ctx.load_test_config();
+    let first = test.transactions[0].description.clone();
+
+    let result = ctx.run_trix(&["test", "tests/basic.toml", "--dry-run"]);
+    assert_success(&result);
+    assert_output_contains(&result, &first);
+
+    let result = ctx.run_trix(&[
+        "test",
+        "tests/basic.toml",
+        "--dry-run",
+        "--filter",
+        "no-such-transaction",
+    ]);
+    assert!(!result.success(), "a filter matching nothing should fail");
+}
+
+#[test]
+fn test_dry_parse_without_path_checks_every_test_file() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    ctx.write_file("tests/nested/extra.toml", "[[transactions]]\n");
+
+    let result = ctx.run_trix(&["test", "--dry-parse"]);
+    assert!(!result.success(), "the incomplete extra.toml should fail");
+
+    ctx.write_file("tests/nested/extra.toml", "");
+
+    let result = ctx.run_trix(&["test", "--dry-parse"]);
+    assert_success(&result);
+    assert_output_contains(&result, "basic.toml: test file is valid");
+    assert_output_contains(&result, "extra.toml: test file is valid");
+}
+
+#[test]
+fn profile_add_writes_trix_toml_without_prompts() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let args = [
+        "profile",
+        "add",
+        "staging",
+        "--network",
+        "cardano-preview",
+        "--trp-url",
+        "https://trp.example",
+        "--api-key",
+        "env:STAGING_KEY",
+        "--non-interactive",
+    ];
+    assert_success(&ctx.run_trix(&args));
+
+    let config = ctx.read_file("trix.toml");
+    assert!(config.contains("[profiles.staging]"), "{config}");
+    assert!(config.contains("https://trp.example"), "{config}");
+
+    let again = ctx.run_trix(&args);
+    assert!(!again.success(), "an existing profile needs --force");
+    assert!(again.stderr.contains("--force"), "{}", again.stderr);
+}
+
+#[test]
+fn devnet_probe_fails_silently_without_a_devnet() {
+    let ctx = TestContext::new();
+    let home = ctx.file_path("no-devnet");
+
+    for probe in ["--liveness", "--readiness"] {
+        let result = ctx.run_trix(&[
+            "devnet",
+            "probe",
+            probe,
+            "--devnet-home",
+            home.to_str().unwrap(),
+        ]);
+
+        assert_eq!(result.status.code(), Some(1), "{probe}");
+        assert!(result.stdout.is_empty(), "{probe}: {}", result.stdout);
+        assert!(result.stderr.is_empty(), "{probe}: {}", result.stderr);
+    }
+}
+
+#[test]
+fn codegen_list_plugins_works_without_a_project() {
+    let ctx = TestContext::new();
+
+    let result = ctx.run_trix(&["codegen", "--list-plugins"]);
+
+    assert_success(&result);
+    for plugin in ["ts-client", "rust-client", "python-client", "go-client"] {
+        assert_output_contains(&result, plugin);
+    }
+    assert_output_contains(&result, "tx3-lang/rust-sdk");
+}
+
+const STAGING_PROFILE: &str = r#"
+[profiles.staging]
+extends = "preview"
+"#;
+
+#[test]
+fn config_diff_compares_two_files_as_json() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let current = ctx.read_file("trix.toml");
+    ctx.write_file("old.toml", &current);
+    ctx.write_file("new.toml", &format!("{current}{STAGING_PROFILE}"));
+
+    let result = ctx.run_trix(&[
+        "config-diff",
+        "--file",
+        "old.toml",
+        "--file",
+        "new.toml",
+        "--output",
+        "json",
+    ]);
+
+    assert_success(&result);
+
+    let report: serde_json::Value =
+        serde_json::from_str(&result.stdout).expect("config-diff should print JSON");
+    let changes = report["changes"].as_array().unwrap();
+
+    assert!(
+        changes.iter().any(|change| change["change"] == "added"
+            && change["key"] == "profiles.staging.network"
+            && change["value"] == "cardano-preview"),
+        "{}",
+        result.stdout
+    );
+    assert!(
+        changes.iter().all(|change| change["change"] == "added"),
+        "only additions expected:\n{}",
+        result.stdout
+    );
+}
+
+#[test]
+fn config_diff_reads_the_old_version_from_git() {
+    let ctx = TestContext::new();
+    assert_success(&ctx.run_trix(&["init", "--yes"]));
+
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=trix", "-c", "user.email=trix@example.com"])
+            .args(args)
+            .current_dir(ctx.path())
+            .status()
+            .expect("git should run");
+        assert!(status.success(), "git {args:?} failed");
+    };
+
+    git(&["init", "-q"]);
+    git(&["add", "trix.toml"]);
+    git(&["commit", "-q", "-m", "init"]);
+
+    let current = ctx.read_file("trix.toml");
+    ctx.write_file("trix.toml", &format!("{current}{STAGING_PROFILE}"));
+
+    let result = ctx.run_trix(&["config-diff", "--git", "HEAD", "staging"]);
+
+    assert_success(&result);
+    assert_output_contains(&result, "profiles.staging.network");
+    assert_output_contains(&result, "error: staging profile not found");
+}